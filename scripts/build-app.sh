@@ -72,7 +72,7 @@ if [[ "$RELEASE" == true ]]; then
 fi
 
 echo "Building yashiki..."
-cargo build -p yashiki -p yashiki-layout-tatami -p yashiki-layout-byobu "${CARGO_ARGS[@]}"
+cargo build -p yashiki -p yashiki-layout-tatami -p yashiki-layout-byobu -p yashiki-layout-deck -p yashiki-layout-centered -p yashiki-layout-grid -p yashiki-layout-spiral "${CARGO_ARGS[@]}"
 
 # Get version from Cargo.toml
 VERSION=$(grep '^version' "${PROJECT_ROOT}/Cargo.toml" | head -1 | sed 's/.*"\(.*\)".*/\1/')
@@ -121,6 +121,10 @@ mkdir -p "${APP_DIR}/Contents/Resources/layouts"
 cp "${BUILD_DIR}/yashiki" "${APP_DIR}/Contents/MacOS/"
 cp "${BUILD_DIR}/yashiki-layout-tatami" "${APP_DIR}/Contents/Resources/layouts/"
 cp "${BUILD_DIR}/yashiki-layout-byobu" "${APP_DIR}/Contents/Resources/layouts/"
+cp "${BUILD_DIR}/yashiki-layout-deck" "${APP_DIR}/Contents/Resources/layouts/"
+cp "${BUILD_DIR}/yashiki-layout-centered" "${APP_DIR}/Contents/Resources/layouts/"
+cp "${BUILD_DIR}/yashiki-layout-grid" "${APP_DIR}/Contents/Resources/layouts/"
+cp "${BUILD_DIR}/yashiki-layout-spiral" "${APP_DIR}/Contents/Resources/layouts/"
 
 # Copy assets
 cp "${PROJECT_ROOT}/resources/icon/Assets.car" "${APP_DIR}/Contents/Resources/"
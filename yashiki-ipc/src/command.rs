@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::OuterGap;
+use crate::{OuterGap, WindowGeometry};
 
 /// Cursor warp mode - controls when the mouse cursor follows focus
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -29,6 +29,31 @@ pub enum WindowStatus {
     Ignored,
 }
 
+/// Why an on-screen window is not managed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnmanagedReason {
+    /// System UI that is never managed (Control Center)
+    SystemUi,
+    /// Non-normal window level (panels, overlays) without a matching non-ignore rule
+    WindowLevel,
+    /// Matched an `ignore` rule
+    IgnoreRule,
+    /// Excluded with `unmanage-window`
+    Manual,
+}
+
+impl std::fmt::Display for UnmanagedReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnmanagedReason::SystemUi => write!(f, "system UI"),
+            UnmanagedReason::WindowLevel => write!(f, "non-normal window level"),
+            UnmanagedReason::IgnoreRule => write!(f, "ignore rule"),
+            UnmanagedReason::Manual => write!(f, "unmanage-window"),
+        }
+    }
+}
+
 /// Button state matcher for window rules
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -531,7 +556,7 @@ pub enum RuleAction {
     /// Include in tiling (default behavior)
     NoFloat,
     /// Set initial tags (bitmask)
-    Tags { tags: u32 },
+    Tags { tags: u64 },
     /// Set initial display
     Output { output: OutputSpecifier },
     /// Set initial position (for floating windows)
@@ -589,30 +614,126 @@ pub enum Command {
     // Window operations
     WindowFocus {
         direction: Direction,
+        /// Restrict directional focus to the focused display
+        #[serde(default)]
+        same_output: bool,
+    },
+    /// Focus the visible window with the greatest or least area on the focused display
+    WindowFocusBySize {
+        which: SizeSelection,
     },
     WindowSwap {
         direction: Direction,
+        /// Restrict directional swap to the focused display
+        #[serde(default)]
+        same_output: bool,
+    },
+    WindowShift {
+        direction: OutputDirection,
+    },
+    /// Move the focused window to a 0-based position among the windows tiled on its
+    /// display (clamped to the last position), then retile
+    WindowToSlot {
+        index: usize,
     },
     WindowClose,
+    /// Minimize the focused window to the Dock
+    WindowMinimize,
+    /// Restore a minimized window and focus it (default: the most recently minimized)
+    WindowUnminimize {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        id: Option<u32>,
+    },
     WindowToggleFloat,
+    /// Float every managed window (true), or return the ones it floated to tiling (false)
+    FloatAll {
+        enabled: bool,
+    },
     WindowToggleFullscreen,
+    /// Keep a window raised above tiled windows (focused window if id is None)
+    WindowToggleAlwaysOnTop {
+        id: Option<u32>,
+    },
+    /// Keep a window at its native size, centered in its tile (focused window if id is None)
+    WindowToggleNativeSize {
+        id: Option<u32>,
+    },
+    /// Enlarge the focused window to most of the display; a second peek restores it
+    WindowPeek,
+    /// Return a peeked focused window to its tiled slot
+    WindowPeekRelease,
+    /// Flag a window as needing attention
+    WindowSetUrgent {
+        id: u32,
+    },
+    /// Switch to the tag/display of the most recently flagged urgent window and focus it
+    FocusUrgent,
     WindowMoveToTag {
-        tags: u32,
+        tags: u64,
     },
     WindowToggleTag {
-        tags: u32,
+        tags: u64,
+    },
+    /// Set the focused window's tags to exactly the focused display's visible tags
+    WindowMatchView,
+    /// Set the tags of every window whose title matches a regex
+    TagWindowsByTitle {
+        pattern: String,
+        tags: u64,
+    },
+    /// Spread the visible windows round-robin over the single tags in the mask
+    DistributeWindows {
+        tags: u64,
     },
 
     // Tag operations
     TagView {
-        tags: u32,
+        tags: u64,
+        output: Option<OutputSpecifier>,
+    },
+    /// View a single tag by number (1-64) instead of bitmask
+    TagViewN {
+        n: u32,
         output: Option<OutputSpecifier>,
     },
+    /// View the single tag labelled `name` by set-tag-name
+    TagViewName {
+        name: String,
+        output: Option<OutputSpecifier>,
+    },
+    /// Label a tag number (1-64); an empty name removes the label
+    SetTagName {
+        tag: u32,
+        name: String,
+    },
     TagToggle {
-        tags: u32,
+        tags: u64,
         output: Option<OutputSpecifier>,
     },
     TagViewLast,
+    /// Go back `steps` entries in the focused display's tag history
+    TagViewBack {
+        steps: u32,
+    },
+    /// Forget the focused display's tag history
+    TagHistoryClear,
+    /// View tags and set the layout of the newly visible tag in one step
+    TagViewWithLayout {
+        tags: u64,
+        output: Option<OutputSpecifier>,
+        layout: String,
+    },
+    /// View the next/previous single tag on the focused display, wrapping around
+    TagCycle {
+        direction: OutputDirection,
+        /// Skip tags that have no windows on the focused display
+        #[serde(default)]
+        only_occupied: bool,
+    },
+    /// Make views and windows always use exactly one tag
+    SetExclusiveTags {
+        enabled: bool,
+    },
 
     // Output (display) operations
     OutputFocus {
@@ -621,38 +742,136 @@ pub enum Command {
     OutputSend {
         direction: OutputDirection,
     },
+    /// Make a display the system main display (menu bar), then retile
+    SetMainOutput {
+        output: OutputSpecifier,
+    },
+    /// Directional focus/swap on the output treats left/right as up/down (portrait displays)
+    SetFocusAxisSwap {
+        output: Option<OutputSpecifier>,
+        enabled: bool,
+    },
 
     // Layout operations
     LayoutSetDefault {
         layout: String,
     },
     LayoutSet {
-        tags: Option<u32>,
+        tags: Option<u64>,
         output: Option<OutputSpecifier>,
         layout: String,
     },
+    /// Set the layout engine for the focused display's visible tags and retile
+    LayoutSetCurrent {
+        layout: String,
+    },
     LayoutGet {
-        tags: Option<u32>,
+        tags: Option<u64>,
         output: Option<OutputSpecifier>,
     },
+    /// Query the main ratio of the layout engine active on the output/tags
+    MainRatioGet {
+        output: Option<OutputSpecifier>,
+        tags: Option<u64>,
+    },
+    /// Step the current layout's main ratio toward `target` (0.1-0.9) over `duration_ms`
+    MainRatioAnimate {
+        target: f64,
+        duration_ms: u32,
+    },
+    /// Main ratio and inner gap sent to each layout engine when it is spawned.
+    /// Replaces the previous defaults; omitted values are left to the engine.
+    SetDefaultLayoutParams {
+        #[serde(default)]
+        main_ratio: Option<f64>,
+        #[serde(default)]
+        inner_gap: Option<u32>,
+    },
     LayoutCommand {
         layout: Option<String>,
         cmd: String,
         args: Vec<String>,
     },
+    /// Recent stderr output of a running layout engine (all buffered lines if `lines` is None)
+    LayoutEngineLog {
+        name: String,
+        #[serde(default)]
+        lines: Option<usize>,
+    },
+    /// Window list and size a running layout engine last received
+    LayoutLastInput {
+        name: String,
+    },
+    /// Canonical name and supported commands reported by a layout engine
+    LayoutDescribe {
+        name: String,
+    },
+    /// Run `yashiki-layout-<target>` whenever layout `alias` is used
+    LayoutAlias {
+        alias: String,
+        target: String,
+    },
+    /// Default layout, running engines and aliases
+    LayoutList,
+    /// Send a command to every layout engine in use, then retile all displays
+    LayoutCommandAll {
+        cmd: String,
+        args: Vec<String>,
+    },
+    /// Send a command to the engine bound to `tags`, then retile `output`
+    LayoutCommandFor {
+        tags: u64,
+        output: OutputSpecifier,
+        cmd: String,
+        args: Vec<String>,
+    },
+    ToggleOrientation {
+        output: Option<OutputSpecifier>,
+        tags: Option<u64>,
+    },
+    ToggleGaps {
+        output: Option<OutputSpecifier>,
+        tags: Option<u64>,
+    },
     Retile {
         output: Option<OutputSpecifier>,
     },
+    LayoutReverse {
+        output: Option<OutputSpecifier>,
+    },
+    /// Move a window to the front of its display's window order (the main slot), then retile
+    SetMain {
+        id: u32,
+    },
+    /// Keep a window in the main slot of every retile in which it is visible
+    SetStickyMain {
+        id: u32,
+    },
+    ClearStickyMain,
+    /// Reset ratios, counts and gaps of every layout engine in use, then retile
+    LayoutBalanceAll,
 
     // Keybinding operations
     Bind {
         key: String,
         action: Box<Command>,
+        /// Human-readable description shown by `list-bindings`
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        label: Option<String>,
+        /// Command line `action` was parsed from, written back by `export-bindings`
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        args: Vec<String>,
     },
     Unbind {
         key: String,
     },
     ListBindings,
+    /// Write current bindings as `yashiki bind` lines to `path`, or return them if None
+    ExportBindings {
+        path: Option<String>,
+    },
+    /// Key names accepted by `bind`
+    ListKeys,
 
     // Queries
     ListWindows {
@@ -660,10 +879,55 @@ pub enum Command {
         all: bool,
         #[serde(default)]
         debug: bool,
+        #[serde(default)]
+        visible: bool,
     },
     ListOutputs,
+    /// Area a display tiles into: its frame minus menu bar, edge reservations and outer gap
+    OutputUsableFrame {
+        output: Option<OutputSpecifier>,
+    },
     GetState,
+    /// Effective daemon settings in one object
+    GetConfig,
+    /// Retile timing and event-loop throughput
+    Stats,
+    /// Window count and visibility of each tag in use on a display
+    TagStats {
+        output: Option<OutputSpecifier>,
+    },
+    /// On-screen windows the daemon does not manage, with the reason
+    ListUnmanaged,
+    /// Manage a window regardless of rules and window level (persisted)
+    ManageWindow {
+        id: u32,
+    },
+    /// Stop managing a window regardless of rules (persisted)
+    UnmanageWindow {
+        id: u32,
+    },
     FocusedWindow,
+    /// Live frame of a managed window, read from the accessibility API
+    WindowGeometry {
+        id: u32,
+    },
+    /// Display (output) containing the center of a window
+    WindowOutput {
+        id: u32,
+    },
+    /// Tag numbers a window belongs to
+    WindowTags {
+        id: u32,
+    },
+    /// Update the tracked frame of a window from the accessibility API without retiling
+    ResyncWindow {
+        id: u32,
+    },
+    /// Ignore (or stop ignoring) an app's window move/resize events
+    SetAppEventsPaused {
+        app_name: String,
+        paused: bool,
+    },
 
     // Exec
     Exec {
@@ -675,6 +939,14 @@ pub enum Command {
         app_name: String,
         command: String,
     },
+    /// Dismiss the app's window to a reserved tag, or bring it back onto the current
+    /// tags and focus it. Launches the app (`command`, default `open -a <app_name>`)
+    /// when it has no window.
+    ScratchpadToggle {
+        app_name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        command: Option<String>,
+    },
 
     // Exec path
     GetExecPath,
@@ -709,15 +981,60 @@ pub enum Command {
         delay_ms: u64,
     },
     GetAutoRaise,
+    /// Whether focusing a window also raises it (AXRaise). On by default.
+    SetRaiseOnFocus {
+        enabled: bool,
+    },
+    /// Modifiers (`cmd-alt`) that turn a left drag into a move and a right drag into a
+    /// resize of the floating window under the cursor. None disables dragging.
+    SetDragModifier {
+        modifier: Option<String>,
+    },
 
     // Outer gap
     SetOuterGap {
         values: Vec<String>,
     },
     GetOuterGap,
+    /// Keep `size` pixels at a display edge free for an external bar (adds to earlier reservations)
+    ReserveEdge {
+        #[serde(default)]
+        output: Option<OutputSpecifier>,
+        edge: Edge,
+        size: u32,
+    },
+    /// Drop a display's edge reservations and outer gap (until the next set-outer-gap)
+    ClearReservations {
+        #[serde(default)]
+        output: Option<OutputSpecifier>,
+    },
+    /// Force inner and outer gaps to zero while only one display is connected
+    SetGapsMultiMonitorOnly {
+        enabled: bool,
+    },
+
+    /// Event-loop tick in milliseconds: how often coalesced retiles are flushed
+    SetTickRate {
+        ms: u32,
+    },
+
+    // Window arrangement snapshots
+    SnapshotSave {
+        name: String,
+    },
+    SnapshotRestore {
+        name: String,
+    },
 
     // Control
-    Quit,
+    Reconcile,
+    /// Re-read every display and window from the system and retile everything.
+    /// Heavier than `Reconcile`; meant for recovering from a drifted state.
+    Resync,
+    Quit {
+        #[serde(default)]
+        force: bool,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -731,6 +1048,23 @@ pub enum Direction {
     Prev,
 }
 
+/// Display edge for `reserve-edge`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Edge {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SizeSelection {
+    Largest,
+    Smallest,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum OutputDirection {
@@ -749,24 +1083,151 @@ pub enum OutputSpecifier {
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum Response {
     Ok,
-    Error { message: String },
-    Windows { windows: Vec<WindowInfo> },
-    Outputs { outputs: Vec<OutputInfo> },
-    State { state: StateInfo },
-    Bindings { bindings: Vec<BindingInfo> },
-    Rules { rules: Vec<RuleInfo> },
-    WindowId { id: Option<u32> },
-    Layout { layout: String },
-    ExecPath { path: String },
-    CursorWarp { mode: CursorWarpMode },
-    AutoRaise { mode: AutoRaiseMode, delay_ms: u64 },
-    OuterGap { outer_gap: OuterGap },
+    Error {
+        message: String,
+    },
+    Windows {
+        windows: Vec<WindowInfo>,
+    },
+    Outputs {
+        outputs: Vec<OutputInfo>,
+    },
+    State {
+        state: StateInfo,
+    },
+    Config {
+        config: ConfigInfo,
+    },
+    Bindings {
+        bindings: Vec<BindingInfo>,
+    },
+    Rules {
+        rules: Vec<RuleInfo>,
+    },
+    WindowId {
+        id: Option<u32>,
+    },
+    Layout {
+        layout: String,
+    },
+    ExecPath {
+        path: String,
+    },
+    CursorWarp {
+        mode: CursorWarpMode,
+    },
+    AutoRaise {
+        mode: AutoRaiseMode,
+        delay_ms: u64,
+    },
+    OuterGap {
+        outer_gap: OuterGap,
+    },
+    WindowGeometry {
+        geometry: WindowGeometry,
+    },
+    MainRatio {
+        ratio: f64,
+    },
+    OutputId {
+        id: u32,
+    },
+    WindowTags {
+        id: u32,
+        tags: Vec<u32>,
+    },
+    UsableFrame {
+        output: u32,
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+    },
+    Log {
+        lines: Vec<String>,
+    },
+    LayoutInput {
+        width: u32,
+        height: u32,
+        windows: Vec<u32>,
+    },
+    LayoutCapabilities {
+        name: String,
+        commands: Vec<String>,
+    },
+    Stats {
+        stats: StatsInfo,
+    },
+    TagStats {
+        tags: Vec<TagStatsInfo>,
+    },
+    UnmanagedWindows {
+        windows: Vec<UnmanagedWindowInfo>,
+    },
+    WindowCount {
+        count: usize,
+    },
+    BindingsExport {
+        content: String,
+    },
+    Keys {
+        keys: Vec<String>,
+    },
+    Layouts {
+        layouts: Vec<LayoutInfo>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagStatsInfo {
+    /// Tag number (1-64)
+    pub tag: u32,
+    /// Windows on the display that carry this tag
+    pub windows: usize,
+    pub visible: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnmanagedWindowInfo {
+    pub id: u32,
+    pub pid: i32,
+    pub app_name: String,
+    pub title: String,
+    pub window_level: i32,
+    pub reason: UnmanagedReason,
+}
+
+/// Daemon performance counters reported by `stats`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatsInfo {
+    /// Duration of the most recent retile in microseconds (None before the first retile)
+    pub last_retile_us: Option<u64>,
+    /// Windows the most recent retile wrote a frame to
+    pub last_retile_writes: u32,
+    /// Windows the most recent retile skipped because they were already at their target frame
+    pub last_retile_skipped: u32,
+    pub retile_count: u64,
+    /// Observer/workspace events and commands handled by the main loop
+    pub events_processed: u64,
+    pub uptime_secs: u64,
+    pub events_per_sec: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LayoutInfo {
+    pub name: String,
+    /// Engine the name is an alias for (`layout-alias`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias_of: Option<String>,
+    pub running: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BindingInfo {
     pub key: String,
     pub action: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -778,7 +1239,10 @@ pub struct OutputInfo {
     pub width: u32,
     pub height: u32,
     pub is_main: bool,
-    pub visible_tags: u32,
+    pub visible_tags: u64,
+    /// Labels of the visible tags, lowest tag first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub visible_tag_names: Vec<String>,
     pub is_focused: bool,
 }
 
@@ -790,7 +1254,7 @@ pub struct WindowInfo {
     pub app_name: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub app_id: Option<String>,
-    pub tags: u32,
+    pub tags: u64,
     pub x: i32,
     pub y: i32,
     pub width: u32,
@@ -821,11 +1285,56 @@ pub struct WindowInfo {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StateInfo {
-    pub visible_tags: u32,
+    pub visible_tags: u64,
+    /// Labels of the visible tags, lowest tag first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub visible_tag_names: Vec<String>,
     pub focused_window_id: Option<u32>,
     pub window_count: usize,
     pub default_layout: String,
     pub current_layout: Option<String>,
+    pub raise_on_focus: bool,
+    /// Entries in the focused display's tag history (tag-view-back)
+    pub tag_history_depth: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagLayoutInfo {
+    /// Tag number (1-64)
+    pub tag: u8,
+    pub layout: String,
+}
+
+/// Parameters pushed to every newly spawned layout engine (set-default-layout-params)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LayoutDefaultsInfo {
+    pub main_ratio: Option<f64>,
+    pub inner_gap: Option<u32>,
+}
+
+/// Effective runtime settings of the daemon
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigInfo {
+    pub default_layout: String,
+    pub default_tag: u64,
+    /// Layout engines set per tag via `layout-set`, sorted by tag
+    pub tag_layouts: Vec<TagLayoutInfo>,
+    pub exec_path: String,
+    pub cursor_warp: CursorWarpMode,
+    pub auto_raise: AutoRaiseMode,
+    pub auto_raise_delay_ms: u64,
+    pub outer_gap: OuterGap,
+    /// Gaps are forced off while only one display is connected
+    #[serde(default)]
+    pub gaps_multi_monitor_only: bool,
+    pub exclusive_tags: bool,
+    pub tick_rate_ms: u32,
+    #[serde(default)]
+    pub layout_defaults: LayoutDefaultsInfo,
+    pub raise_on_focus: bool,
+    /// Modifiers for dragging floating windows (None: disabled)
+    #[serde(default)]
+    pub drag_modifier: Option<String>,
 }
 
 #[cfg(test)]
@@ -847,12 +1356,57 @@ mod tests {
             Command::TagView { tags, .. } => assert_eq!(tags, 1),
             _ => panic!("Wrong variant"),
         }
+
+        // Tags above 32 need the full u64 mask
+        let json = serde_json::to_string(&Command::TagView {
+            tags: 1 << 32,
+            output: None,
+        })
+        .unwrap();
+        assert!(json.contains("\"tags\":4294967296"));
+        match serde_json::from_str(&json).unwrap() {
+            Command::TagView { tags, .. } => assert_eq!(tags, 1 << 32),
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_tag_name_commands_serialization() {
+        let json = serde_json::to_string(&Command::SetTagName {
+            tag: 4,
+            name: "mail".to_string(),
+        })
+        .unwrap();
+        assert_eq!(json, r#"{"type":"set_tag_name","tag":4,"name":"mail"}"#);
+
+        let cmd: Command =
+            serde_json::from_str(r#"{"type":"tag_view_name","name":"mail","output":null}"#)
+                .unwrap();
+        match cmd {
+            Command::TagViewName { name, output } => {
+                assert_eq!(name, "mail");
+                assert_eq!(output, None);
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_command_tag_view_n_serialization() {
+        let cmd = Command::TagViewN { n: 3, output: None };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains("\"type\":\"tag_view_n\""));
+        assert!(json.contains("\"n\":3"));
+
+        let deserialized: Command = serde_json::from_str(&json).unwrap();
+        assert!(matches!(deserialized, Command::TagViewN { n: 3, .. }));
     }
 
     #[test]
     fn test_command_window_focus_serialization() {
         let cmd = Command::WindowFocus {
             direction: Direction::Next,
+            same_output: false,
         };
         let json = serde_json::to_string(&cmd).unwrap();
         assert!(json.contains("\"type\":\"window_focus\""));
@@ -860,86 +1414,396 @@ mod tests {
     }
 
     #[test]
-    fn test_command_bind_serialization() {
-        let cmd = Command::Bind {
-            key: "alt-1".to_string(),
-            action: Box::new(Command::TagView {
-                tags: 1,
-                output: None,
-            }),
+    fn test_command_window_focus_by_size_serialization() {
+        let cmd = Command::WindowFocusBySize {
+            which: SizeSelection::Largest,
         };
         let json = serde_json::to_string(&cmd).unwrap();
+        assert_eq!(
+            json,
+            "{\"type\":\"window_focus_by_size\",\"which\":\"largest\"}"
+        );
+        assert!(matches!(
+            serde_json::from_str::<Command>(&json).unwrap(),
+            Command::WindowFocusBySize {
+                which: SizeSelection::Largest
+            }
+        ));
+    }
 
-        let deserialized: Command = serde_json::from_str(&json).unwrap();
-        match deserialized {
-            Command::Bind { key, action } => {
-                assert_eq!(key, "alt-1");
-                match *action {
-                    Command::TagView { tags, .. } => assert_eq!(tags, 1),
-                    _ => panic!("Wrong inner variant"),
-                }
+    #[test]
+    fn test_command_window_focus_same_output_defaults_to_false() {
+        let cmd: Command =
+            serde_json::from_str("{\"type\":\"window_focus\",\"direction\":\"left\"}").unwrap();
+        assert!(matches!(
+            cmd,
+            Command::WindowFocus {
+                direction: Direction::Left,
+                same_output: false
             }
-            _ => panic!("Wrong variant"),
-        }
+        ));
     }
 
     #[test]
-    fn test_command_layout_command_serialization() {
-        let cmd = Command::LayoutCommand {
-            layout: None,
-            cmd: "set-main-ratio".to_string(),
-            args: vec!["0.6".to_string()],
-        };
+    fn test_command_float_all_serialization() {
+        let cmd = Command::FloatAll { enabled: false };
         let json = serde_json::to_string(&cmd).unwrap();
+        assert_eq!(json, r#"{"type":"float_all","enabled":false}"#);
 
         let deserialized: Command = serde_json::from_str(&json).unwrap();
-        match deserialized {
-            Command::LayoutCommand { layout, cmd, args } => {
-                assert_eq!(layout, None);
-                assert_eq!(cmd, "set-main-ratio");
-                assert_eq!(args, vec!["0.6"]);
-            }
-            _ => panic!("Wrong variant"),
-        }
+        assert!(matches!(deserialized, Command::FloatAll { enabled: false }));
+    }
 
-        // With layout specified
-        let cmd = Command::LayoutCommand {
-            layout: Some("tatami".to_string()),
-            cmd: "set-outer-gap".to_string(),
-            args: vec!["10".to_string()],
+    #[test]
+    fn test_command_set_exclusive_tags_serialization() {
+        let cmd = Command::SetExclusiveTags { enabled: true };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert_eq!(json, r#"{"type":"set_exclusive_tags","enabled":true}"#);
+
+        let deserialized: Command = serde_json::from_str(&json).unwrap();
+        assert!(matches!(
+            deserialized,
+            Command::SetExclusiveTags { enabled: true }
+        ));
+    }
+
+    #[test]
+    fn test_command_set_focus_axis_swap_serialization() {
+        let cmd = Command::SetFocusAxisSwap {
+            output: Some(OutputSpecifier::Id(2)),
+            enabled: true,
         };
         let json = serde_json::to_string(&cmd).unwrap();
-        assert!(json.contains("\"layout\":\"tatami\""));
+        assert!(json.contains("\"type\":\"set_focus_axis_swap\""));
+        assert!(json.contains("\"enabled\":true"));
 
         let deserialized: Command = serde_json::from_str(&json).unwrap();
         match deserialized {
-            Command::LayoutCommand { layout, cmd, args } => {
-                assert_eq!(layout, Some("tatami".to_string()));
-                assert_eq!(cmd, "set-outer-gap");
-                assert_eq!(args, vec!["10"]);
+            Command::SetFocusAxisSwap { output, enabled } => {
+                assert_eq!(output, Some(OutputSpecifier::Id(2)));
+                assert!(enabled);
             }
             _ => panic!("Wrong variant"),
         }
     }
 
     #[test]
-    fn test_direction_serialization() {
-        let cases = [
-            (Direction::Left, "\"left\""),
-            (Direction::Right, "\"right\""),
-            (Direction::Up, "\"up\""),
-            (Direction::Down, "\"down\""),
-            (Direction::Next, "\"next\""),
-            (Direction::Prev, "\"prev\""),
-        ];
+    fn test_command_urgent_serialization() {
+        let json = serde_json::to_string(&Command::WindowSetUrgent { id: 42 }).unwrap();
+        assert_eq!(json, r#"{"type":"window_set_urgent","id":42}"#);
+        let deserialized: Command = serde_json::from_str(&json).unwrap();
+        assert!(matches!(deserialized, Command::WindowSetUrgent { id: 42 }));
 
-        for (direction, expected) in cases {
-            let json = serde_json::to_string(&direction).unwrap();
-            assert_eq!(json, expected);
+        let json = serde_json::to_string(&Command::FocusUrgent).unwrap();
+        assert_eq!(json, r#"{"type":"focus_urgent"}"#);
+        let deserialized: Command = serde_json::from_str(&json).unwrap();
+        assert!(matches!(deserialized, Command::FocusUrgent));
+    }
 
-            let deserialized: Direction = serde_json::from_str(&json).unwrap();
-            assert_eq!(deserialized, direction);
-        }
+    #[test]
+    fn test_command_window_toggle_always_on_top_serialization() {
+        let cmd = Command::WindowToggleAlwaysOnTop { id: Some(42) };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains("\"type\":\"window_toggle_always_on_top\""));
+        assert!(json.contains("\"id\":42"));
+
+        let deserialized: Command =
+            serde_json::from_str("{\"type\":\"window_toggle_always_on_top\",\"id\":null}").unwrap();
+        assert!(matches!(
+            deserialized,
+            Command::WindowToggleAlwaysOnTop { id: None }
+        ));
+    }
+
+    #[test]
+    fn test_command_window_toggle_native_size_serialization() {
+        let cmd = Command::WindowToggleNativeSize { id: Some(42) };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert_eq!(json, r#"{"type":"window_toggle_native_size","id":42}"#);
+
+        let deserialized: Command =
+            serde_json::from_str(r#"{"type":"window_toggle_native_size","id":null}"#).unwrap();
+        assert!(matches!(
+            deserialized,
+            Command::WindowToggleNativeSize { id: None }
+        ));
+    }
+
+    #[test]
+    fn test_command_snapshot_serialization() {
+        let json = serde_json::to_string(&Command::SnapshotSave {
+            name: "work".to_string(),
+        })
+        .unwrap();
+        assert_eq!(json, "{\"type\":\"snapshot_save\",\"name\":\"work\"}");
+
+        let cmd: Command =
+            serde_json::from_str("{\"type\":\"snapshot_restore\",\"name\":\"work\"}").unwrap();
+        assert!(matches!(cmd, Command::SnapshotRestore { name } if name == "work"));
+    }
+
+    #[test]
+    fn test_command_layout_balance_all_serialization() {
+        let json = serde_json::to_string(&Command::LayoutBalanceAll).unwrap();
+        assert_eq!(json, "{\"type\":\"layout_balance_all\"}");
+
+        let deserialized: Command = serde_json::from_str(&json).unwrap();
+        assert!(matches!(deserialized, Command::LayoutBalanceAll));
+    }
+
+    #[test]
+    fn test_command_window_shift_serialization() {
+        let cmd = Command::WindowShift {
+            direction: OutputDirection::Prev,
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains("\"type\":\"window_shift\""));
+        assert!(json.contains("\"direction\":\"prev\""));
+    }
+
+    #[test]
+    fn test_command_window_to_slot_serialization() {
+        let cmd = Command::WindowToSlot { index: 2 };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert_eq!(json, r#"{"type":"window_to_slot","index":2}"#);
+
+        let deserialized: Command = serde_json::from_str(&json).unwrap();
+        assert!(matches!(deserialized, Command::WindowToSlot { index: 2 }));
+    }
+
+    #[test]
+    fn test_command_quit_force_defaults_to_false() {
+        let cmd: Command = serde_json::from_str("{\"type\":\"quit\"}").unwrap();
+        assert!(matches!(cmd, Command::Quit { force: false }));
+
+        let json = serde_json::to_string(&Command::Quit { force: true }).unwrap();
+        assert!(json.contains("\"force\":true"));
+    }
+
+    #[test]
+    fn test_command_list_windows_visible_defaults_to_false() {
+        let cmd: Command = serde_json::from_str("{\"type\":\"list_windows\"}").unwrap();
+        assert!(matches!(
+            cmd,
+            Command::ListWindows {
+                all: false,
+                debug: false,
+                visible: false
+            }
+        ));
+
+        let json = serde_json::to_string(&Command::ListWindows {
+            all: false,
+            debug: false,
+            visible: true,
+        })
+        .unwrap();
+        assert!(json.contains("\"visible\":true"));
+    }
+
+    #[test]
+    fn test_command_tag_cycle_serialization() {
+        let cmd = Command::TagCycle {
+            direction: OutputDirection::Prev,
+            only_occupied: true,
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains("\"direction\":\"prev\""));
+
+        let deserialized: Command = serde_json::from_str(&json).unwrap();
+        assert!(matches!(
+            deserialized,
+            Command::TagCycle {
+                direction: OutputDirection::Prev,
+                only_occupied: true
+            }
+        ));
+
+        let defaulted: Command =
+            serde_json::from_str(r#"{"type":"tag_cycle","direction":"next"}"#).unwrap();
+        assert!(matches!(
+            defaulted,
+            Command::TagCycle {
+                only_occupied: false,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_command_bind_serialization() {
+        let cmd = Command::Bind {
+            key: "alt-1".to_string(),
+            action: Box::new(Command::TagView {
+                tags: 1,
+                output: None,
+            }),
+            label: None,
+            args: vec![],
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(!json.contains("label"));
+        assert!(!json.contains("args"));
+
+        let deserialized: Command = serde_json::from_str(&json).unwrap();
+        match deserialized {
+            Command::Bind {
+                key,
+                action,
+                label,
+                args,
+            } => {
+                assert_eq!(key, "alt-1");
+                assert_eq!(label, None);
+                assert!(args.is_empty());
+                match *action {
+                    Command::TagView { tags, .. } => assert_eq!(tags, 1),
+                    _ => panic!("Wrong inner variant"),
+                }
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_command_bind_with_label_serialization() {
+        let cmd = Command::Bind {
+            key: "cmd-h".to_string(),
+            action: Box::new(Command::WindowFocus {
+                direction: Direction::Left,
+                same_output: false,
+            }),
+            label: Some("focus window left".to_string()),
+            args: vec!["window-focus".to_string(), "left".to_string()],
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains("\"label\":\"focus window left\""));
+        assert!(json.contains("\"args\":[\"window-focus\",\"left\"]"));
+
+        let deserialized: Command = serde_json::from_str(&json).unwrap();
+        match deserialized {
+            Command::Bind { label, args, .. } => {
+                assert_eq!(label.as_deref(), Some("focus window left"));
+                assert_eq!(args, vec!["window-focus", "left"]);
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_command_layout_command_serialization() {
+        let cmd = Command::LayoutCommand {
+            layout: None,
+            cmd: "set-main-ratio".to_string(),
+            args: vec!["0.6".to_string()],
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+
+        let deserialized: Command = serde_json::from_str(&json).unwrap();
+        match deserialized {
+            Command::LayoutCommand { layout, cmd, args } => {
+                assert_eq!(layout, None);
+                assert_eq!(cmd, "set-main-ratio");
+                assert_eq!(args, vec!["0.6"]);
+            }
+            _ => panic!("Wrong variant"),
+        }
+
+        // With layout specified
+        let cmd = Command::LayoutCommand {
+            layout: Some("tatami".to_string()),
+            cmd: "set-outer-gap".to_string(),
+            args: vec!["10".to_string()],
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains("\"layout\":\"tatami\""));
+
+        let deserialized: Command = serde_json::from_str(&json).unwrap();
+        match deserialized {
+            Command::LayoutCommand { layout, cmd, args } => {
+                assert_eq!(layout, Some("tatami".to_string()));
+                assert_eq!(cmd, "set-outer-gap");
+                assert_eq!(args, vec!["10"]);
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_command_layout_command_all_serialization() {
+        let cmd = Command::LayoutCommandAll {
+            cmd: "set-inner-gap".to_string(),
+            args: vec!["8".to_string()],
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains("\"type\":\"layout_command_all\""));
+
+        let deserialized: Command = serde_json::from_str(&json).unwrap();
+        match deserialized {
+            Command::LayoutCommandAll { cmd, args } => {
+                assert_eq!(cmd, "set-inner-gap");
+                assert_eq!(args, vec!["8"]);
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_command_layout_command_for_serialization() {
+        let cmd = Command::LayoutCommandFor {
+            tags: 0b10,
+            output: OutputSpecifier::Name("DELL".to_string()),
+            cmd: "set-padding".to_string(),
+            args: vec!["40".to_string()],
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains("\"type\":\"layout_command_for\""));
+
+        let deserialized: Command = serde_json::from_str(&json).unwrap();
+        match deserialized {
+            Command::LayoutCommandFor {
+                tags,
+                output,
+                cmd,
+                args,
+            } => {
+                assert_eq!(tags, 0b10);
+                assert_eq!(output, OutputSpecifier::Name("DELL".to_string()));
+                assert_eq!(cmd, "set-padding");
+                assert_eq!(args, vec!["40"]);
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_command_set_main_output_serialization() {
+        let cmd: Command =
+            serde_json::from_str(r#"{"type":"set_main_output","output":2}"#).unwrap();
+        match cmd {
+            Command::SetMainOutput { output } => assert_eq!(output, OutputSpecifier::Id(2)),
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_direction_serialization() {
+        let cases = [
+            (Direction::Left, "\"left\""),
+            (Direction::Right, "\"right\""),
+            (Direction::Up, "\"up\""),
+            (Direction::Down, "\"down\""),
+            (Direction::Next, "\"next\""),
+            (Direction::Prev, "\"prev\""),
+        ];
+
+        for (direction, expected) in cases {
+            let json = serde_json::to_string(&direction).unwrap();
+            assert_eq!(json, expected);
+
+            let deserialized: Direction = serde_json::from_str(&json).unwrap();
+            assert_eq!(deserialized, direction);
+        }
     }
 
     #[test]
@@ -1024,10 +1888,13 @@ mod tests {
         let resp = Response::State {
             state: StateInfo {
                 visible_tags: 0b0011,
+                visible_tag_names: vec!["web".to_string()],
                 focused_window_id: Some(42),
                 window_count: 5,
                 default_layout: "tatami".to_string(),
                 current_layout: Some("byobu".to_string()),
+                raise_on_focus: true,
+                tag_history_depth: 3,
             },
         };
         let json = serde_json::to_string(&resp).unwrap();
@@ -1040,11 +1907,25 @@ mod tests {
                 assert_eq!(state.window_count, 5);
                 assert_eq!(state.default_layout, "tatami");
                 assert_eq!(state.current_layout, Some("byobu".to_string()));
+                assert_eq!(state.tag_history_depth, 3);
             }
             _ => panic!("Wrong variant"),
         }
     }
 
+    #[test]
+    fn test_command_tag_view_back_serialization() {
+        let cmd = Command::TagViewBack { steps: 2 };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert_eq!(json, r#"{"type":"tag_view_back","steps":2}"#);
+
+        let cmd: Command = serde_json::from_str(&json).unwrap();
+        assert!(matches!(cmd, Command::TagViewBack { steps: 2 }));
+
+        let json = serde_json::to_string(&Command::TagHistoryClear).unwrap();
+        assert_eq!(json, r#"{"type":"tag_history_clear"}"#);
+    }
+
     #[test]
     fn test_command_layout_set_default_serialization() {
         let cmd = Command::LayoutSetDefault {
@@ -1061,6 +1942,43 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_command_tag_windows_by_title_serialization() {
+        let cmd = Command::TagWindowsByTitle {
+            pattern: "^Slack".to_string(),
+            tags: 4,
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert_eq!(
+            json,
+            r#"{"type":"tag_windows_by_title","pattern":"^Slack","tags":4}"#
+        );
+
+        let deserialized: Command = serde_json::from_str(&json).unwrap();
+        match deserialized {
+            Command::TagWindowsByTitle { pattern, tags } => {
+                assert_eq!(pattern, "^Slack");
+                assert_eq!(tags, 4);
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_command_layout_set_current_serialization() {
+        let cmd = Command::LayoutSetCurrent {
+            layout: "deck".to_string(),
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert_eq!(json, r#"{"type":"layout_set_current","layout":"deck"}"#);
+
+        let deserialized: Command = serde_json::from_str(&json).unwrap();
+        match deserialized {
+            Command::LayoutSetCurrent { layout } => assert_eq!(layout, "deck"),
+            _ => panic!("Wrong variant"),
+        }
+    }
+
     #[test]
     fn test_command_layout_set_serialization() {
         // Without tags (current tag)
@@ -1132,6 +2050,44 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_command_toggle_orientation_serialization() {
+        let cmd = Command::ToggleOrientation {
+            output: Some(OutputSpecifier::Id(2)),
+            tags: None,
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains("\"type\":\"toggle_orientation\""));
+
+        let deserialized: Command = serde_json::from_str(&json).unwrap();
+        match deserialized {
+            Command::ToggleOrientation { output, tags } => {
+                assert_eq!(output, Some(OutputSpecifier::Id(2)));
+                assert_eq!(tags, None);
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_command_toggle_gaps_serialization() {
+        let cmd = Command::ToggleGaps {
+            output: None,
+            tags: Some(1),
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains("\"type\":\"toggle_gaps\""));
+
+        let deserialized: Command = serde_json::from_str(&json).unwrap();
+        assert!(matches!(
+            deserialized,
+            Command::ToggleGaps {
+                output: None,
+                tags: Some(1)
+            }
+        ));
+    }
+
     #[test]
     fn test_response_layout_serialization() {
         let resp = Response::Layout {
@@ -1154,6 +2110,7 @@ mod tests {
             bindings: vec![BindingInfo {
                 key: "alt-1".to_string(),
                 action: "tag-view 1".to_string(),
+                label: Some("view tag 1".to_string()),
             }],
         };
         let json = serde_json::to_string(&resp).unwrap();
@@ -1163,6 +2120,7 @@ mod tests {
             Response::Bindings { bindings } => {
                 assert_eq!(bindings.len(), 1);
                 assert_eq!(bindings[0].key, "alt-1");
+                assert_eq!(bindings[0].label.as_deref(), Some("view tag 1"));
             }
             _ => panic!("Wrong variant"),
         }
@@ -1234,6 +2192,556 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_command_window_geometry_serialization() {
+        let json = serde_json::to_string(&Command::WindowGeometry { id: 42 }).unwrap();
+        assert_eq!(json, "{\"type\":\"window_geometry\",\"id\":42}");
+
+        let deserialized: Command = serde_json::from_str(&json).unwrap();
+        assert!(matches!(deserialized, Command::WindowGeometry { id: 42 }));
+    }
+
+    #[test]
+    fn test_window_output_serialization() {
+        let json = serde_json::to_string(&Command::WindowOutput { id: 7 }).unwrap();
+        assert_eq!(json, r#"{"type":"window_output","id":7}"#);
+        let deserialized: Command = serde_json::from_str(&json).unwrap();
+        assert!(matches!(deserialized, Command::WindowOutput { id: 7 }));
+
+        let json = serde_json::to_string(&Response::OutputId { id: 2 }).unwrap();
+        assert_eq!(json, r#"{"type":"output_id","id":2}"#);
+        let deserialized: Response = serde_json::from_str(&json).unwrap();
+        assert!(matches!(deserialized, Response::OutputId { id: 2 }));
+    }
+
+    #[test]
+    fn test_distribute_windows_serialization() {
+        let json = serde_json::to_string(&Command::DistributeWindows { tags: 0b111 }).unwrap();
+        assert_eq!(json, r#"{"type":"distribute_windows","tags":7}"#);
+        let deserialized: Command = serde_json::from_str(&json).unwrap();
+        assert!(matches!(
+            deserialized,
+            Command::DistributeWindows { tags: 0b111 }
+        ));
+    }
+
+    #[test]
+    fn test_window_tags_serialization() {
+        let json = serde_json::to_string(&Command::WindowTags { id: 42 }).unwrap();
+        assert_eq!(json, r#"{"type":"window_tags","id":42}"#);
+
+        let json = serde_json::to_string(&Response::WindowTags {
+            id: 42,
+            tags: vec![1, 3],
+        })
+        .unwrap();
+        assert_eq!(json, r#"{"type":"window_tags","id":42,"tags":[1,3]}"#);
+        match serde_json::from_str(&json).unwrap() {
+            Response::WindowTags { id, tags } => {
+                assert_eq!(id, 42);
+                assert_eq!(tags, vec![1, 3]);
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_output_usable_frame_serialization() {
+        let json = serde_json::to_string(&Command::OutputUsableFrame { output: None }).unwrap();
+        assert_eq!(json, r#"{"type":"output_usable_frame","output":null}"#);
+        let cmd: Command =
+            serde_json::from_str(r#"{"type":"output_usable_frame","output":"DELL"}"#).unwrap();
+        assert!(matches!(
+            cmd,
+            Command::OutputUsableFrame {
+                output: Some(OutputSpecifier::Name(_))
+            }
+        ));
+
+        let json = serde_json::to_string(&Response::UsableFrame {
+            output: 1,
+            x: 10,
+            y: 35,
+            width: 1900,
+            height: 1035,
+        })
+        .unwrap();
+        assert_eq!(
+            json,
+            r#"{"type":"usable_frame","output":1,"x":10,"y":35,"width":1900,"height":1035}"#
+        );
+    }
+
+    #[test]
+    fn test_response_stats_serialization() {
+        let stats = StatsInfo {
+            last_retile_us: Some(1250),
+            last_retile_writes: 3,
+            last_retile_skipped: 1,
+            retile_count: 7,
+            events_processed: 40,
+            uptime_secs: 20,
+            events_per_sec: 2.0,
+        };
+        let json = serde_json::to_string(&Response::Stats {
+            stats: stats.clone(),
+        })
+        .unwrap();
+        assert!(json.contains("\"type\":\"stats\""));
+        assert!(json.contains("\"last_retile_us\":1250"));
+
+        let deserialized: Response = serde_json::from_str(&json).unwrap();
+        match deserialized {
+            Response::Stats { stats: decoded } => assert_eq!(decoded, stats),
+            _ => panic!("Expected Stats response"),
+        }
+    }
+
+    #[test]
+    fn test_command_set_app_events_paused_serialization() {
+        let cmd = Command::SetAppEventsPaused {
+            app_name: "Electron".to_string(),
+            paused: true,
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert_eq!(
+            json,
+            "{\"type\":\"set_app_events_paused\",\"app_name\":\"Electron\",\"paused\":true}"
+        );
+
+        let deserialized: Command = serde_json::from_str(&json).unwrap();
+        assert!(matches!(
+            deserialized,
+            Command::SetAppEventsPaused { app_name, paused: true } if app_name == "Electron"
+        ));
+    }
+
+    #[test]
+    fn test_response_tag_stats_serialization() {
+        let response = Response::TagStats {
+            tags: vec![TagStatsInfo {
+                tag: 2,
+                windows: 3,
+                visible: true,
+            }],
+        };
+        let json = serde_json::to_string(&response).unwrap();
+        assert_eq!(
+            json,
+            "{\"type\":\"tag_stats\",\"tags\":[{\"tag\":2,\"windows\":3,\"visible\":true}]}"
+        );
+
+        let deserialized: Response = serde_json::from_str(&json).unwrap();
+        assert!(matches!(deserialized, Response::TagStats { tags } if tags.len() == 1));
+    }
+
+    #[test]
+    fn test_response_unmanaged_windows_serialization() {
+        let json = serde_json::to_string(&Command::ListUnmanaged).unwrap();
+        assert_eq!(json, "{\"type\":\"list_unmanaged\"}");
+
+        let info = UnmanagedWindowInfo {
+            id: 42,
+            pid: 1000,
+            app_name: "Raycast".to_string(),
+            title: "Search".to_string(),
+            window_level: 8,
+            reason: UnmanagedReason::WindowLevel,
+        };
+        let json = serde_json::to_string(&info).unwrap();
+        assert_eq!(
+            json,
+            "{\"id\":42,\"pid\":1000,\"app_name\":\"Raycast\",\"title\":\"Search\",\"window_level\":8,\"reason\":\"window_level\"}"
+        );
+
+        let response = Response::UnmanagedWindows {
+            windows: vec![info.clone()],
+        };
+        let json = serde_json::to_string(&response).unwrap();
+        match serde_json::from_str(&json).unwrap() {
+            Response::UnmanagedWindows { windows } => assert_eq!(windows, vec![info]),
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_command_tag_view_with_layout_serialization() {
+        let cmd = Command::TagViewWithLayout {
+            tags: 4,
+            output: None,
+            layout: "tatami".to_string(),
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains("\"type\":\"tag_view_with_layout\""));
+        assert!(json.contains("\"layout\":\"tatami\""));
+
+        let deserialized: Command = serde_json::from_str(&json).unwrap();
+        match deserialized {
+            Command::TagViewWithLayout { tags, layout, .. } => {
+                assert_eq!(tags, 4);
+                assert_eq!(layout, "tatami");
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_command_main_ratio_animate_serialization() {
+        let cmd = Command::MainRatioAnimate {
+            target: 0.65,
+            duration_ms: 150,
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert_eq!(
+            json,
+            "{\"type\":\"main_ratio_animate\",\"target\":0.65,\"duration_ms\":150}"
+        );
+
+        let deserialized: Command = serde_json::from_str(&json).unwrap();
+        match deserialized {
+            Command::MainRatioAnimate {
+                target,
+                duration_ms,
+            } => {
+                assert_eq!(target, 0.65);
+                assert_eq!(duration_ms, 150);
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_command_set_default_layout_params_serialization() {
+        let cmd = Command::SetDefaultLayoutParams {
+            main_ratio: Some(0.6),
+            inner_gap: None,
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert_eq!(
+            json,
+            "{\"type\":\"set_default_layout_params\",\"main_ratio\":0.6,\"inner_gap\":null}"
+        );
+
+        let deserialized: Command =
+            serde_json::from_str("{\"type\":\"set_default_layout_params\",\"inner_gap\":8}")
+                .unwrap();
+        match deserialized {
+            Command::SetDefaultLayoutParams {
+                main_ratio,
+                inner_gap,
+            } => {
+                assert_eq!(main_ratio, None);
+                assert_eq!(inner_gap, Some(8));
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_resync_serialization() {
+        let json = serde_json::to_string(&Command::Resync).unwrap();
+        assert_eq!(json, "{\"type\":\"resync\"}");
+        assert!(matches!(
+            serde_json::from_str::<Command>(&json).unwrap(),
+            Command::Resync
+        ));
+
+        let json = serde_json::to_string(&Response::WindowCount { count: 3 }).unwrap();
+        assert_eq!(json, "{\"type\":\"window_count\",\"count\":3}");
+    }
+
+    #[test]
+    fn test_layout_alias_serialization() {
+        let cmd = Command::LayoutAlias {
+            alias: "myalias".to_string(),
+            target: "byobu".to_string(),
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert_eq!(
+            json,
+            r#"{"type":"layout_alias","alias":"myalias","target":"byobu"}"#
+        );
+        match serde_json::from_str(&json).unwrap() {
+            Command::LayoutAlias { alias, target } => {
+                assert_eq!(alias, "myalias");
+                assert_eq!(target, "byobu");
+            }
+            _ => panic!("Wrong variant"),
+        }
+
+        let resp = Response::Layouts {
+            layouts: vec![
+                LayoutInfo {
+                    name: "tatami".to_string(),
+                    alias_of: None,
+                    running: true,
+                },
+                LayoutInfo {
+                    name: "myalias".to_string(),
+                    alias_of: Some("byobu".to_string()),
+                    running: false,
+                },
+            ],
+        };
+        let json = serde_json::to_string(&resp).unwrap();
+        assert_eq!(
+            json,
+            r#"{"type":"layouts","layouts":[{"name":"tatami","running":true},{"name":"myalias","alias_of":"byobu","running":false}]}"#
+        );
+        match serde_json::from_str::<Response>(&json).unwrap() {
+            Response::Layouts { layouts } => {
+                assert_eq!(layouts[1].alias_of.as_deref(), Some("byobu"));
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_list_keys_serialization() {
+        let json = serde_json::to_string(&Command::ListKeys).unwrap();
+        assert_eq!(json, "{\"type\":\"list_keys\"}");
+        assert!(matches!(
+            serde_json::from_str::<Command>(&json).unwrap(),
+            Command::ListKeys
+        ));
+
+        let resp = Response::Keys {
+            keys: vec!["a".to_string(), "f1".to_string()],
+        };
+        let json = serde_json::to_string(&resp).unwrap();
+        assert_eq!(json, "{\"type\":\"keys\",\"keys\":[\"a\",\"f1\"]}");
+        match serde_json::from_str::<Response>(&json).unwrap() {
+            Response::Keys { keys } => assert_eq!(keys, vec!["a", "f1"]),
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_command_set_raise_on_focus_serialization() {
+        let json = serde_json::to_string(&Command::SetRaiseOnFocus { enabled: false }).unwrap();
+        assert_eq!(json, "{\"type\":\"set_raise_on_focus\",\"enabled\":false}");
+        assert!(matches!(
+            serde_json::from_str::<Command>(&json).unwrap(),
+            Command::SetRaiseOnFocus { enabled: false }
+        ));
+    }
+
+    #[test]
+    fn test_command_window_unminimize_serialization() {
+        let json = serde_json::to_string(&Command::WindowUnminimize { id: None }).unwrap();
+        assert_eq!(json, "{\"type\":\"window_unminimize\"}");
+        let json = serde_json::to_string(&Command::WindowUnminimize { id: Some(123) }).unwrap();
+        assert!(matches!(
+            serde_json::from_str::<Command>(&json).unwrap(),
+            Command::WindowUnminimize { id: Some(123) }
+        ));
+    }
+
+    #[test]
+    fn test_command_scratchpad_toggle_serialization() {
+        let cmd = Command::ScratchpadToggle {
+            app_name: "Terminal".to_string(),
+            command: None,
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert_eq!(
+            json,
+            "{\"type\":\"scratchpad_toggle\",\"app_name\":\"Terminal\"}"
+        );
+        assert!(matches!(
+            serde_json::from_str::<Command>(&json).unwrap(),
+            Command::ScratchpadToggle { app_name, command: None } if app_name == "Terminal"
+        ));
+    }
+
+    #[test]
+    fn test_command_set_drag_modifier_serialization() {
+        let cmd = Command::SetDragModifier {
+            modifier: Some("cmd-alt".to_string()),
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert_eq!(
+            json,
+            "{\"type\":\"set_drag_modifier\",\"modifier\":\"cmd-alt\"}"
+        );
+        assert!(matches!(
+            serde_json::from_str::<Command>(&json).unwrap(),
+            Command::SetDragModifier { modifier: Some(m) } if m == "cmd-alt"
+        ));
+
+        let json = serde_json::to_string(&Command::SetDragModifier { modifier: None }).unwrap();
+        assert!(matches!(
+            serde_json::from_str::<Command>(&json).unwrap(),
+            Command::SetDragModifier { modifier: None }
+        ));
+    }
+
+    #[test]
+    fn test_command_set_tick_rate_serialization() {
+        let json = serde_json::to_string(&Command::SetTickRate { ms: 8 }).unwrap();
+        assert_eq!(json, "{\"type\":\"set_tick_rate\",\"ms\":8}");
+        let deserialized: Command = serde_json::from_str(&json).unwrap();
+        assert!(matches!(deserialized, Command::SetTickRate { ms: 8 }));
+    }
+
+    #[test]
+    fn test_command_manage_window_serialization() {
+        let json = serde_json::to_string(&Command::ManageWindow { id: 42 }).unwrap();
+        assert_eq!(json, "{\"type\":\"manage_window\",\"id\":42}");
+        let deserialized: Command = serde_json::from_str(&json).unwrap();
+        assert!(matches!(deserialized, Command::ManageWindow { id: 42 }));
+
+        let json = serde_json::to_string(&Command::UnmanageWindow { id: 7 }).unwrap();
+        assert_eq!(json, "{\"type\":\"unmanage_window\",\"id\":7}");
+        let deserialized: Command = serde_json::from_str(&json).unwrap();
+        assert!(matches!(deserialized, Command::UnmanageWindow { id: 7 }));
+    }
+
+    #[test]
+    fn test_command_set_main_serialization() {
+        let json = serde_json::to_string(&Command::SetMain { id: 42 }).unwrap();
+        assert_eq!(json, "{\"type\":\"set_main\",\"id\":42}");
+
+        let deserialized: Command = serde_json::from_str(&json).unwrap();
+        assert!(matches!(deserialized, Command::SetMain { id: 42 }));
+    }
+
+    #[test]
+    fn test_command_sticky_main_serialization() {
+        let json = serde_json::to_string(&Command::SetStickyMain { id: 42 }).unwrap();
+        assert_eq!(json, "{\"type\":\"set_sticky_main\",\"id\":42}");
+        let deserialized: Command = serde_json::from_str(&json).unwrap();
+        assert!(matches!(deserialized, Command::SetStickyMain { id: 42 }));
+
+        let json = serde_json::to_string(&Command::ClearStickyMain).unwrap();
+        assert_eq!(json, "{\"type\":\"clear_sticky_main\"}");
+        let deserialized: Command = serde_json::from_str(&json).unwrap();
+        assert!(matches!(deserialized, Command::ClearStickyMain));
+    }
+
+    #[test]
+    fn test_command_resync_window_serialization() {
+        let json = serde_json::to_string(&Command::ResyncWindow { id: 7 }).unwrap();
+        assert_eq!(json, "{\"type\":\"resync_window\",\"id\":7}");
+
+        let deserialized: Command = serde_json::from_str(&json).unwrap();
+        assert!(matches!(deserialized, Command::ResyncWindow { id: 7 }));
+    }
+
+    #[test]
+    fn test_response_window_geometry_serialization() {
+        let resp = Response::WindowGeometry {
+            geometry: WindowGeometry {
+                id: 42,
+                x: -100,
+                y: 25,
+                width: 800,
+                height: 600,
+            },
+        };
+        let json = serde_json::to_string(&resp).unwrap();
+        assert!(json.contains("\"type\":\"window_geometry\""));
+        assert!(json.contains("\"x\":-100"));
+
+        let deserialized: Response = serde_json::from_str(&json).unwrap();
+        match deserialized {
+            Response::WindowGeometry { geometry } => {
+                assert_eq!(geometry.id, 42);
+                assert_eq!(geometry.width, 800);
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_main_ratio_serialization() {
+        let json = serde_json::to_string(&Command::MainRatioGet {
+            output: None,
+            tags: Some(2),
+        })
+        .unwrap();
+        assert!(json.contains("\"type\":\"main_ratio_get\""));
+        assert!(json.contains("\"tags\":2"));
+
+        let json = serde_json::to_string(&Response::MainRatio { ratio: 0.55 }).unwrap();
+        assert_eq!(json, "{\"type\":\"main_ratio\",\"ratio\":0.55}");
+    }
+
+    #[test]
+    fn test_layout_engine_log_serialization() {
+        let cmd: Command =
+            serde_json::from_str(r#"{"type":"layout_engine_log","name":"byobu"}"#).unwrap();
+        match cmd {
+            Command::LayoutEngineLog { name, lines } => {
+                assert_eq!(name, "byobu");
+                assert_eq!(lines, None);
+            }
+            _ => panic!("Wrong variant"),
+        }
+
+        let resp = Response::Log {
+            lines: vec!["first".to_string(), "second".to_string()],
+        };
+        let json = serde_json::to_string(&resp).unwrap();
+        match serde_json::from_str(&json).unwrap() {
+            Response::Log { lines } => assert_eq!(lines, vec!["first", "second"]),
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_layout_describe_serialization() {
+        let cmd: Command =
+            serde_json::from_str(r#"{"type":"layout_describe","name":"grid"}"#).unwrap();
+        match cmd {
+            Command::LayoutDescribe { name } => assert_eq!(name, "grid"),
+            _ => panic!("Wrong variant"),
+        }
+
+        let resp = Response::LayoutCapabilities {
+            name: "grid".to_string(),
+            commands: vec!["set-inner-gap".to_string()],
+        };
+        let json = serde_json::to_string(&resp).unwrap();
+        assert!(json.contains(r#""type":"layout_capabilities""#));
+        match serde_json::from_str(&json).unwrap() {
+            Response::LayoutCapabilities { name, commands } => {
+                assert_eq!(name, "grid");
+                assert_eq!(commands, vec!["set-inner-gap"]);
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_layout_last_input_serialization() {
+        let cmd: Command =
+            serde_json::from_str(r#"{"type":"layout_last_input","name":"byobu"}"#).unwrap();
+        match cmd {
+            Command::LayoutLastInput { name } => assert_eq!(name, "byobu"),
+            _ => panic!("Wrong variant"),
+        }
+
+        let resp = Response::LayoutInput {
+            width: 1920,
+            height: 1080,
+            windows: vec![101, 100],
+        };
+        let json = serde_json::to_string(&resp).unwrap();
+        assert!(json.contains("\"type\":\"layout_input\""));
+        match serde_json::from_str(&json).unwrap() {
+            Response::LayoutInput {
+                width,
+                height,
+                windows,
+            } => {
+                assert_eq!((width, height), (1920, 1080));
+                assert_eq!(windows, vec![101, 100]);
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
     #[test]
     fn test_response_exec_path_serialization() {
         let resp = Response::ExecPath {
@@ -1703,6 +3211,43 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_command_reserve_edge_serialization() {
+        let cmd = Command::ReserveEdge {
+            output: None,
+            edge: Edge::Top,
+            size: 30,
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert_eq!(
+            json,
+            r#"{"type":"reserve_edge","output":null,"edge":"top","size":30}"#
+        );
+
+        let cmd: Command =
+            serde_json::from_str(r#"{"type":"reserve_edge","edge":"left","size":40}"#).unwrap();
+        match cmd {
+            Command::ReserveEdge { output, edge, size } => {
+                assert_eq!(output, None);
+                assert_eq!(edge, Edge::Left);
+                assert_eq!(size, 40);
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_command_clear_reservations_serialization() {
+        let cmd: Command = serde_json::from_str(r#"{"type":"clear_reservations"}"#).unwrap();
+        assert!(matches!(cmd, Command::ClearReservations { output: None }));
+
+        let json = serde_json::to_string(&Command::ClearReservations {
+            output: Some(OutputSpecifier::Id(2)),
+        })
+        .unwrap();
+        assert_eq!(json, r#"{"type":"clear_reservations","output":2}"#);
+    }
+
     #[test]
     fn test_command_set_outer_gap_serialization() {
         let cmd = Command::SetOuterGap {
@@ -1734,6 +3279,57 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_response_config_serialization() {
+        let resp = Response::Config {
+            config: ConfigInfo {
+                default_layout: "tatami".to_string(),
+                default_tag: 1,
+                tag_layouts: vec![TagLayoutInfo {
+                    tag: 3,
+                    layout: "byobu".to_string(),
+                }],
+                exec_path: "/usr/local/bin".to_string(),
+                cursor_warp: CursorWarpMode::OnFocusChange,
+                auto_raise: AutoRaiseMode::Enabled,
+                auto_raise_delay_ms: 150,
+                outer_gap: OuterGap::all(8),
+                gaps_multi_monitor_only: true,
+                exclusive_tags: true,
+                tick_rate_ms: 16,
+                layout_defaults: LayoutDefaultsInfo {
+                    main_ratio: Some(0.6),
+                    inner_gap: None,
+                },
+                raise_on_focus: false,
+                drag_modifier: Some("cmd-alt".to_string()),
+            },
+        };
+        let json = serde_json::to_string(&resp).unwrap();
+        assert!(json.contains("\"type\":\"config\""));
+        assert!(json.contains("\"tag_layouts\":[{\"tag\":3,\"layout\":\"byobu\"}]"));
+        assert!(json.contains("\"cursor_warp\":\"on_focus_change\""));
+
+        let deserialized: Response = serde_json::from_str(&json).unwrap();
+        match deserialized {
+            Response::Config { config } => {
+                assert_eq!(config.default_layout, "tatami");
+                assert_eq!(config.tag_layouts[0].tag, 3);
+                assert_eq!(config.tag_layouts[0].layout, "byobu");
+                assert_eq!(config.auto_raise, AutoRaiseMode::Enabled);
+                assert_eq!(config.auto_raise_delay_ms, 150);
+                assert_eq!(config.outer_gap, OuterGap::all(8));
+                assert!(config.gaps_multi_monitor_only);
+                assert!(config.exclusive_tags);
+                assert_eq!(config.tick_rate_ms, 16);
+                assert_eq!(config.layout_defaults.main_ratio, Some(0.6));
+                assert_eq!(config.layout_defaults.inner_gap, None);
+                assert_eq!(config.drag_modifier.as_deref(), Some("cmd-alt"));
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
     #[test]
     fn test_command_get_outer_gap_serialization() {
         let cmd = Command::GetOuterGap;
@@ -1744,6 +3340,22 @@ mod tests {
         assert!(matches!(deserialized, Command::GetOuterGap));
     }
 
+    #[test]
+    fn test_command_set_gaps_multi_monitor_only_serialization() {
+        let cmd = Command::SetGapsMultiMonitorOnly { enabled: true };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert_eq!(
+            json,
+            "{\"type\":\"set_gaps_multi_monitor_only\",\"enabled\":true}"
+        );
+
+        let deserialized: Command = serde_json::from_str(&json).unwrap();
+        assert!(matches!(
+            deserialized,
+            Command::SetGapsMultiMonitorOnly { enabled: true }
+        ));
+    }
+
     #[test]
     fn test_response_outer_gap_serialization() {
         let resp = Response::OuterGap {
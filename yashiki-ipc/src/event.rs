@@ -123,8 +123,8 @@ pub enum StateEvent {
     // Tag events
     TagsChanged {
         display_id: u32,
-        visible_tags: u32,
-        previous_tags: u32,
+        visible_tags: u64,
+        previous_tags: u64,
     },
 
     // Layout events
@@ -333,6 +333,7 @@ mod tests {
                 height: 1080,
                 is_main: true,
                 visible_tags: 1,
+                visible_tag_names: vec![],
                 is_focused: true,
             },
         };
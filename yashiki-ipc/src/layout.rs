@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::OuterGap;
+
 /// Message from yashiki to layout engine
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -12,6 +14,10 @@ pub enum LayoutMessage {
     },
     /// Send command to layout engine
     Command { cmd: String, args: Vec<String> },
+    /// Ask the engine to echo back the most recent `Layout` message it processed
+    LastInput,
+    /// Ask the engine for its canonical name and the commands it accepts
+    Describe,
 }
 
 /// Response from layout engine to yashiki
@@ -24,6 +30,16 @@ pub enum LayoutResult {
     Ok,
     /// Command succeeded and requests retile
     NeedsRetile,
+    /// Query command succeeded and returned a value
+    Value { value: String },
+    /// Echo of the most recent `Layout` message
+    LastInput {
+        width: u32,
+        height: u32,
+        windows: Vec<u32>,
+    },
+    /// Reply to `Describe`
+    Capabilities { name: String, commands: Vec<String> },
     /// Error occurred
     Error { message: String },
 }
@@ -37,10 +53,141 @@ pub struct WindowGeometry {
     pub height: u32,
 }
 
+/// Edge gap commands understood by `handle_edge_gap_command`, for engines to list in
+/// their Describe reply.
+pub const EDGE_GAP_COMMANDS: &[&str] = &[
+    "set-outer-gap",
+    "set-gap-top",
+    "set-gap-bottom",
+    "set-gap-left",
+    "set-gap-right",
+    "get-outer-gap",
+];
+
+/// Smallest size engines keep for a tiled area they size themselves (e.g. tatami's
+/// stack next to an absolute main width), unless the output itself is smaller.
+pub const MIN_TILING_SIZE: u32 = 100;
+
+/// Handle one of `EDGE_GAP_COMMANDS` for an engine's edge gaps.
+/// Returns None if `cmd` is not an edge gap command.
+pub fn handle_edge_gap_command(
+    gap: &mut OuterGap,
+    cmd: &str,
+    args: &[String],
+) -> Option<LayoutResult> {
+    if cmd == "get-outer-gap" {
+        return Some(LayoutResult::Value {
+            // top bottom left right
+            value: format!("{} {} {} {}", gap.top, gap.bottom, gap.left, gap.right),
+        });
+    }
+    if !EDGE_GAP_COMMANDS.contains(&cmd) {
+        return None;
+    }
+    let Some(value) = args.first().and_then(|s| s.parse::<u32>().ok()) else {
+        return Some(LayoutResult::Error {
+            message: "invalid gap value".to_string(),
+        });
+    };
+    match cmd {
+        "set-gap-top" => gap.top = value,
+        "set-gap-bottom" => gap.bottom = value,
+        "set-gap-left" => gap.left = value,
+        "set-gap-right" => gap.right = value,
+        _ => *gap = OuterGap::all(value),
+    }
+    Some(LayoutResult::Ok)
+}
+
+/// Run `tile` on the area inside the edge gaps and shift its windows into place.
+/// Gaps larger than the output leave an empty area rather than wrapping.
+pub fn layout_inside_gap(
+    gap: &OuterGap,
+    width: u32,
+    height: u32,
+    tile: impl FnOnce(u32, u32) -> Vec<WindowGeometry>,
+) -> Vec<WindowGeometry> {
+    let (left, inner_width) = fit_gaps(width, gap.left, gap.right);
+    let (top, inner_height) = fit_gaps(height, gap.top, gap.bottom);
+    let mut windows = tile(inner_width, inner_height);
+    for window in &mut windows {
+        window.x = window.x.saturating_add(left as i32);
+        window.y = window.y.saturating_add(top as i32);
+    }
+    windows
+}
+
+/// Leading gap and remaining size for a span with gaps `before` and `after`.
+fn fit_gaps(size: u32, before: u32, after: u32) -> (u32, u32) {
+    (before, size.saturating_sub(before).saturating_sub(after))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_edge_gap_commands() {
+        let mut gap = OuterGap::default();
+        let result = handle_edge_gap_command(&mut gap, "set-outer-gap", &["10".to_string()]);
+        assert!(matches!(result, Some(LayoutResult::Ok)));
+        assert_eq!(gap, OuterGap::all(10));
+
+        handle_edge_gap_command(&mut gap, "set-gap-left", &["30".to_string()]);
+        match handle_edge_gap_command(&mut gap, "get-outer-gap", &[]) {
+            Some(LayoutResult::Value { value }) => assert_eq!(value, "10 10 30 10"),
+            _ => panic!("Expected Value"),
+        }
+
+        let result = handle_edge_gap_command(&mut gap, "set-gap-top", &["wide".to_string()]);
+        assert!(matches!(result, Some(LayoutResult::Error { .. })));
+        assert!(handle_edge_gap_command(&mut gap, "set-inner-gap", &[]).is_none());
+    }
+
+    #[test]
+    fn test_layout_inside_gap_insets_and_offsets() {
+        let gap = OuterGap {
+            top: 40,
+            right: 10,
+            bottom: 0,
+            left: 20,
+        };
+        let windows = layout_inside_gap(&gap, 1920, 1080, |width, height| {
+            assert_eq!((width, height), (1890, 1040));
+            vec![WindowGeometry {
+                id: 1,
+                x: 0,
+                y: 0,
+                width,
+                height,
+            }]
+        });
+        assert_eq!((windows[0].x, windows[0].y), (20, 40));
+    }
+
+    #[test]
+    fn test_layout_inside_gap_saturates_oversized_gaps() {
+        let gap = OuterGap {
+            top: 0,
+            right: 1500,
+            bottom: u32::MAX,
+            left: 1500,
+        };
+        let windows = layout_inside_gap(&gap, 1920, 1080, |width, height| {
+            assert_eq!((width, height), (0, 0));
+            vec![WindowGeometry {
+                id: 1,
+                x: 0,
+                y: 0,
+                width,
+                height,
+            }]
+        });
+        let w = &windows[0];
+        assert_eq!((w.x, w.y), (1500, 0));
+        assert_eq!((w.width, w.height), (0, 0));
+    }
+
     #[test]
     fn test_layout_message_layout_serialization() {
         let msg = LayoutMessage::Layout {
@@ -132,6 +279,21 @@ mod tests {
         matches!(deserialized, LayoutResult::Ok);
     }
 
+    #[test]
+    fn test_layout_result_value_serialization() {
+        let result = LayoutResult::Value {
+            value: "8".to_string(),
+        };
+        let json = serde_json::to_string(&result).unwrap();
+        assert_eq!(json, "{\"type\":\"value\",\"value\":\"8\"}");
+
+        let deserialized: LayoutResult = serde_json::from_str(&json).unwrap();
+        match deserialized {
+            LayoutResult::Value { value } => assert_eq!(value, "8"),
+            _ => panic!("Wrong variant"),
+        }
+    }
+
     #[test]
     fn test_layout_result_error_serialization() {
         let result = LayoutResult::Error {
@@ -146,6 +308,65 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_layout_message_last_input_serialization() {
+        let json = serde_json::to_string(&LayoutMessage::LastInput).unwrap();
+        assert_eq!(json, "{\"type\":\"last_input\"}");
+
+        let deserialized: LayoutMessage = serde_json::from_str(&json).unwrap();
+        assert!(matches!(deserialized, LayoutMessage::LastInput));
+    }
+
+    #[test]
+    fn test_layout_result_last_input_serialization() {
+        let result = LayoutResult::LastInput {
+            width: 1920,
+            height: 1080,
+            windows: vec![3, 1, 2],
+        };
+        let json = serde_json::to_string(&result).unwrap();
+        assert!(json.contains("\"type\":\"last_input\""));
+        assert!(json.contains("\"windows\":[3,1,2]"));
+
+        let deserialized: LayoutResult = serde_json::from_str(&json).unwrap();
+        match deserialized {
+            LayoutResult::LastInput {
+                width,
+                height,
+                windows,
+            } => {
+                assert_eq!((width, height), (1920, 1080));
+                assert_eq!(windows, vec![3, 1, 2]);
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn test_describe_and_capabilities_serialization() {
+        let json = serde_json::to_string(&LayoutMessage::Describe).unwrap();
+        assert_eq!(json, r#"{"type":"describe"}"#);
+        let deserialized: LayoutMessage = serde_json::from_str(&json).unwrap();
+        assert!(matches!(deserialized, LayoutMessage::Describe));
+
+        let result = LayoutResult::Capabilities {
+            name: "tatami".to_string(),
+            commands: vec!["zoom".to_string(), "set-main-ratio".to_string()],
+        };
+        let json = serde_json::to_string(&result).unwrap();
+        assert_eq!(
+            json,
+            r#"{"type":"capabilities","name":"tatami","commands":["zoom","set-main-ratio"]}"#
+        );
+        match serde_json::from_str(&json).unwrap() {
+            LayoutResult::Capabilities { name, commands } => {
+                assert_eq!(name, "tatami");
+                assert_eq!(commands, vec!["zoom", "set-main-ratio"]);
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
     #[test]
     fn test_window_geometry_equality() {
         let g1 = WindowGeometry {
@@ -2,12 +2,16 @@ pub mod command;
 pub mod event;
 pub mod layout;
 pub mod outer_gap;
+#[cfg(feature = "testing")]
+pub mod testing;
 
 pub use command::{
-    AutoRaiseMode, BindingInfo, ButtonInfo, ButtonState, Command, CursorWarpMode, Direction,
-    ExtendedWindowAttributes, GlobPattern, OutputDirection, OutputInfo, OutputSpecifier, Response,
-    RuleAction, RuleInfo, RuleMatcher, StateInfo, WindowInfo, WindowLevel, WindowLevelName,
-    WindowLevelOther, WindowRule, WindowStatus,
+    AutoRaiseMode, BindingInfo, ButtonInfo, ButtonState, Command, ConfigInfo, CursorWarpMode,
+    Direction, Edge, ExtendedWindowAttributes, GlobPattern, LayoutDefaultsInfo, LayoutInfo,
+    OutputDirection, OutputInfo, OutputSpecifier, Response, RuleAction, RuleInfo, RuleMatcher,
+    SizeSelection, StateInfo, StatsInfo, TagLayoutInfo, TagStatsInfo, UnmanagedReason,
+    UnmanagedWindowInfo, WindowInfo, WindowLevel, WindowLevelName, WindowLevelOther, WindowRule,
+    WindowStatus,
 };
 pub use event::{EventFilter, StateEvent, SubscribeRequest};
 pub use layout::{LayoutMessage, LayoutResult, WindowGeometry};
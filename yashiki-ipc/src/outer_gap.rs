@@ -52,11 +52,11 @@ impl OuterGap {
     }
 
     pub fn horizontal(&self) -> u32 {
-        self.left + self.right
+        self.left.saturating_add(self.right)
     }
 
     pub fn vertical(&self) -> u32 {
-        self.top + self.bottom
+        self.top.saturating_add(self.bottom)
     }
 }
 
@@ -0,0 +1,56 @@
+//! Assertions shared by the layout engines' unit tests.
+
+use crate::layout::{LayoutMessage, LayoutResult, WindowGeometry};
+
+/// (id, x, y, width, height) of each window, for compact comparisons.
+pub fn frames(windows: &[WindowGeometry]) -> Vec<(u32, i32, i32, u32, u32)> {
+    windows
+        .iter()
+        .map(|w| (w.id, w.x, w.y, w.width, w.height))
+        .collect()
+}
+
+/// Windows must not overlap and together cover exactly width x height.
+pub fn assert_covers(windows: &[WindowGeometry], width: u32, height: u32) {
+    let area: u64 = windows
+        .iter()
+        .map(|w| w.width as u64 * w.height as u64)
+        .sum();
+    assert_eq!(area, width as u64 * height as u64);
+
+    for (i, a) in windows.iter().enumerate() {
+        assert!(a.x >= 0 && a.y >= 0);
+        assert!(a.x as u32 + a.width <= width);
+        assert!(a.y as u32 + a.height <= height);
+        for b in &windows[i + 1..] {
+            let overlap_x = a.x < b.x + b.width as i32 && b.x < a.x + a.width as i32;
+            let overlap_y = a.y < b.y + b.height as i32 && b.y < a.y + a.height as i32;
+            assert!(!(overlap_x && overlap_y), "{} overlaps {}", a.id, b.id);
+        }
+    }
+}
+
+/// The engine must describe itself as `name`, and every command it advertises
+/// must be known to it (it may still reject the missing arguments).
+pub fn assert_describes(name: &str, mut handle_message: impl FnMut(LayoutMessage) -> LayoutResult) {
+    let commands = match handle_message(LayoutMessage::Describe) {
+        LayoutResult::Capabilities {
+            name: described,
+            commands,
+        } => {
+            assert_eq!(described, name);
+            commands
+        }
+        _ => panic!("Expected Capabilities"),
+    };
+
+    for cmd in commands {
+        let result = handle_message(LayoutMessage::Command {
+            cmd,
+            args: Vec::new(),
+        });
+        if let LayoutResult::Error { message } = result {
+            assert!(!message.starts_with("unknown command"), "{}", message);
+        }
+    }
+}
@@ -2,7 +2,23 @@ use std::io::{self, BufRead, Write};
 
 use anyhow::Result;
 
-use yashiki_ipc::layout::{LayoutMessage, LayoutResult, WindowGeometry};
+use yashiki_ipc::layout::{
+    handle_edge_gap_command, layout_inside_gap, LayoutMessage, LayoutResult, WindowGeometry,
+    EDGE_GAP_COMMANDS,
+};
+use yashiki_ipc::OuterGap;
+
+/// Commands accepted by `handle_command`, reported in reply to Describe
+const COMMANDS: &[&str] = &[
+    "set-padding",
+    "inc-padding",
+    "dec-padding",
+    "set-orientation",
+    "toggle-orientation",
+    "get-inner-gap",
+    "reset",
+    "focus-changed",
+];
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum Orientation {
@@ -14,6 +30,10 @@ struct LayoutState {
     padding: u32,
     orientation: Orientation,
     focused_window_id: Option<u32>,
+    /// Insets of the whole tiling area from each output edge
+    outer_gap: OuterGap,
+    /// Most recent Layout message (width, height, window ids), echoed by LastInput
+    last_input: Option<(u32, u32, Vec<u32>)>,
 }
 
 impl Default for LayoutState {
@@ -22,6 +42,8 @@ impl Default for LayoutState {
             padding: 30,
             orientation: Orientation::Horizontal,
             focused_window_id: None,
+            outer_gap: OuterGap::default(),
+            last_input: None,
         }
     }
 }
@@ -51,11 +73,30 @@ fn handle_message(state: &mut LayoutState, msg: LayoutMessage) -> LayoutResult {
             windows,
         } => {
             let geometries = generate_layout(state, width, height, &windows);
+            state.last_input = Some((width, height, windows));
             LayoutResult::Layout {
                 windows: geometries,
             }
         }
         LayoutMessage::Command { cmd, args } => handle_command(state, &cmd, &args),
+        LayoutMessage::LastInput => match &state.last_input {
+            Some((width, height, windows)) => LayoutResult::LastInput {
+                width: *width,
+                height: *height,
+                windows: windows.clone(),
+            },
+            None => LayoutResult::Error {
+                message: "no layout received yet".to_string(),
+            },
+        },
+        LayoutMessage::Describe => LayoutResult::Capabilities {
+            name: "byobu".to_string(),
+            commands: COMMANDS
+                .iter()
+                .chain(EDGE_GAP_COMMANDS)
+                .map(|c| c.to_string())
+                .collect(),
+        },
     }
 }
 
@@ -111,6 +152,18 @@ fn handle_command(state: &mut LayoutState, cmd: &str, args: &[String]) -> Layout
             };
             LayoutResult::Ok
         }
+        // Windows overlap by design, so there is never a gap between them
+        "get-inner-gap" => LayoutResult::Value {
+            value: "0".to_string(),
+        },
+        "reset" => {
+            *state = LayoutState {
+                focused_window_id: state.focused_window_id,
+                last_input: state.last_input.take(),
+                ..Default::default()
+            };
+            LayoutResult::Ok
+        }
         "focus-changed" => {
             if let Some(id) = args.first().and_then(|s| s.parse::<u32>().ok()) {
                 state.focused_window_id = Some(id);
@@ -121,9 +174,11 @@ fn handle_command(state: &mut LayoutState, cmd: &str, args: &[String]) -> Layout
                 }
             }
         }
-        _ => LayoutResult::Error {
-            message: format!("unknown command: {}", cmd),
-        },
+        _ => handle_edge_gap_command(&mut state.outer_gap, cmd, args).unwrap_or_else(|| {
+            LayoutResult::Error {
+                message: format!("unknown command: {}", cmd),
+            }
+        }),
     }
 }
 
@@ -132,6 +187,17 @@ fn generate_layout(
     width: u32,
     height: u32,
     window_ids: &[u32],
+) -> Vec<WindowGeometry> {
+    layout_inside_gap(&state.outer_gap, width, height, |width, height| {
+        tile_windows(state, width, height, window_ids)
+    })
+}
+
+fn tile_windows(
+    state: &LayoutState,
+    width: u32,
+    height: u32,
+    window_ids: &[u32],
 ) -> Vec<WindowGeometry> {
     if window_ids.is_empty() {
         return vec![];
@@ -203,6 +269,7 @@ fn generate_layout(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use yashiki_ipc::testing::assert_describes;
 
     #[test]
     fn test_single_window() {
@@ -330,6 +397,50 @@ mod tests {
         assert_eq!(windows[4].width, 1920 - 120);
     }
 
+    #[test]
+    fn test_outer_gap_insets_single_window() {
+        let mut state = LayoutState::default();
+        let result = handle_command(&mut state, "set-outer-gap", &["10".to_string()]);
+        assert!(matches!(result, LayoutResult::Ok));
+
+        let windows = generate_layout(&state, 1920, 1080, &[1]);
+        assert_eq!(windows[0].x, 10);
+        assert_eq!(windows[0].y, 10);
+        assert_eq!(windows[0].width, 1900);
+        assert_eq!(windows[0].height, 1060);
+    }
+
+    #[test]
+    fn test_outer_gap_with_padding() {
+        let state = LayoutState {
+            outer_gap: OuterGap::all(10),
+            ..Default::default()
+        };
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2]);
+
+        // Usable width = 1900, minus one 30px tab offset
+        assert_eq!(windows[0].x, 10);
+        assert_eq!(windows[1].x, 40);
+        for window in &windows {
+            assert_eq!(window.y, 10);
+            assert_eq!(window.width, 1870);
+            assert_eq!(window.height, 1060);
+        }
+    }
+
+    #[test]
+    fn test_top_gap_only() {
+        let mut state = LayoutState::default();
+        let result = handle_command(&mut state, "set-gap-top", &["40".to_string()]);
+        assert!(matches!(result, LayoutResult::Ok));
+
+        let windows = generate_layout(&state, 1920, 1080, &[1]);
+        assert_eq!(windows[0].x, 0);
+        assert_eq!(windows[0].y, 40);
+        assert_eq!(windows[0].width, 1920);
+        assert_eq!(windows[0].height, 1080 - 40);
+    }
+
     #[test]
     fn test_focus_changed_command() {
         let mut state = LayoutState::default();
@@ -346,6 +457,13 @@ mod tests {
         assert_eq!(state.padding, 50);
     }
 
+    #[test]
+    fn test_get_inner_gap_is_zero() {
+        let mut state = LayoutState::default();
+        let result = handle_command(&mut state, "get-inner-gap", &[]);
+        assert!(matches!(result, LayoutResult::Value { value } if value == "0"));
+    }
+
     #[test]
     fn test_toggle_orientation_command() {
         let mut state = LayoutState::default();
@@ -357,4 +475,75 @@ mod tests {
         handle_command(&mut state, "toggle-orientation", &[]);
         assert_eq!(state.orientation, Orientation::Horizontal);
     }
+
+    #[test]
+    fn test_reset_command_restores_defaults() {
+        let mut state = LayoutState::default();
+        handle_command(&mut state, "set-padding", &["50".to_string()]);
+        handle_command(&mut state, "toggle-orientation", &[]);
+        handle_command(&mut state, "focus-changed", &["3".to_string()]);
+
+        let result = handle_command(&mut state, "reset", &[]);
+        assert!(matches!(result, LayoutResult::Ok));
+        assert_eq!(state.padding, 30);
+        assert_eq!(state.orientation, Orientation::Horizontal);
+        assert_eq!(state.focused_window_id, Some(3));
+    }
+
+    #[test]
+    fn test_last_input_echoes_layout_message() {
+        let mut state = LayoutState::default();
+        handle_message(
+            &mut state,
+            LayoutMessage::Layout {
+                width: 1920,
+                height: 1080,
+                windows: vec![3, 1, 2],
+            },
+        );
+
+        match handle_message(&mut state, LayoutMessage::LastInput) {
+            LayoutResult::LastInput {
+                width,
+                height,
+                windows,
+            } => {
+                assert_eq!((width, height), (1920, 1080));
+                assert_eq!(windows, vec![3, 1, 2]);
+            }
+            _ => panic!("Expected LastInput"),
+        }
+    }
+
+    #[test]
+    fn test_last_input_before_any_layout_is_error() {
+        let mut state = LayoutState::default();
+        let result = handle_message(&mut state, LayoutMessage::LastInput);
+        assert!(matches!(result, LayoutResult::Error { .. }));
+    }
+
+    #[test]
+    fn test_last_input_survives_reset() {
+        let mut state = LayoutState::default();
+        handle_message(
+            &mut state,
+            LayoutMessage::Layout {
+                width: 800,
+                height: 600,
+                windows: vec![7, 8],
+            },
+        );
+        handle_command(&mut state, "reset", &[]);
+
+        match handle_message(&mut state, LayoutMessage::LastInput) {
+            LayoutResult::LastInput { windows, .. } => assert_eq!(windows, vec![7, 8]),
+            _ => panic!("Expected LastInput"),
+        }
+    }
+
+    #[test]
+    fn test_describe_lists_accepted_commands() {
+        let mut state = LayoutState::default();
+        assert_describes("byobu", |msg| handle_message(&mut state, msg));
+    }
 }
@@ -0,0 +1,489 @@
+use std::io::{self, BufRead, Write};
+
+use anyhow::Result;
+
+use yashiki_ipc::layout::{
+    handle_edge_gap_command, layout_inside_gap, LayoutMessage, LayoutResult, WindowGeometry,
+    EDGE_GAP_COMMANDS,
+};
+use yashiki_ipc::OuterGap;
+
+/// Commands accepted by `handle_command`, reported in reply to Describe
+const COMMANDS: &[&str] = &[
+    "set-main-ratio",
+    "get-main-ratio",
+    "inc-main-ratio",
+    "dec-main-ratio",
+    "set-main-count",
+    "set-inner-gap",
+    "get-inner-gap",
+    "reset",
+    "focus-changed",
+];
+
+struct LayoutState {
+    main_count: u32,
+    main_ratio: f64,
+    inner_gap: u32,
+    /// Insets of the whole tiling area from each output edge
+    outer_gap: OuterGap,
+    /// Most recent Layout message (width, height, window ids), echoed by LastInput
+    last_input: Option<(u32, u32, Vec<u32>)>,
+}
+
+impl Default for LayoutState {
+    fn default() -> Self {
+        Self {
+            main_count: 1,
+            main_ratio: 0.5,
+            inner_gap: 0,
+            outer_gap: OuterGap::default(),
+            last_input: None,
+        }
+    }
+}
+
+fn main() -> Result<()> {
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+    let mut state = LayoutState::default();
+
+    for line in stdin.lock().lines() {
+        let line = line?;
+        let msg: LayoutMessage = serde_json::from_str(&line)?;
+        let result = handle_message(&mut state, msg);
+        serde_json::to_writer(&mut stdout, &result)?;
+        writeln!(stdout)?;
+        stdout.flush()?;
+    }
+
+    Ok(())
+}
+
+fn handle_message(state: &mut LayoutState, msg: LayoutMessage) -> LayoutResult {
+    match msg {
+        LayoutMessage::Layout {
+            width,
+            height,
+            windows,
+        } => {
+            let geometries = generate_layout(state, width, height, &windows);
+            state.last_input = Some((width, height, windows));
+            LayoutResult::Layout {
+                windows: geometries,
+            }
+        }
+        LayoutMessage::Command { cmd, args } => handle_command(state, &cmd, &args),
+        LayoutMessage::LastInput => match &state.last_input {
+            Some((width, height, windows)) => LayoutResult::LastInput {
+                width: *width,
+                height: *height,
+                windows: windows.clone(),
+            },
+            None => LayoutResult::Error {
+                message: "no layout received yet".to_string(),
+            },
+        },
+        LayoutMessage::Describe => LayoutResult::Capabilities {
+            name: "centered".to_string(),
+            commands: COMMANDS
+                .iter()
+                .chain(EDGE_GAP_COMMANDS)
+                .map(|c| c.to_string())
+                .collect(),
+        },
+    }
+}
+
+fn handle_command(state: &mut LayoutState, cmd: &str, args: &[String]) -> LayoutResult {
+    match cmd {
+        "set-main-ratio" => {
+            if let Some(ratio) = args.first().and_then(|s| s.parse::<f64>().ok()) {
+                if (0.1..=0.9).contains(&ratio) {
+                    state.main_ratio = ratio;
+                    return LayoutResult::Ok;
+                }
+            }
+            LayoutResult::Error {
+                message: "invalid ratio (must be 0.1-0.9)".to_string(),
+            }
+        }
+        "get-main-ratio" => LayoutResult::Value {
+            value: state.main_ratio.to_string(),
+        },
+        "inc-main-ratio" => {
+            let delta = args
+                .first()
+                .and_then(|s| s.parse::<f64>().ok())
+                .unwrap_or(0.05);
+            state.main_ratio = (state.main_ratio + delta).min(0.9);
+            LayoutResult::Ok
+        }
+        "dec-main-ratio" => {
+            let delta = args
+                .first()
+                .and_then(|s| s.parse::<f64>().ok())
+                .unwrap_or(0.05);
+            state.main_ratio = (state.main_ratio - delta).max(0.1);
+            LayoutResult::Ok
+        }
+        "set-main-count" => {
+            if let Some(count) = args.first().and_then(|s| s.parse::<u32>().ok()) {
+                if count >= 1 {
+                    state.main_count = count;
+                    return LayoutResult::Ok;
+                }
+            }
+            LayoutResult::Error {
+                message: "invalid count (must be >= 1)".to_string(),
+            }
+        }
+        "set-inner-gap" => {
+            if let Some(gap) = args.first().and_then(|s| s.parse::<u32>().ok()) {
+                state.inner_gap = gap;
+                return LayoutResult::Ok;
+            }
+            LayoutResult::Error {
+                message: "invalid gap value".to_string(),
+            }
+        }
+        "get-inner-gap" => LayoutResult::Value {
+            value: state.inner_gap.to_string(),
+        },
+        "reset" => {
+            *state = LayoutState {
+                last_input: state.last_input.take(),
+                ..Default::default()
+            };
+            LayoutResult::Ok
+        }
+        "focus-changed" => LayoutResult::Ok,
+        _ => handle_edge_gap_command(&mut state.outer_gap, cmd, args).unwrap_or_else(|| {
+            LayoutResult::Error {
+                message: format!("unknown command: {}", cmd),
+            }
+        }),
+    }
+}
+
+/// Stack windows vertically in a column. The last window absorbs rounding.
+fn push_column(
+    windows: &mut Vec<WindowGeometry>,
+    ids: &[u32],
+    x: u32,
+    width: u32,
+    height: u32,
+    inner_gap: u32,
+) {
+    if ids.is_empty() {
+        return;
+    }
+    let count = ids.len() as u32;
+    let total_gaps = inner_gap.saturating_mul(count - 1);
+    let window_height = height.saturating_sub(total_gaps) / count;
+
+    for (i, &id) in ids.iter().enumerate() {
+        let y = (i as u32)
+            .saturating_mul(window_height.saturating_add(inner_gap))
+            .min(height);
+        let h = if i == ids.len() - 1 {
+            height.saturating_sub(y)
+        } else {
+            window_height
+        };
+        windows.push(WindowGeometry {
+            id,
+            x: x as i32,
+            y: y as i32,
+            width,
+            height: h,
+        });
+    }
+}
+
+fn generate_layout(
+    state: &LayoutState,
+    width: u32,
+    height: u32,
+    window_ids: &[u32],
+) -> Vec<WindowGeometry> {
+    layout_inside_gap(&state.outer_gap, width, height, |width, height| {
+        tile_windows(state, width, height, window_ids)
+    })
+}
+
+fn tile_windows(
+    state: &LayoutState,
+    width: u32,
+    height: u32,
+    window_ids: &[u32],
+) -> Vec<WindowGeometry> {
+    if window_ids.is_empty() {
+        return vec![];
+    }
+
+    let inner_gap = state.inner_gap;
+    let main_count = (state.main_count as usize).min(window_ids.len());
+    let (main_ids, stack_ids) = window_ids.split_at(main_count);
+
+    let mut windows = Vec::with_capacity(window_ids.len());
+
+    // Only main windows: they take the full width
+    if stack_ids.is_empty() {
+        push_column(&mut windows, main_ids, 0, width, height, inner_gap);
+        return windows;
+    }
+
+    // A single stack window: main on the left, stack on the right
+    if stack_ids.len() == 1 {
+        let available = width.saturating_sub(inner_gap);
+        let main_width = (available as f64 * state.main_ratio) as u32;
+        let stack_width = available.saturating_sub(main_width);
+        push_column(&mut windows, main_ids, 0, main_width, height, inner_gap);
+        push_column(
+            &mut windows,
+            stack_ids,
+            main_width.saturating_add(inner_gap).min(width),
+            stack_width,
+            height,
+            inner_gap,
+        );
+        return windows;
+    }
+
+    // Main centered, stack windows alternate between the right and left columns
+    // Total: left_width + gap + main_width + gap + right_width = width
+    let available = width.saturating_sub(inner_gap.saturating_mul(2));
+    let main_width = (available as f64 * state.main_ratio) as u32;
+    let side_width = available.saturating_sub(main_width);
+    let left_width = side_width / 2;
+    let right_width = side_width - left_width;
+
+    let right_ids: Vec<u32> = stack_ids.iter().step_by(2).copied().collect();
+    let left_ids: Vec<u32> = stack_ids.iter().skip(1).step_by(2).copied().collect();
+
+    let main_x = left_width.saturating_add(inner_gap).min(width);
+    let right_x = main_x
+        .saturating_add(main_width)
+        .saturating_add(inner_gap)
+        .min(width);
+
+    push_column(
+        &mut windows,
+        main_ids,
+        main_x,
+        main_width,
+        height,
+        inner_gap,
+    );
+    push_column(&mut windows, &left_ids, 0, left_width, height, inner_gap);
+    push_column(
+        &mut windows,
+        &right_ids,
+        right_x,
+        right_width,
+        height,
+        inner_gap,
+    );
+
+    windows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yashiki_ipc::testing::assert_describes;
+
+    fn find(windows: &[WindowGeometry], id: u32) -> &WindowGeometry {
+        windows.iter().find(|w| w.id == id).unwrap()
+    }
+
+    #[test]
+    fn test_single_window_full_frame() {
+        let state = LayoutState::default();
+        let windows = generate_layout(&state, 1920, 1080, &[1]);
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows[0].x, 0);
+        assert_eq!(windows[0].y, 0);
+        assert_eq!(windows[0].width, 1920);
+        assert_eq!(windows[0].height, 1080);
+    }
+
+    #[test]
+    fn test_two_windows_main_and_one_side() {
+        let state = LayoutState::default();
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2]);
+        assert_eq!(windows.len(), 2);
+
+        let main = find(&windows, 1);
+        assert_eq!(main.x, 0);
+        assert_eq!(main.width, 960);
+        assert_eq!(main.height, 1080);
+
+        let side = find(&windows, 2);
+        assert_eq!(side.x, 960);
+        assert_eq!(side.width, 960);
+        assert_eq!(side.height, 1080);
+    }
+
+    #[test]
+    fn test_left_gap_shifts_columns() {
+        let mut state = LayoutState::default();
+        handle_command(&mut state, "set-gap-left", &["100".to_string()]);
+
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2]);
+        let main = find(&windows, 1);
+        assert_eq!((main.x, main.width), (100, 910));
+        let side = find(&windows, 2);
+        assert_eq!((side.x, side.width), (1010, 910));
+    }
+
+    #[test]
+    fn test_three_windows_one_per_side() {
+        let state = LayoutState::default();
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2, 3]);
+        assert_eq!(windows.len(), 3);
+
+        // Main centered at half width, 480px columns on each side
+        let main = find(&windows, 1);
+        assert_eq!(main.x, 480);
+        assert_eq!(main.width, 960);
+
+        let right = find(&windows, 2);
+        assert_eq!(right.x, 1440);
+        assert_eq!(right.width, 480);
+        assert_eq!(right.height, 1080);
+
+        let left = find(&windows, 3);
+        assert_eq!(left.x, 0);
+        assert_eq!(left.width, 480);
+        assert_eq!(left.height, 1080);
+    }
+
+    #[test]
+    fn test_five_windows_alternate_sides() {
+        let state = LayoutState::default();
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2, 3, 4, 5]);
+        assert_eq!(windows.len(), 5);
+
+        let main = find(&windows, 1);
+        assert_eq!((main.x, main.width, main.height), (480, 960, 1080));
+
+        // Right column: 2, 4
+        let w2 = find(&windows, 2);
+        assert_eq!((w2.x, w2.y, w2.height), (1440, 0, 540));
+        let w4 = find(&windows, 4);
+        assert_eq!((w4.x, w4.y, w4.height), (1440, 540, 540));
+
+        // Left column: 3, 5
+        let w3 = find(&windows, 3);
+        assert_eq!((w3.x, w3.y, w3.height), (0, 0, 540));
+        let w5 = find(&windows, 5);
+        assert_eq!((w5.x, w5.y, w5.height), (0, 540, 540));
+    }
+
+    #[test]
+    fn test_inner_gap_between_columns() {
+        let state = LayoutState {
+            inner_gap: 10,
+            ..Default::default()
+        };
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2, 3]);
+
+        // available = 1900, main = 950, sides = 475 each
+        let left = find(&windows, 3);
+        assert_eq!((left.x, left.width), (0, 475));
+        let main = find(&windows, 1);
+        assert_eq!((main.x, main.width), (485, 950));
+        let right = find(&windows, 2);
+        assert_eq!((right.x, right.width), (1445, 475));
+    }
+
+    #[test]
+    fn test_huge_inner_gap_keeps_windows_inside_frame() {
+        let state = LayoutState {
+            inner_gap: u32::MAX,
+            main_count: 2,
+            ..Default::default()
+        };
+        for count in 1..=6 {
+            let ids: Vec<u32> = (1..=count).collect();
+            let windows = generate_layout(&state, 1920, 1080, &ids);
+            assert_eq!(windows.len(), ids.len());
+            assert!(windows.iter().all(|w| (0..=1920).contains(&w.x)
+                && (0..=1080).contains(&w.y)
+                && w.x as u32 + w.width <= 1920
+                && w.y as u32 + w.height <= 1080));
+        }
+    }
+
+    #[test]
+    fn test_main_count_stacks_main_windows() {
+        let state = LayoutState {
+            main_count: 2,
+            ..Default::default()
+        };
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2, 3, 4]);
+
+        let w1 = find(&windows, 1);
+        let w2 = find(&windows, 2);
+        assert_eq!((w1.x, w1.y, w1.height), (480, 0, 540));
+        assert_eq!((w2.x, w2.y, w2.height), (480, 540, 540));
+        assert_eq!(find(&windows, 3).x, 1440);
+        assert_eq!(find(&windows, 4).x, 0);
+    }
+
+    #[test]
+    fn test_set_main_ratio_command() {
+        let mut state = LayoutState::default();
+        let result = handle_command(&mut state, "set-main-ratio", &["0.6".to_string()]);
+        assert!(matches!(result, LayoutResult::Ok));
+        assert_eq!(state.main_ratio, 0.6);
+
+        let result = handle_command(&mut state, "set-main-ratio", &["0.95".to_string()]);
+        assert!(matches!(result, LayoutResult::Error { .. }));
+        assert_eq!(state.main_ratio, 0.6);
+    }
+
+    #[test]
+    fn test_set_main_count_command() {
+        let mut state = LayoutState::default();
+        let result = handle_command(&mut state, "set-main-count", &["2".to_string()]);
+        assert!(matches!(result, LayoutResult::Ok));
+        assert_eq!(state.main_count, 2);
+
+        let result = handle_command(&mut state, "set-main-count", &["0".to_string()]);
+        assert!(matches!(result, LayoutResult::Error { .. }));
+    }
+
+    #[test]
+    fn test_reset_command_restores_defaults() {
+        let mut state = LayoutState::default();
+        handle_command(&mut state, "set-main-ratio", &["0.7".to_string()]);
+        handle_command(&mut state, "set-main-count", &["3".to_string()]);
+        handle_command(&mut state, "set-inner-gap", &["12".to_string()]);
+
+        let result = handle_command(&mut state, "reset", &[]);
+        assert!(matches!(result, LayoutResult::Ok));
+        assert_eq!(state.main_ratio, 0.5);
+        assert_eq!(state.main_count, 1);
+        assert_eq!(state.inner_gap, 0);
+    }
+
+    #[test]
+    fn test_get_main_ratio_command() {
+        let mut state = LayoutState::default();
+        handle_command(&mut state, "set-main-ratio", &["0.65".to_string()]);
+
+        match handle_command(&mut state, "get-main-ratio", &[]) {
+            LayoutResult::Value { value } => assert_eq!(value, "0.65"),
+            _ => panic!("Expected Value"),
+        }
+    }
+
+    #[test]
+    fn test_describe_lists_accepted_commands() {
+        let mut state = LayoutState::default();
+        assert_describes("centered", |msg| handle_message(&mut state, msg));
+    }
+}
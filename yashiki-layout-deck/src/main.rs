@@ -0,0 +1,474 @@
+use std::io::{self, BufRead, Write};
+
+use anyhow::Result;
+
+use yashiki_ipc::layout::{
+    handle_edge_gap_command, layout_inside_gap, LayoutMessage, LayoutResult, WindowGeometry,
+    EDGE_GAP_COMMANDS,
+};
+use yashiki_ipc::OuterGap;
+
+/// Commands accepted by `handle_command`, reported in reply to Describe
+const COMMANDS: &[&str] = &[
+    "set-tab-size",
+    "set-inner-gap",
+    "get-inner-gap",
+    "set-orientation",
+    "toggle-orientation",
+    "reset",
+    "focus-changed",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Orientation {
+    /// Tabs along the left edge
+    Horizontal,
+    /// Tabs along the top edge
+    Vertical,
+}
+
+struct LayoutState {
+    tab_size: u32,
+    inner_gap: u32,
+    orientation: Orientation,
+    focused_window_id: Option<u32>,
+    /// Insets of the whole tiling area from each output edge
+    outer_gap: OuterGap,
+    /// Most recent Layout message (width, height, window ids), echoed by LastInput
+    last_input: Option<(u32, u32, Vec<u32>)>,
+}
+
+impl Default for LayoutState {
+    fn default() -> Self {
+        Self {
+            tab_size: 40,
+            inner_gap: 0,
+            orientation: Orientation::Horizontal,
+            focused_window_id: None,
+            outer_gap: OuterGap::default(),
+            last_input: None,
+        }
+    }
+}
+
+fn main() -> Result<()> {
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+    let mut state = LayoutState::default();
+
+    for line in stdin.lock().lines() {
+        let line = line?;
+        let msg: LayoutMessage = serde_json::from_str(&line)?;
+        let result = handle_message(&mut state, msg);
+        serde_json::to_writer(&mut stdout, &result)?;
+        writeln!(stdout)?;
+        stdout.flush()?;
+    }
+
+    Ok(())
+}
+
+fn handle_message(state: &mut LayoutState, msg: LayoutMessage) -> LayoutResult {
+    match msg {
+        LayoutMessage::Layout {
+            width,
+            height,
+            windows,
+        } => {
+            let geometries = generate_layout(state, width, height, &windows);
+            state.last_input = Some((width, height, windows));
+            LayoutResult::Layout {
+                windows: geometries,
+            }
+        }
+        LayoutMessage::Command { cmd, args } => handle_command(state, &cmd, &args),
+        LayoutMessage::LastInput => match &state.last_input {
+            Some((width, height, windows)) => LayoutResult::LastInput {
+                width: *width,
+                height: *height,
+                windows: windows.clone(),
+            },
+            None => LayoutResult::Error {
+                message: "no layout received yet".to_string(),
+            },
+        },
+        LayoutMessage::Describe => LayoutResult::Capabilities {
+            name: "deck".to_string(),
+            commands: COMMANDS
+                .iter()
+                .chain(EDGE_GAP_COMMANDS)
+                .map(|c| c.to_string())
+                .collect(),
+        },
+    }
+}
+
+fn handle_command(state: &mut LayoutState, cmd: &str, args: &[String]) -> LayoutResult {
+    match cmd {
+        "set-tab-size" => {
+            if let Some(size) = args.first().and_then(|s| s.parse::<u32>().ok()) {
+                state.tab_size = size;
+                return LayoutResult::Ok;
+            }
+            LayoutResult::Error {
+                message: "invalid tab size".to_string(),
+            }
+        }
+        "set-inner-gap" => {
+            if let Some(gap) = args.first().and_then(|s| s.parse::<u32>().ok()) {
+                state.inner_gap = gap;
+                return LayoutResult::Ok;
+            }
+            LayoutResult::Error {
+                message: "invalid gap value".to_string(),
+            }
+        }
+        "get-inner-gap" => LayoutResult::Value {
+            value: state.inner_gap.to_string(),
+        },
+        "set-orientation" => {
+            if let Some(orient) = args.first() {
+                match orient.as_str() {
+                    "horizontal" | "h" => {
+                        state.orientation = Orientation::Horizontal;
+                        return LayoutResult::Ok;
+                    }
+                    "vertical" | "v" => {
+                        state.orientation = Orientation::Vertical;
+                        return LayoutResult::Ok;
+                    }
+                    _ => {}
+                }
+            }
+            LayoutResult::Error {
+                message: "invalid orientation (use horizontal/h or vertical/v)".to_string(),
+            }
+        }
+        "toggle-orientation" => {
+            state.orientation = match state.orientation {
+                Orientation::Horizontal => Orientation::Vertical,
+                Orientation::Vertical => Orientation::Horizontal,
+            };
+            LayoutResult::Ok
+        }
+        "reset" => {
+            *state = LayoutState {
+                focused_window_id: state.focused_window_id,
+                last_input: state.last_input.take(),
+                ..Default::default()
+            };
+            LayoutResult::Ok
+        }
+        "focus-changed" => {
+            if let Some(id) = args.first().and_then(|s| s.parse::<u32>().ok()) {
+                state.focused_window_id = Some(id);
+                LayoutResult::NeedsRetile
+            } else {
+                LayoutResult::Error {
+                    message: "usage: focus-changed <window_id>".to_string(),
+                }
+            }
+        }
+        _ => handle_edge_gap_command(&mut state.outer_gap, cmd, args).unwrap_or_else(|| {
+            LayoutResult::Error {
+                message: format!("unknown command: {}", cmd),
+            }
+        }),
+    }
+}
+
+fn generate_layout(
+    state: &LayoutState,
+    width: u32,
+    height: u32,
+    window_ids: &[u32],
+) -> Vec<WindowGeometry> {
+    layout_inside_gap(&state.outer_gap, width, height, |width, height| {
+        tile_windows(state, width, height, window_ids)
+    })
+}
+
+fn tile_windows(
+    state: &LayoutState,
+    width: u32,
+    height: u32,
+    window_ids: &[u32],
+) -> Vec<WindowGeometry> {
+    if window_ids.is_empty() {
+        return vec![];
+    }
+
+    // Single window: full size, no tabs
+    if window_ids.len() == 1 {
+        return vec![WindowGeometry {
+            id: window_ids[0],
+            x: 0,
+            y: 0,
+            width,
+            height,
+        }];
+    }
+
+    let focused_id = state
+        .focused_window_id
+        .filter(|id| window_ids.contains(id))
+        .unwrap_or(window_ids[0]);
+
+    // Unfocused windows keep their order and become slivers along the tab edge
+    let tab_ids: Vec<u32> = window_ids
+        .iter()
+        .copied()
+        .filter(|&id| id != focused_id)
+        .collect();
+
+    // Tabs never push past the frame, however large the tab size or gap
+    let extent = match state.orientation {
+        Orientation::Horizontal => width,
+        Orientation::Vertical => height,
+    };
+    let stride = state.tab_size.saturating_add(state.inner_gap);
+    let tabs_extent = stride.saturating_mul(tab_ids.len() as u32).min(extent);
+
+    let mut geometries = Vec::with_capacity(window_ids.len());
+
+    geometries.push(match state.orientation {
+        Orientation::Horizontal => WindowGeometry {
+            id: focused_id,
+            x: tabs_extent as i32,
+            y: 0,
+            width: width.saturating_sub(tabs_extent),
+            height,
+        },
+        Orientation::Vertical => WindowGeometry {
+            id: focused_id,
+            x: 0,
+            y: tabs_extent as i32,
+            width,
+            height: height.saturating_sub(tabs_extent),
+        },
+    });
+
+    for (index, &id) in tab_ids.iter().enumerate() {
+        let offset = stride.saturating_mul(index as u32).min(extent) as i32;
+        geometries.push(match state.orientation {
+            Orientation::Horizontal => WindowGeometry {
+                id,
+                x: offset,
+                y: 0,
+                width: state.tab_size,
+                height,
+            },
+            Orientation::Vertical => WindowGeometry {
+                id,
+                x: 0,
+                y: offset,
+                width,
+                height: state.tab_size,
+            },
+        });
+    }
+
+    geometries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yashiki_ipc::testing::assert_describes;
+
+    fn find(windows: &[WindowGeometry], id: u32) -> &WindowGeometry {
+        windows.iter().find(|w| w.id == id).unwrap()
+    }
+
+    #[test]
+    fn test_single_window() {
+        let state = LayoutState::default();
+        let windows = generate_layout(&state, 1920, 1080, &[1]);
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows[0].x, 0);
+        assert_eq!(windows[0].y, 0);
+        assert_eq!(windows[0].width, 1920);
+        assert_eq!(windows[0].height, 1080);
+    }
+
+    #[test]
+    fn test_outer_gap_insets_layout() {
+        let mut state = LayoutState::default();
+        let result = handle_command(&mut state, "set-outer-gap", &["10".to_string()]);
+        assert!(matches!(result, LayoutResult::Ok));
+
+        let windows = generate_layout(&state, 1920, 1080, &[1]);
+        let w = &windows[0];
+        assert_eq!((w.x, w.y, w.width, w.height), (10, 10, 1900, 1060));
+    }
+
+    #[test]
+    fn test_focused_window_gets_main_area() {
+        let state = LayoutState {
+            tab_size: 40,
+            focused_window_id: Some(2),
+            ..Default::default()
+        };
+
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2, 3]);
+        assert_eq!(windows.len(), 3);
+
+        // Two tabs of 40px on the left, focused window fills the rest
+        let focused = find(&windows, 2);
+        assert_eq!(focused.x, 80);
+        assert_eq!(focused.y, 0);
+        assert_eq!(focused.width, 1920 - 80);
+        assert_eq!(focused.height, 1080);
+    }
+
+    #[test]
+    fn test_unfocused_windows_get_tab_sliver() {
+        let state = LayoutState {
+            tab_size: 40,
+            focused_window_id: Some(2),
+            ..Default::default()
+        };
+
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2, 3]);
+
+        // Tabs keep window order: 1, then 3
+        let tab1 = find(&windows, 1);
+        assert_eq!(tab1.x, 0);
+        assert_eq!(tab1.width, 40);
+        assert_eq!(tab1.height, 1080);
+
+        let tab3 = find(&windows, 3);
+        assert_eq!(tab3.x, 40);
+        assert_eq!(tab3.width, 40);
+        assert_eq!(tab3.height, 1080);
+    }
+
+    #[test]
+    fn test_unknown_focus_defaults_to_first_window() {
+        let state = LayoutState {
+            focused_window_id: Some(99),
+            ..Default::default()
+        };
+
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2]);
+        assert_eq!(find(&windows, 1).width, 1920 - 40);
+        assert_eq!(find(&windows, 2).width, 40);
+    }
+
+    #[test]
+    fn test_inner_gap_separates_tabs() {
+        let state = LayoutState {
+            tab_size: 30,
+            inner_gap: 10,
+            focused_window_id: Some(1),
+            ..Default::default()
+        };
+
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2, 3]);
+
+        assert_eq!(find(&windows, 2).x, 0);
+        assert_eq!(find(&windows, 3).x, 40);
+
+        let focused = find(&windows, 1);
+        assert_eq!(focused.x, 80);
+        assert_eq!(focused.width, 1920 - 80);
+    }
+
+    #[test]
+    fn test_huge_tab_size_stays_inside_frame() {
+        let state = LayoutState {
+            tab_size: u32::MAX,
+            inner_gap: u32::MAX,
+            focused_window_id: Some(1),
+            ..Default::default()
+        };
+
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2, 3]);
+
+        assert_eq!(find(&windows, 2).x, 0);
+        assert_eq!(find(&windows, 3).x, 1920);
+        let focused = find(&windows, 1);
+        assert_eq!(focused.x, 1920);
+        assert_eq!(focused.width, 0);
+    }
+
+    #[test]
+    fn test_vertical_orientation() {
+        let state = LayoutState {
+            tab_size: 40,
+            orientation: Orientation::Vertical,
+            focused_window_id: Some(1),
+            ..Default::default()
+        };
+
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2]);
+
+        let tab = find(&windows, 2);
+        assert_eq!(tab.y, 0);
+        assert_eq!(tab.width, 1920);
+        assert_eq!(tab.height, 40);
+
+        let focused = find(&windows, 1);
+        assert_eq!(focused.y, 40);
+        assert_eq!(focused.width, 1920);
+        assert_eq!(focused.height, 1080 - 40);
+    }
+
+    #[test]
+    fn test_focus_changed_cycles_main_window() {
+        let mut state = LayoutState::default();
+
+        let result = handle_command(&mut state, "focus-changed", &["3".to_string()]);
+        assert!(matches!(result, LayoutResult::NeedsRetile));
+
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2, 3]);
+        assert_eq!(find(&windows, 3).width, 1920 - 80);
+        assert_eq!(find(&windows, 1).width, 40);
+    }
+
+    #[test]
+    fn test_set_tab_size_command() {
+        let mut state = LayoutState::default();
+        let result = handle_command(&mut state, "set-tab-size", &["24".to_string()]);
+        assert!(matches!(result, LayoutResult::Ok));
+        assert_eq!(state.tab_size, 24);
+
+        let result = handle_command(&mut state, "set-tab-size", &["wide".to_string()]);
+        assert!(matches!(result, LayoutResult::Error { .. }));
+        assert_eq!(state.tab_size, 24);
+    }
+
+    #[test]
+    fn test_inner_gap_commands() {
+        let mut state = LayoutState::default();
+        let result = handle_command(&mut state, "set-inner-gap", &["8".to_string()]);
+        assert!(matches!(result, LayoutResult::Ok));
+
+        match handle_command(&mut state, "get-inner-gap", &[]) {
+            LayoutResult::Value { value } => assert_eq!(value, "8"),
+            _ => panic!("Expected Value"),
+        }
+    }
+
+    #[test]
+    fn test_reset_command_restores_defaults() {
+        let mut state = LayoutState::default();
+        handle_command(&mut state, "set-tab-size", &["24".to_string()]);
+        handle_command(&mut state, "set-inner-gap", &["8".to_string()]);
+        handle_command(&mut state, "toggle-orientation", &[]);
+        handle_command(&mut state, "focus-changed", &["2".to_string()]);
+
+        let result = handle_command(&mut state, "reset", &[]);
+        assert!(matches!(result, LayoutResult::Ok));
+        assert_eq!(state.tab_size, 40);
+        assert_eq!(state.inner_gap, 0);
+        assert_eq!(state.orientation, Orientation::Horizontal);
+        assert_eq!(state.focused_window_id, Some(2));
+    }
+
+    #[test]
+    fn test_describe_lists_accepted_commands() {
+        let mut state = LayoutState::default();
+        assert_describes("deck", |msg| handle_message(&mut state, msg));
+    }
+}
@@ -0,0 +1,312 @@
+use std::io::{self, BufRead, Write};
+
+use anyhow::Result;
+
+use yashiki_ipc::layout::{
+    handle_edge_gap_command, layout_inside_gap, LayoutMessage, LayoutResult, WindowGeometry,
+    EDGE_GAP_COMMANDS,
+};
+use yashiki_ipc::OuterGap;
+
+/// Commands accepted by `handle_command`, reported in reply to Describe
+const COMMANDS: &[&str] = &["set-inner-gap", "get-inner-gap", "reset", "focus-changed"];
+
+#[derive(Default)]
+struct LayoutState {
+    inner_gap: u32,
+    /// Insets of the whole tiling area from each output edge
+    outer_gap: OuterGap,
+    /// Most recent Layout message (width, height, window ids), echoed by LastInput
+    last_input: Option<(u32, u32, Vec<u32>)>,
+}
+
+fn main() -> Result<()> {
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+    let mut state = LayoutState::default();
+
+    for line in stdin.lock().lines() {
+        let line = line?;
+        let msg: LayoutMessage = serde_json::from_str(&line)?;
+        let result = handle_message(&mut state, msg);
+        serde_json::to_writer(&mut stdout, &result)?;
+        writeln!(stdout)?;
+        stdout.flush()?;
+    }
+
+    Ok(())
+}
+
+fn handle_message(state: &mut LayoutState, msg: LayoutMessage) -> LayoutResult {
+    match msg {
+        LayoutMessage::Layout {
+            width,
+            height,
+            windows,
+        } => {
+            let geometries = generate_layout(state, width, height, &windows);
+            state.last_input = Some((width, height, windows));
+            LayoutResult::Layout {
+                windows: geometries,
+            }
+        }
+        LayoutMessage::Command { cmd, args } => handle_command(state, &cmd, &args),
+        LayoutMessage::LastInput => match &state.last_input {
+            Some((width, height, windows)) => LayoutResult::LastInput {
+                width: *width,
+                height: *height,
+                windows: windows.clone(),
+            },
+            None => LayoutResult::Error {
+                message: "no layout received yet".to_string(),
+            },
+        },
+        LayoutMessage::Describe => LayoutResult::Capabilities {
+            name: "grid".to_string(),
+            commands: COMMANDS
+                .iter()
+                .chain(EDGE_GAP_COMMANDS)
+                .map(|c| c.to_string())
+                .collect(),
+        },
+    }
+}
+
+fn handle_command(state: &mut LayoutState, cmd: &str, args: &[String]) -> LayoutResult {
+    match cmd {
+        "set-inner-gap" => {
+            if let Some(gap) = args.first().and_then(|s| s.parse::<u32>().ok()) {
+                state.inner_gap = gap;
+                return LayoutResult::Ok;
+            }
+            LayoutResult::Error {
+                message: "invalid gap value".to_string(),
+            }
+        }
+        "get-inner-gap" => LayoutResult::Value {
+            value: state.inner_gap.to_string(),
+        },
+        "reset" => {
+            *state = LayoutState {
+                last_input: state.last_input.take(),
+                ..Default::default()
+            };
+            LayoutResult::Ok
+        }
+        "focus-changed" => LayoutResult::Ok,
+        _ => handle_edge_gap_command(&mut state.outer_gap, cmd, args).unwrap_or_else(|| {
+            LayoutResult::Error {
+                message: format!("unknown command: {}", cmd),
+            }
+        }),
+    }
+}
+
+/// Split `extent` into `count` cells separated by `gap`, as (offset, size) pairs.
+/// The last cell absorbs rounding so the cells reach the far edge; offsets never pass it.
+fn split(extent: u32, count: u32, gap: u32) -> Vec<(u32, u32)> {
+    let total_gaps = gap.saturating_mul(count.saturating_sub(1));
+    let cell = extent.saturating_sub(total_gaps) / count.max(1);
+
+    (0..count)
+        .map(|i| {
+            let offset = i.saturating_mul(cell.saturating_add(gap)).min(extent);
+            let size = if i == count - 1 {
+                extent.saturating_sub(offset)
+            } else {
+                cell
+            };
+            (offset, size)
+        })
+        .collect()
+}
+
+fn generate_layout(
+    state: &LayoutState,
+    width: u32,
+    height: u32,
+    window_ids: &[u32],
+) -> Vec<WindowGeometry> {
+    layout_inside_gap(&state.outer_gap, width, height, |width, height| {
+        tile_windows(state, width, height, window_ids)
+    })
+}
+
+fn tile_windows(
+    state: &LayoutState,
+    width: u32,
+    height: u32,
+    window_ids: &[u32],
+) -> Vec<WindowGeometry> {
+    if window_ids.is_empty() {
+        return vec![];
+    }
+
+    // Most-square grid: columns = ceil(sqrt(n)), rows = ceil(n / columns)
+    let count = window_ids.len() as u32;
+    let cols = (count as f64).sqrt().ceil() as u32;
+    let rows = count.div_ceil(cols);
+
+    let mut windows = Vec::with_capacity(window_ids.len());
+    let mut ids = window_ids.iter();
+
+    for (row, (y, h)) in split(height, rows, state.inner_gap).into_iter().enumerate() {
+        // A partly filled last row widens its cells to span the full width
+        let row_cols = if row as u32 == rows - 1 {
+            count - cols * (rows - 1)
+        } else {
+            cols
+        };
+        for (x, w) in split(width, row_cols, state.inner_gap) {
+            let Some(&id) = ids.next() else {
+                break;
+            };
+            windows.push(WindowGeometry {
+                id,
+                x: x as i32,
+                y: y as i32,
+                width: w,
+                height: h,
+            });
+        }
+    }
+
+    windows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yashiki_ipc::testing::{assert_covers, assert_describes, frames};
+
+    #[test]
+    fn test_single_window_full_frame() {
+        let state = LayoutState::default();
+        let windows = generate_layout(&state, 1920, 1080, &[1]);
+        assert_eq!(frames(&windows), vec![(1, 0, 0, 1920, 1080)]);
+        assert_covers(&windows, 1920, 1080);
+    }
+
+    #[test]
+    fn test_two_windows_side_by_side() {
+        let state = LayoutState::default();
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2]);
+        assert_eq!(
+            frames(&windows),
+            vec![(1, 0, 0, 960, 1080), (2, 960, 0, 960, 1080)]
+        );
+        assert_covers(&windows, 1920, 1080);
+    }
+
+    #[test]
+    fn test_top_gap_insets_cells() {
+        let mut state = LayoutState::default();
+        handle_command(&mut state, "set-gap-top", &["40".to_string()]);
+
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2]);
+        assert_eq!(
+            frames(&windows),
+            vec![(1, 0, 40, 960, 1040), (2, 960, 40, 960, 1040)]
+        );
+    }
+
+    #[test]
+    fn test_four_windows_two_by_two() {
+        let state = LayoutState::default();
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2, 3, 4]);
+        assert_eq!(
+            frames(&windows),
+            vec![
+                (1, 0, 0, 960, 540),
+                (2, 960, 0, 960, 540),
+                (3, 0, 540, 960, 540),
+                (4, 960, 540, 960, 540),
+            ]
+        );
+        assert_covers(&windows, 1920, 1080);
+    }
+
+    #[test]
+    fn test_five_windows_widen_last_row() {
+        let state = LayoutState::default();
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2, 3, 4, 5]);
+
+        // 3 columns x 2 rows; the last row's two cells share the full width
+        assert_eq!(
+            frames(&windows),
+            vec![
+                (1, 0, 0, 640, 540),
+                (2, 640, 0, 640, 540),
+                (3, 1280, 0, 640, 540),
+                (4, 0, 540, 960, 540),
+                (5, 960, 540, 960, 540),
+            ]
+        );
+        assert_covers(&windows, 1920, 1080);
+    }
+
+    #[test]
+    fn test_remainder_goes_to_last_column_and_row() {
+        let state = LayoutState::default();
+        let windows = generate_layout(&state, 1000, 701, &[1, 2, 3, 4, 5, 6, 7, 8, 9]);
+
+        // 1000 / 3 = 333 and 701 / 3 = 233; the rightmost column and bottom row get the rest
+        assert_eq!(
+            frames(&windows[6..]),
+            vec![
+                (7, 0, 466, 333, 235),
+                (8, 333, 466, 333, 235),
+                (9, 666, 466, 334, 235),
+            ]
+        );
+        assert_covers(&windows, 1000, 701);
+    }
+
+    #[test]
+    fn test_inner_gap_between_cells() {
+        let mut state = LayoutState::default();
+        let result = handle_command(&mut state, "set-inner-gap", &["10".to_string()]);
+        assert!(matches!(result, LayoutResult::Ok));
+
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2, 3, 4]);
+        // (1920 - 10) / 2 = 955, (1080 - 10) / 2 = 535
+        assert_eq!(
+            frames(&windows),
+            vec![
+                (1, 0, 0, 955, 535),
+                (2, 965, 0, 955, 535),
+                (3, 0, 545, 955, 535),
+                (4, 965, 545, 955, 535),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_huge_inner_gap_keeps_cells_inside_extent() {
+        assert_eq!(split(100, 3, u32::MAX), vec![(0, 0), (100, 0), (100, 0)]);
+
+        let mut state = LayoutState::default();
+        handle_command(&mut state, "set-inner-gap", &[u32::MAX.to_string()]);
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2, 3, 4, 5]);
+        assert!(windows.iter().all(|w| w.x <= 1920 && w.y <= 1080));
+    }
+
+    #[test]
+    fn test_reset_command_restores_defaults() {
+        let mut state = LayoutState::default();
+        handle_command(&mut state, "set-inner-gap", &["12".to_string()]);
+
+        let result = handle_command(&mut state, "reset", &[]);
+        assert!(matches!(result, LayoutResult::Ok));
+        assert_eq!(state.inner_gap, 0);
+
+        let result = handle_command(&mut state, "set-inner-gap", &["wide".to_string()]);
+        assert!(matches!(result, LayoutResult::Error { .. }));
+    }
+
+    #[test]
+    fn test_describe_lists_accepted_commands() {
+        let mut state = LayoutState::default();
+        assert_describes("grid", |msg| handle_message(&mut state, msg));
+    }
+}
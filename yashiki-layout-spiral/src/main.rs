@@ -0,0 +1,307 @@
+use std::io::{self, BufRead, Write};
+
+use anyhow::Result;
+
+use yashiki_ipc::layout::{
+    handle_edge_gap_command, layout_inside_gap, LayoutMessage, LayoutResult, WindowGeometry,
+    EDGE_GAP_COMMANDS,
+};
+use yashiki_ipc::OuterGap;
+
+/// Commands accepted by `handle_command`, reported in reply to Describe
+const COMMANDS: &[&str] = &[
+    "set-main-ratio",
+    "get-main-ratio",
+    "inc-main-ratio",
+    "dec-main-ratio",
+    "reset",
+    "focus-changed",
+];
+
+struct LayoutState {
+    /// Share of the remaining rectangle each window takes before the next split
+    main_ratio: f64,
+    /// Insets of the whole tiling area from each output edge
+    outer_gap: OuterGap,
+    /// Most recent Layout message (width, height, window ids), echoed by LastInput
+    last_input: Option<(u32, u32, Vec<u32>)>,
+}
+
+impl Default for LayoutState {
+    fn default() -> Self {
+        Self {
+            main_ratio: 0.5,
+            outer_gap: OuterGap::default(),
+            last_input: None,
+        }
+    }
+}
+
+fn main() -> Result<()> {
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+    let mut state = LayoutState::default();
+
+    for line in stdin.lock().lines() {
+        let line = line?;
+        let msg: LayoutMessage = serde_json::from_str(&line)?;
+        let result = handle_message(&mut state, msg);
+        serde_json::to_writer(&mut stdout, &result)?;
+        writeln!(stdout)?;
+        stdout.flush()?;
+    }
+
+    Ok(())
+}
+
+fn handle_message(state: &mut LayoutState, msg: LayoutMessage) -> LayoutResult {
+    match msg {
+        LayoutMessage::Layout {
+            width,
+            height,
+            windows,
+        } => {
+            let geometries = generate_layout(state, width, height, &windows);
+            state.last_input = Some((width, height, windows));
+            LayoutResult::Layout {
+                windows: geometries,
+            }
+        }
+        LayoutMessage::Command { cmd, args } => handle_command(state, &cmd, &args),
+        LayoutMessage::LastInput => match &state.last_input {
+            Some((width, height, windows)) => LayoutResult::LastInput {
+                width: *width,
+                height: *height,
+                windows: windows.clone(),
+            },
+            None => LayoutResult::Error {
+                message: "no layout received yet".to_string(),
+            },
+        },
+        LayoutMessage::Describe => LayoutResult::Capabilities {
+            name: "spiral".to_string(),
+            commands: COMMANDS
+                .iter()
+                .chain(EDGE_GAP_COMMANDS)
+                .map(|c| c.to_string())
+                .collect(),
+        },
+    }
+}
+
+fn handle_command(state: &mut LayoutState, cmd: &str, args: &[String]) -> LayoutResult {
+    match cmd {
+        "set-main-ratio" => {
+            if let Some(ratio) = args.first().and_then(|s| s.parse::<f64>().ok()) {
+                if (0.1..=0.9).contains(&ratio) {
+                    state.main_ratio = ratio;
+                    return LayoutResult::Ok;
+                }
+            }
+            LayoutResult::Error {
+                message: "invalid ratio (must be 0.1-0.9)".to_string(),
+            }
+        }
+        "get-main-ratio" => LayoutResult::Value {
+            value: state.main_ratio.to_string(),
+        },
+        "inc-main-ratio" => {
+            let delta = args
+                .first()
+                .and_then(|s| s.parse::<f64>().ok())
+                .unwrap_or(0.05);
+            state.main_ratio = (state.main_ratio + delta).min(0.9);
+            LayoutResult::Ok
+        }
+        "dec-main-ratio" => {
+            let delta = args
+                .first()
+                .and_then(|s| s.parse::<f64>().ok())
+                .unwrap_or(0.05);
+            state.main_ratio = (state.main_ratio - delta).max(0.1);
+            LayoutResult::Ok
+        }
+        "reset" => {
+            *state = LayoutState {
+                last_input: state.last_input.take(),
+                ..Default::default()
+            };
+            LayoutResult::Ok
+        }
+        "focus-changed" => LayoutResult::Ok,
+        _ => handle_edge_gap_command(&mut state.outer_gap, cmd, args).unwrap_or_else(|| {
+            LayoutResult::Error {
+                message: format!("unknown command: {}", cmd),
+            }
+        }),
+    }
+}
+
+fn generate_layout(
+    state: &LayoutState,
+    width: u32,
+    height: u32,
+    window_ids: &[u32],
+) -> Vec<WindowGeometry> {
+    layout_inside_gap(&state.outer_gap, width, height, |width, height| {
+        tile_windows(state, width, height, window_ids)
+    })
+}
+
+fn tile_windows(
+    state: &LayoutState,
+    width: u32,
+    height: u32,
+    window_ids: &[u32],
+) -> Vec<WindowGeometry> {
+    // Remaining rectangle: x, y, width, height
+    let (mut x, mut y, mut w, mut h) = (0u32, 0u32, width, height);
+    let mut windows = Vec::with_capacity(window_ids.len());
+
+    for (i, &id) in window_ids.iter().enumerate() {
+        // The last window fills whatever is left
+        if i == window_ids.len() - 1 {
+            windows.push(geometry(id, x, y, w, h));
+            break;
+        }
+
+        // Splits alternate between width and height, and the window's side
+        // turns clockwise (left, top, right, bottom) to form the spiral
+        match i % 4 {
+            0 => {
+                let part = (w as f64 * state.main_ratio) as u32;
+                windows.push(geometry(id, x, y, part, h));
+                x += part;
+                w -= part;
+            }
+            1 => {
+                let part = (h as f64 * state.main_ratio) as u32;
+                windows.push(geometry(id, x, y, w, part));
+                y += part;
+                h -= part;
+            }
+            2 => {
+                let part = (w as f64 * state.main_ratio) as u32;
+                windows.push(geometry(id, x + w - part, y, part, h));
+                w -= part;
+            }
+            _ => {
+                let part = (h as f64 * state.main_ratio) as u32;
+                windows.push(geometry(id, x, y + h - part, w, part));
+                h -= part;
+            }
+        }
+    }
+
+    windows
+}
+
+fn geometry(id: u32, x: u32, y: u32, width: u32, height: u32) -> WindowGeometry {
+    WindowGeometry {
+        id,
+        x: x as i32,
+        y: y as i32,
+        width,
+        height,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yashiki_ipc::testing::{assert_covers, assert_describes, frames};
+
+    #[test]
+    fn test_single_window_full_frame() {
+        let state = LayoutState::default();
+        let windows = generate_layout(&state, 1920, 1080, &[1]);
+        assert_eq!(frames(&windows), vec![(1, 0, 0, 1920, 1080)]);
+        assert_covers(&windows, 1920, 1080);
+    }
+
+    #[test]
+    fn test_three_windows() {
+        let state = LayoutState::default();
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2, 3]);
+        assert_eq!(
+            frames(&windows),
+            vec![
+                (1, 0, 0, 960, 1080),
+                (2, 960, 0, 960, 540),
+                (3, 960, 540, 960, 540),
+            ]
+        );
+        assert_covers(&windows, 1920, 1080);
+    }
+
+    #[test]
+    fn test_outer_gap_insets_spiral() {
+        let mut state = LayoutState::default();
+        handle_command(&mut state, "set-outer-gap", &["20".to_string()]);
+
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2, 3]);
+        assert_eq!(
+            frames(&windows),
+            vec![
+                (1, 20, 20, 940, 1040),
+                (2, 960, 20, 940, 520),
+                (3, 960, 540, 940, 520),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_four_windows_turn_clockwise() {
+        let state = LayoutState::default();
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2, 3, 4]);
+
+        // The third window takes the right side of what is left
+        assert_eq!(
+            frames(&windows),
+            vec![
+                (1, 0, 0, 960, 1080),
+                (2, 960, 0, 960, 540),
+                (3, 1440, 540, 480, 540),
+                (4, 960, 540, 480, 540),
+            ]
+        );
+        assert_covers(&windows, 1920, 1080);
+    }
+
+    #[test]
+    fn test_main_ratio_and_many_windows_cover_output() {
+        let mut state = LayoutState::default();
+        let result = handle_command(&mut state, "set-main-ratio", &["0.6".to_string()]);
+        assert!(matches!(result, LayoutResult::Ok));
+
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2, 3, 4]);
+        assert_eq!(frames(&windows)[0], (1, 0, 0, 1152, 1080));
+        assert_covers(&windows, 1920, 1080);
+
+        let ids: Vec<u32> = (1..=9).collect();
+        let windows = generate_layout(&state, 1917, 1079, &ids);
+        assert_eq!(windows.len(), 9);
+        assert_covers(&windows, 1917, 1079);
+    }
+
+    #[test]
+    fn test_ratio_commands() {
+        let mut state = LayoutState::default();
+        handle_command(&mut state, "inc-main-ratio", &[]);
+        assert!((state.main_ratio - 0.55).abs() < 1e-9);
+        handle_command(&mut state, "dec-main-ratio", &["0.5".to_string()]);
+        assert_eq!(state.main_ratio, 0.1);
+
+        let result = handle_command(&mut state, "set-main-ratio", &["0.95".to_string()]);
+        assert!(matches!(result, LayoutResult::Error { .. }));
+
+        handle_command(&mut state, "reset", &[]);
+        assert_eq!(state.main_ratio, 0.5);
+    }
+
+    #[test]
+    fn test_describe_lists_accepted_commands() {
+        let mut state = LayoutState::default();
+        assert_describes("spiral", |msg| handle_message(&mut state, msg));
+    }
+}
@@ -2,14 +2,59 @@ use std::io::{self, BufRead, Write};
 
 use anyhow::Result;
 
-use yashiki_ipc::layout::{LayoutMessage, LayoutResult, WindowGeometry};
+use yashiki_ipc::layout::{
+    handle_edge_gap_command, layout_inside_gap, LayoutMessage, LayoutResult, WindowGeometry,
+    EDGE_GAP_COMMANDS, MIN_TILING_SIZE,
+};
+use yashiki_ipc::OuterGap;
+
+/// Commands accepted by `handle_command`, reported in reply to Describe
+const COMMANDS: &[&str] = &[
+    "set-main-ratio",
+    "get-main-ratio",
+    "inc-main-ratio",
+    "dec-main-ratio",
+    "set-main-width",
+    "clear-main-width",
+    "set-main-orientation",
+    "toggle-main-orientation",
+    "set-stack-ratio",
+    "inc-main-count",
+    "dec-main-count",
+    "set-main-count",
+    "set-inner-gap",
+    "get-inner-gap",
+    "inc-inner-gap",
+    "dec-inner-gap",
+    "focus-changed",
+    "reset",
+    "zoom",
+];
+
+/// How multiple main windows share the main area
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MainOrientation {
+    /// Stacked top to bottom
+    Vertical,
+    /// Side by side
+    Horizontal,
+}
 
 struct LayoutState {
     main_count: u32,
     main_ratio: f64,
+    /// Fixed main area width in pixels, overriding `main_ratio` when set
+    main_width: Option<u32>,
+    main_orientation: MainOrientation,
+    /// Share of the stack height given to the first stack window (None: even split)
+    stack_ratio: Option<f64>,
     inner_gap: u32,
     main_window_id: Option<u32>,
     focused_window_id: Option<u32>,
+    /// Insets of the whole tiling area from each output edge
+    outer_gap: OuterGap,
+    /// Most recent Layout message (width, height, window ids), echoed by LastInput
+    last_input: Option<(u32, u32, Vec<u32>)>,
 }
 
 impl Default for LayoutState {
@@ -17,9 +62,14 @@ impl Default for LayoutState {
         Self {
             main_count: 1,
             main_ratio: 0.5,
+            main_width: None,
+            main_orientation: MainOrientation::Vertical,
+            stack_ratio: None,
             inner_gap: 0,
             main_window_id: None,
             focused_window_id: None,
+            outer_gap: OuterGap::default(),
+            last_input: None,
         }
     }
 }
@@ -49,11 +99,30 @@ fn handle_message(state: &mut LayoutState, msg: LayoutMessage) -> LayoutResult {
             windows,
         } => {
             let geometries = generate_layout(state, width, height, &windows);
+            state.last_input = Some((width, height, windows));
             LayoutResult::Layout {
                 windows: geometries,
             }
         }
         LayoutMessage::Command { cmd, args } => handle_command(state, &cmd, &args),
+        LayoutMessage::LastInput => match &state.last_input {
+            Some((width, height, windows)) => LayoutResult::LastInput {
+                width: *width,
+                height: *height,
+                windows: windows.clone(),
+            },
+            None => LayoutResult::Error {
+                message: "no layout received yet".to_string(),
+            },
+        },
+        LayoutMessage::Describe => LayoutResult::Capabilities {
+            name: "tatami".to_string(),
+            commands: COMMANDS
+                .iter()
+                .chain(EDGE_GAP_COMMANDS)
+                .map(|c| c.to_string())
+                .collect(),
+        },
     }
 }
 
@@ -70,6 +139,9 @@ fn handle_command(state: &mut LayoutState, cmd: &str, args: &[String]) -> Layout
                 message: "invalid ratio (must be 0.1-0.9)".to_string(),
             }
         }
+        "get-main-ratio" => LayoutResult::Value {
+            value: state.main_ratio.to_string(),
+        },
         "inc-main-ratio" => {
             let delta = args
                 .first()
@@ -86,6 +158,51 @@ fn handle_command(state: &mut LayoutState, cmd: &str, args: &[String]) -> Layout
             state.main_ratio = (state.main_ratio - delta).max(0.1);
             LayoutResult::Ok
         }
+        "set-main-width" => {
+            if let Some(width) = args.first().and_then(|s| s.parse::<u32>().ok()) {
+                if width > 0 {
+                    state.main_width = Some(width);
+                    return LayoutResult::Ok;
+                }
+            }
+            LayoutResult::Error {
+                message: "invalid width (must be > 0)".to_string(),
+            }
+        }
+        "clear-main-width" => {
+            state.main_width = None;
+            LayoutResult::Ok
+        }
+        "set-main-orientation" => {
+            match args.first().map(|s| s.as_str()) {
+                Some("vertical" | "v") => state.main_orientation = MainOrientation::Vertical,
+                Some("horizontal" | "h") => state.main_orientation = MainOrientation::Horizontal,
+                _ => {
+                    return LayoutResult::Error {
+                        message: "invalid orientation (use vertical/v or horizontal/h)".to_string(),
+                    }
+                }
+            }
+            LayoutResult::Ok
+        }
+        "toggle-main-orientation" => {
+            state.main_orientation = match state.main_orientation {
+                MainOrientation::Vertical => MainOrientation::Horizontal,
+                MainOrientation::Horizontal => MainOrientation::Vertical,
+            };
+            LayoutResult::Ok
+        }
+        "set-stack-ratio" => {
+            if let Some(ratio) = args.first().and_then(|s| s.parse::<f64>().ok()) {
+                if (0.1..=0.9).contains(&ratio) {
+                    state.stack_ratio = Some(ratio);
+                    return LayoutResult::Ok;
+                }
+            }
+            LayoutResult::Error {
+                message: "invalid ratio (must be 0.1-0.9)".to_string(),
+            }
+        }
         "inc-main-count" => {
             state.main_count = state.main_count.saturating_add(1);
             LayoutResult::Ok
@@ -116,6 +233,9 @@ fn handle_command(state: &mut LayoutState, cmd: &str, args: &[String]) -> Layout
                 message: "invalid gap value".to_string(),
             }
         }
+        "get-inner-gap" => LayoutResult::Value {
+            value: state.inner_gap.to_string(),
+        },
         "inc-inner-gap" => {
             let delta = args
                 .first()
@@ -142,6 +262,16 @@ fn handle_command(state: &mut LayoutState, cmd: &str, args: &[String]) -> Layout
                 }
             }
         }
+        "reset" => {
+            // Restore default ratio, count and gap; keep track of which windows are main/focused
+            *state = LayoutState {
+                main_window_id: state.main_window_id,
+                focused_window_id: state.focused_window_id,
+                last_input: state.last_input.take(),
+                ..Default::default()
+            };
+            LayoutResult::Ok
+        }
         "zoom" => {
             let id = args
                 .first()
@@ -157,9 +287,11 @@ fn handle_command(state: &mut LayoutState, cmd: &str, args: &[String]) -> Layout
                 }
             }
         }
-        _ => LayoutResult::Error {
-            message: format!("unknown command: {}", cmd),
-        },
+        _ => handle_edge_gap_command(&mut state.outer_gap, cmd, args).unwrap_or_else(|| {
+            LayoutResult::Error {
+                message: format!("unknown command: {}", cmd),
+            }
+        }),
     }
 }
 
@@ -168,6 +300,17 @@ fn generate_layout(
     width: u32,
     height: u32,
     window_ids: &[u32],
+) -> Vec<WindowGeometry> {
+    layout_inside_gap(&state.outer_gap, width, height, |width, height| {
+        tile_windows(state, width, height, window_ids)
+    })
+}
+
+fn tile_windows(
+    state: &LayoutState,
+    width: u32,
+    height: u32,
+    window_ids: &[u32],
 ) -> Vec<WindowGeometry> {
     if window_ids.is_empty() {
         return vec![];
@@ -196,7 +339,14 @@ fn generate_layout(
     // Total: main_width + inner_gap + stack_width = width (when stack exists)
     let (main_width, stack_width) = if stack_count > 0 {
         let available_for_windows = width.saturating_sub(inner_gap);
-        let mw = (available_for_windows as f64 * state.main_ratio) as u32;
+        let mw = match state.main_width {
+            // An absolute width never pushes the stack off screen: it keeps at least
+            // MIN_TILING_SIZE, or half the frame when the frame is narrower than that
+            Some(px) => {
+                px.min(available_for_windows - MIN_TILING_SIZE.min(available_for_windows / 2))
+            }
+            None => (available_for_windows as f64 * state.main_ratio) as u32,
+        };
         let sw = available_for_windows.saturating_sub(mw);
         (mw, sw)
     } else {
@@ -205,44 +355,64 @@ fn generate_layout(
 
     let mut windows = Vec::with_capacity(window_ids.len());
 
-    // Main area - vertically stacked
-    // Total: n * h + (n-1) * gap = height
-    // h = (height - (n-1) * gap) / n
+    // Main area - stacked along the main orientation
+    // Total: n * size + (n-1) * gap = extent
+    // size = (extent - (n-1) * gap) / n
+    let main_extent = match state.main_orientation {
+        MainOrientation::Vertical => height,
+        MainOrientation::Horizontal => main_width,
+    };
     let main_total_gaps = inner_gap.saturating_mul(main_count.saturating_sub(1));
-    let main_window_height = height.saturating_sub(main_total_gaps) / main_count.max(1);
+    let main_window_size = main_extent.saturating_sub(main_total_gaps) / main_count.max(1);
 
     for (i, &window_id) in window_ids.iter().enumerate().take(main_count as usize) {
-        let y = i as u32 * (main_window_height + inner_gap);
+        let offset = i as u32 * (main_window_size + inner_gap);
         // Last window in main fills remaining space to handle rounding
-        let h = if i == main_count as usize - 1 {
-            height.saturating_sub(y)
+        let size = if i == main_count as usize - 1 {
+            main_extent.saturating_sub(offset)
         } else {
-            main_window_height
+            main_window_size
         };
-        windows.push(WindowGeometry {
-            id: window_id,
-            x: 0,
-            y: y as i32,
-            width: main_width,
-            height: h,
+        windows.push(match state.main_orientation {
+            MainOrientation::Vertical => WindowGeometry {
+                id: window_id,
+                x: 0,
+                y: offset as i32,
+                width: main_width,
+                height: size,
+            },
+            MainOrientation::Horizontal => WindowGeometry {
+                id: window_id,
+                x: offset as i32,
+                y: 0,
+                width: size,
+                height,
+            },
         });
     }
 
     // Stack area - vertically stacked
     if stack_count > 0 {
         let stack_total_gaps = inner_gap.saturating_mul(stack_count.saturating_sub(1));
-        let stack_window_height = height.saturating_sub(stack_total_gaps) / stack_count;
+        let available = height.saturating_sub(stack_total_gaps);
         let stack_x = main_width + inner_gap;
 
+        // With a stack ratio, each window takes that share of the height left for it
+        // and the next ones; without one, the stack splits evenly
+        let mut remaining = available;
+        let mut y = 0;
         for i in 0..stack_count as usize {
             let idx = main_count as usize + i;
-            let y = i as u32 * (stack_window_height + inner_gap);
             // Last window fills remaining space to handle rounding
             let h = if i == stack_count as usize - 1 {
                 height.saturating_sub(y)
             } else {
-                stack_window_height
+                match state.stack_ratio {
+                    Some(ratio) => (remaining as f64 * ratio) as u32,
+                    None => available / stack_count,
+                }
             };
+            remaining = remaining.saturating_sub(h);
             windows.push(WindowGeometry {
                 id: window_ids[idx],
                 x: stack_x as i32,
@@ -250,8 +420,378 @@ fn generate_layout(
                 width: stack_width,
                 height: h,
             });
+            y += h + inner_gap;
         }
     }
 
     windows
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yashiki_ipc::testing::assert_describes;
+
+    fn heights(windows: &[WindowGeometry]) -> Vec<(u32, i32, u32)> {
+        windows.iter().map(|w| (w.id, w.y, w.height)).collect()
+    }
+
+    #[test]
+    fn test_stack_splits_evenly_by_default() {
+        let state = LayoutState::default();
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2, 3, 4]);
+        assert_eq!(
+            heights(&windows[1..]),
+            vec![(2, 0, 360), (3, 360, 360), (4, 720, 360)]
+        );
+    }
+
+    #[test]
+    fn test_stack_ratio_sizes_whole_stack() {
+        let mut state = LayoutState::default();
+        let result = handle_command(&mut state, "set-stack-ratio", &["0.6".to_string()]);
+        assert!(matches!(result, LayoutResult::Ok));
+
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2, 3, 4]);
+        // 60% of 1080, then 60% of the remaining 432, the last takes the rest
+        assert_eq!(
+            heights(&windows[1..]),
+            vec![(2, 0, 648), (3, 648, 259), (4, 907, 173)]
+        );
+        // Main window is unaffected
+        assert_eq!(windows[0].height, 1080);
+
+        // Every split halves what is left
+        handle_command(&mut state, "set-stack-ratio", &["0.5".to_string()]);
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2, 3, 4, 5]);
+        assert_eq!(
+            heights(&windows[1..]),
+            vec![(2, 0, 540), (3, 540, 270), (4, 810, 135), (5, 945, 135)]
+        );
+    }
+
+    #[test]
+    fn test_stack_ratio_with_gap_and_single_stack_window() {
+        let state = LayoutState {
+            stack_ratio: Some(0.75),
+            inner_gap: 10,
+            ..Default::default()
+        };
+
+        // available = 1080 - 10 = 1070, first = 802, second fills the rest
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2, 3]);
+        assert_eq!(heights(&windows[1..]), vec![(2, 0, 802), (3, 812, 268)]);
+
+        // A lone stack window always takes the full height
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2]);
+        assert_eq!(heights(&windows[1..]), vec![(2, 0, 1080)]);
+    }
+
+    fn frame(w: &WindowGeometry) -> (i32, i32, u32, u32) {
+        (w.x, w.y, w.width, w.height)
+    }
+
+    #[test]
+    fn test_two_main_windows_vertical() {
+        let state = LayoutState {
+            main_count: 2,
+            inner_gap: 10,
+            ..Default::default()
+        };
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2, 3]);
+
+        // available width = 1910, main = 955; heights = (1080 - 10) / 2
+        assert_eq!(frame(&windows[0]), (0, 0, 955, 535));
+        assert_eq!(frame(&windows[1]), (0, 545, 955, 535));
+        assert_eq!(frame(&windows[2]), (965, 0, 955, 1080));
+    }
+
+    #[test]
+    fn test_two_main_windows_horizontal() {
+        let mut state = LayoutState {
+            main_count: 2,
+            inner_gap: 10,
+            ..Default::default()
+        };
+        let result = handle_command(&mut state, "set-main-orientation", &["h".to_string()]);
+        assert!(matches!(result, LayoutResult::Ok));
+
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2, 3]);
+
+        // Main area of 955px split side by side: (955 - 10) / 2 = 472, last absorbs rounding
+        assert_eq!(frame(&windows[0]), (0, 0, 472, 1080));
+        assert_eq!(frame(&windows[1]), (482, 0, 473, 1080));
+        assert_eq!(frame(&windows[2]), (965, 0, 955, 1080));
+    }
+
+    #[test]
+    fn test_main_width_overrides_ratio() {
+        let mut state = LayoutState::default();
+        let result = handle_command(&mut state, "set-main-width", &["800".to_string()]);
+        assert!(matches!(result, LayoutResult::Ok));
+
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2]);
+        assert_eq!(frame(&windows[0]), (0, 0, 800, 1080));
+        assert_eq!(frame(&windows[1]), (800, 0, 1120, 1080));
+
+        // Same width on a smaller display
+        let windows = generate_layout(&state, 1280, 800, &[1, 2]);
+        assert_eq!(windows[0].width, 800);
+
+        handle_command(&mut state, "clear-main-width", &[]);
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2]);
+        assert_eq!(windows[0].width, 960);
+    }
+
+    #[test]
+    fn test_main_width_clamped_to_frame() {
+        let state = LayoutState {
+            main_width: Some(3000),
+            inner_gap: 10,
+            ..Default::default()
+        };
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2]);
+        assert_eq!(windows[0].width, 1910 - MIN_TILING_SIZE);
+        // The stack stays visible
+        assert_eq!(frame(&windows[1]), (1820, 0, MIN_TILING_SIZE, 1080));
+
+        // On a frame narrower than the minimum, main and stack share it
+        let windows = generate_layout(&state, 130, 100, &[1, 2]);
+        assert_eq!((windows[0].width, windows[1].width), (60, 60));
+
+        // Without a stack the main window still fills the frame
+        let windows = generate_layout(&state, 1920, 1080, &[1]);
+        assert_eq!(windows[0].width, 1920);
+    }
+
+    #[test]
+    fn test_set_main_width_rejects_invalid_values() {
+        let mut state = LayoutState::default();
+        for arg in ["0", "-5", "wide"] {
+            let result = handle_command(&mut state, "set-main-width", &[arg.to_string()]);
+            assert!(matches!(result, LayoutResult::Error { .. }));
+        }
+        assert_eq!(state.main_width, None);
+
+        handle_command(&mut state, "set-main-width", &["640".to_string()]);
+        handle_command(&mut state, "reset", &[]);
+        assert_eq!(state.main_width, None);
+    }
+
+    #[test]
+    fn test_main_orientation_commands() {
+        let mut state = LayoutState::default();
+        handle_command(&mut state, "toggle-main-orientation", &[]);
+        assert_eq!(state.main_orientation, MainOrientation::Horizontal);
+        handle_command(&mut state, "toggle-main-orientation", &[]);
+        assert_eq!(state.main_orientation, MainOrientation::Vertical);
+
+        let result = handle_command(
+            &mut state,
+            "set-main-orientation",
+            &["diagonal".to_string()],
+        );
+        assert!(matches!(result, LayoutResult::Error { .. }));
+
+        handle_command(
+            &mut state,
+            "set-main-orientation",
+            &["horizontal".to_string()],
+        );
+        handle_command(&mut state, "reset", &[]);
+        assert_eq!(state.main_orientation, MainOrientation::Vertical);
+    }
+
+    #[test]
+    fn test_set_stack_ratio_rejects_out_of_range() {
+        let mut state = LayoutState::default();
+        let result = handle_command(&mut state, "set-stack-ratio", &["0.95".to_string()]);
+        assert!(matches!(result, LayoutResult::Error { .. }));
+        assert_eq!(state.stack_ratio, None);
+
+        handle_command(&mut state, "set-stack-ratio", &["0.3".to_string()]);
+        handle_command(&mut state, "reset", &[]);
+        assert_eq!(state.stack_ratio, None);
+    }
+
+    #[test]
+    fn test_outer_gap_insets_single_window() {
+        let mut state = LayoutState::default();
+        let result = handle_command(&mut state, "set-outer-gap", &["10".to_string()]);
+        assert!(matches!(result, LayoutResult::Ok));
+
+        let windows = generate_layout(&state, 1920, 1080, &[1]);
+        assert_eq!(frame(&windows[0]), (10, 10, 1900, 1060));
+
+        handle_command(&mut state, "reset", &[]);
+        assert_eq!(state.outer_gap, OuterGap::default());
+    }
+
+    #[test]
+    fn test_outer_gap_with_inner_gap() {
+        let state = LayoutState {
+            inner_gap: 5,
+            outer_gap: OuterGap::all(10),
+            ..Default::default()
+        };
+        let windows = generate_layout(&state, 1920, 1080, &[1, 2]);
+
+        // Usable width = 1900, available = 1895, main = 947
+        assert_eq!(frame(&windows[0]), (10, 10, 947, 1060));
+        assert_eq!(frame(&windows[1]), (962, 10, 948, 1060));
+    }
+
+    #[test]
+    fn test_top_gap_only() {
+        let mut state = LayoutState::default();
+        let result = handle_command(&mut state, "set-gap-top", &["40".to_string()]);
+        assert!(matches!(result, LayoutResult::Ok));
+
+        let windows = generate_layout(&state, 1920, 1080, &[1]);
+        assert_eq!(frame(&windows[0]), (0, 40, 1920, 1040));
+    }
+
+    #[test]
+    fn test_edge_gaps_larger_than_frame() {
+        let mut state = LayoutState::default();
+        handle_command(&mut state, "set-gap-left", &["1500".to_string()]);
+        handle_command(&mut state, "set-gap-right", &["1500".to_string()]);
+
+        // The width saturates to zero instead of wrapping
+        let windows = generate_layout(&state, 1920, 1080, &[1]);
+        assert_eq!(frame(&windows[0]), (1500, 0, 0, 1080));
+
+        match handle_command(&mut state, "get-outer-gap", &[]) {
+            LayoutResult::Value { value } => assert_eq!(value, "0 0 1500 1500"),
+            _ => panic!("Expected Value"),
+        }
+    }
+
+    /// Feed one JSON line through the engine the way `main` does.
+    fn roundtrip(state: &mut LayoutState, line: &str) -> String {
+        let msg: LayoutMessage = serde_json::from_str(line).unwrap();
+        serde_json::to_string(&handle_message(state, msg)).unwrap()
+    }
+
+    #[test]
+    fn test_set_main_ratio_over_protocol() {
+        let mut state = LayoutState::default();
+        let reply = roundtrip(
+            &mut state,
+            r#"{"type":"command","cmd":"set-main-ratio","args":["0.6"]}"#,
+        );
+        assert_eq!(reply, r#"{"type":"ok"}"#);
+
+        let reply = roundtrip(
+            &mut state,
+            r#"{"type":"layout","width":1000,"height":800,"windows":[5,6]}"#,
+        );
+        let result: LayoutResult = serde_json::from_str(&reply).unwrap();
+        match result {
+            LayoutResult::Layout { windows } => {
+                assert_eq!((windows[0].id, windows[0].width), (5, 600));
+                assert_eq!((windows[1].id, windows[1].x), (6, 600));
+            }
+            _ => panic!("Expected Layout"),
+        }
+
+        let reply = roundtrip(
+            &mut state,
+            r#"{"type":"command","cmd":"set-main-ratio","args":["2"]}"#,
+        );
+        assert!(reply.starts_with(r#"{"type":"error""#));
+    }
+
+    #[test]
+    fn test_last_input_echoes_layout_message() {
+        let mut state = LayoutState::default();
+        handle_message(
+            &mut state,
+            LayoutMessage::Layout {
+                width: 1920,
+                height: 1080,
+                windows: vec![3, 1, 2],
+            },
+        );
+
+        match handle_message(&mut state, LayoutMessage::LastInput) {
+            LayoutResult::LastInput {
+                width,
+                height,
+                windows,
+            } => {
+                assert_eq!((width, height), (1920, 1080));
+                assert_eq!(windows, vec![3, 1, 2]);
+            }
+            _ => panic!("Expected LastInput"),
+        }
+    }
+
+    #[test]
+    fn test_last_input_before_any_layout_is_error() {
+        let mut state = LayoutState::default();
+        let result = handle_message(&mut state, LayoutMessage::LastInput);
+        assert!(matches!(result, LayoutResult::Error { .. }));
+    }
+
+    #[test]
+    fn test_reset_restores_defaults() {
+        let mut state = LayoutState {
+            main_window_id: Some(3),
+            focused_window_id: Some(4),
+            ..Default::default()
+        };
+        for (cmd, arg) in [
+            ("set-main-ratio", "0.7"),
+            ("set-main-count", "2"),
+            ("set-main-width", "800"),
+            ("set-main-orientation", "horizontal"),
+            ("set-stack-ratio", "0.6"),
+            ("set-inner-gap", "12"),
+            ("set-outer-gap", "20"),
+        ] {
+            let result = handle_command(&mut state, cmd, &[arg.to_string()]);
+            assert!(matches!(result, LayoutResult::Ok), "{}", cmd);
+        }
+
+        let result = handle_command(&mut state, "reset", &[]);
+        assert!(matches!(result, LayoutResult::Ok));
+
+        let defaults = LayoutState::default();
+        assert_eq!(state.main_ratio, defaults.main_ratio);
+        assert_eq!(state.main_count, defaults.main_count);
+        assert_eq!(state.main_width, defaults.main_width);
+        assert_eq!(state.main_orientation, defaults.main_orientation);
+        assert_eq!(state.stack_ratio, defaults.stack_ratio);
+        assert_eq!(state.inner_gap, defaults.inner_gap);
+        assert_eq!(state.outer_gap, defaults.outer_gap);
+        // Which windows are main and focused is not a parameter
+        assert_eq!(state.main_window_id, Some(3));
+        assert_eq!(state.focused_window_id, Some(4));
+    }
+
+    #[test]
+    fn test_last_input_survives_reset() {
+        let mut state = LayoutState::default();
+        handle_message(
+            &mut state,
+            LayoutMessage::Layout {
+                width: 800,
+                height: 600,
+                windows: vec![7, 8],
+            },
+        );
+        handle_command(&mut state, "reset", &[]);
+
+        match handle_message(&mut state, LayoutMessage::LastInput) {
+            LayoutResult::LastInput { windows, .. } => assert_eq!(windows, vec![7, 8]),
+            _ => panic!("Expected LastInput"),
+        }
+    }
+
+    #[test]
+    fn test_describe_lists_accepted_commands() {
+        let mut state = LayoutState::default();
+        assert_describes("tatami", |msg| handle_message(&mut state, msg));
+    }
+}
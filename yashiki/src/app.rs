@@ -1,14 +1,19 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::ptr;
 use std::sync::atomic::{AtomicPtr, Ordering};
 use std::sync::mpsc as std_mpsc;
 use std::sync::Arc;
+use std::time::Instant;
 
 use anyhow::Result;
 use core_foundation::base::TCFType;
 use core_foundation::runloop::{kCFRunLoopDefaultMode, CFRunLoop};
+use core_foundation_sys::date::CFAbsoluteTimeGetCurrent;
 use core_foundation_sys::runloop::{
-    CFRunLoopAddSource, CFRunLoopGetMain, CFRunLoopSourceContext, CFRunLoopSourceCreate,
+    kCFRunLoopBeforeWaiting, CFRunLoopActivity, CFRunLoopAddObserver, CFRunLoopAddSource,
+    CFRunLoopAddTimer, CFRunLoopGetMain, CFRunLoopObserverContext, CFRunLoopObserverCreate,
+    CFRunLoopObserverRef, CFRunLoopSourceContext, CFRunLoopSourceCreate, CFRunLoopTimerContext,
+    CFRunLoopTimerCreate, CFRunLoopTimerRef, CFRunLoopTimerSetNextFireDate,
 };
 use objc2::rc::Retained;
 use objc2_app_kit::{NSApplication, NSApplicationActivationPolicy, NSEvent, NSEventType};
@@ -26,27 +31,31 @@ mod sync_helper;
 
 use channels::{create_channels, run_async, IpcCommandWithResponse, MainChannels, SnapshotRequest};
 use dispatch::dispatch_command;
-use focus::{notify_layout_focus, switch_tag_for_focused_window};
+use effects::execute_effects;
+use focus::{notify_layout_focus, reassert_always_on_top, switch_tag_for_focused_window};
 use retile::{do_retile, do_retile_display};
 use sync_helper::{process_new_windows, sync_and_process_new_windows, sync_focused_and_process};
 
 use crate::core::State;
+use crate::effect::Effect;
 use crate::event::Event;
 use crate::event_emitter::{create_snapshot, EventEmitter};
+use crate::ipc;
 use crate::layout::LayoutEngineManager;
 use crate::macos;
 use crate::macos::{
-    DisplayReconfigEvent, HotkeyManager, MousePosition, MouseTracker, ObserverManager,
-    WorkspaceEvent, WorkspaceWatcher,
+    DisplayReconfigEvent, DragEvent, DragTracker, HotkeyManager, MousePosition, MouseTracker,
+    ObserverManager, WorkspaceEvent, WorkspaceWatcher,
 };
 use crate::pid;
 use crate::platform::{MacOSWindowManipulator, MacOSWindowSystem, WindowManipulator};
-use yashiki_ipc::Command;
+use yashiki_ipc::{Command, Response};
 
 struct RunLoopContext {
     ipc_cmd_rx: std_mpsc::Receiver<IpcCommandWithResponse>,
     hotkey_cmd_rx: std_mpsc::Receiver<Command>,
     mouse_event_rx: std_mpsc::Receiver<MousePosition>,
+    drag_event_rx: std_mpsc::Receiver<DragEvent>,
     observer_event_rx: std_mpsc::Receiver<Event>,
     workspace_event_rx: std_mpsc::Receiver<WorkspaceEvent>,
     snapshot_request_rx: std_mpsc::Receiver<SnapshotRequest>,
@@ -58,9 +67,30 @@ struct RunLoopContext {
     layout_engine_manager: RefCell<LayoutEngineManager>,
     hotkey_manager: RefCell<HotkeyManager>,
     mouse_tracker: RefCell<MouseTracker>,
+    drag_tracker: RefCell<DragTracker>,
     window_system: MacOSWindowSystem,
     window_manipulator: MacOSWindowManipulator,
     ns_app: Retained<NSApplication>,
+    /// Wakes the run loop to flush work deferred by the debouncers
+    tick_timer: Cell<CFRunLoopTimerRef>,
+}
+
+/// Fire date for the idle tick timer: it only runs when rescheduled by `schedule_tick`.
+const TICK_TIMER_IDLE_SECS: f64 = 1.0e9;
+
+/// Arm the tick timer for the earliest deferred deadline, if any.
+fn schedule_tick(ctx: &RunLoopContext) {
+    let timer = ctx.tick_timer.get();
+    let Some(deadline) = ctx.state.borrow().next_tick_deadline() else {
+        return;
+    };
+    if timer.is_null() {
+        return;
+    }
+    let delay = deadline.saturating_duration_since(Instant::now());
+    unsafe {
+        CFRunLoopTimerSetNextFireDate(timer, CFAbsoluteTimeGetCurrent() + delay.as_secs_f64());
+    }
 }
 
 pub struct App {}
@@ -97,8 +127,9 @@ impl App {
         let app = App {};
         app.run_main_loop(main_channels);
 
-        // Clean up PID file on exit
+        // Clean up PID file and sockets on exit
         pid::remove_pid();
+        ipc::remove_sockets();
         Ok(())
     }
 
@@ -114,6 +145,7 @@ impl App {
             display_reconfig_tx,
             display_reconfig_rx,
             ipc_source: ipc_source_ptr,
+            subscriber_count,
         } = channels;
 
         tracing::info!("Starting main loop");
@@ -144,6 +176,16 @@ impl App {
         let window_system = MacOSWindowSystem;
         let mut state = State::new();
         state.config.exec_path = build_initial_exec_path();
+        state.event_subscribers = subscriber_count;
+        if let Some(dir) = crate::core::snapshot_dir() {
+            state.snapshots = crate::core::load_snapshots(&dir);
+        }
+        if let Some(path) = crate::core::window_overrides_path() {
+            state.window_overrides = crate::core::load_window_overrides(&path)
+                .into_iter()
+                .map(|o| (o.id, o))
+                .collect();
+        }
         // Initial sync has no hidden windows, so rehide_moves is always empty
         // Note: new_window_ids are not processed here - rules aren't loaded yet,
         // ApplyRules command is sent after init script runs
@@ -186,11 +228,19 @@ impl App {
         let (mouse_event_tx, mouse_event_rx) = std_mpsc::channel::<MousePosition>();
         let mouse_tracker = MouseTracker::new(mouse_event_tx, mouse_source_clone);
 
+        // Create drag tracker (shares the mouse CFRunLoopSource)
+        let (drag_event_tx, drag_event_rx) = std_mpsc::channel::<DragEvent>();
+        let mut drag_tracker = DragTracker::new(drag_event_tx, Arc::clone(&mouse_source_ptr));
+        if let Err(e) = drag_tracker.set_modifiers(state.borrow().config.drag_modifier) {
+            tracing::warn!("Failed to start drag tracker: {}", e);
+        }
+
         // Create shared context for IPC/hotkey/display sources
         let context = Box::new(RunLoopContext {
             ipc_cmd_rx,
             hotkey_cmd_rx,
             mouse_event_rx,
+            drag_event_rx,
             observer_event_rx,
             workspace_event_rx,
             snapshot_request_rx,
@@ -202,9 +252,11 @@ impl App {
             layout_engine_manager,
             hotkey_manager: RefCell::new(hotkey_manager),
             mouse_tracker: RefCell::new(mouse_tracker),
+            drag_tracker: RefCell::new(drag_tracker),
             window_system,
             window_manipulator,
             ns_app: ns_app.clone(),
+            tick_timer: Cell::new(ptr::null_mut()),
         });
         let context_ptr = Box::into_raw(context) as *mut std::ffi::c_void;
 
@@ -221,6 +273,7 @@ impl App {
             // Process all pending IPC commands
             while let Ok((cmd, resp_tx)) = ctx.ipc_cmd_rx.try_recv() {
                 tracing::debug!("Received IPC command: {:?}", cmd);
+                ctx.state.borrow_mut().stats.record_event();
 
                 let response = dispatch_command(
                     &cmd,
@@ -232,14 +285,18 @@ impl App {
                     &ctx.event_emitter,
                     &ctx.observer_manager,
                 );
+                let should_quit =
+                    matches!(cmd, Command::Quit { .. }) && matches!(response, Response::Ok);
                 let _ = resp_tx.blocking_send(response);
 
                 // Handle Quit command after sending response
-                if matches!(cmd, Command::Quit) {
+                if should_quit {
                     // Terminate all tracked processes
                     for process in ctx.state.borrow().tracked_processes.iter() {
                         ctx.window_manipulator.terminate_process(process.pid);
                     }
+                    // The run loop context is never dropped, so stop layout engines explicitly
+                    ctx.layout_engine_manager.borrow_mut().shutdown();
                     // Stop NSApplication and post a dummy event to exit run() immediately
                     ctx.ns_app.stop(None);
                     // Post dummy event to wake up NSApp.run()
@@ -259,6 +316,9 @@ impl App {
                 }
             }
 
+            // Commands may start tick-driven work (main-ratio-animate)
+            schedule_tick(ctx);
+
             // Apply pending hotkey binding changes
             if let Err(e) = ctx.hotkey_manager.borrow_mut().ensure_tap() {
                 tracing::error!("Failed to update hotkey tap: {}", e);
@@ -284,6 +344,12 @@ impl App {
                     }
                 }
             }
+
+            // Sync drag tracker with the configured drag modifier
+            let drag_modifier = ctx.state.borrow().config.drag_modifier;
+            if let Err(e) = ctx.drag_tracker.borrow_mut().set_modifiers(drag_modifier) {
+                tracing::error!("Failed to update drag tracker: {}", e);
+            }
         }
 
         let mut source_context = CFRunLoopSourceContext {
@@ -324,6 +390,7 @@ impl App {
             // Process all pending hotkey commands
             while let Ok(cmd) = ctx.hotkey_cmd_rx.try_recv() {
                 tracing::debug!("Received hotkey command: {:?}", cmd);
+                ctx.state.borrow_mut().stats.record_event();
 
                 let _ = dispatch_command(
                     &cmd,
@@ -336,6 +403,7 @@ impl App {
                     &ctx.observer_manager,
                 );
             }
+            schedule_tick(ctx);
         }
 
         let mut hotkey_source_context = CFRunLoopSourceContext {
@@ -370,7 +438,7 @@ impl App {
             tracing::info!("Hotkey CFRunLoopSource created and registered");
         }
 
-        // Create CFRunLoopSource for mouse events (auto-raise)
+        // Create CFRunLoopSource for mouse events (auto-raise, modifier+drag)
         extern "C" fn mouse_source_callback(info: *const std::ffi::c_void) {
             let ctx = unsafe { &*(info as *const RunLoopContext) };
 
@@ -379,6 +447,34 @@ impl App {
 
             use crate::core::WindowAtPoint;
 
+            // Move/resize the dragged floating window. Only the last of consecutive
+            // updates is applied, so slow AX writes don't replay stale positions.
+            let drag_events: Vec<DragEvent> = ctx.drag_event_rx.try_iter().collect();
+            for (i, event) in drag_events.iter().enumerate() {
+                match *event {
+                    DragEvent::Start { kind, x, y } => {
+                        ctx.state.borrow_mut().begin_window_drag(kind, x, y);
+                    }
+                    DragEvent::Update { x, y } => {
+                        if matches!(drag_events.get(i + 1), Some(DragEvent::Update { .. })) {
+                            continue;
+                        }
+                        let effect = ctx.state.borrow_mut().update_window_drag(x, y);
+                        if let Some(effect) = effect {
+                            if let Err(e) = execute_effects(
+                                vec![effect],
+                                &ctx.state,
+                                &ctx.layout_engine_manager,
+                                &ctx.window_manipulator,
+                            ) {
+                                tracing::warn!("Failed to drag window: {}", e);
+                            }
+                        }
+                    }
+                    DragEvent::End => ctx.state.borrow_mut().end_window_drag(),
+                }
+            }
+
             // Process all pending mouse events
             while let Ok(pos) = ctx.mouse_event_rx.try_recv() {
                 // Check if auto-raise is enabled
@@ -387,6 +483,16 @@ impl App {
                     continue;
                 }
 
+                // Windows may still be sliding under the pointer from a retile
+                if ctx
+                    .state
+                    .borrow()
+                    .auto_raise_state
+                    .is_suppressed_at(Instant::now())
+                {
+                    continue;
+                }
+
                 let delay_ms = ctx.state.borrow().config.auto_raise_delay_ms;
 
                 // Find topmost window at cursor position (managed or ignored)
@@ -411,6 +517,7 @@ impl App {
                                     if state.focused != Some(window_id) {
                                         // Set focus intent before focusing
                                         state.set_focus_intent(window_id, pid);
+                                        let raise = state.config.raise_on_focus;
                                         drop(state); // Release borrow before manipulator call
                                         tracing::debug!(
                                             "Auto-raise: focusing window {} at ({}, {})",
@@ -418,7 +525,7 @@ impl App {
                                             pos.x,
                                             pos.y
                                         );
-                                        ctx.window_manipulator.focus_window(window_id, pid);
+                                        ctx.window_manipulator.focus_window(window_id, pid, raise);
                                         ctx.state.borrow_mut().set_focused(Some(window_id));
                                         ctx.event_emitter.emit_window_focused(Some(window_id));
                                         // Clear hover state after focusing
@@ -487,16 +594,18 @@ impl App {
                 );
 
                 // Handle display change
+                let gaps_suppressed_before = ctx.state.borrow().gaps_suppressed();
                 let result = ctx
                     .state
                     .borrow_mut()
                     .handle_display_change(&ctx.window_system);
+                // Gaps turning on/off affects every display, not just the changed ones
+                let gaps_changed = ctx.state.borrow().gaps_suppressed() != gaps_suppressed_before;
 
                 // Emit display events
-                let focused_display = ctx.state.borrow().focused_display;
                 for display in &result.added {
                     ctx.event_emitter
-                        .emit_display_added(display, focused_display);
+                        .emit_display_added(display, &ctx.state.borrow());
                 }
                 for display_id in &result.removed {
                     ctx.event_emitter.emit_display_removed(*display_id);
@@ -506,8 +615,7 @@ impl App {
                 {
                     let state = ctx.state.borrow();
                     for disp in state.displays.values() {
-                        ctx.event_emitter
-                            .emit_display_updated(disp, focused_display);
+                        ctx.event_emitter.emit_display_updated(disp, &state);
                     }
                 }
 
@@ -527,7 +635,7 @@ impl App {
                 );
 
                 // Retile affected displays
-                if !result.displays_to_retile.is_empty() {
+                if !result.displays_to_retile.is_empty() && !gaps_changed {
                     for display_id in result.displays_to_retile {
                         do_retile_display(
                             &ctx.state,
@@ -592,6 +700,7 @@ impl App {
 
             // Process workspace events (app launch/terminate)
             while let Ok(event) = ctx.workspace_event_rx.try_recv() {
+                ctx.state.borrow_mut().stats.record_event();
                 match event {
                     WorkspaceEvent::AppLaunched { pid } => {
                         tracing::info!("App launched, adding observer for pid {}", pid);
@@ -769,6 +878,19 @@ impl App {
             // Process observer events and forward to tokio
             let mut needs_retile = false;
             while let Ok(event) = ctx.observer_event_rx.try_recv() {
+                ctx.state.borrow_mut().stats.record_event();
+
+                // Drags flood move/resize events: sync each app at most once per
+                // MOVE_SYNC_INTERVAL, with a trailing sync from the tick timer
+                if let Event::WindowMoved { pid } | Event::WindowResized { pid } = event {
+                    if !ctx
+                        .state
+                        .borrow_mut()
+                        .request_move_sync(pid, Instant::now())
+                    {
+                        continue;
+                    }
+                }
                 let is_focus_event = matches!(
                     event,
                     Event::FocusedWindowChanged | Event::ApplicationActivated { .. }
@@ -835,6 +957,38 @@ impl App {
                     &ctx.event_emitter,
                 );
 
+                // The focused window was destroyed: focus its successor in the window order
+                let successor = ctx.state.borrow_mut().take_focus_successor();
+                if let Some((window_id, pid)) = successor {
+                    tracing::info!("Focusing successor {} of destroyed window", window_id);
+                    let effects = vec![Effect::FocusWindow {
+                        window_id,
+                        pid,
+                        is_output_change: false,
+                    }];
+                    if let Err(e) = execute_effects(
+                        effects,
+                        &ctx.state,
+                        &ctx.layout_engine_manager,
+                        &ctx.window_manipulator,
+                    ) {
+                        tracing::warn!("Failed to focus successor window: {}", e);
+                    }
+                }
+
+                // Destroyed windows take their manage/unmanage overrides with them
+                if ctx.state.borrow_mut().take_window_overrides_pruned() {
+                    let overrides = ctx.state.borrow().window_override_list();
+                    if let Err(e) = execute_effects(
+                        vec![Effect::PersistWindowOverrides { overrides }],
+                        &ctx.state,
+                        &ctx.layout_engine_manager,
+                        &ctx.window_manipulator,
+                    ) {
+                        tracing::warn!("Failed to save window overrides: {}", e);
+                    }
+                }
+
                 // On external focus change, notify layout engine and switch tag if focused window is hidden
                 if is_focus_event {
                     let focused_id = ctx.state.borrow().focused;
@@ -860,7 +1014,8 @@ impl App {
                                 "Suppressing spurious focus change, refocusing window {}",
                                 intended_id
                             );
-                            ctx.window_manipulator.focus_window(intended_id, pid);
+                            let raise = ctx.state.borrow().config.raise_on_focus;
+                            ctx.window_manipulator.focus_window(intended_id, pid, raise);
                             ctx.state.borrow_mut().set_focused(Some(intended_id));
                             // Skip further focus handling - don't switch tags
                             continue;
@@ -871,6 +1026,7 @@ impl App {
                     ctx.event_emitter.emit_window_focused(focused_id);
 
                     if let Some(focused_id) = focused_id {
+                        reassert_always_on_top(&ctx.state, &ctx.window_manipulator, focused_id);
                         if notify_layout_focus(&ctx.state, &ctx.layout_engine_manager, focused_id) {
                             needs_retile = true;
                         }
@@ -898,12 +1054,17 @@ impl App {
                 }
             }
             if needs_retile {
-                do_retile(
-                    &ctx.state,
-                    &ctx.layout_engine_manager,
-                    &ctx.window_manipulator,
-                );
+                // Event storms (drags, app switching) retile at most once per tick
+                if ctx.state.borrow_mut().request_retile(Instant::now()) {
+                    do_retile(
+                        &ctx.state,
+                        &ctx.layout_engine_manager,
+                        &ctx.window_manipulator,
+                    );
+                }
             }
+            // Deferred retiles, move syncs and focus log lines are flushed by the tick timer
+            schedule_tick(ctx);
         }
 
         let mut observer_source_context = CFRunLoopSourceContext {
@@ -938,6 +1099,143 @@ impl App {
             tracing::info!("Observer CFRunLoopSource created and registered");
         }
 
+        // Create CFRunLoopTimer that flushes deferred retiles
+        extern "C" fn tick_timer_callback(_timer: CFRunLoopTimerRef, info: *mut std::ffi::c_void) {
+            let ctx = unsafe { &*(info as *const RunLoopContext) };
+            let now = Instant::now();
+
+            let ratio_step = ctx.state.borrow_mut().take_due_ratio_step(now);
+            if let Some((layout, display_ids, ratio)) = ratio_step {
+                let sent = ctx.layout_engine_manager.borrow_mut().send_command(
+                    &layout,
+                    "set-main-ratio",
+                    &[ratio.to_string()],
+                );
+                match sent {
+                    // The engine's ratio is shared by every display showing the layout
+                    Ok(_) => {
+                        for display_id in display_ids {
+                            do_retile_display(
+                                &ctx.state,
+                                &ctx.layout_engine_manager,
+                                &ctx.window_manipulator,
+                                display_id,
+                            );
+                        }
+                    }
+                    Err(e) => {
+                        tracing::warn!("Stopping main ratio animation: {}", e);
+                        ctx.state.borrow_mut().ratio_animation = None;
+                    }
+                }
+            }
+
+            // Trailing syncs of move/resize events coalesced during a drag
+            let pids = ctx.state.borrow_mut().take_due_move_syncs(now);
+            for pid in pids {
+                let (changed, _, rehide_moves) = ctx
+                    .state
+                    .borrow_mut()
+                    .handle_event(&ctx.window_system, &Event::WindowMoved { pid });
+                if !rehide_moves.is_empty() {
+                    ctx.window_manipulator.apply_window_moves(&rehide_moves);
+                }
+                if changed && ctx.state.borrow_mut().request_retile(now) {
+                    do_retile(
+                        &ctx.state,
+                        &ctx.layout_engine_manager,
+                        &ctx.window_manipulator,
+                    );
+                }
+            }
+
+            ctx.state.borrow_mut().flush_focus_log(now);
+
+            if ctx.state.borrow_mut().take_due_retile(now) {
+                do_retile(
+                    &ctx.state,
+                    &ctx.layout_engine_manager,
+                    &ctx.window_manipulator,
+                );
+            }
+            schedule_tick(ctx);
+        }
+
+        let mut tick_timer_context = CFRunLoopTimerContext {
+            version: 0,
+            info: context_ptr,
+            retain: None,
+            release: None,
+            copyDescription: None,
+        };
+
+        let tick_timer = unsafe {
+            CFRunLoopTimerCreate(
+                ptr::null(),
+                CFAbsoluteTimeGetCurrent() + TICK_TIMER_IDLE_SECS,
+                TICK_TIMER_IDLE_SECS,
+                0,
+                0,
+                tick_timer_callback,
+                &mut tick_timer_context,
+            )
+        };
+        if tick_timer.is_null() {
+            tracing::error!("Failed to create CFRunLoopTimer for tick");
+        } else {
+            unsafe {
+                CFRunLoopAddTimer(CFRunLoopGetMain(), tick_timer, kCFRunLoopDefaultMode);
+                (*(context_ptr as *const RunLoopContext))
+                    .tick_timer
+                    .set(tick_timer);
+            }
+            tracing::info!("Tick CFRunLoopTimer created and registered");
+        }
+
+        // Publish the drag tap's hit-test snapshot before the run loop sleeps, so it
+        // reflects whatever the callbacks above changed
+        extern "C" fn before_waiting_callback(
+            _observer: CFRunLoopObserverRef,
+            _activity: CFRunLoopActivity,
+            info: *mut std::ffi::c_void,
+        ) {
+            let ctx = unsafe { &*(info as *const RunLoopContext) };
+            let drag_tracker = ctx.drag_tracker.borrow();
+            if drag_tracker.is_active() {
+                drag_tracker.set_targets(ctx.state.borrow().drag_targets());
+            }
+        }
+
+        let mut before_waiting_context = CFRunLoopObserverContext {
+            version: 0,
+            info: context_ptr,
+            retain: None,
+            release: None,
+            copyDescription: None,
+        };
+
+        let before_waiting_observer = unsafe {
+            CFRunLoopObserverCreate(
+                ptr::null(),
+                kCFRunLoopBeforeWaiting,
+                1,
+                0,
+                before_waiting_callback,
+                &mut before_waiting_context,
+            )
+        };
+        if before_waiting_observer.is_null() {
+            tracing::error!("Failed to create CFRunLoopObserver for drag targets");
+        } else {
+            unsafe {
+                CFRunLoopAddObserver(
+                    CFRunLoopGetMain(),
+                    before_waiting_observer,
+                    kCFRunLoopDefaultMode,
+                );
+            }
+        }
+
         // Run init script in background thread
         std::thread::spawn(|| {
             run_init_script();
@@ -1040,11 +1338,11 @@ fn send_apply_rules(needs_delay: bool) {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::app::command::process_command;
+    use crate::app::command::{process_command, record_window_override};
     use crate::app::state_events::{capture_event_state, emit_state_change_events};
     use crate::effect::Effect;
     use crate::platform::mock::{create_test_display, create_test_window, MockWindowSystem};
-    use yashiki_ipc::{Command, Direction, Response};
+    use yashiki_ipc::{Command, Direction, OutputDirection, OutputSpecifier, Response};
 
     fn setup_state() -> (State, HotkeyManager) {
         let ws = MockWindowSystem::new()
@@ -1077,6 +1375,7 @@ mod tests {
             &Command::ListWindows {
                 all: false,
                 debug: false,
+                visible: false,
             },
         );
         assert!(result.effects.is_empty());
@@ -1099,159 +1398,1324 @@ mod tests {
     }
 
     #[test]
-    fn test_list_windows_with_debug_includes_debug_fields() {
+    fn test_bind_label_round_trips_through_list_bindings() {
         let (mut state, mut hotkey_manager) = setup_state();
+        let bind = |key: &str, label: Option<&str>| Command::Bind {
+            key: key.to_string(),
+            action: Box::new(Command::WindowFocus {
+                direction: Direction::Left,
+                same_output: false,
+            }),
+            label: label.map(str::to_string),
+            args: vec![],
+        };
 
-        let result = process_command(
+        process_command(
             &mut state,
             &mut hotkey_manager,
-            &Command::ListWindows {
-                all: false,
-                debug: true,
-            },
+            &bind("cmd-h", Some("focus window left")),
         );
+        process_command(&mut state, &mut hotkey_manager, &bind("cmd-j", None));
 
-        assert!(result.effects.is_empty());
-
-        if let Response::Windows { windows } = result.response {
-            assert!(!windows.is_empty());
-            // Debug fields should be populated when debug=true
-            let first_window = &windows[0];
-            // window_level should be Some when debug=true
-            assert!(first_window.window_level.is_some());
-            // close_button should be Some when debug=true
-            assert!(first_window.close_button.is_some());
-            // Status should be None for non-all mode
-            assert!(first_window.status.is_none());
-        } else {
-            panic!("Expected Response::Windows");
-        }
+        let result = process_command(&mut state, &mut hotkey_manager, &Command::ListBindings);
+        let Response::Bindings { mut bindings } = result.response else {
+            panic!("Expected Bindings response");
+        };
+        bindings.sort_by(|a, b| a.key.cmp(&b.key));
+        assert_eq!(bindings[0].key, "cmd-h");
+        assert_eq!(bindings[0].label.as_deref(), Some("focus window left"));
+        assert_eq!(bindings[1].key, "cmd-j");
+        assert_eq!(bindings[1].label, None);
+
+        // Rebinding without a label drops the old one
+        process_command(&mut state, &mut hotkey_manager, &bind("cmd-h", None));
+        let result = process_command(&mut state, &mut hotkey_manager, &Command::ListBindings);
+        let Response::Bindings { bindings } = result.response else {
+            panic!("Expected Bindings response");
+        };
+        assert!(bindings.iter().all(|b| b.label.is_none()));
     }
 
     #[test]
-    fn test_list_windows_all_true_returns_empty_marker() {
+    fn test_export_bindings_returns_or_writes_content() {
         let (mut state, mut hotkey_manager) = setup_state();
+        process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::Bind {
+                key: "cmd-h".to_string(),
+                action: Box::new(Command::WindowFocus {
+                    direction: Direction::Left,
+                    same_output: false,
+                }),
+                label: Some("focus left".to_string()),
+                args: vec!["window-focus".to_string(), "left".to_string()],
+            },
+        );
 
-        // When all=true, process_command returns an empty marker
-        // (the actual implementation happens in handle_ipc_command with system access)
         let result = process_command(
             &mut state,
             &mut hotkey_manager,
-            &Command::ListWindows {
-                all: true,
-                debug: false,
+            &Command::ExportBindings { path: None },
+        );
+        let Response::BindingsExport { content } = result.response else {
+            panic!("Expected BindingsExport response");
+        };
+        assert!(content.contains("yashiki bind --label 'focus left' cmd-h window-focus left\n"));
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::ExportBindings {
+                path: Some("/tmp/bindings".to_string()),
             },
         );
+        assert!(matches!(result.response, Response::Ok));
+        assert_eq!(
+            result.effects,
+            vec![Effect::WriteBindingsExport {
+                path: "/tmp/bindings".to_string(),
+                content,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_window_toggle_always_on_top_raises_when_enabled() {
+        let (mut state, mut hotkey_manager) = setup_state();
+        let cmd = Command::WindowToggleAlwaysOnTop { id: None };
+
+        let result = process_command(&mut state, &mut hotkey_manager, &cmd);
+        assert_eq!(
+            result.effects,
+            vec![Effect::RaiseWindow {
+                window_id: 100,
+                pid: 1000
+            }]
+        );
 
+        let result = process_command(&mut state, &mut hotkey_manager, &cmd);
         assert!(result.effects.is_empty());
+        assert!(!state.windows.get(&100).unwrap().is_always_on_top);
 
-        if let Response::Windows { windows } = result.response {
-            // Empty marker - actual implementation is in handle_ipc_command
-            assert!(windows.is_empty());
-        } else {
-            panic!("Expected Response::Windows");
-        }
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::WindowToggleAlwaysOnTop { id: Some(999) },
+        );
+        assert!(matches!(result.response, Response::Error { .. }));
     }
 
     #[test]
-    fn test_tag_view_produces_correct_effects() {
+    fn test_snapshot_save_and_restore() {
         let (mut state, mut hotkey_manager) = setup_state();
 
         let result = process_command(
             &mut state,
             &mut hotkey_manager,
-            &Command::TagView {
-                tags: 0b10,
-                output: None,
+            &Command::SnapshotSave {
+                name: "work".to_string(),
             },
         );
-
-        assert!(matches!(result.response, Response::Ok));
-        assert_eq!(result.effects.len(), 3);
-
-        // Should have ApplyWindowMoves, RetileDisplays, FocusVisibleWindowIfNeeded
-        assert!(matches!(result.effects[0], Effect::ApplyWindowMoves(_)));
-        assert!(matches!(result.effects[1], Effect::RetileDisplays(_)));
         assert!(matches!(
-            result.effects[2],
-            Effect::FocusVisibleWindowIfNeeded
+            result.effects.as_slice(),
+            [Effect::PersistSnapshot { name, snapshot }]
+                if name == "work" && snapshot.windows.len() == 3
         ));
-    }
-
-    #[test]
-    fn test_window_focus_produces_focus_effect() {
-        let (mut state, mut hotkey_manager) = setup_state();
 
+        // A window that closed since the snapshot is skipped on restore
+        state.windows.remove(&102);
         let result = process_command(
             &mut state,
             &mut hotkey_manager,
-            &Command::WindowFocus {
-                direction: Direction::Next,
+            &Command::SnapshotRestore {
+                name: "work".to_string(),
             },
         );
+        assert_eq!(result.effects.len(), 4);
+        assert!(result.effects.contains(&Effect::MoveWindowToPosition {
+            window_id: 101,
+            pid: 1001,
+            x: 960,
+            y: 0,
+        }));
 
-        assert!(matches!(result.response, Response::Ok));
-        assert_eq!(result.effects.len(), 1);
-
-        match &result.effects[0] {
-            Effect::FocusWindow {
-                window_id,
-                pid,
-                is_output_change,
-            } => {
-                assert_eq!(*window_id, 101); // Next window after 100
-                assert_eq!(*pid, 1001);
-                assert!(!is_output_change);
-            }
-            _ => panic!("Expected FocusWindow effect"),
-        }
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::SnapshotRestore {
+                name: "missing".to_string(),
+            },
+        );
+        assert!(matches!(result.response, Response::Error { .. }));
     }
 
     #[test]
-    fn test_exec_produces_exec_effect() {
+    fn test_window_output_query() {
         let (mut state, mut hotkey_manager) = setup_state();
 
         let result = process_command(
             &mut state,
             &mut hotkey_manager,
-            &Command::Exec {
-                command: "open -a Safari".to_string(),
-                track: false,
-            },
+            &Command::WindowOutput { id: 101 },
         );
+        assert!(matches!(result.response, Response::OutputId { id: 1 }));
 
-        assert!(matches!(result.response, Response::Ok));
-        assert_eq!(result.effects.len(), 1);
-
-        match &result.effects[0] {
-            Effect::ExecCommand { command, .. } => {
-                assert_eq!(command, "open -a Safari");
-            }
-            _ => panic!("Expected ExecCommand effect"),
-        }
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::WindowOutput { id: 999 },
+        );
+        assert!(matches!(result.response, Response::Error { .. }));
     }
 
     #[test]
-    fn test_exec_tracked_produces_exec_tracked_effect() {
+    fn test_window_tags_query() {
         let (mut state, mut hotkey_manager) = setup_state();
+        state.windows.get_mut(&101).unwrap().tags = crate::core::Tag::from_mask(0b101);
 
         let result = process_command(
             &mut state,
             &mut hotkey_manager,
-            &Command::Exec {
-                command: "sleep 1000".to_string(),
-                track: true,
-            },
+            &Command::WindowTags { id: 101 },
         );
-
-        assert!(matches!(result.response, Response::Ok));
-        assert_eq!(result.effects.len(), 1);
-
-        match &result.effects[0] {
-            Effect::ExecCommandTracked { command, .. } => {
-                assert_eq!(command, "sleep 1000");
+        match result.response {
+            Response::WindowTags { id, tags } => {
+                assert_eq!(id, 101);
+                assert_eq!(tags, vec![1, 3]);
             }
-            _ => panic!("Expected ExecCommandTracked effect"),
+            _ => panic!("Expected WindowTags response"),
+        }
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::WindowTags { id: 999 },
+        );
+        assert!(matches!(result.response, Response::Error { .. }));
+    }
+
+    #[test]
+    fn test_exclusive_tags_view_uses_single_tag() {
+        let (mut state, mut hotkey_manager) = setup_state();
+        let view = Command::TagView {
+            tags: 0b110,
+            output: None,
+        };
+
+        process_command(&mut state, &mut hotkey_manager, &view);
+        assert_eq!(state.visible_tags().mask(), 0b110);
+
+        process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::SetExclusiveTags { enabled: true },
+        );
+        process_command(&mut state, &mut hotkey_manager, &view);
+        assert_eq!(state.visible_tags().mask(), 0b10);
+    }
+
+    #[test]
+    fn test_output_usable_frame_composes_menu_bar_gap_and_reservation() {
+        // The main display's frame starts below a 25px menu bar
+        let ws = MockWindowSystem::new()
+            .with_displays(vec![create_test_display(1, 0.0, 25.0, 1920.0, 1055.0)])
+            .with_windows(vec![]);
+        let mut state = State::new();
+        state.sync_all(&ws);
+        let (tx, _rx) = std_mpsc::channel();
+        let mut hotkey_manager =
+            HotkeyManager::new(tx, Arc::new(AtomicPtr::new(std::ptr::null_mut())));
+
+        for cmd in [
+            Command::ReserveEdge {
+                output: None,
+                edge: yashiki_ipc::Edge::Bottom,
+                size: 40,
+            },
+            Command::SetOuterGap {
+                values: vec!["10".to_string()],
+            },
+        ] {
+            process_command(&mut state, &mut hotkey_manager, &cmd);
+        }
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::OutputUsableFrame { output: None },
+        );
+        match result.response {
+            Response::UsableFrame {
+                output,
+                x,
+                y,
+                width,
+                height,
+            } => {
+                assert_eq!(output, 1);
+                assert_eq!((x, y), (10, 35));
+                assert_eq!((width, height), (1900, 1055 - 40 - 20));
+            }
+            other => panic!("Expected UsableFrame, got {:?}", other),
+        }
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::OutputUsableFrame {
+                output: Some(OutputSpecifier::Id(99)),
+            },
+        );
+        assert!(matches!(result.response, Response::Error { .. }));
+    }
+
+    #[test]
+    fn test_float_all_disable_retiles_once() {
+        let (mut state, mut hotkey_manager) = setup_state();
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::FloatAll { enabled: true },
+        );
+        assert!(result.effects.is_empty());
+        assert!(state.windows.values().all(|w| w.is_floating));
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::FloatAll { enabled: false },
+        );
+        assert_eq!(result.effects, vec![Effect::RetileDisplays(vec![1])]);
+        assert!(state.windows.values().all(|w| !w.is_floating));
+    }
+
+    #[test]
+    fn test_exclusive_tags_toggle_behaves_like_view() {
+        let (mut state, mut hotkey_manager) = setup_state();
+        let toggle = Command::TagToggle {
+            tags: 0b100,
+            output: None,
+        };
+
+        process_command(&mut state, &mut hotkey_manager, &toggle);
+        assert_eq!(state.visible_tags().mask(), 0b101);
+        process_command(&mut state, &mut hotkey_manager, &toggle);
+        assert_eq!(state.visible_tags().mask(), 0b1);
+
+        process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::SetExclusiveTags { enabled: true },
+        );
+        let result = process_command(&mut state, &mut hotkey_manager, &toggle);
+        assert_eq!(state.visible_tags().mask(), 0b100);
+        assert!(result.effects.contains(&Effect::RetileDisplays(vec![1])));
+
+        // Toggling the only visible tag again keeps it visible rather than combining
+        process_command(&mut state, &mut hotkey_manager, &toggle);
+        assert_eq!(state.visible_tags().mask(), 0b100);
+    }
+
+    #[test]
+    fn test_focus_urgent_switches_tag_and_focuses() {
+        let (mut state, mut hotkey_manager) = setup_state();
+        state.windows.get_mut(&101).unwrap().tags = crate::core::Tag::from_mask(0b10);
+        process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::WindowSetUrgent { id: 101 },
+        );
+
+        let result = process_command(&mut state, &mut hotkey_manager, &Command::FocusUrgent);
+
+        assert!(matches!(result.effects[0], Effect::ApplyWindowMoves(_)));
+        assert_eq!(
+            result.effects[1..],
+            [
+                Effect::Retile,
+                Effect::FocusWindow {
+                    window_id: 101,
+                    pid: 1001,
+                    is_output_change: false,
+                }
+            ]
+        );
+        assert_eq!(state.visible_tags().mask(), 0b10);
+    }
+
+    #[test]
+    fn test_focus_urgent_noop_without_urgent_windows() {
+        let (mut state, mut hotkey_manager) = setup_state();
+
+        let result = process_command(&mut state, &mut hotkey_manager, &Command::FocusUrgent);
+
+        assert!(matches!(result.response, Response::Ok));
+        assert!(result.effects.is_empty());
+    }
+
+    #[test]
+    fn test_get_config_reflects_changed_settings() {
+        let (mut state, mut hotkey_manager) = setup_state();
+        process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::LayoutSetDefault {
+                layout: "byobu".to_string(),
+            },
+        );
+        process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::SetOuterGap {
+                values: vec!["10".to_string(), "20".to_string()],
+            },
+        );
+
+        let result = process_command(&mut state, &mut hotkey_manager, &Command::GetConfig);
+
+        assert!(result.effects.is_empty());
+        match result.response {
+            Response::Config { config } => {
+                assert_eq!(config.default_layout, "byobu");
+                assert_eq!(config.default_tag, 0b1);
+                assert_eq!(
+                    config.outer_gap,
+                    yashiki_ipc::OuterGap::vertical_horizontal(10, 20)
+                );
+                assert_eq!(config.auto_raise, yashiki_ipc::AutoRaiseMode::Disabled);
+            }
+            _ => panic!("Expected Config response"),
+        }
+    }
+
+    #[test]
+    fn test_set_default_layout_params_updates_engine_manager() {
+        let (mut state, mut hotkey_manager) = setup_state();
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::SetDefaultLayoutParams {
+                main_ratio: Some(0.6),
+                inner_gap: Some(8),
+            },
+        );
+        let defaults = crate::core::LayoutDefaults {
+            main_ratio: Some(0.6),
+            inner_gap: Some(8),
+        };
+        assert!(matches!(result.response, Response::Ok));
+        assert_eq!(
+            result.effects,
+            vec![Effect::UpdateLayoutDefaults {
+                defaults: defaults.clone()
+            }]
+        );
+        assert_eq!(state.config.layout_defaults, defaults);
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::SetDefaultLayoutParams {
+                main_ratio: Some(1.5),
+                inner_gap: None,
+            },
+        );
+        assert!(matches!(result.response, Response::Error { .. }));
+        assert_eq!(state.config.layout_defaults, defaults);
+
+        let result = process_command(&mut state, &mut hotkey_manager, &Command::GetConfig);
+        match result.response {
+            Response::Config { config } => assert_eq!(
+                config.layout_defaults,
+                yashiki_ipc::LayoutDefaultsInfo {
+                    main_ratio: Some(0.6),
+                    inner_gap: Some(8),
+                }
+            ),
+            _ => panic!("Expected Config response"),
+        }
+    }
+
+    #[test]
+    fn test_layout_alias_sets_alias_and_retiles() {
+        let (mut state, mut hotkey_manager) = setup_state();
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::LayoutAlias {
+                alias: "myalias".to_string(),
+                target: "byobu".to_string(),
+            },
+        );
+        assert!(matches!(result.response, Response::Ok));
+        assert_eq!(
+            result.effects,
+            vec![
+                Effect::SetLayoutAlias {
+                    alias: "myalias".to_string(),
+                    target: "byobu".to_string(),
+                },
+                Effect::Retile,
+            ]
+        );
+
+        for (alias, target) in [("myalias", "myalias"), ("my alias", "byobu")] {
+            let result = process_command(
+                &mut state,
+                &mut hotkey_manager,
+                &Command::LayoutAlias {
+                    alias: alias.to_string(),
+                    target: target.to_string(),
+                },
+            );
+            assert!(matches!(result.response, Response::Error { .. }));
+            assert!(result.effects.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_clear_reservations_restores_display_frame() {
+        let (mut state, mut hotkey_manager) = setup_state();
+        process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::ReserveEdge {
+                output: None,
+                edge: yashiki_ipc::Edge::Top,
+                size: 30,
+            },
+        );
+        process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::SetOuterGap {
+                values: vec!["10".to_string()],
+            },
+        );
+        assert_eq!(state.usable_frame(1).unwrap().height, 1080 - 30 - 20);
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::ClearReservations { output: None },
+        );
+        assert!(matches!(result.response, Response::Ok));
+        assert_eq!(result.effects, vec![Effect::RetileDisplays(vec![1])]);
+        assert_eq!(
+            state.displays[&1].reserved,
+            yashiki_ipc::OuterGap::default()
+        );
+        // The display frame already excludes the menu bar
+        assert_eq!(state.usable_frame(1), Some(state.displays[&1].frame));
+        // The global outer gap is kept for other displays and comes back with set-outer-gap
+        assert_eq!(state.config.outer_gap, yashiki_ipc::OuterGap::all(10));
+
+        process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::SetOuterGap {
+                values: vec!["5".to_string()],
+            },
+        );
+        assert_eq!(state.usable_frame(1).unwrap().height, 1080 - 10);
+    }
+
+    #[test]
+    fn test_manage_and_unmanage_window_check_managed_windows() {
+        let (mut state, mut hotkey_manager) = setup_state();
+        // handle_ipc_command records the override before process_command runs
+        state.set_window_override(100, "Safari", false);
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::UnmanageWindow { id: 100 },
+        );
+        assert!(matches!(result.response, Response::Ok));
+        assert!(!state.windows.contains_key(&100));
+
+        // Already unmanaged
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::UnmanageWindow { id: 100 },
+        );
+        assert!(matches!(result.response, Response::Error { .. }));
+
+        // The resync did not pick the window up
+        state.set_window_override(100, "Safari", true);
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::ManageWindow { id: 100 },
+        );
+        assert!(matches!(result.response, Response::Error { .. }));
+
+        state.set_window_override(101, "Terminal", true);
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::ManageWindow { id: 101 },
+        );
+        assert!(matches!(result.response, Response::Ok));
+    }
+
+    #[test]
+    fn test_failed_window_override_is_not_kept() {
+        let ws = MockWindowSystem::new()
+            .with_displays(vec![create_test_display(1, 0.0, 0.0, 1920.0, 1080.0)])
+            .with_windows(vec![create_test_window(
+                100, 1000, "Safari", 0.0, 0.0, 960.0, 1080.0,
+            )]);
+        let state = RefCell::new(State::new());
+
+        // Not managed yet: nothing to unmanage
+        let result = record_window_override(&state, &ws, 100, false, |_| {});
+        assert!(result.is_err());
+        assert!(state.borrow().window_overrides.is_empty());
+
+        // The resync does not pick the window up: the override is rolled back
+        let mut resynced = None;
+        let result = record_window_override(&state, &ws, 100, true, |pid| resynced = Some(pid));
+        assert!(result.is_err());
+        assert_eq!(resynced, Some(1000));
+        assert!(state.borrow().window_overrides.is_empty());
+
+        // An earlier override survives a failed attempt
+        state.borrow_mut().set_window_override(100, "Safari", false);
+        let result = record_window_override(&state, &ws, 100, true, |_| {});
+        assert!(result.is_err());
+        assert_eq!(state.borrow().window_override(100, "Safari"), Some(false));
+
+        let result = record_window_override(&state, &ws, 100, true, |_| {
+            state.borrow_mut().sync_all(&ws);
+        });
+        assert!(result.is_ok());
+        assert_eq!(state.borrow().window_override(100, "Safari"), Some(true));
+        assert!(state.borrow().windows.contains_key(&100));
+    }
+
+    #[test]
+    fn test_set_tick_rate_clamps_to_minimum() {
+        let (mut state, mut hotkey_manager) = setup_state();
+        // Retiles are immediate unless a tick is configured
+        assert_eq!(state.config.tick_rate_ms, 0);
+        assert_eq!(
+            state.config.tick_rate_ms,
+            crate::core::Config::default().tick_rate_ms
+        );
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::SetTickRate { ms: 1 },
+        );
+        assert!(matches!(result.response, Response::Ok));
+        assert!(result.effects.is_empty());
+        assert_eq!(state.config.tick_rate_ms, crate::core::MIN_TICK_RATE_MS);
+
+        process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::SetTickRate { ms: 0 },
+        );
+        assert_eq!(state.config.tick_rate_ms, 0);
+
+        process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::SetTickRate { ms: 50 },
+        );
+        let result = process_command(&mut state, &mut hotkey_manager, &Command::GetConfig);
+        match result.response {
+            Response::Config { config } => assert_eq!(config.tick_rate_ms, 50),
+            _ => panic!("Expected Config response"),
+        }
+    }
+
+    #[test]
+    fn test_set_gaps_multi_monitor_only_drops_outer_gap_on_single_display() {
+        let (mut state, mut hotkey_manager) = setup_state();
+        state.config.outer_gap = yashiki_ipc::OuterGap::all(10);
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::SetGapsMultiMonitorOnly { enabled: true },
+        );
+
+        assert!(matches!(result.response, Response::Ok));
+        assert_eq!(result.effects, vec![Effect::Retile]);
+        assert!(state.gaps_suppressed());
+        assert_eq!(
+            state.effective_outer_gap(),
+            yashiki_ipc::OuterGap::default()
+        );
+        let result = process_command(&mut state, &mut hotkey_manager, &Command::GetConfig);
+        match result.response {
+            Response::Config { config } => assert!(config.gaps_multi_monitor_only),
+            _ => panic!("Expected Config response"),
+        }
+
+        process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::SetGapsMultiMonitorOnly { enabled: false },
+        );
+        assert_eq!(state.effective_outer_gap(), yashiki_ipc::OuterGap::all(10));
+    }
+
+    #[test]
+    fn test_tag_windows_by_title_retiles_matches() {
+        let (mut state, mut hotkey_manager) = setup_state();
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::TagWindowsByTitle {
+                pattern: "^(Safari|VSCode)".to_string(),
+                tags: 0b10,
+            },
+        );
+
+        assert!(matches!(result.response, Response::Ok));
+        assert!(result.effects.contains(&Effect::Retile));
+        assert_eq!(state.windows[&100].tags.mask(), 0b10);
+        assert_eq!(state.windows[&101].tags.mask(), 0b1);
+        assert_eq!(state.windows[&102].tags.mask(), 0b10);
+    }
+
+    #[test]
+    fn test_tag_windows_by_title_invalid_pattern() {
+        let (mut state, mut hotkey_manager) = setup_state();
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::TagWindowsByTitle {
+                pattern: "(unclosed".to_string(),
+                tags: 0b10,
+            },
+        );
+
+        match result.response {
+            Response::Error { message } => assert!(message.contains("Invalid pattern")),
+            _ => panic!("Expected Error response"),
+        }
+        assert!(result.effects.is_empty());
+        assert!(state.windows.values().all(|w| w.tags.mask() == 0b1));
+    }
+
+    #[test]
+    fn test_layout_set_current_stores_previous_and_retiles() {
+        let (mut state, mut hotkey_manager) = setup_state();
+        state.displays.get_mut(&1).unwrap().current_layout = Some("tatami".to_string());
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::LayoutSetCurrent {
+                layout: "byobu".to_string(),
+            },
+        );
+
+        assert!(matches!(result.response, Response::Ok));
+        assert_eq!(result.effects, vec![Effect::RetileDisplays(vec![1])]);
+        let display = &state.displays[&1];
+        assert_eq!(display.current_layout.as_deref(), Some("byobu"));
+        assert_eq!(display.previous_layout.as_deref(), Some("tatami"));
+        assert_eq!(state.tag_layouts.get(&1).map(String::as_str), Some("byobu"));
+    }
+
+    #[test]
+    fn test_layout_set_current_without_focused_display() {
+        let (mut state, mut hotkey_manager) = setup_state();
+        state.focused_display = 99;
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::LayoutSetCurrent {
+                layout: "byobu".to_string(),
+            },
+        );
+
+        assert!(matches!(result.response, Response::Error { .. }));
+        assert!(result.effects.is_empty());
+    }
+
+    #[test]
+    fn test_layout_balance_all_resets_every_active_engine() {
+        let (mut state, mut hotkey_manager) = setup_state();
+        state.tag_layouts.insert(2, "byobu".to_string());
+        state.tag_layouts.insert(3, "tatami".to_string());
+        state.saved_inner_gaps.insert("tatami".to_string(), 10);
+
+        let result = process_command(&mut state, &mut hotkey_manager, &Command::LayoutBalanceAll);
+
+        let reset = |layout: &str| Effect::SendLayoutCommand {
+            layout: Some(layout.to_string()),
+            cmd: "reset".to_string(),
+            args: vec![],
+        };
+        assert_eq!(
+            result.effects,
+            vec![reset("byobu"), reset("tatami"), Effect::Retile]
+        );
+        assert!(state.saved_inner_gaps.is_empty());
+    }
+
+    #[test]
+    fn test_layout_command_all_targets_every_active_layout() {
+        let (mut state, mut hotkey_manager) = setup_state();
+        state.tag_layouts.insert(2, "byobu".to_string());
+        state.tag_layouts.insert(3, "tatami".to_string());
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::LayoutCommandAll {
+                cmd: "set-inner-gap".to_string(),
+                args: vec!["8".to_string()],
+            },
+        );
+
+        assert_eq!(
+            result.effects,
+            vec![
+                Effect::BroadcastLayoutCommand {
+                    layouts: vec!["byobu".to_string(), "tatami".to_string()],
+                    cmd: "set-inner-gap".to_string(),
+                    args: vec!["8".to_string()],
+                },
+                Effect::Retile,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_layout_command_for_routes_by_tag() {
+        let (mut state, mut hotkey_manager) = setup_state();
+        state.tag_layouts.insert(2, "byobu".to_string());
+        state.tag_layouts.insert(3, "tatami".to_string());
+
+        let command = |tags: u64| Command::LayoutCommandFor {
+            tags,
+            output: OutputSpecifier::Id(1),
+            cmd: "set-inner-gap".to_string(),
+            args: vec!["8".to_string()],
+        };
+        let expected = |layout: &str| {
+            vec![
+                Effect::SendLayoutCommand {
+                    layout: Some(layout.to_string()),
+                    cmd: "set-inner-gap".to_string(),
+                    args: vec!["8".to_string()],
+                },
+                Effect::RetileDisplays(vec![1]),
+            ]
+        };
+
+        let result = process_command(&mut state, &mut hotkey_manager, &command(0b10));
+        assert_eq!(result.effects, expected("byobu"));
+        let result = process_command(&mut state, &mut hotkey_manager, &command(0b100));
+        assert_eq!(result.effects, expected("tatami"));
+
+        // Unknown output is rejected before anything is sent
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::LayoutCommandFor {
+                tags: 0b10,
+                output: OutputSpecifier::Id(99),
+                cmd: "set-inner-gap".to_string(),
+                args: vec![],
+            },
+        );
+        assert!(matches!(result.response, Response::Error { .. }));
+        assert!(result.effects.is_empty());
+    }
+
+    #[test]
+    fn test_window_peek_toggles() {
+        let (mut state, mut hotkey_manager) = setup_state();
+
+        let result = process_command(&mut state, &mut hotkey_manager, &Command::WindowPeek);
+        assert_eq!(
+            result.effects,
+            vec![
+                Effect::MoveWindowToPosition {
+                    window_id: 100,
+                    pid: 1000,
+                    x: 192,
+                    y: 108,
+                },
+                Effect::SetWindowDimensions {
+                    window_id: 100,
+                    pid: 1000,
+                    width: 1536,
+                    height: 864,
+                },
+                Effect::RaiseWindow {
+                    window_id: 100,
+                    pid: 1000
+                },
+            ]
+        );
+        assert!(state.windows[&100].peek_slot.is_some());
+
+        let result = process_command(&mut state, &mut hotkey_manager, &Command::WindowPeek);
+        assert_eq!(result.effects, vec![Effect::RetileDisplays(vec![1])]);
+        assert!(state.windows[&100].peek_slot.is_none());
+
+        let result = process_command(&mut state, &mut hotkey_manager, &Command::WindowPeekRelease);
+        assert!(result.effects.is_empty());
+    }
+
+    #[test]
+    fn test_list_windows_with_debug_includes_debug_fields() {
+        let (mut state, mut hotkey_manager) = setup_state();
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::ListWindows {
+                all: false,
+                debug: true,
+                visible: false,
+            },
+        );
+
+        assert!(result.effects.is_empty());
+
+        if let Response::Windows { windows } = result.response {
+            assert!(!windows.is_empty());
+            // Debug fields should be populated when debug=true
+            let first_window = &windows[0];
+            // window_level should be Some when debug=true
+            assert!(first_window.window_level.is_some());
+            // close_button should be Some when debug=true
+            assert!(first_window.close_button.is_some());
+            // Status should be None for non-all mode
+            assert!(first_window.status.is_none());
+        } else {
+            panic!("Expected Response::Windows");
+        }
+    }
+
+    #[test]
+    fn test_list_windows_all_true_returns_empty_marker() {
+        let (mut state, mut hotkey_manager) = setup_state();
+
+        // When all=true, process_command returns an empty marker
+        // (the actual implementation happens in handle_ipc_command with system access)
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::ListWindows {
+                all: true,
+                debug: false,
+                visible: false,
+            },
+        );
+
+        assert!(result.effects.is_empty());
+
+        if let Response::Windows { windows } = result.response {
+            // Empty marker - actual implementation is in handle_ipc_command
+            assert!(windows.is_empty());
+        } else {
+            panic!("Expected Response::Windows");
+        }
+    }
+
+    fn list_window_ids(state: &mut State, hotkey_manager: &mut HotkeyManager) -> Vec<u32> {
+        let result = process_command(
+            state,
+            hotkey_manager,
+            &Command::ListWindows {
+                all: false,
+                debug: false,
+                visible: true,
+            },
+        );
+        let Response::Windows { windows } = result.response else {
+            panic!("Expected Response::Windows");
+        };
+        let mut ids: Vec<u32> = windows.iter().map(|w| w.id).collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn test_list_windows_visible_filters_hidden_tags() {
+        let (mut state, mut hotkey_manager) = setup_state();
+        state.windows.get_mut(&101).unwrap().tags = crate::core::Tag::from_mask(0b10);
+
+        assert_eq!(
+            list_window_ids(&mut state, &mut hotkey_manager),
+            vec![100, 102]
+        );
+
+        // Without the flag every managed window is listed
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::ListWindows {
+                all: false,
+                debug: false,
+                visible: false,
+            },
+        );
+        let Response::Windows { windows } = result.response else {
+            panic!("Expected Response::Windows");
+        };
+        assert_eq!(windows.len(), 3);
+
+        // Viewing tag 2 flips which windows are visible
+        state.displays.get_mut(&1).unwrap().visible_tags = crate::core::Tag::from_mask(0b10);
+        assert_eq!(list_window_ids(&mut state, &mut hotkey_manager), vec![101]);
+    }
+
+    #[test]
+    fn test_tag_view_n_views_tag_by_number() {
+        let (mut state, mut hotkey_manager) = setup_state();
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::TagViewN { n: 3, output: None },
+        );
+
+        assert!(matches!(result.response, Response::Ok));
+        assert!(matches!(result.effects[1], Effect::RetileDisplays(ref ids) if ids == &[1]));
+        assert_eq!(state.displays[&1].visible_tags.mask(), 0b100);
+    }
+
+    #[test]
+    fn test_tag_view_name_switches_to_named_tag() {
+        let (mut state, mut hotkey_manager) = setup_state();
+        let view = Command::TagViewName {
+            name: "mail".to_string(),
+            output: None,
+        };
+
+        let result = process_command(&mut state, &mut hotkey_manager, &view);
+        assert!(matches!(result.response, Response::Error { .. }));
+
+        process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::SetTagName {
+                tag: 4,
+                name: "mail".to_string(),
+            },
+        );
+        let result = process_command(&mut state, &mut hotkey_manager, &view);
+        assert!(matches!(result.response, Response::Ok));
+        assert_eq!(state.visible_tags().mask(), 0b1000);
+
+        match process_command(&mut state, &mut hotkey_manager, &Command::GetState).response {
+            Response::State { state } => assert_eq!(state.visible_tag_names, vec!["mail"]),
+            _ => panic!("Expected State"),
+        }
+    }
+
+    #[test]
+    fn test_tag_view_n_rejects_out_of_range() {
+        let (mut state, mut hotkey_manager) = setup_state();
+
+        for n in [0, 65] {
+            let result = process_command(
+                &mut state,
+                &mut hotkey_manager,
+                &Command::TagViewN { n, output: None },
+            );
+            assert!(matches!(result.response, Response::Error { .. }));
+            assert!(result.effects.is_empty());
+        }
+        assert_eq!(state.displays[&1].visible_tags.mask(), 0b1);
+    }
+
+    #[test]
+    fn test_layout_reverse_reverses_window_order() {
+        let ws = MockWindowSystem::new()
+            .with_displays(vec![create_test_display(1, 0.0, 0.0, 1920.0, 1080.0)])
+            .with_windows(vec![
+                create_test_window(100, 1000, "Safari", 0.0, 0.0, 960.0, 1080.0),
+                create_test_window(101, 1001, "Terminal", 960.0, 0.0, 960.0, 1080.0),
+                create_test_window(102, 1002, "VSCode", 0.0, 0.0, 960.0, 540.0),
+                create_test_window(103, 1003, "Finder", 0.0, 540.0, 960.0, 540.0),
+            ]);
+        let mut state = State::new();
+        state.sync_all(&ws);
+        let (_, mut hotkey_manager) = setup_state();
+        assert_eq!(state.displays[&1].window_order, vec![100, 101, 102, 103]);
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::LayoutReverse { output: None },
+        );
+
+        assert!(matches!(result.response, Response::Ok));
+        assert_eq!(state.displays[&1].window_order, vec![103, 102, 101, 100]);
+        assert_eq!(result.effects.len(), 1);
+        assert!(matches!(result.effects[0], Effect::RetileDisplays(ref ids) if ids == &[1]));
+    }
+
+    #[test]
+    fn test_set_app_events_paused_retiles_only_on_resume() {
+        let (mut state, mut hotkey_manager) = setup_state();
+        let set_paused = |paused: bool| Command::SetAppEventsPaused {
+            app_name: "Safari".to_string(),
+            paused,
+        };
+
+        let result = process_command(&mut state, &mut hotkey_manager, &set_paused(true));
+        assert!(matches!(result.response, Response::Ok));
+        assert!(result.effects.is_empty());
+        assert!(state.paused_event_apps.contains("Safari"));
+
+        let result = process_command(&mut state, &mut hotkey_manager, &set_paused(false));
+        assert_eq!(result.effects, vec![Effect::Retile]);
+        assert!(state.paused_event_apps.is_empty());
+
+        // Resuming an app that was not paused is a no-op
+        let result = process_command(&mut state, &mut hotkey_manager, &set_paused(false));
+        assert!(result.effects.is_empty());
+    }
+
+    #[test]
+    fn test_set_main_moves_window_to_front_and_retiles() {
+        let (mut state, mut hotkey_manager) = setup_state();
+        assert_eq!(state.displays[&1].window_order, vec![100, 101, 102]);
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::SetMain { id: 102 },
+        );
+
+        assert!(matches!(result.response, Response::Ok));
+        assert_eq!(state.displays[&1].window_order, vec![102, 100, 101]);
+        assert_eq!(
+            result.effects,
+            vec![
+                Effect::ZoomLayoutWindow {
+                    layout: "tatami".to_string(),
+                    window_id: 102,
+                },
+                Effect::RetileDisplays(vec![1]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_set_main_unknown_window_is_error() {
+        let (mut state, mut hotkey_manager) = setup_state();
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::SetMain { id: 999 },
+        );
+
+        assert!(matches!(result.response, Response::Error { .. }));
+        assert!(result.effects.is_empty());
+        assert_eq!(state.displays[&1].window_order, vec![100, 101, 102]);
+    }
+
+    #[test]
+    fn test_tag_view_produces_correct_effects() {
+        let (mut state, mut hotkey_manager) = setup_state();
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::TagView {
+                tags: 0b10,
+                output: None,
+            },
+        );
+
+        assert!(matches!(result.response, Response::Ok));
+        assert_eq!(result.effects.len(), 3);
+
+        // Should have ApplyWindowMoves, RetileDisplays, FocusVisibleWindowIfNeeded
+        assert!(matches!(result.effects[0], Effect::ApplyWindowMoves(_)));
+        assert!(matches!(result.effects[1], Effect::RetileDisplays(_)));
+        assert!(matches!(
+            result.effects[2],
+            Effect::FocusVisibleWindowIfNeeded
+        ));
+    }
+
+    #[test]
+    fn test_tag_view_with_layout_switches_tag_and_layout_in_one_retile() {
+        let (mut state, mut hotkey_manager) = setup_state();
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::TagViewWithLayout {
+                tags: 0b10,
+                output: None,
+                layout: "byobu".to_string(),
+            },
+        );
+
+        assert!(matches!(result.response, Response::Ok));
+        assert_eq!(result.effects.len(), 3);
+        assert!(matches!(result.effects[0], Effect::ApplyWindowMoves(_)));
+        assert_eq!(result.effects[1], Effect::RetileDisplays(vec![1]));
+        assert!(matches!(
+            result.effects[2],
+            Effect::FocusVisibleWindowIfNeeded
+        ));
+
+        assert_eq!(state.displays[&1].visible_tags.mask(), 0b10);
+        assert_eq!(state.displays[&1].current_layout.as_deref(), Some("byobu"));
+        assert_eq!(state.tag_layouts.get(&2).map(String::as_str), Some("byobu"));
+    }
+
+    #[test]
+    fn test_tag_view_with_layout_rejects_invalid_layout_name() {
+        let (mut state, mut hotkey_manager) = setup_state();
+
+        for layout in ["", "../tatami", "my layout"] {
+            let result = process_command(
+                &mut state,
+                &mut hotkey_manager,
+                &Command::TagViewWithLayout {
+                    tags: 0b10,
+                    output: None,
+                    layout: layout.to_string(),
+                },
+            );
+            assert!(matches!(result.response, Response::Error { .. }));
+            assert!(result.effects.is_empty());
+        }
+        // Nothing changed
+        assert_eq!(state.displays[&1].visible_tags.mask(), 0b1);
+    }
+
+    #[test]
+    fn test_tag_view_without_output_only_changes_focused_display() {
+        let ws = MockWindowSystem::new()
+            .with_displays(vec![
+                create_test_display(1, 0.0, 0.0, 1920.0, 1080.0),
+                create_test_display(2, 1920.0, 0.0, 1920.0, 1080.0),
+            ])
+            .with_windows(vec![
+                create_test_window(100, 1000, "Safari", 100.0, 100.0, 800.0, 600.0),
+                create_test_window(101, 1001, "Terminal", 2000.0, 100.0, 800.0, 600.0),
+            ])
+            .with_focused(Some(101));
+        let mut state = State::new();
+        state.sync_all(&ws);
+        let (_, mut hotkey_manager) = setup_state();
+        assert_eq!(state.focused_display, 2);
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::TagView {
+                tags: 0b10,
+                output: None,
+            },
+        );
+
+        assert!(matches!(result.response, Response::Ok));
+        assert!(matches!(&result.effects[1], Effect::RetileDisplays(ids) if ids == &vec![2]));
+        assert_eq!(state.displays.get(&1).unwrap().visible_tags.mask(), 0b1);
+        assert_eq!(state.displays.get(&2).unwrap().visible_tags.mask(), 0b10);
+
+        let result = process_command(&mut state, &mut hotkey_manager, &Command::TagViewLast);
+
+        assert!(matches!(&result.effects[1], Effect::RetileDisplays(ids) if ids == &vec![2]));
+        assert_eq!(state.displays.get(&1).unwrap().visible_tags.mask(), 0b1);
+        assert_eq!(state.displays.get(&2).unwrap().visible_tags.mask(), 0b1);
+    }
+
+    #[test]
+    fn test_window_focus_produces_focus_effect() {
+        let (mut state, mut hotkey_manager) = setup_state();
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::WindowFocus {
+                direction: Direction::Next,
+                same_output: false,
+            },
+        );
+
+        assert!(matches!(result.response, Response::Ok));
+        assert_eq!(result.effects.len(), 1);
+
+        match &result.effects[0] {
+            Effect::FocusWindow {
+                window_id,
+                pid,
+                is_output_change,
+            } => {
+                assert_eq!(*window_id, 101); // Next window after 100
+                assert_eq!(*pid, 1001);
+                assert!(!is_output_change);
+            }
+            _ => panic!("Expected FocusWindow effect"),
+        }
+    }
+
+    #[test]
+    fn test_exec_produces_exec_effect() {
+        let (mut state, mut hotkey_manager) = setup_state();
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::Exec {
+                command: "open -a Safari".to_string(),
+                track: false,
+            },
+        );
+
+        assert!(matches!(result.response, Response::Ok));
+        assert_eq!(result.effects.len(), 1);
+
+        match &result.effects[0] {
+            Effect::ExecCommand { command, .. } => {
+                assert_eq!(command, "open -a Safari");
+            }
+            _ => panic!("Expected ExecCommand effect"),
+        }
+    }
+
+    #[test]
+    fn test_exec_tracked_produces_exec_tracked_effect() {
+        let (mut state, mut hotkey_manager) = setup_state();
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::Exec {
+                command: "sleep 1000".to_string(),
+                track: true,
+            },
+        );
+
+        assert!(matches!(result.response, Response::Ok));
+        assert_eq!(result.effects.len(), 1);
+
+        match &result.effects[0] {
+            Effect::ExecCommandTracked { command, .. } => {
+                assert_eq!(command, "sleep 1000");
+            }
+            _ => panic!("Expected ExecCommandTracked effect"),
         }
     }
 
@@ -1311,6 +2775,130 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_window_minimize_and_unminimize() {
+        let (mut state, mut hotkey_manager) = setup_state();
+        state.focused = Some(100);
+
+        let result = process_command(&mut state, &mut hotkey_manager, &Command::WindowMinimize);
+        assert_eq!(
+            result.effects,
+            vec![Effect::SetWindowMinimized {
+                window_id: 100,
+                pid: 1000,
+                minimized: true,
+            }]
+        );
+
+        let unminimize = |id| Command::WindowUnminimize { id };
+        let result = process_command(&mut state, &mut hotkey_manager, &unminimize(None));
+        assert!(matches!(result.response, Response::Error { .. }));
+        let result = process_command(&mut state, &mut hotkey_manager, &unminimize(Some(100)));
+        assert!(matches!(result.response, Response::Error { .. }));
+
+        // Sync saw 101 minimized: restore it and focus it
+        state.windows.get_mut(&101).unwrap().is_minimized = true;
+        state.minimized_order.push(101);
+        let result = process_command(&mut state, &mut hotkey_manager, &unminimize(None));
+        assert!(matches!(result.response, Response::Ok));
+        assert_eq!(
+            result.effects,
+            vec![
+                Effect::SetWindowMinimized {
+                    window_id: 101,
+                    pid: 1001,
+                    minimized: false,
+                },
+                Effect::FocusWindow {
+                    window_id: 101,
+                    pid: 1001,
+                    is_output_change: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scratchpad_toggle_dismisses_summons_and_launches() {
+        let (mut state, mut hotkey_manager) = setup_state();
+        let toggle = |app_name: &str| Command::ScratchpadToggle {
+            app_name: app_name.to_string(),
+            command: None,
+        };
+
+        let result = process_command(&mut state, &mut hotkey_manager, &toggle("Terminal"));
+        assert!(matches!(result.response, Response::Ok));
+        assert!(matches!(
+            result.effects.last(),
+            Some(Effect::FocusVisibleWindowIfNeeded)
+        ));
+        assert!(!state.is_window_visible(&state.windows[&101]));
+
+        let result = process_command(&mut state, &mut hotkey_manager, &toggle("Terminal"));
+        assert!(matches!(
+            result.effects.last(),
+            Some(Effect::FocusWindow {
+                window_id: 101,
+                pid: 1001,
+                ..
+            })
+        ));
+        assert!(state.is_window_visible(&state.windows[&101]));
+
+        // A minimized scratchpad is restored when summoned
+        process_command(&mut state, &mut hotkey_manager, &toggle("Terminal"));
+        state.windows.get_mut(&101).unwrap().is_minimized = true;
+        let result = process_command(&mut state, &mut hotkey_manager, &toggle("Terminal"));
+        assert!(result.effects.contains(&Effect::SetWindowMinimized {
+            window_id: 101,
+            pid: 1001,
+            minimized: false,
+        }));
+
+        // No window: launch the app by name, quoted for the shell
+        let result = process_command(&mut state, &mut hotkey_manager, &toggle("Notes"));
+        match &result.effects[..] {
+            [Effect::ExecCommand { command, .. }] => assert_eq!(command, "open -a Notes"),
+            effects => panic!("Expected ExecCommand effect, got {:?}", effects),
+        }
+        let result = process_command(&mut state, &mut hotkey_manager, &toggle("Bob's \"App\""));
+        match &result.effects[..] {
+            [Effect::ExecCommand { command, .. }] => {
+                assert_eq!(command, r#"open -a 'Bob'\''s "App"'"#)
+            }
+            effects => panic!("Expected ExecCommand effect, got {:?}", effects),
+        }
+    }
+
+    #[test]
+    fn test_scratchpad_tag_is_reserved() {
+        let (mut state, mut hotkey_manager) = setup_state();
+        let reserved = crate::core::Tag::new(crate::core::SCRATCHPAD_TAG).mask();
+        for cmd in [
+            Command::TagView {
+                tags: reserved | 1,
+                output: None,
+            },
+            Command::TagViewN {
+                n: crate::core::SCRATCHPAD_TAG,
+                output: None,
+            },
+            Command::WindowMoveToTag { tags: reserved },
+            Command::WindowToggleTag { tags: reserved },
+        ] {
+            let result = process_command(&mut state, &mut hotkey_manager, &cmd);
+            assert!(
+                matches!(result.response, Response::Error { .. }),
+                "{:?}",
+                cmd
+            );
+        }
+        assert_eq!(state.visible_tags(), crate::core::Tag::new(1));
+        assert!(state
+            .set_tag_name(crate::core::SCRATCHPAD_TAG, "pad")
+            .is_err());
+    }
+
     #[test]
     fn test_layout_command_produces_send_and_retile() {
         let (mut state, mut hotkey_manager) = setup_state();
@@ -1363,6 +2951,131 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_toggle_orientation_targets_active_layout() {
+        let (mut state, mut hotkey_manager) = setup_state();
+        state.set_layout_on_display(None, Some(1), "byobu".to_string());
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::ToggleOrientation {
+                output: None,
+                tags: None,
+            },
+        );
+
+        assert!(matches!(result.response, Response::Ok));
+        assert_eq!(result.effects.len(), 2);
+        match &result.effects[0] {
+            Effect::SendLayoutCommand { layout, cmd, args } => {
+                assert_eq!(*layout, Some("byobu".to_string()));
+                assert_eq!(cmd, "toggle-orientation");
+                assert!(args.is_empty());
+            }
+            _ => panic!("Expected SendLayoutCommand effect"),
+        }
+        assert!(matches!(&result.effects[1], Effect::RetileDisplays(ids) if ids == &vec![1]));
+    }
+
+    #[test]
+    fn test_set_main_output() {
+        let ws = MockWindowSystem::new().with_displays(vec![
+            create_test_display(1, 0.0, 0.0, 1920.0, 1080.0),
+            create_test_display(2, 1920.0, 0.0, 1920.0, 1080.0),
+        ]);
+        let mut state = State::new();
+        state.sync_all(&ws);
+        let (_, mut hotkey_manager) = setup_state();
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::SetMainOutput {
+                output: OutputSpecifier::Name("Display 2".to_string()),
+            },
+        );
+        assert!(matches!(result.response, Response::Ok));
+        assert_eq!(
+            result.effects,
+            vec![Effect::SetMainDisplay { display_id: 2 }, Effect::Retile]
+        );
+
+        // Already the main display: nothing to do
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::SetMainOutput {
+                output: OutputSpecifier::Id(1),
+            },
+        );
+        assert!(matches!(result.response, Response::Ok));
+        assert!(result.effects.is_empty());
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::SetMainOutput {
+                output: OutputSpecifier::Id(99),
+            },
+        );
+        assert!(matches!(result.response, Response::Error { .. }));
+        assert!(result.effects.is_empty());
+    }
+
+    #[test]
+    fn test_toggle_orientation_unknown_output_errors() {
+        let (mut state, mut hotkey_manager) = setup_state();
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::ToggleOrientation {
+                output: Some(OutputSpecifier::Id(99)),
+                tags: None,
+            },
+        );
+
+        assert!(matches!(result.response, Response::Error { .. }));
+        assert!(result.effects.is_empty());
+    }
+
+    #[test]
+    fn test_toggle_gaps_retiles_every_display_using_the_layout() {
+        let ws = MockWindowSystem::new()
+            .with_displays(vec![
+                create_test_display(1, 0.0, 0.0, 1920.0, 1080.0),
+                create_test_display(2, 1920.0, 0.0, 1920.0, 1080.0),
+                create_test_display(3, 3840.0, 0.0, 1920.0, 1080.0),
+            ])
+            .with_windows(vec![])
+            .with_focused(None);
+        let mut state = State::new();
+        state.sync_all(&ws);
+        state.displays.get_mut(&3).unwrap().current_layout = Some("byobu".to_string());
+        let (_, mut hotkey_manager) = setup_state();
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::ToggleGaps {
+                output: None,
+                tags: None,
+            },
+        );
+
+        assert!(matches!(result.response, Response::Ok));
+        assert_eq!(
+            result.effects,
+            vec![
+                Effect::ToggleInnerGap {
+                    layout: "tatami".to_string(),
+                },
+                Effect::RetileDisplays(vec![1, 2]),
+            ]
+        );
+    }
+
     #[test]
     fn test_retile_produces_retile_effect() {
         let (mut state, mut hotkey_manager) = setup_state();
@@ -1382,12 +3095,39 @@ mod tests {
     fn test_quit_has_no_effects() {
         let (mut state, mut hotkey_manager) = setup_state();
 
-        let result = process_command(&mut state, &mut hotkey_manager, &Command::Quit);
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::Quit { force: false },
+        );
 
         assert!(matches!(result.response, Response::Ok));
         assert!(result.effects.is_empty());
     }
 
+    #[test]
+    fn test_quit_refused_with_active_subscribers() {
+        let (mut state, mut hotkey_manager) = setup_state();
+        state.event_subscribers.store(2, Ordering::SeqCst);
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::Quit { force: false },
+        );
+        match result.response {
+            Response::Error { message } => assert!(message.contains("2 event subscribers")),
+            _ => panic!("Expected Error response"),
+        }
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::Quit { force: true },
+        );
+        assert!(matches!(result.response, Response::Ok));
+    }
+
     #[test]
     fn test_window_property_change_detection_tags() {
         use crate::event_emitter::EventEmitter;
@@ -1695,6 +3435,7 @@ mod tests {
             &mut hotkey_manager,
             &Command::WindowSwap {
                 direction: Direction::Next,
+                same_output: false,
             },
         );
 
@@ -1710,6 +3451,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_window_shift_produces_retile_effect() {
+        let (mut state, mut hotkey_manager) = setup_state();
+
+        let result = process_command(
+            &mut state,
+            &mut hotkey_manager,
+            &Command::WindowShift {
+                direction: OutputDirection::Next,
+            },
+        );
+
+        assert!(matches!(result.response, Response::Ok));
+        assert!(matches!(&result.effects[..], [Effect::RetileDisplays(ids)] if ids == &vec![1]));
+    }
+
     #[test]
     fn test_window_swap_no_target_has_no_effects() {
         let ws = MockWindowSystem::new()
@@ -1732,6 +3489,7 @@ mod tests {
             &mut hotkey_manager,
             &Command::WindowSwap {
                 direction: Direction::Next,
+                same_output: false,
             },
         );
 
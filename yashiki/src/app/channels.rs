@@ -1,5 +1,5 @@
 use std::ptr;
-use std::sync::atomic::{AtomicPtr, Ordering};
+use std::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
 use std::sync::mpsc as std_mpsc;
 use std::sync::Arc;
 
@@ -28,6 +28,7 @@ pub struct EventStreaming {
     pub broadcaster: EventBroadcaster,
     pub event_server_rx: tokio::sync::broadcast::Receiver<StateEvent>,
     pub state_event_rx: std_mpsc::Receiver<StateEvent>,
+    pub subscriber_count: Arc<AtomicUsize>,
 }
 
 pub struct SnapshotRelay {
@@ -53,6 +54,7 @@ pub struct MainChannels {
     pub display_reconfig_tx: std_mpsc::Sender<DisplayReconfigEvent>,
     pub display_reconfig_rx: std_mpsc::Receiver<DisplayReconfigEvent>,
     pub ipc_source: Arc<AtomicPtr<std::ffi::c_void>>,
+    pub subscriber_count: Arc<AtomicUsize>,
 }
 
 pub fn create_channels() -> (TokioChannels, MainChannels) {
@@ -71,6 +73,8 @@ pub fn create_channels() -> (TokioChannels, MainChannels) {
     // Event broadcasting for state streaming
     let event_broadcaster = EventBroadcaster::new(256);
     let event_server_rx = event_broadcaster.subscribe();
+    // Number of connected event subscribers (updated by tokio, read by main thread)
+    let subscriber_count = Arc::new(AtomicUsize::new(0));
 
     // Channel: state events (main thread -> tokio)
     let (state_event_tx, state_event_rx) = std_mpsc::channel::<StateEvent>();
@@ -100,6 +104,7 @@ pub fn create_channels() -> (TokioChannels, MainChannels) {
             broadcaster: event_broadcaster,
             event_server_rx,
             state_event_rx,
+            subscriber_count: Arc::clone(&subscriber_count),
         },
         snapshots: SnapshotRelay {
             request_tx: snapshot_request_tx,
@@ -119,6 +124,7 @@ pub fn create_channels() -> (TokioChannels, MainChannels) {
         display_reconfig_tx,
         display_reconfig_rx,
         ipc_source,
+        subscriber_count,
     };
 
     (tokio_channels, main_channels)
@@ -142,6 +148,7 @@ pub async fn run_async(channels: TokioChannels) {
         broadcaster: event_broadcaster,
         event_server_rx,
         state_event_rx,
+        subscriber_count,
     } = events;
     let SnapshotRelay {
         request_tx: snapshot_request_tx,
@@ -160,7 +167,7 @@ pub async fn run_async(channels: TokioChannels) {
     });
 
     // Start Event server
-    let event_server = EventServer::new(event_server_rx, snapshot_request_tx);
+    let event_server = EventServer::new(event_server_rx, snapshot_request_tx, subscriber_count);
     tokio::spawn(async move {
         if let Err(e) = event_server.run().await {
             tracing::error!("Event server error: {}", e);
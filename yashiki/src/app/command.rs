@@ -1,11 +1,18 @@
 use std::cell::RefCell;
+use std::time::{Duration, Instant};
 
-use crate::core::{FocusOutputResult, State};
+use crate::core::{
+    format_bindings_export, is_valid_snapshot_name, main_ratio_steps, shell_quote,
+    FocusOutputResult, LayoutDefaults, MainRatioAnimation, Rect, ScratchpadToggle, Snapshot, State,
+    Tag, MAX_ANIMATED_RATIO, MIN_ANIMATED_RATIO, SCRATCHPAD_TAG,
+};
 use crate::effect::{CommandResult, Effect};
-use crate::macos::HotkeyManager;
+use crate::layout::{is_valid_layout_name, parse_main_ratio, LayoutEngineManager};
+use crate::macos::{format_modifiers, parse_modifiers, supported_key_names, HotkeyManager};
 use crate::platform::WindowSystem;
 use yashiki_ipc::{
-    BindingInfo, ButtonState, Command, OuterGap, OutputInfo, Response, RuleInfo, StateInfo,
+    BindingInfo, ButtonState, Command, ConfigInfo, LayoutDefaultsInfo, OuterGap, OutputInfo,
+    OutputSpecifier, Response, RuleInfo, StateInfo, TagLayoutInfo, TagStatsInfo, WindowGeometry,
     WindowInfo, WindowLevel, WindowLevelName, WindowLevelOther, WindowStatus,
 };
 
@@ -29,6 +36,25 @@ fn apply_rules_effects(state: &mut State) -> Vec<Effect> {
     effects
 }
 
+/// Tags a command would view or assign, checked against the reserved `SCRATCHPAD_TAG`.
+fn requested_tags(cmd: &Command) -> Option<u64> {
+    match cmd {
+        Command::TagView { tags, .. }
+        | Command::TagViewWithLayout { tags, .. }
+        | Command::TagToggle { tags, .. }
+        | Command::WindowMoveToTag { tags }
+        | Command::WindowToggleTag { tags }
+        | Command::TagWindowsByTitle { tags, .. }
+        | Command::DistributeWindows { tags } => Some(*tags),
+        Command::TagViewN { n, .. } => Tag::try_new(*n).map(Tag::mask),
+        Command::RuleAdd { rule } => match rule.action {
+            yashiki_ipc::RuleAction::Tags { tags } => Some(tags),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
 /// Pure function: processes a command and returns a response with effects.
 /// This function does not perform any side effects - it only mutates state and computes effects.
 pub fn process_command(
@@ -36,9 +62,23 @@ pub fn process_command(
     hotkey_manager: &mut HotkeyManager,
     cmd: &Command,
 ) -> CommandResult {
+    // Dismissed scratchpads live on their own tag, out of every view
+    if requested_tags(cmd)
+        .is_some_and(|tags| Tag::from_mask(tags).intersects(Tag::new(SCRATCHPAD_TAG)))
+    {
+        return CommandResult::error(format!(
+            "Tag {} is reserved for scratchpads",
+            SCRATCHPAD_TAG
+        ));
+    }
+
     match cmd {
         // Query commands - no effects
-        Command::ListWindows { all, debug } => {
+        Command::ListWindows {
+            all,
+            debug,
+            visible,
+        } => {
             // For all=true, we need system access - handled specially in handle_ipc_command
             // For all=false, we can use state data only
             if *all {
@@ -48,6 +88,7 @@ pub fn process_command(
                 let windows: Vec<WindowInfo> = state
                     .windows
                     .values()
+                    .filter(|w| !*visible || state.is_window_visible(w))
                     .map(|w| WindowInfo {
                         id: w.id,
                         pid: w.pid,
@@ -105,6 +146,7 @@ pub fn process_command(
                     height: d.frame.height,
                     is_main: d.is_main,
                     visible_tags: d.visible_tags.mask(),
+                    visible_tag_names: state.tag_names_of(d.visible_tags),
                     is_focused: state.focused_display == d.id,
                 })
                 .collect();
@@ -113,6 +155,7 @@ pub fn process_command(
         Command::GetState => CommandResult::with_response(Response::State {
             state: StateInfo {
                 visible_tags: state.visible_tags().mask(),
+                visible_tag_names: state.tag_names_of(state.visible_tags()),
                 focused_window_id: state.focused,
                 window_count: state.windows.len(),
                 default_layout: state.default_layout.clone(),
@@ -120,36 +163,230 @@ pub fn process_command(
                     .displays
                     .get(&state.focused_display)
                     .and_then(|d| d.current_layout.clone()),
+                raise_on_focus: state.config.raise_on_focus,
+                tag_history_depth: state
+                    .displays
+                    .get(&state.focused_display)
+                    .map_or(0, |d| d.tag_history.len()),
+            },
+        }),
+        Command::OutputUsableFrame { output } => {
+            let display_id = match state.get_target_display(output.as_ref()) {
+                Ok(id) => id,
+                Err(e) => return CommandResult::error(e),
+            };
+            match state.usable_frame(display_id) {
+                Some(frame) => CommandResult::with_response(Response::UsableFrame {
+                    output: display_id,
+                    x: frame.x,
+                    y: frame.y,
+                    width: frame.width,
+                    height: frame.height,
+                }),
+                None => CommandResult::error(format!("Output {} not found", display_id)),
+            }
+        }
+        Command::TagStats { output } => {
+            let display_id = match state.get_target_display(output.as_ref()) {
+                Ok(id) => id,
+                Err(e) => return CommandResult::error(e),
+            };
+            let tags = state
+                .tag_stats(display_id)
+                .into_iter()
+                .map(|(tag, windows, visible)| TagStatsInfo {
+                    tag,
+                    windows,
+                    visible,
+                })
+                .collect();
+            CommandResult::with_response(Response::TagStats { tags })
+        }
+        Command::Stats => CommandResult::with_response(Response::Stats {
+            stats: state.stats.info(Instant::now()),
+        }),
+        Command::GetConfig => CommandResult::with_response(Response::Config {
+            config: ConfigInfo {
+                default_layout: state.default_layout.clone(),
+                default_tag: state.default_tag.mask(),
+                tag_layouts: {
+                    let mut tag_layouts: Vec<_> = state
+                        .tag_layouts
+                        .iter()
+                        .map(|(tag, layout)| TagLayoutInfo {
+                            tag: *tag,
+                            layout: layout.clone(),
+                        })
+                        .collect();
+                    tag_layouts.sort_by_key(|t| t.tag);
+                    tag_layouts
+                },
+                exec_path: state.config.exec_path.clone(),
+                cursor_warp: state.config.cursor_warp,
+                auto_raise: state.config.auto_raise_mode,
+                auto_raise_delay_ms: state.config.auto_raise_delay_ms,
+                outer_gap: state.config.outer_gap,
+                gaps_multi_monitor_only: state.config.gaps_multi_monitor_only,
+                exclusive_tags: state.config.exclusive_tags,
+                tick_rate_ms: state.config.tick_rate_ms,
+                layout_defaults: LayoutDefaultsInfo {
+                    main_ratio: state.config.layout_defaults.main_ratio,
+                    inner_gap: state.config.layout_defaults.inner_gap,
+                },
+                raise_on_focus: state.config.raise_on_focus,
+                drag_modifier: state.config.drag_modifier.as_ref().map(format_modifiers),
             },
         }),
         Command::FocusedWindow => {
             CommandResult::with_response(Response::WindowId { id: state.focused })
         }
+        Command::WindowOutput { id } => match state.window_output(*id) {
+            Some(display_id) => CommandResult::with_response(Response::OutputId { id: display_id }),
+            None => CommandResult::error(format!("Window {} not found", id)),
+        },
+        Command::WindowTags { id } => match state.windows.get(id) {
+            Some(window) => CommandResult::with_response(Response::WindowTags {
+                id: *id,
+                tags: window.tags.iter_tags().collect(),
+            }),
+            None => CommandResult::error(format!("Window {} not found", id)),
+        },
+        Command::ResyncWindow { id } => {
+            // The live AX frame is read in handle_ipc_command; this only validates the id
+            if state.windows.contains_key(id) {
+                CommandResult::ok()
+            } else {
+                CommandResult::error(format!("Window {} not found", id))
+            }
+        }
+        Command::ListUnmanaged => {
+            // Needs the on-screen window list - handled in handle_ipc_command
+            CommandResult::error("list-unmanaged requires a system query")
+        }
+        Command::ManageWindow { id } => {
+            // The override is recorded and the app resynced in handle_ipc_command
+            if state.windows.contains_key(id) {
+                CommandResult::ok_with_effects(vec![
+                    Effect::PersistWindowOverrides {
+                        overrides: state.window_override_list(),
+                    },
+                    Effect::Retile,
+                ])
+            } else {
+                CommandResult::error(format!("Window {} could not be managed", id))
+            }
+        }
+        Command::UnmanageWindow { id } => {
+            if !state.windows.contains_key(id) {
+                return CommandResult::error(format!("Window {} is not managed", id));
+            }
+            let mut effects = Vec::new();
+            // A window hidden on an inactive tag would otherwise be stranded offscreen
+            if let Some(window) = state.windows.get(id) {
+                if let Some(frame) = window.saved_frame {
+                    effects.push(Effect::MoveWindowToPosition {
+                        window_id: window.id,
+                        pid: window.pid,
+                        x: frame.x,
+                        y: frame.y,
+                    });
+                }
+            }
+            state.unmanage_window(*id);
+            effects.push(Effect::PersistWindowOverrides {
+                overrides: state.window_override_list(),
+            });
+            effects.push(Effect::Retile);
+            effects.push(Effect::FocusVisibleWindowIfNeeded);
+            CommandResult::ok_with_effects(effects)
+        }
+        Command::SetAppEventsPaused { app_name, paused } => {
+            // Resuming snaps the app's windows back into the layout
+            if state.set_app_events_paused(app_name, *paused) && !*paused {
+                CommandResult::ok_with_effects(vec![Effect::Retile])
+            } else {
+                CommandResult::ok()
+            }
+        }
+        Command::WindowGeometry { id } => {
+            // The live AX frame is read in handle_ipc_command; this falls back to the cached frame
+            match state.windows.get(id) {
+                Some(w) => CommandResult::with_response(Response::WindowGeometry {
+                    geometry: WindowGeometry {
+                        id: w.id,
+                        x: w.frame.x,
+                        y: w.frame.y,
+                        width: w.frame.width,
+                        height: w.frame.height,
+                    },
+                }),
+                None => CommandResult::error(format!("Window {} not found", id)),
+            }
+        }
         Command::ListBindings => {
             let bindings: Vec<BindingInfo> = hotkey_manager
                 .list_bindings()
                 .into_iter()
-                .map(|(key, cmd)| BindingInfo {
+                .map(|(key, cmd, label)| BindingInfo {
                     key,
                     action: format!("{:?}", cmd),
+                    label,
                 })
                 .collect();
             CommandResult::with_response(Response::Bindings { bindings })
         }
+        Command::ExportBindings { path } => {
+            let content = format_bindings_export(&hotkey_manager.export_bindings());
+            match path {
+                Some(path) => CommandResult::ok_with_effects(vec![Effect::WriteBindingsExport {
+                    path: path.clone(),
+                    content,
+                }]),
+                None => CommandResult::with_response(Response::BindingsExport { content }),
+            }
+        }
+        Command::ListKeys => CommandResult::with_response(Response::Keys {
+            keys: supported_key_names()
+                .into_iter()
+                .map(String::from)
+                .collect(),
+        }),
 
         // Tag operations - mutate state, return effects
-        Command::TagView { tags, output } => {
+        Command::TagView { tags, output } => view_tags(state, *tags, output.as_ref()),
+        Command::TagViewWithLayout {
+            tags,
+            output,
+            layout,
+        } => {
+            if !is_valid_layout_name(layout) {
+                return CommandResult::error(format!("Invalid layout name: {:?}", layout));
+            }
             let display_id = match state.get_target_display(output.as_ref()) {
                 Ok(id) => id,
                 Err(e) => return CommandResult::error(e),
             };
+            // Set the layout after switching so it applies to the newly visible tag
             let moves = state.view_tags_on_display(*tags, display_id);
+            state.set_layout_on_display(None, Some(display_id), layout.clone());
             CommandResult::ok_with_effects(vec![
                 Effect::ApplyWindowMoves(moves),
                 Effect::RetileDisplays(vec![display_id]),
                 Effect::FocusVisibleWindowIfNeeded,
             ])
         }
+        Command::TagViewN { n, output } => match Tag::try_new(*n) {
+            Some(tag) => view_tags(state, tag.mask(), output.as_ref()),
+            None => CommandResult::error(format!("Tag number must be 1-{}, got {}", Tag::MAX, n)),
+        },
+        Command::TagViewName { name, output } => match state.tag_by_name(name) {
+            Some(n) => view_tags(state, Tag::new(n).mask(), output.as_ref()),
+            None => CommandResult::error(format!("No tag named '{}'", name)),
+        },
+        Command::SetTagName { tag, name } => match state.set_tag_name(*tag, name) {
+            Ok(()) => CommandResult::ok(),
+            Err(e) => CommandResult::error(e),
+        },
         Command::TagToggle { tags, output } => {
             let display_id = match state.get_target_display(output.as_ref()) {
                 Ok(id) => id,
@@ -162,11 +399,44 @@ pub fn process_command(
                 Effect::FocusVisibleWindowIfNeeded,
             ])
         }
+        Command::SetExclusiveTags { enabled } => {
+            tracing::info!("Set exclusive tags: {}", enabled);
+            state.config.exclusive_tags = *enabled;
+            CommandResult::ok()
+        }
         Command::TagViewLast => {
+            let display_id = state.focused_display;
             let moves = state.view_tags_last();
             CommandResult::ok_with_effects(vec![
                 Effect::ApplyWindowMoves(moves),
-                Effect::Retile,
+                Effect::RetileDisplays(vec![display_id]),
+                Effect::FocusVisibleWindowIfNeeded,
+            ])
+        }
+        Command::TagViewBack { steps } => {
+            let display_id = state.focused_display;
+            let moves = state.view_tags_back(*steps);
+            CommandResult::ok_with_effects(vec![
+                Effect::ApplyWindowMoves(moves),
+                Effect::RetileDisplays(vec![display_id]),
+                Effect::FocusVisibleWindowIfNeeded,
+            ])
+        }
+        Command::TagHistoryClear => {
+            if let Some(display) = state.displays.get_mut(&state.focused_display) {
+                display.tag_history.clear();
+            }
+            CommandResult::ok()
+        }
+        Command::TagCycle {
+            direction,
+            only_occupied,
+        } => {
+            let display_id = state.focused_display;
+            let moves = state.cycle_tags(*direction, *only_occupied);
+            CommandResult::ok_with_effects(vec![
+                Effect::ApplyWindowMoves(moves),
+                Effect::RetileDisplays(vec![display_id]),
                 Effect::FocusVisibleWindowIfNeeded,
             ])
         }
@@ -186,9 +456,52 @@ pub fn process_command(
                 Effect::FocusVisibleWindowIfNeeded,
             ])
         }
+        Command::WindowMatchView => match state.match_focused_to_view() {
+            Some((display_ids, moves)) => CommandResult::ok_with_effects(vec![
+                Effect::ApplyWindowMoves(moves),
+                Effect::RetileDisplays(display_ids),
+            ]),
+            None => CommandResult::ok(),
+        },
+        Command::DistributeWindows { tags } => {
+            if *tags == 0 {
+                return CommandResult::error("Tags must not be empty");
+            }
+            let moves = state.distribute_windows(*tags);
+            CommandResult::ok_with_effects(vec![
+                Effect::ApplyWindowMoves(moves),
+                Effect::Retile,
+                Effect::FocusVisibleWindowIfNeeded,
+            ])
+        }
+        Command::TagWindowsByTitle { pattern, tags } => {
+            if *tags == 0 {
+                return CommandResult::error("Tags must not be empty");
+            }
+            let regex = match regex::Regex::new(pattern) {
+                Ok(regex) => regex,
+                Err(e) => {
+                    return CommandResult::error(format!("Invalid pattern '{}': {}", pattern, e))
+                }
+            };
+            let (matched, moves) = state.tag_windows_by_title(&regex, *tags);
+            if matched.is_empty() {
+                return CommandResult::ok();
+            }
+            CommandResult::ok_with_effects(vec![
+                Effect::ApplyWindowMoves(moves),
+                Effect::Retile,
+                Effect::FocusVisibleWindowIfNeeded,
+            ])
+        }
 
         // Hotkey operations
-        Command::Bind { key, action } => match hotkey_manager.bind(key, *action.clone()) {
+        Command::Bind {
+            key,
+            action,
+            label,
+            args,
+        } => match hotkey_manager.bind(key, *action.clone(), label.clone(), args.clone()) {
             Ok(()) => CommandResult::ok(),
             Err(e) => CommandResult::error(e),
         },
@@ -198,8 +511,11 @@ pub fn process_command(
         },
 
         // Focus operations
-        Command::WindowFocus { direction } => {
-            if let Some((window_id, pid)) = state.focus_window(*direction) {
+        Command::WindowFocus {
+            direction,
+            same_output,
+        } => {
+            if let Some((window_id, pid)) = state.focus_window(*direction, *same_output) {
                 tracing::info!("Focusing window {} (pid {})", window_id, pid);
                 CommandResult::ok_with_effects(vec![Effect::FocusWindow {
                     window_id,
@@ -210,8 +526,37 @@ pub fn process_command(
                 CommandResult::ok()
             }
         }
-        Command::WindowSwap { direction } => {
-            if let Some(display_id) = state.swap_window(*direction) {
+        Command::WindowFocusBySize { which } => {
+            if let Some((window_id, pid)) = state.focus_window_by_size(*which) {
+                tracing::info!("Focusing window {} (pid {})", window_id, pid);
+                CommandResult::ok_with_effects(vec![Effect::FocusWindow {
+                    window_id,
+                    pid,
+                    is_output_change: false,
+                }])
+            } else {
+                CommandResult::ok()
+            }
+        }
+        Command::WindowSwap {
+            direction,
+            same_output,
+        } => {
+            if let Some(display_ids) = state.swap_window(*direction, *same_output) {
+                CommandResult::ok_with_effects(vec![Effect::RetileDisplays(display_ids)])
+            } else {
+                CommandResult::ok()
+            }
+        }
+        Command::WindowShift { direction } => {
+            if let Some(display_id) = state.shift_window(*direction) {
+                CommandResult::ok_with_effects(vec![Effect::RetileDisplays(vec![display_id])])
+            } else {
+                CommandResult::ok()
+            }
+        }
+        Command::WindowToSlot { index } => {
+            if let Some(display_id) = state.move_focused_window_to_slot(*index) {
                 CommandResult::ok_with_effects(vec![Effect::RetileDisplays(vec![display_id])])
             } else {
                 CommandResult::ok()
@@ -269,6 +614,94 @@ pub fn process_command(
             }
         }
 
+        Command::FloatAll { enabled } => {
+            let displays = state.set_float_all(*enabled);
+            // Floated windows keep their frames; only restoring them needs a retile
+            if *enabled || displays.is_empty() {
+                CommandResult::ok()
+            } else {
+                CommandResult::ok_with_effects(vec![Effect::RetileDisplays(displays)])
+            }
+        }
+
+        Command::WindowToggleAlwaysOnTop { id } => match state.toggle_always_on_top(*id) {
+            Some((window_id, pid, true)) => {
+                CommandResult::ok_with_effects(vec![Effect::RaiseWindow { window_id, pid }])
+            }
+            Some(_) => CommandResult::ok(),
+            None => match id {
+                Some(id) => CommandResult::error(format!("Window {} not found", id)),
+                None => CommandResult::ok(),
+            },
+        },
+        Command::WindowToggleNativeSize { id } => match state.toggle_native_size(*id) {
+            Some((display_id, _)) => {
+                CommandResult::ok_with_effects(vec![Effect::RetileDisplays(vec![display_id])])
+            }
+            None => match id {
+                Some(id) => CommandResult::error(format!("Window {} not found", id)),
+                None => CommandResult::ok(),
+            },
+        },
+
+        // Peek
+        Command::WindowPeek => {
+            if let Some(display_id) = state.release_peek() {
+                return CommandResult::ok_with_effects(vec![Effect::RetileDisplays(vec![
+                    display_id,
+                ])]);
+            }
+            match state.start_peek() {
+                Some((window_id, pid, frame)) => CommandResult::ok_with_effects(vec![
+                    Effect::MoveWindowToPosition {
+                        window_id,
+                        pid,
+                        x: frame.x,
+                        y: frame.y,
+                    },
+                    Effect::SetWindowDimensions {
+                        window_id,
+                        pid,
+                        width: frame.width,
+                        height: frame.height,
+                    },
+                    Effect::RaiseWindow { window_id, pid },
+                ]),
+                None => CommandResult::ok(),
+            }
+        }
+        Command::WindowPeekRelease => match state.release_peek() {
+            Some(display_id) => {
+                CommandResult::ok_with_effects(vec![Effect::RetileDisplays(vec![display_id])])
+            }
+            None => CommandResult::ok(),
+        },
+
+        // Urgency
+        Command::WindowSetUrgent { id } => {
+            if state.set_urgent(*id) {
+                CommandResult::ok()
+            } else {
+                CommandResult::error(format!("Window {} not found", id))
+            }
+        }
+        Command::FocusUrgent => {
+            let Some(result) = state.focus_urgent() else {
+                return CommandResult::ok();
+            };
+            let mut effects = Vec::new();
+            if !result.window_moves.is_empty() {
+                effects.push(Effect::ApplyWindowMoves(result.window_moves));
+                effects.push(Effect::Retile);
+            }
+            effects.push(Effect::FocusWindow {
+                window_id: result.window_id,
+                pid: result.pid,
+                is_output_change: result.display_changed,
+            });
+            CommandResult::ok_with_effects(effects)
+        }
+
         // Window close
         Command::WindowClose => {
             if let Some(focused_id) = state.focused {
@@ -284,6 +717,41 @@ pub fn process_command(
                 CommandResult::error("No focused window")
             }
         }
+        Command::WindowMinimize => match state.focused.and_then(|id| state.windows.get(&id)) {
+            Some(window) => CommandResult::ok_with_effects(vec![Effect::SetWindowMinimized {
+                window_id: window.id,
+                pid: window.pid,
+                minimized: true,
+            }]),
+            None => CommandResult::error("No focused window"),
+        },
+        Command::WindowUnminimize { id } => {
+            let target = match id {
+                Some(id) => match state.windows.get(id) {
+                    Some(window) if window.is_minimized => Some((window.id, window.pid)),
+                    Some(_) => {
+                        return CommandResult::error(format!("Window {} is not minimized", id))
+                    }
+                    None => return CommandResult::error(format!("Window {} not found", id)),
+                },
+                None => state.last_minimized_window(),
+            };
+            match target {
+                Some((window_id, pid)) => CommandResult::ok_with_effects(vec![
+                    Effect::SetWindowMinimized {
+                        window_id,
+                        pid,
+                        minimized: false,
+                    },
+                    Effect::FocusWindow {
+                        window_id,
+                        pid,
+                        is_output_change: false,
+                    },
+                ]),
+                None => CommandResult::error("No minimized window"),
+            }
+        }
 
         // Send to output - returns displays that need retiling
         Command::OutputSend { direction } => {
@@ -301,6 +769,33 @@ pub fn process_command(
             }
         }
 
+        Command::SetMainOutput { output } => {
+            let display_id = match state.get_target_display(Some(output)) {
+                Ok(id) => id,
+                Err(e) => return CommandResult::error(e),
+            };
+            if state.displays.get(&display_id).is_some_and(|d| d.is_main) {
+                return CommandResult::ok();
+            }
+            // Display frames are refreshed by the reconfiguration callback that follows
+            CommandResult::ok_with_effects(vec![
+                Effect::SetMainDisplay { display_id },
+                Effect::Retile,
+            ])
+        }
+
+        Command::SetFocusAxisSwap { output, enabled } => {
+            let display_id = match state.get_target_display(output.as_ref()) {
+                Ok(id) => id,
+                Err(e) => return CommandResult::error(e),
+            };
+            if let Some(display) = state.displays.get_mut(&display_id) {
+                tracing::info!("Focus axis swap on display {}: {}", display_id, enabled);
+                display.focus_axis_swap = *enabled;
+            }
+            CommandResult::ok()
+        }
+
         // Layout configuration
         Command::LayoutSetDefault { layout } => {
             state.set_default_layout(layout.clone());
@@ -327,6 +822,14 @@ pub fn process_command(
                 CommandResult::ok()
             }
         }
+        Command::LayoutSetCurrent { layout } => {
+            let display_id = state.focused_display;
+            if !state.displays.contains_key(&display_id) {
+                return CommandResult::error("No focused display");
+            }
+            state.set_layout_on_display(None, Some(display_id), layout.clone());
+            CommandResult::ok_with_effects(vec![Effect::RetileDisplays(vec![display_id])])
+        }
         Command::LayoutGet { tags, output } => {
             let display_id = match state.get_target_display(output.as_ref()) {
                 Ok(id) => Some(id),
@@ -336,6 +839,70 @@ pub fn process_command(
             CommandResult::with_response(Response::Layout { layout })
         }
 
+        Command::SetDefaultLayoutParams {
+            main_ratio,
+            inner_gap,
+        } => {
+            if let Some(ratio) = main_ratio {
+                if !ratio.is_finite() || *ratio <= 0.0 || *ratio >= 1.0 {
+                    return CommandResult::error(format!(
+                        "Main ratio must be between 0 and 1, got {}",
+                        ratio
+                    ));
+                }
+            }
+            let defaults = LayoutDefaults {
+                main_ratio: *main_ratio,
+                inner_gap: *inner_gap,
+            };
+            tracing::info!("Set default layout params: {:?}", defaults);
+            state.config.layout_defaults = defaults.clone();
+            CommandResult::ok_with_effects(vec![Effect::UpdateLayoutDefaults { defaults }])
+        }
+        Command::MainRatioAnimate { .. } => {
+            // Needs the engine's current ratio - handled in handle_ipc_command
+            CommandResult::error("main-ratio-animate requires a layout engine query")
+        }
+        Command::MainRatioGet { .. } => {
+            // Needs a reply from the layout engine - handled in handle_ipc_command
+            CommandResult::error("main-ratio-get requires a layout engine query")
+        }
+        Command::LayoutEngineLog { .. } => {
+            // Reads the engine's captured stderr - handled in handle_ipc_command
+            CommandResult::error("layout-engine-log requires the layout engine manager")
+        }
+        Command::LayoutLastInput { .. } => {
+            // Queries the running engine - handled in handle_ipc_command
+            CommandResult::error("layout-last-input requires the layout engine manager")
+        }
+        Command::LayoutDescribe { .. } => {
+            // Asks the engine (spawning it if needed) - handled in handle_ipc_command
+            CommandResult::error("layout-describe requires the layout engine manager")
+        }
+        Command::LayoutAlias { alias, target } => {
+            for name in [alias, target] {
+                if !is_valid_layout_name(name) {
+                    return CommandResult::error(format!("Invalid layout name: {}", name));
+                }
+            }
+            if alias == target {
+                return CommandResult::error(format!("Layout '{}' cannot alias itself", alias));
+            }
+            tracing::info!("Layout alias: {} -> {}", alias, target);
+            // Retile so displays using the alias switch to the target engine
+            CommandResult::ok_with_effects(vec![
+                Effect::SetLayoutAlias {
+                    alias: alias.clone(),
+                    target: target.clone(),
+                },
+                Effect::Retile,
+            ])
+        }
+        Command::LayoutList => {
+            // Lists running engines - handled in handle_ipc_command
+            CommandResult::error("layout-list requires the layout engine manager")
+        }
+
         // Layout commands - need layout engine interaction (handled as effects)
         Command::LayoutCommand { layout, cmd, args } => {
             let mut effects = vec![Effect::SendLayoutCommand {
@@ -349,6 +916,72 @@ pub fn process_command(
             }
             CommandResult::ok_with_effects(effects)
         }
+        Command::LayoutCommandAll { cmd, args } => {
+            let layouts = state.active_layouts();
+            tracing::info!("Broadcasting '{}' to layouts: {}", cmd, layouts.join(", "));
+            CommandResult::ok_with_effects(vec![
+                Effect::BroadcastLayoutCommand {
+                    layouts,
+                    cmd: cmd.clone(),
+                    args: args.clone(),
+                },
+                Effect::Retile,
+            ])
+        }
+        Command::LayoutCommandFor {
+            tags,
+            output,
+            cmd,
+            args,
+        } => {
+            let display_id = match state.get_target_display(Some(output)) {
+                Ok(id) => id,
+                Err(e) => return CommandResult::error(e),
+            };
+            let layout = state
+                .get_layout_on_display(Some(*tags), Some(display_id))
+                .to_string();
+            CommandResult::ok_with_effects(vec![
+                Effect::SendLayoutCommand {
+                    layout: Some(layout),
+                    cmd: cmd.clone(),
+                    args: args.clone(),
+                },
+                Effect::RetileDisplays(vec![display_id]),
+            ])
+        }
+        Command::ToggleOrientation { output, tags } => {
+            let display_id = match state.get_target_display(output.as_ref()) {
+                Ok(id) => id,
+                Err(e) => return CommandResult::error(e),
+            };
+            let layout = state
+                .get_layout_on_display(*tags, Some(display_id))
+                .to_string();
+            CommandResult::ok_with_effects(vec![
+                Effect::SendLayoutCommand {
+                    layout: Some(layout),
+                    cmd: "toggle-orientation".to_string(),
+                    args: vec![],
+                },
+                Effect::RetileDisplays(vec![display_id]),
+            ])
+        }
+        Command::ToggleGaps { output, tags } => {
+            let display_id = match state.get_target_display(output.as_ref()) {
+                Ok(id) => id,
+                Err(e) => return CommandResult::error(e),
+            };
+            let layout = state
+                .get_layout_on_display(*tags, Some(display_id))
+                .to_string();
+            // The gap belongs to the engine, so every display showing it changes
+            let display_ids = state.displays_using_layout(&layout);
+            CommandResult::ok_with_effects(vec![
+                Effect::ToggleInnerGap { layout },
+                Effect::RetileDisplays(display_ids),
+            ])
+        }
         Command::Retile { output } => {
             if let Some(ref spec) = output {
                 let display_id = match state.get_target_display(Some(spec)) {
@@ -360,6 +993,59 @@ pub fn process_command(
                 CommandResult::ok_with_effects(vec![Effect::Retile])
             }
         }
+        Command::LayoutReverse { output } => {
+            let display_id = match state.get_target_display(output.as_ref()) {
+                Ok(id) => id,
+                Err(e) => return CommandResult::error(e),
+            };
+            if state.reverse_window_order(display_id) {
+                CommandResult::ok_with_effects(vec![Effect::RetileDisplays(vec![display_id])])
+            } else {
+                CommandResult::ok()
+            }
+        }
+        Command::SetMain { id } => match state.move_window_to_front(*id) {
+            Some(display_id) => CommandResult::ok_with_effects(vec![
+                Effect::ZoomLayoutWindow {
+                    layout: state.current_layout_for_display(display_id).to_string(),
+                    window_id: *id,
+                },
+                Effect::RetileDisplays(vec![display_id]),
+            ]),
+            None => CommandResult::error(format!("Window {} not found", id)),
+        },
+        Command::SetStickyMain { id } => match state.windows.get(id) {
+            Some(window) => {
+                let display_id = window.display_id;
+                tracing::info!("Set sticky main window: {}", id);
+                state.sticky_main = Some(*id);
+                CommandResult::ok_with_effects(vec![Effect::RetileDisplays(vec![display_id])])
+            }
+            None => CommandResult::error(format!("Window {} not found", id)),
+        },
+        Command::ClearStickyMain => {
+            if let Some(id) = state.sticky_main.take() {
+                tracing::info!("Cleared sticky main window: {}", id);
+            }
+            CommandResult::ok()
+        }
+
+        Command::LayoutBalanceAll => {
+            let layouts = state.active_layouts();
+            tracing::info!("Resetting layouts: {}", layouts.join(", "));
+            // Engines fall back to their default gap, so saved toggle-gaps values are stale
+            state.saved_inner_gaps.clear();
+            let mut effects: Vec<Effect> = layouts
+                .into_iter()
+                .map(|layout| Effect::SendLayoutCommand {
+                    layout: Some(layout),
+                    cmd: "reset".to_string(),
+                    args: vec![],
+                })
+                .collect();
+            effects.push(Effect::Retile);
+            CommandResult::ok_with_effects(effects)
+        }
 
         // Exec path commands
         Command::GetExecPath => CommandResult::with_response(Response::ExecPath {
@@ -475,6 +1161,55 @@ pub fn process_command(
             }
         }
 
+        Command::ScratchpadToggle { app_name, command } => {
+            match state.scratchpad_toggle(app_name) {
+                ScratchpadToggle::Launch => {
+                    let command = command
+                        .clone()
+                        .unwrap_or_else(|| format!("open -a {}", shell_quote(app_name)));
+                    tracing::info!(
+                        "No window for scratchpad app '{}', executing command",
+                        app_name
+                    );
+                    CommandResult::ok_with_effects(vec![Effect::ExecCommand {
+                        command,
+                        path: state.config.exec_path.clone(),
+                    }])
+                }
+                ScratchpadToggle::Hidden {
+                    display_id,
+                    window_moves,
+                } => CommandResult::ok_with_effects(vec![
+                    Effect::ApplyWindowMoves(window_moves),
+                    Effect::RetileDisplays(vec![display_id]),
+                    Effect::FocusVisibleWindowIfNeeded,
+                ]),
+                ScratchpadToggle::Shown {
+                    window_id,
+                    pid,
+                    minimized,
+                    display_ids,
+                    window_moves,
+                } => {
+                    let mut effects = vec![Effect::ApplyWindowMoves(window_moves)];
+                    if minimized {
+                        effects.push(Effect::SetWindowMinimized {
+                            window_id,
+                            pid,
+                            minimized: false,
+                        });
+                    }
+                    effects.push(Effect::RetileDisplays(display_ids));
+                    effects.push(Effect::FocusWindow {
+                        window_id,
+                        pid,
+                        is_output_change: false,
+                    });
+                    CommandResult::ok_with_effects(effects)
+                }
+            }
+        }
+
         // Rules
         Command::RuleAdd { rule } => {
             state.add_rule(rule.clone());
@@ -566,12 +1301,31 @@ pub fn process_command(
             mode: state.config.auto_raise_mode,
             delay_ms: state.config.auto_raise_delay_ms,
         }),
+        Command::SetRaiseOnFocus { enabled } => {
+            tracing::info!("Set raise on focus: {}", enabled);
+            state.config.raise_on_focus = *enabled;
+            CommandResult::ok()
+        }
+        Command::SetDragModifier { modifier } => {
+            let parsed = match modifier.as_deref().map(parse_modifiers).transpose() {
+                Ok(parsed) => parsed,
+                Err(e) => return CommandResult::error(e),
+            };
+            tracing::info!("Set drag modifier: {:?}", modifier);
+            state.config.drag_modifier = parsed;
+            state.end_window_drag();
+            // DragTracker is updated in ipc_source_callback after command processing
+            CommandResult::ok()
+        }
 
         // Outer gap
         Command::SetOuterGap { values } => match OuterGap::from_args(values) {
             Some(gap) => {
                 tracing::info!("Set outer gap: {}", gap);
                 state.config.outer_gap = gap;
+                for display in state.displays.values_mut() {
+                    display.outer_gap = None;
+                }
                 CommandResult::ok_with_effects(vec![Effect::Retile])
             }
             None => CommandResult::error("usage: set-outer-gap <all> | <v h> | <t r b l>"),
@@ -579,15 +1333,126 @@ pub fn process_command(
         Command::GetOuterGap => CommandResult::with_response(Response::OuterGap {
             outer_gap: state.config.outer_gap,
         }),
+        Command::ReserveEdge { output, edge, size } => {
+            let display_id = match state.get_target_display(output.as_ref()) {
+                Ok(id) => id,
+                Err(e) => return CommandResult::error(e),
+            };
+            let Some(display) = state.displays.get_mut(&display_id) else {
+                return CommandResult::error(format!("Display {} not found", display_id));
+            };
+            if let Err(e) = display.reserve_edge(*edge, *size) {
+                return CommandResult::error(e);
+            }
+            let reserved = display.reserved;
+            tracing::info!(
+                "Reserved {}px at {:?} edge of display {} (now {})",
+                size,
+                edge,
+                display_id,
+                reserved
+            );
+            CommandResult::ok_with_effects(vec![Effect::RetileDisplays(vec![display_id])])
+        }
+        Command::ClearReservations { output } => {
+            let display_id = match state.get_target_display(output.as_ref()) {
+                Ok(id) => id,
+                Err(e) => return CommandResult::error(e),
+            };
+            let Some(display) = state.displays.get_mut(&display_id) else {
+                return CommandResult::error(format!("Display {} not found", display_id));
+            };
+            display.reserved = OuterGap::default();
+            display.outer_gap = Some(OuterGap::default());
+            tracing::info!("Cleared reservations of display {}", display_id);
+            CommandResult::ok_with_effects(vec![Effect::RetileDisplays(vec![display_id])])
+        }
+        Command::SetGapsMultiMonitorOnly { enabled } => {
+            tracing::info!("Set gaps multi-monitor only: {}", enabled);
+            state.config.gaps_multi_monitor_only = *enabled;
+            CommandResult::ok_with_effects(vec![Effect::Retile])
+        }
+        Command::SetTickRate { ms } => {
+            let stored = state.config.set_tick_rate(*ms);
+            if stored != *ms {
+                tracing::warn!(
+                    "Tick rate {}ms is below the minimum, using {}ms",
+                    ms,
+                    stored
+                );
+            } else {
+                tracing::info!("Set tick rate: {}ms", stored);
+            }
+            CommandResult::ok()
+        }
+
+        // Snapshots
+        Command::SnapshotSave { name } => {
+            if !is_valid_snapshot_name(name) {
+                return CommandResult::error(format!("Invalid snapshot name: {:?}", name));
+            }
+            let snapshot = Snapshot::capture(state.windows.values());
+            tracing::info!(
+                "Saved snapshot '{}' with {} window(s)",
+                name,
+                snapshot.windows.len()
+            );
+            state.snapshots.insert(name.clone(), snapshot.clone());
+            CommandResult::ok_with_effects(vec![Effect::PersistSnapshot {
+                name: name.clone(),
+                snapshot,
+            }])
+        }
+        Command::SnapshotRestore { name } => match state.snapshots.get(name) {
+            Some(snapshot) => {
+                tracing::info!("Restoring snapshot '{}'", name);
+                CommandResult::ok_with_effects(snapshot.restore_effects(&state.windows))
+            }
+            None => CommandResult::error(format!("Snapshot not found: {}", name)),
+        },
 
         // Control
-        Command::Quit => {
+        Command::Reconcile => {
+            // Stale windows are pruned in handle_ipc_command (requires system query)
+            CommandResult::ok_with_effects(vec![Effect::Retile, Effect::FocusVisibleWindowIfNeeded])
+        }
+        Command::Resync => {
+            // Displays and windows are re-read in handle_ipc_command (requires system query)
+            CommandResult {
+                response: Response::WindowCount {
+                    count: state.windows.len(),
+                },
+                effects: vec![Effect::Retile, Effect::FocusVisibleWindowIfNeeded],
+            }
+        }
+        Command::Quit { force } => {
+            let subscribers = state.event_subscriber_count();
+            if !force && subscribers > 0 {
+                return CommandResult::error(format!(
+                    "{} event subscriber{} connected (use --force to quit anyway)",
+                    subscribers,
+                    if subscribers == 1 { " is" } else { "s are" }
+                ));
+            }
             tracing::info!("Quit command received");
             CommandResult::ok()
         }
     }
 }
 
+fn view_tags(state: &mut State, tags: u64, output: Option<&OutputSpecifier>) -> CommandResult {
+    let display_id = match state.get_target_display(output) {
+        Ok(id) => id,
+        Err(e) => return CommandResult::error(e),
+    };
+    let moves = state.view_tags_on_display(tags, display_id);
+    CommandResult::ok_with_effects(vec![
+        Effect::ApplyWindowMoves(moves),
+        Effect::RetileDisplays(vec![display_id]),
+        Effect::FocusVisibleWindowIfNeeded,
+    ])
+}
+
 /// List all system windows (managed and ignored) for --all option
 pub fn list_all_windows<S: WindowSystem>(
     state: &RefCell<State>,
@@ -724,3 +1589,251 @@ fn format_button_state(state: ButtonState) -> String {
         ButtonState::Disabled => "disabled".to_string(),
     }
 }
+
+/// Read a managed window's frame directly from AX so callers never see a stale cache.
+fn read_live_frame<S: WindowSystem>(
+    state: &RefCell<State>,
+    window_system: &S,
+    id: u32,
+) -> Result<Rect, String> {
+    let pid = state
+        .borrow()
+        .windows
+        .get(&id)
+        .map(|w| w.pid)
+        .ok_or_else(|| format!("Window {} not found", id))?;
+    window_system
+        .get_window_frame(id, pid)
+        .ok_or_else(|| format!("Failed to read frame of window {}", id))
+}
+
+pub fn query_window_geometry<S: WindowSystem>(
+    state: &RefCell<State>,
+    window_system: &S,
+    id: u32,
+) -> Response {
+    match read_live_frame(state, window_system, id) {
+        Ok(frame) => Response::WindowGeometry {
+            geometry: WindowGeometry {
+                id,
+                x: frame.x,
+                y: frame.y,
+                width: frame.width,
+                height: frame.height,
+            },
+        },
+        Err(message) => Response::Error { message },
+    }
+}
+
+/// Replace a window's tracked frame with its live AX frame. Does not retile.
+pub fn resync_window<S: WindowSystem>(
+    state: &RefCell<State>,
+    window_system: &S,
+    id: u32,
+) -> Response {
+    match read_live_frame(state, window_system, id) {
+        Ok(frame) => {
+            if let Some(window) = state.borrow_mut().windows.get_mut(&id) {
+                tracing::info!(
+                    "Resynced window {} frame: {:?} -> {:?}",
+                    id,
+                    window.frame,
+                    frame
+                );
+                window.frame = frame;
+            }
+            Response::Ok
+        }
+        Err(message) => Response::Error { message },
+    }
+}
+
+/// Record a manage/unmanage override for a window, keyed by its current app name.
+/// A manage override resyncs the app through `resync` (given the owning pid) and is
+/// rolled back if the window still isn't managed afterwards. Unmanaging a window
+/// that isn't managed records nothing.
+pub fn record_window_override<S: WindowSystem>(
+    state: &RefCell<State>,
+    window_system: &S,
+    id: u32,
+    managed: bool,
+    resync: impl FnOnce(i32),
+) -> Result<(), String> {
+    if !managed && !state.borrow().windows.contains_key(&id) {
+        return Err(format!("Window {} is not managed", id));
+    }
+    let owner = state
+        .borrow()
+        .windows
+        .get(&id)
+        .map(|w| (w.pid, w.app_name.clone()))
+        .or_else(|| {
+            window_system
+                .get_on_screen_windows()
+                .into_iter()
+                .find(|info| info.window_id == id)
+                .map(|info| (info.pid, info.owner_name))
+        });
+    let (pid, app_name) = owner.ok_or_else(|| format!("Window {} not found", id))?;
+    let previous = state.borrow().window_overrides.get(&id).cloned();
+    state
+        .borrow_mut()
+        .set_window_override(id, &app_name, managed);
+    if !managed {
+        return Ok(());
+    }
+
+    resync(pid);
+    if state.borrow().windows.contains_key(&id) {
+        return Ok(());
+    }
+    let mut state = state.borrow_mut();
+    match previous {
+        Some(previous) => state.window_overrides.insert(id, previous),
+        None => state.window_overrides.remove(&id),
+    };
+    Err(format!("Window {} could not be managed", id))
+}
+
+/// Recent stderr lines of a running layout engine.
+pub fn layout_engine_log(
+    layout_engine_manager: &RefCell<LayoutEngineManager>,
+    name: &str,
+    lines: Option<usize>,
+) -> Response {
+    match layout_engine_manager
+        .borrow()
+        .stderr_lines(name, lines.unwrap_or(usize::MAX))
+    {
+        Some(lines) => Response::Log { lines },
+        None => Response::Error {
+            message: format!("Layout engine '{}' is not running", name),
+        },
+    }
+}
+
+/// Ask a running layout engine which window list it last laid out.
+pub fn layout_last_input(
+    layout_engine_manager: &RefCell<LayoutEngineManager>,
+    name: &str,
+) -> Response {
+    match layout_engine_manager.borrow_mut().last_input(name) {
+        Some(Ok((width, height, windows))) => Response::LayoutInput {
+            width,
+            height,
+            windows,
+        },
+        Some(Err(e)) => Response::Error {
+            message: e.to_string(),
+        },
+        None => Response::Error {
+            message: format!("Layout engine '{}' is not running", name),
+        },
+    }
+}
+
+pub fn layout_describe(
+    layout_engine_manager: &RefCell<LayoutEngineManager>,
+    name: &str,
+) -> Response {
+    match layout_engine_manager.borrow_mut().describe(name) {
+        Ok((name, commands)) => Response::LayoutCapabilities { name, commands },
+        Err(e) => Response::Error {
+            message: e.to_string(),
+        },
+    }
+}
+
+pub fn layout_list(
+    state: &RefCell<State>,
+    layout_engine_manager: &RefCell<LayoutEngineManager>,
+) -> Response {
+    Response::Layouts {
+        layouts: layout_engine_manager
+            .borrow()
+            .list_layouts(&state.borrow().default_layout),
+    }
+}
+
+/// Start animating the main ratio of the focused display's layout from the engine's
+/// current value; every display using that layout follows.
+/// The first step is applied on the next tick; later steps follow one per tick.
+pub fn animate_main_ratio(
+    state: &RefCell<State>,
+    layout_engine_manager: &RefCell<LayoutEngineManager>,
+    target: f64,
+    duration_ms: u32,
+) -> Response {
+    if !target.is_finite() {
+        return Response::Error {
+            message: format!("Invalid target ratio: {}", target),
+        };
+    }
+    let target = target.clamp(MIN_ANIMATED_RATIO, MAX_ANIMATED_RATIO);
+    let (layout, tick) = {
+        let state = state.borrow();
+        (
+            state.current_layout().to_string(),
+            state.config.animation_frame(),
+        )
+    };
+
+    let start = match layout_engine_manager
+        .borrow_mut()
+        .query(&layout, "get-main-ratio", &[])
+        .map_err(|e| e.to_string())
+        .and_then(|value| parse_main_ratio(&layout, &value))
+    {
+        Ok(ratio) => ratio,
+        Err(message) => return Response::Error { message },
+    };
+
+    let duration = Duration::from_millis(duration_ms as u64);
+    let steps = main_ratio_steps(start, target, duration, tick);
+    tracing::info!(
+        "Animating main ratio of '{}': {:.3} -> {:.3} in {} step(s)",
+        layout,
+        start,
+        target,
+        steps.len()
+    );
+    state.borrow_mut().ratio_animation =
+        Some(MainRatioAnimation::new(layout, steps, tick, Instant::now()));
+    Response::Ok
+}
+
+/// Ask the layout engine active on the target output/tags for its main ratio.
+pub fn query_main_ratio(
+    state: &RefCell<State>,
+    layout_engine_manager: &RefCell<LayoutEngineManager>,
+    output: Option<&OutputSpecifier>,
+    tags: Option<u64>,
+) -> Response {
+    let layout = {
+        let state = state.borrow();
+        match state.get_target_display(output) {
+            Ok(display_id) => state
+                .get_layout_on_display(tags, Some(display_id))
+                .to_string(),
+            Err(e) => return Response::Error { message: e },
+        }
+    };
+
+    let value = match layout_engine_manager
+        .borrow_mut()
+        .query(&layout, "get-main-ratio", &[])
+    {
+        Ok(value) => value,
+        Err(e) => {
+            return Response::Error {
+                message: e.to_string(),
+            }
+        }
+    };
+
+    match parse_main_ratio(&layout, &value) {
+        Ok(ratio) => Response::MainRatio { ratio },
+        Err(message) => Response::Error { message },
+    }
+}
@@ -1,16 +1,24 @@
 use std::cell::RefCell;
 
 use crate::core::State;
+use crate::effect::Effect;
 use crate::event_emitter::EventEmitter;
-use crate::layout::LayoutEngineManager;
+use crate::layout::{is_valid_layout_name, LayoutEngineManager};
 use crate::macos::{DisplayId, HotkeyManager, ObserverManager};
 use crate::platform::{WindowManipulator, WindowSystem};
 use yashiki_ipc::{Command, Response};
 
-use super::command::{list_all_windows, process_command};
+use super::command::{
+    animate_main_ratio, layout_describe, layout_engine_log, layout_last_input, layout_list,
+    list_all_windows, process_command, query_main_ratio, query_window_geometry,
+    record_window_override, resync_window,
+};
 use super::effects::execute_effects;
 use super::state_events::{capture_event_state, emit_state_change_events};
-use super::sync_helper::sync_display_and_process_new_windows;
+use super::sync_helper::{
+    resync_all_and_process_new_windows, sync_and_process_new_windows,
+    sync_display_and_process_new_windows,
+};
 
 /// Unified command dispatcher for IPC and hotkey commands.
 /// Handles the common pattern: capture state -> process command -> execute effects -> emit events.
@@ -49,10 +57,13 @@ pub fn dispatch_command<S: WindowSystem, M: WindowManipulator>(
 /// Returns target display_id for tag-view commands, None for other commands.
 fn get_tag_view_display(cmd: &Command, state: &State) -> Option<DisplayId> {
     match cmd {
-        Command::TagView { output, .. } | Command::TagToggle { output, .. } => {
+        Command::TagView { output, .. }
+        | Command::TagViewN { output, .. }
+        | Command::TagToggle { output, .. }
+        | Command::TagViewWithLayout { output, .. } => {
             state.get_target_display(output.as_ref()).ok()
         }
-        Command::TagViewLast => Some(state.focused_display),
+        Command::TagViewLast | Command::TagViewBack { .. } => Some(state.focused_display),
         _ => None,
     }
 }
@@ -70,10 +81,101 @@ fn handle_ipc_command<S: WindowSystem, M: WindowManipulator>(
     cmd: &Command,
 ) -> Response {
     // Handle ListWindows with all=true specially (requires system query)
-    if let Command::ListWindows { all: true, debug } = cmd {
+    if let Command::ListWindows {
+        all: true, debug, ..
+    } = cmd
+    {
         return list_all_windows(state, window_system, *debug);
     }
 
+    // MainRatioGet needs a reply from the layout engine
+    if let Command::MainRatioGet { output, tags } = cmd {
+        return query_main_ratio(state, layout_engine_manager, output.as_ref(), *tags);
+    }
+    if let Command::MainRatioAnimate {
+        target,
+        duration_ms,
+    } = cmd
+    {
+        return animate_main_ratio(state, layout_engine_manager, *target, *duration_ms);
+    }
+    if let Command::LayoutEngineLog { name, lines } = cmd {
+        return layout_engine_log(layout_engine_manager, name, *lines);
+    }
+    if let Command::LayoutLastInput { name } = cmd {
+        return layout_last_input(layout_engine_manager, name);
+    }
+    if let Command::LayoutDescribe { name } = cmd {
+        return layout_describe(layout_engine_manager, name);
+    }
+    if let Command::LayoutList = cmd {
+        return layout_list(state, layout_engine_manager);
+    }
+
+    // WindowGeometry reads the live frame from AX (requires system query)
+    if let Command::WindowGeometry { id } = cmd {
+        return query_window_geometry(state, window_system, *id);
+    }
+    if let Command::ResyncWindow { id } = cmd {
+        return resync_window(state, window_system, *id);
+    }
+    if let Command::ListUnmanaged = cmd {
+        return Response::UnmanagedWindows {
+            windows: state.borrow().unmanaged_windows(window_system),
+        };
+    }
+
+    // Overrides are keyed by the window's app name, which needs a system query.
+    // Managing resyncs the app so the window is picked up before retiling.
+    if let Command::ManageWindow { id } | Command::UnmanageWindow { id } = cmd {
+        let managed = matches!(cmd, Command::ManageWindow { .. });
+        let recorded = record_window_override(state, window_system, *id, managed, |pid| {
+            let _ = sync_and_process_new_windows(
+                state,
+                window_system,
+                layout_engine_manager,
+                manipulator,
+                event_emitter,
+                observer_manager,
+                pid,
+            );
+        });
+        if let Err(message) = recorded {
+            return Response::Error { message };
+        }
+    }
+
+    // Refuse to switch tags if the layout engine can't be started
+    if let Command::TagViewWithLayout { layout, .. } = cmd {
+        if is_valid_layout_name(layout) {
+            if let Err(e) = layout_engine_manager.borrow_mut().get_or_spawn(layout) {
+                return Response::Error {
+                    message: e.to_string(),
+                };
+            }
+        }
+    }
+
+    // Prune windows that vanished without a destroy notification before retiling
+    if let Command::Reconcile = cmd {
+        let removed = state.borrow_mut().reconcile_windows(window_system);
+        if !removed.is_empty() {
+            tracing::info!("Reconcile pruned {} stale window(s)", removed.len());
+        }
+    }
+
+    // Full re-initialization; process_command reports the new window count and retiles
+    if let Command::Resync = cmd {
+        resync_all_and_process_new_windows(
+            state,
+            window_system,
+            layout_engine_manager,
+            manipulator,
+            event_emitter,
+            observer_manager,
+        );
+    }
+
     // Handle tag-view commands with pre-sync to remove stale windows
     // Get display_id in a separate scope to avoid borrow conflict
     let tag_view_display = get_tag_view_display(cmd, &state.borrow());
@@ -97,7 +199,15 @@ fn handle_ipc_command<S: WindowSystem, M: WindowManipulator>(
         cmd,
     );
 
-    if let Err(e) = execute_effects(result.effects, state, layout_engine_manager, manipulator) {
+    let mut effects = result.effects;
+    // Windows pruned by the syncs above take their manage/unmanage overrides with them
+    if state.borrow_mut().take_window_overrides_pruned() {
+        effects.push(Effect::PersistWindowOverrides {
+            overrides: state.borrow().window_override_list(),
+        });
+    }
+
+    if let Err(e) = execute_effects(effects, state, layout_engine_manager, manipulator) {
         return Response::Error { message: e };
     }
 
@@ -181,6 +291,7 @@ mod tests {
             &Command::ListWindows {
                 all: false,
                 debug: false,
+                visible: false,
             },
             &state,
             &layout_manager,
@@ -194,6 +305,192 @@ mod tests {
         assert!(matches!(response, Response::Windows { .. }));
     }
 
+    #[test]
+    fn test_dispatch_command_reconcile_prunes_crashed_windows() {
+        let (
+            state,
+            layout_manager,
+            hotkey_manager,
+            mut ws,
+            manipulator,
+            event_emitter,
+            observer_manager,
+        ) = setup_test_context();
+
+        ws.remove_window(101);
+        ws.set_ax_accessible(1001, false);
+        ws.set_process_running(1001, false);
+
+        let response = dispatch_command(
+            &Command::Reconcile,
+            &state,
+            &layout_manager,
+            &hotkey_manager,
+            &ws,
+            &manipulator,
+            &event_emitter,
+            &observer_manager,
+        );
+
+        assert!(matches!(response, Response::Ok));
+        assert!(state.borrow().windows.contains_key(&100));
+        assert!(!state.borrow().windows.contains_key(&101));
+    }
+
+    #[test]
+    fn test_dispatch_command_resync_matches_fresh_enumeration() {
+        let (
+            state,
+            layout_manager,
+            hotkey_manager,
+            mut ws,
+            manipulator,
+            event_emitter,
+            observer_manager,
+        ) = setup_test_context();
+
+        // Drift: a window closed and another opened without any notifications
+        ws.remove_window(101);
+        ws.set_ax_accessible(1001, false);
+        ws.set_process_running(1001, false);
+        ws.add_window(create_test_window(
+            102, 1002, "VSCode", 200.0, 100.0, 800.0, 600.0,
+        ));
+
+        let response = dispatch_command(
+            &Command::Resync,
+            &state,
+            &layout_manager,
+            &hotkey_manager,
+            &ws,
+            &manipulator,
+            &event_emitter,
+            &observer_manager,
+        );
+
+        let mut fresh = State::new();
+        fresh.sync_all(&ws);
+        let mut expected: Vec<_> = fresh.windows.keys().copied().collect();
+        expected.sort();
+        let mut actual: Vec<_> = state.borrow().windows.keys().copied().collect();
+        actual.sort();
+
+        assert_eq!(actual, expected);
+        assert_eq!(actual, vec![100, 102]);
+        assert!(matches!(response, Response::WindowCount { count: 2 }));
+    }
+
+    #[test]
+    fn test_dispatch_command_window_geometry_reads_live_frame() {
+        let (
+            state,
+            layout_manager,
+            hotkey_manager,
+            mut ws,
+            manipulator,
+            event_emitter,
+            observer_manager,
+        ) = setup_test_context();
+
+        // Window moved since the last sync; the cached frame is stale
+        ws.remove_window(101);
+        ws.add_window(create_test_window(
+            101, 1001, "Terminal", 1000.0, 50.0, 800.0, 600.0,
+        ));
+
+        let response = dispatch_command(
+            &Command::WindowGeometry { id: 101 },
+            &state,
+            &layout_manager,
+            &hotkey_manager,
+            &ws,
+            &manipulator,
+            &event_emitter,
+            &observer_manager,
+        );
+
+        match response {
+            Response::WindowGeometry { geometry } => {
+                assert_eq!(
+                    (
+                        geometry.id,
+                        geometry.x,
+                        geometry.y,
+                        geometry.width,
+                        geometry.height
+                    ),
+                    (101, 1000, 50, 800, 600)
+                );
+            }
+            _ => panic!("Expected WindowGeometry"),
+        }
+    }
+
+    #[test]
+    fn test_dispatch_command_resync_window_updates_frame() {
+        let (
+            state,
+            layout_manager,
+            hotkey_manager,
+            mut ws,
+            manipulator,
+            event_emitter,
+            observer_manager,
+        ) = setup_test_context();
+
+        // User dragged the window; the tracked frame is stale
+        ws.remove_window(101);
+        ws.add_window(create_test_window(
+            101, 1001, "Terminal", 1000.0, 50.0, 800.0, 600.0,
+        ));
+
+        let response = dispatch_command(
+            &Command::ResyncWindow { id: 101 },
+            &state,
+            &layout_manager,
+            &hotkey_manager,
+            &ws,
+            &manipulator,
+            &event_emitter,
+            &observer_manager,
+        );
+
+        assert!(matches!(response, Response::Ok));
+        let frame = state.borrow().windows.get(&101).unwrap().frame;
+        assert_eq!(
+            (frame.x, frame.y, frame.width, frame.height),
+            (1000, 50, 800, 600)
+        );
+        // Other windows are untouched
+        assert_eq!(state.borrow().windows.get(&100).unwrap().frame.width, 960);
+    }
+
+    #[test]
+    fn test_dispatch_command_window_geometry_unknown_id() {
+        let (
+            state,
+            layout_manager,
+            hotkey_manager,
+            ws,
+            manipulator,
+            event_emitter,
+            observer_manager,
+        ) = setup_test_context();
+
+        let response = dispatch_command(
+            &Command::WindowGeometry { id: 999 },
+            &state,
+            &layout_manager,
+            &hotkey_manager,
+            &ws,
+            &manipulator,
+            &event_emitter,
+            &observer_manager,
+        );
+
+        assert!(matches!(response, Response::Error { .. }));
+    }
+
     #[test]
     fn test_dispatch_command_get_state() {
         let (
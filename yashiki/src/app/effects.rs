@@ -1,12 +1,14 @@
 use std::cell::RefCell;
 
-use crate::core::State;
+use crate::core::{
+    save_snapshot, save_window_overrides, snapshot_dir, window_overrides_path, State,
+};
 use crate::effect::Effect;
 use crate::layout::LayoutEngineManager;
 use crate::platform::WindowManipulator;
 use yashiki_ipc::CursorWarpMode;
 
-use super::focus::{focus_visible_window_if_needed, notify_layout_focus};
+use super::focus::{focus_visible_window_if_needed, notify_layout_focus, reassert_always_on_top};
 use super::retile::{do_retile, do_retile_display};
 
 /// Execute side effects.
@@ -16,6 +18,7 @@ pub fn execute_effects<M: WindowManipulator>(
     layout_engine_manager: &RefCell<LayoutEngineManager>,
     manipulator: &M,
 ) -> Result<(), String> {
+    let mut broadcast_errors = Vec::new();
     for effect in effects {
         match effect {
             Effect::ApplyWindowMoves(moves) => {
@@ -29,7 +32,8 @@ pub fn execute_effects<M: WindowManipulator>(
                 // Set focus intent BEFORE focusing to suppress spurious macOS focus changes
                 state.borrow_mut().set_focus_intent(window_id, pid);
 
-                manipulator.focus_window(window_id, pid);
+                let raise = state.borrow().config.raise_on_focus;
+                manipulator.focus_window(window_id, pid, raise);
 
                 // Update state.focused immediately after focusing
                 // This ensures consecutive focus commands work correctly
@@ -56,6 +60,8 @@ pub fn execute_effects<M: WindowManipulator>(
                     }
                 }
 
+                reassert_always_on_top(state, manipulator, window_id);
+
                 if notify_layout_focus(state, layout_engine_manager, window_id) {
                     do_retile(state, layout_engine_manager, manipulator);
                 }
@@ -76,24 +82,43 @@ pub fn execute_effects<M: WindowManipulator>(
             } => {
                 manipulator.set_window_dimensions(window_id, pid, width, height);
             }
+            Effect::ResizeWindow {
+                window_id,
+                pid,
+                x,
+                y,
+                width,
+                height,
+            } => {
+                manipulator.set_window_frame(window_id, pid, x, y, width, height);
+            }
             Effect::CloseWindow { window_id, pid } => {
                 manipulator.close_window(window_id, pid);
             }
+            Effect::SetWindowMinimized {
+                window_id,
+                pid,
+                minimized,
+            } => {
+                manipulator.set_window_minimized(window_id, pid, minimized);
+            }
+            Effect::RaiseWindow { window_id, pid } => {
+                manipulator.raise_window(window_id, pid);
+            }
             Effect::ApplyFullscreen {
                 window_id,
                 pid,
                 display_id,
             } => {
-                let state = state.borrow();
-                let outer_gap = state.config.outer_gap;
-                if let Some(display) = state.displays.get(&display_id) {
+                let usable = state.borrow().usable_frame(display_id);
+                if let Some(frame) = usable {
                     manipulator.set_window_frame(
                         window_id,
                         pid,
-                        display.frame.x + outer_gap.left as i32,
-                        display.frame.y + outer_gap.top as i32,
-                        display.frame.width.saturating_sub(outer_gap.horizontal()),
-                        display.frame.height.saturating_sub(outer_gap.vertical()),
+                        frame.x,
+                        frame.y,
+                        frame.width,
+                        frame.height,
                     );
                 }
             }
@@ -114,6 +139,37 @@ pub fn execute_effects<M: WindowManipulator>(
                     return Err(format!("Layout command failed: {}", e));
                 }
             }
+            Effect::BroadcastLayoutCommand { layouts, cmd, args } => {
+                let mut manager = layout_engine_manager.borrow_mut();
+                broadcast_errors.extend(send_to_each_layout(&layouts, |layout| {
+                    manager
+                        .send_command(layout, &cmd, &args)
+                        .map(|_| ())
+                        .map_err(|e| e.to_string())
+                }));
+            }
+            Effect::ZoomLayoutWindow { layout, window_id } => {
+                let mut manager = layout_engine_manager.borrow_mut();
+                if let Err(e) = manager.send_command(&layout, "zoom", &[window_id.to_string()]) {
+                    tracing::debug!("Layout '{}' did not zoom {}: {}", layout, window_id, e);
+                }
+            }
+            Effect::ToggleInnerGap { layout } => {
+                let mut manager = layout_engine_manager.borrow_mut();
+                let current_gap = manager
+                    .inner_gap(&layout)
+                    .map_err(|e| format!("Layout command failed: {}", e))?
+                    .ok_or_else(|| format!("Layout '{}' has no inner gap", layout))?;
+                let gap = state.borrow_mut().toggle_inner_gap(&layout, current_gap);
+                // Engines without an inner gap (byobu) report 0 and have nothing to set
+                if gap != current_gap {
+                    if let Err(e) =
+                        manager.send_command(&layout, "set-inner-gap", &[gap.to_string()])
+                    {
+                        return Err(format!("Layout command failed: {}", e));
+                    }
+                }
+            }
             Effect::ExecCommand { command, path } => {
                 manipulator.exec_command(&command, &path)?;
             }
@@ -135,6 +191,14 @@ pub fn execute_effects<M: WindowManipulator>(
             Effect::UpdateLayoutExecPath { path } => {
                 layout_engine_manager.borrow_mut().set_exec_path(&path);
             }
+            Effect::UpdateLayoutDefaults { defaults } => {
+                layout_engine_manager.borrow_mut().set_defaults(defaults);
+            }
+            Effect::SetLayoutAlias { alias, target } => {
+                layout_engine_manager
+                    .borrow_mut()
+                    .set_alias(&alias, &target);
+            }
             Effect::FocusVisibleWindowIfNeeded => {
                 focus_visible_window_if_needed(state, manipulator);
             }
@@ -151,7 +215,125 @@ pub fn execute_effects<M: WindowManipulator>(
                     }
                 }
             }
+            Effect::SetMainDisplay { display_id } => {
+                manipulator.set_main_display(display_id)?;
+            }
+            Effect::PersistSnapshot { name, snapshot } => {
+                let dir = snapshot_dir().ok_or("Could not determine home directory")?;
+                save_snapshot(&dir, &name, &snapshot)?;
+            }
+            Effect::PersistWindowOverrides { overrides } => {
+                let path = window_overrides_path().ok_or("Could not determine home directory")?;
+                save_window_overrides(&path, &overrides)?;
+            }
+            Effect::WriteBindingsExport { path, content } => {
+                std::fs::write(&path, content)
+                    .map_err(|e| format!("Failed to write {}: {}", path, e))?;
+                tracing::info!("Exported bindings to {}", path);
+            }
         }
     }
+    if !broadcast_errors.is_empty() {
+        return Err(format!(
+            "Layout command failed: {}",
+            broadcast_errors.join("; ")
+        ));
+    }
     Ok(())
 }
+
+/// Call `send` for every layout, collecting failures instead of stopping at the first.
+fn send_to_each_layout(
+    layouts: &[String],
+    mut send: impl FnMut(&str) -> Result<(), String>,
+) -> Vec<String> {
+    layouts
+        .iter()
+        .filter_map(|layout| send(layout).err().map(|e| format!("{}: {}", layout, e)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::mock::{
+        create_test_display, create_test_window, MockWindowManipulator, MockWindowSystem,
+    };
+
+    fn focus_with_raise_on_focus(enabled: bool) -> Vec<(u32, i32, bool)> {
+        let ws = MockWindowSystem::new()
+            .with_displays(vec![create_test_display(1, 0.0, 0.0, 1920.0, 1080.0)])
+            .with_windows(vec![create_test_window(
+                100, 1000, "Safari", 0.0, 0.0, 960.0, 1080.0,
+            )]);
+        let mut state = State::new();
+        state.sync_all(&ws);
+        state.config.raise_on_focus = enabled;
+        let state = RefCell::new(state);
+        let layout_engine_manager = RefCell::new(LayoutEngineManager::new());
+        let manipulator = MockWindowManipulator::new();
+
+        execute_effects(
+            vec![Effect::FocusWindow {
+                window_id: 100,
+                pid: 1000,
+                is_output_change: false,
+            }],
+            &state,
+            &layout_engine_manager,
+            &manipulator,
+        )
+        .unwrap();
+
+        manipulator.focus_calls.take()
+    }
+
+    #[test]
+    fn test_focus_effect_raises_by_default() {
+        assert!(crate::core::Config::new().raise_on_focus);
+        assert_eq!(focus_with_raise_on_focus(true), vec![(100, 1000, true)]);
+    }
+
+    #[test]
+    fn test_focus_effect_skips_raise_when_disabled() {
+        assert_eq!(focus_with_raise_on_focus(false), vec![(100, 1000, false)]);
+    }
+
+    #[test]
+    fn test_send_to_each_layout_reaches_every_layout() {
+        let layouts = vec!["byobu".to_string(), "tatami".to_string()];
+        let mut sent = Vec::new();
+
+        let errors = send_to_each_layout(&layouts, |layout| {
+            sent.push(layout.to_string());
+            Ok(())
+        });
+
+        assert!(errors.is_empty());
+        assert_eq!(sent, layouts);
+    }
+
+    #[test]
+    fn test_send_to_each_layout_collects_errors_without_aborting() {
+        let layouts = vec![
+            "byobu".to_string(),
+            "deck".to_string(),
+            "tatami".to_string(),
+        ];
+        let mut sent = Vec::new();
+
+        let errors = send_to_each_layout(&layouts, |layout| {
+            sent.push(layout.to_string());
+            match layout {
+                "tatami" => Ok(()),
+                _ => Err("unknown command".to_string()),
+            }
+        });
+
+        assert_eq!(sent, layouts);
+        assert_eq!(
+            errors,
+            vec!["byobu: unknown command", "deck: unknown command"]
+        );
+    }
+}
@@ -60,7 +60,8 @@ pub fn focus_visible_window_if_needed<M: WindowManipulator>(
         // Set focus intent BEFORE focusing to suppress spurious macOS focus changes
         state.borrow_mut().set_focus_intent(window_id, pid);
 
-        manipulator.focus_window(window_id, pid);
+        let raise = state.borrow().config.raise_on_focus;
+        manipulator.focus_window(window_id, pid, raise);
 
         // Update internal state immediately after focusing
         // This ensures emit_state_change_events will detect the focus change
@@ -113,6 +114,19 @@ pub fn switch_tag_for_focused_window(state: &RefCell<State>) -> Option<Vec<Windo
     Some(moves)
 }
 
+/// Raise always-on-top windows again so the newly focused window does not cover them.
+pub fn reassert_always_on_top<M: WindowManipulator>(
+    state: &RefCell<State>,
+    manipulator: &M,
+    focused_id: u32,
+) {
+    let windows = state.borrow().always_on_top_to_reassert(focused_id);
+    for (window_id, pid) in windows {
+        tracing::debug!("Re-raising always-on-top window {}", window_id);
+        manipulator.raise_window(window_id, pid);
+    }
+}
+
 /// Notify layout engine of focus change.
 /// Returns true if the layout engine requests a retile.
 pub fn notify_layout_focus(
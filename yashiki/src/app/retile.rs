@@ -1,6 +1,9 @@
 use std::cell::RefCell;
+use std::time::Instant;
 
-use crate::core::State;
+use yashiki_ipc::WindowGeometry;
+
+use crate::core::{Rect, RetileStats, State};
 use crate::layout::LayoutEngineManager;
 use crate::macos::DisplayId;
 use crate::platform::WindowManipulator;
@@ -10,12 +13,26 @@ pub fn do_retile<M: WindowManipulator>(
     layout_engine_manager: &RefCell<LayoutEngineManager>,
     manipulator: &M,
 ) {
+    let started = Instant::now();
+    let mut stats = RetileStats::default();
+
+    sync_suppressed_inner_gaps(state, layout_engine_manager);
+
     // Collect display IDs first to avoid borrow issues
     let display_ids: Vec<_> = state.borrow().displays.keys().copied().collect();
 
     for display_id in display_ids {
-        retile_single_display(state, layout_engine_manager, manipulator, display_id);
+        retile_single_display(
+            state,
+            layout_engine_manager,
+            manipulator,
+            display_id,
+            &mut stats,
+        );
     }
+
+    stats.duration = started.elapsed();
+    state.borrow_mut().stats.record_retile(stats);
 }
 
 pub fn do_retile_display<M: WindowManipulator>(
@@ -27,7 +44,68 @@ pub fn do_retile_display<M: WindowManipulator>(
     if !state.borrow().displays.contains_key(&display_id) {
         return;
     }
-    retile_single_display(state, layout_engine_manager, manipulator, display_id);
+    let started = Instant::now();
+    let mut stats = RetileStats::default();
+    retile_single_display(
+        state,
+        layout_engine_manager,
+        manipulator,
+        display_id,
+        &mut stats,
+    );
+    stats.duration = started.elapsed();
+    state.borrow_mut().stats.record_retile(stats);
+}
+
+/// Zero the inner gap of every layout in use while gaps are suppressed
+/// (gaps-multi-monitor-only with a single display), and restore it once they are not.
+fn sync_suppressed_inner_gaps(
+    state: &RefCell<State>,
+    layout_engine_manager: &RefCell<LayoutEngineManager>,
+) {
+    let mut manager = layout_engine_manager.borrow_mut();
+    if !state.borrow().gaps_suppressed() {
+        let saved: Vec<_> = state.borrow_mut().suppressed_inner_gaps.drain().collect();
+        for (layout, gap) in saved {
+            if let Err(e) = manager.send_command(&layout, "set-inner-gap", &[gap.to_string()]) {
+                tracing::warn!("Failed to restore inner gap of '{}': {}", layout, e);
+            }
+        }
+        return;
+    }
+
+    let layouts = state.borrow().active_layouts();
+    for layout in layouts {
+        // Cached by the manager; engines without an inner gap are skipped
+        let Ok(Some(gap)) = manager.inner_gap(&layout) else {
+            continue;
+        };
+        // A gap set while suppressed becomes the one restored later
+        if gap == 0 {
+            continue;
+        }
+        state
+            .borrow_mut()
+            .suppressed_inner_gaps
+            .insert(layout.clone(), gap);
+        if let Err(e) = manager.send_command(&layout, "set-inner-gap", &["0".to_string()]) {
+            tracing::warn!("Failed to suppress inner gap of '{}': {}", layout, e);
+        }
+    }
+}
+
+/// Whether a geometry (display-relative) matches its window's tracked frame, so
+/// writing it would not move the window.
+fn is_in_place(state: &State, display_frame: &Rect, geometry: &WindowGeometry) -> bool {
+    state.windows.get(&geometry.id).is_some_and(|w| {
+        w.frame
+            == Rect {
+                x: geometry.x + display_frame.x,
+                y: geometry.y + display_frame.y,
+                width: geometry.width,
+                height: geometry.height,
+            }
+    })
 }
 
 fn retile_single_display<M: WindowManipulator>(
@@ -35,12 +113,15 @@ fn retile_single_display<M: WindowManipulator>(
     layout_engine_manager: &RefCell<LayoutEngineManager>,
     manipulator: &M,
     display_id: DisplayId,
+    stats: &mut RetileStats,
 ) {
     // First, handle any fullscreen windows on this display
     {
         let state = state.borrow();
-        let outer_gap = state.config.outer_gap;
-        if let Some(display) = state.displays.get(&display_id) {
+        if let (Some(display), Some(target)) = (
+            state.displays.get(&display_id),
+            state.usable_frame(display_id),
+        ) {
             let fullscreen_windows: Vec<_> = state
                 .windows
                 .values()
@@ -53,67 +134,85 @@ fn retile_single_display<M: WindowManipulator>(
                 .map(|w| (w.id, w.pid))
                 .collect();
 
-            // Apply fullscreen with outer gap
+            // Fullscreen fills the usable frame (reservations and outer gap applied)
             for (window_id, pid) in fullscreen_windows {
+                if state
+                    .windows
+                    .get(&window_id)
+                    .is_some_and(|w| w.frame == target)
+                {
+                    stats.skipped += 1;
+                    continue;
+                }
+                stats.writes += 1;
                 manipulator.set_window_frame(
                     window_id,
                     pid,
-                    display.frame.x + outer_gap.left as i32,
-                    display.frame.y + outer_gap.top as i32,
-                    display.frame.width.saturating_sub(outer_gap.horizontal()),
-                    display.frame.height.saturating_sub(outer_gap.vertical()),
+                    target.x,
+                    target.y,
+                    target.width,
+                    target.height,
                 );
             }
         }
     }
 
+    // The sticky main window leads the order whenever it is visible
+    state.borrow_mut().apply_sticky_main(display_id);
+
     // Get layout parameters with immutable borrow
-    let (window_ids, usable_width, usable_height, display_frame, layout_name, outer_gap) = {
+    let (window_ids, usable, display_frame, layout_name) = {
         let state = state.borrow();
-        let Some(display) = state.displays.get(&display_id) else {
+        let (Some(display), Some(usable)) = (
+            state.displays.get(&display_id),
+            state.usable_frame(display_id),
+        ) else {
             return;
         };
-        let visible_windows = state.visible_windows_on_display(display_id);
-        if visible_windows.is_empty() {
+        let window_ids = state.layout_window_ids(display_id);
+        if window_ids.is_empty() {
             return;
         }
-        let window_ids: Vec<u32> = visible_windows.iter().map(|w| w.id).collect();
         let layout_name = state.current_layout_for_display(display_id).to_string();
-        let outer_gap = state.config.outer_gap;
-        // Subtract outer gap from dimensions before sending to layout engine
-        let usable_width = display.frame.width.saturating_sub(outer_gap.horizontal());
-        let usable_height = display.frame.height.saturating_sub(outer_gap.vertical());
-        (
-            window_ids,
-            usable_width,
-            usable_height,
-            display.frame,
-            layout_name,
-            outer_gap,
-        )
+        (window_ids, usable, display.frame, layout_name)
     };
 
     let mut manager = layout_engine_manager.borrow_mut();
-    match manager.request_layout(&layout_name, usable_width, usable_height, &window_ids) {
+    // The engine lays out within the usable frame (reservations and outer gap subtracted)
+    match manager.request_layout(&layout_name, usable.width, usable.height, &window_ids) {
         Ok(geometries) => {
             // Update window_order based on geometries order from layout engine
-            {
-                let mut state = state.borrow_mut();
-                if let Some(display) = state.displays.get_mut(&display_id) {
-                    display.window_order = geometries.iter().map(|g| g.id).collect();
-                }
-            }
-            // Add outer gap offset to geometries before applying
-            let adjusted_geometries: Vec<_> = geometries
+            let laid_out: Vec<_> = geometries.iter().map(|g| g.id).collect();
+            state.borrow_mut().apply_layout_order(display_id, &laid_out);
+            // Offset geometries by the usable frame's inset before applying
+            let mut adjusted_geometries: Vec<_> = geometries
                 .into_iter()
                 .map(|mut g| {
-                    g.x += outer_gap.left as i32;
-                    g.y += outer_gap.top as i32;
+                    g.x += usable.x - display_frame.x;
+                    g.y += usable.y - display_frame.y;
                     g
                 })
                 .collect();
-            // Apply layout using manipulator
-            manipulator.apply_layout(display_id, &display_frame, &adjusted_geometries);
+            state
+                .borrow_mut()
+                .apply_peek_frames(display_id, &mut adjusted_geometries);
+            state.borrow().apply_native_sizes(&mut adjusted_geometries);
+            // Windows already at their target frame are not written again
+            let (in_place, to_write): (Vec<_>, Vec<_>) = {
+                let state = state.borrow();
+                adjusted_geometries
+                    .into_iter()
+                    .partition(|g| is_in_place(&state, &display_frame, g))
+            };
+            stats.skipped += in_place.len() as u32;
+            stats.writes += to_write.len() as u32;
+            if !to_write.is_empty() {
+                manipulator.apply_layout(display_id, &display_frame, &to_write);
+            }
+            state
+                .borrow_mut()
+                .auto_raise_state
+                .record_retile(Instant::now());
         }
         Err(e) => {
             tracing::error!("Layout request failed for display {}: {}", display_id, e);
@@ -7,7 +7,7 @@ use crate::event_emitter::EventEmitter;
 /// Window properties tracked for change detection
 #[derive(Clone, PartialEq)]
 pub struct WindowProperties {
-    tags: u32,
+    tags: u64,
     display_id: u32,
     is_floating: bool,
     is_fullscreen: bool,
@@ -16,7 +16,7 @@ pub struct WindowProperties {
 /// State captured before command execution for event comparison
 pub struct PreEventState {
     /// Map of display_id to (visible_tags, current_layout)
-    pub displays: HashMap<u32, (u32, Option<String>)>,
+    pub displays: HashMap<u32, (u64, Option<String>)>,
     /// Map of window_id to tracked properties
     pub windows: HashMap<u32, WindowProperties>,
     pub focused: Option<u32>,
@@ -1,4 +1,5 @@
 use std::cell::RefCell;
+use std::collections::HashSet;
 
 use crate::core::{State, WindowId};
 use crate::event_emitter::EventEmitter;
@@ -54,6 +55,62 @@ pub fn sync_and_process_new_windows<W: WindowSystem, M: WindowManipulator>(
     SyncResult { changed }
 }
 
+/// Re-read all displays and on-screen windows from the system, as at startup,
+/// then prune windows of exited processes. Used by `resync` to recover from
+/// missed events; the caller retiles afterwards.
+pub fn resync_all_and_process_new_windows<W: WindowSystem, M: WindowManipulator>(
+    state: &RefCell<State>,
+    window_system: &W,
+    layout_engine_manager: &RefCell<LayoutEngineManager>,
+    manipulator: &M,
+    event_emitter: &EventEmitter,
+    observer_manager: &RefCell<ObserverManager>,
+) {
+    let pids: HashSet<i32> = window_system
+        .get_on_screen_windows()
+        .iter()
+        .map(|w| w.pid)
+        .collect();
+    for pid in pids {
+        if !observer_manager.borrow().has_observer(pid) {
+            tracing::info!("Adding observer for pid {} during resync", pid);
+            if let Err(e) = observer_manager.borrow_mut().add_observer(pid) {
+                tracing::warn!("Failed to add observer for pid {}: {}", pid, e);
+            }
+        }
+    }
+
+    // Display changes run a full sync_all and re-home orphaned windows
+    let result = state.borrow_mut().handle_display_change(window_system);
+    let removed = state.borrow_mut().reconcile_windows(window_system);
+    tracing::info!(
+        "Resync: {} display(s) added, {} removed, {} new window(s), {} stale window(s) pruned",
+        result.added.len(),
+        result.removed.len(),
+        result.new_window_ids.len(),
+        removed.len()
+    );
+
+    for display in &result.added {
+        event_emitter.emit_display_added(display, &state.borrow());
+    }
+    for display_id in &result.removed {
+        event_emitter.emit_display_removed(*display_id);
+    }
+
+    if !result.window_moves.is_empty() {
+        manipulator.apply_window_moves(&result.window_moves);
+    }
+
+    process_new_windows(
+        result.new_window_ids,
+        state,
+        layout_engine_manager,
+        manipulator,
+        event_emitter,
+    );
+}
+
 /// Process newly discovered windows: apply rules and emit events.
 pub fn process_new_windows<M: WindowManipulator>(
     new_window_ids: Vec<WindowId>,
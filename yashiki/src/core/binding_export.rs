@@ -0,0 +1,92 @@
+/// A hotkey binding as written back out by `export-bindings`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportedBinding {
+    pub key: String,
+    /// Command line the action was parsed from (empty if bound over raw IPC)
+    pub args: Vec<String>,
+    pub label: Option<String>,
+}
+
+/// Quote an argument for a POSIX shell, leaving plain words untouched.
+pub fn shell_quote(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_.,/:=+@%".contains(c));
+    if plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+/// Render bindings as `yashiki bind` lines for the init script, sorted by key.
+/// Bindings without a recorded command line are kept as comments.
+pub fn format_bindings_export(bindings: &[ExportedBinding]) -> String {
+    let mut sorted: Vec<&ExportedBinding> = bindings.iter().collect();
+    sorted.sort_by(|a, b| a.key.cmp(&b.key));
+
+    let mut out = String::from("# Generated by `yashiki export-bindings`\n");
+    for binding in sorted {
+        if binding.args.is_empty() {
+            out.push_str(&format!(
+                "# {}: bound without a command line, not exported\n",
+                binding.key
+            ));
+            continue;
+        }
+        let mut words = vec!["yashiki".to_string(), "bind".to_string()];
+        if let Some(label) = &binding.label {
+            words.push("--label".to_string());
+            words.push(shell_quote(label));
+        }
+        words.push(shell_quote(&binding.key));
+        words.extend(binding.args.iter().map(|arg| shell_quote(arg)));
+        out.push_str(&words.join(" "));
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("window-focus"), "window-focus");
+        assert_eq!(shell_quote("-0.05"), "-0.05");
+        assert_eq!(shell_quote("focus left"), "'focus left'");
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+        assert_eq!(shell_quote(""), "''");
+    }
+
+    #[test]
+    fn test_format_bindings_export_sorts_and_skips_unexportable() {
+        let bindings = vec![
+            ExportedBinding {
+                key: "cmd-j".to_string(),
+                args: vec!["window-focus".to_string(), "next".to_string()],
+                label: None,
+            },
+            ExportedBinding {
+                key: "cmd-x".to_string(),
+                args: vec![],
+                label: None,
+            },
+            ExportedBinding {
+                key: "cmd-h".to_string(),
+                args: vec!["window-focus".to_string(), "left".to_string()],
+                label: Some("focus left".to_string()),
+            },
+        ];
+
+        assert_eq!(
+            format_bindings_export(&bindings),
+            "# Generated by `yashiki export-bindings`\n\
+             yashiki bind --label 'focus left' cmd-h window-focus left\n\
+             yashiki bind cmd-j window-focus next\n\
+             # cmd-x: bound without a command line, not exported\n"
+        );
+    }
+}
@@ -1,5 +1,35 @@
+use std::time::Duration;
+
+use crate::macos::Modifiers;
 use yashiki_ipc::{AutoRaiseMode, CursorWarpMode, OuterGap};
 
+/// Lower bound for a non-zero `set-tick-rate`; shorter ticks only burn CPU.
+pub const MIN_TICK_RATE_MS: u32 = 4;
+/// Animation step used while the tick rate is 0: about one frame at 60Hz.
+pub const ANIMATION_FRAME_MS: u32 = 16;
+
+/// Layout parameters pushed to every layout engine right after it is spawned,
+/// so switching layouts keeps the user's tuning instead of engine defaults.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LayoutDefaults {
+    pub main_ratio: Option<f64>,
+    pub inner_gap: Option<u32>,
+}
+
+impl LayoutDefaults {
+    /// Engine commands that apply these defaults, in order.
+    pub fn commands(&self) -> Vec<(&'static str, Vec<String>)> {
+        let mut commands = Vec::new();
+        if let Some(ratio) = self.main_ratio {
+            commands.push(("set-main-ratio", vec![ratio.to_string()]));
+        }
+        if let Some(gap) = self.inner_gap {
+            commands.push(("set-inner-gap", vec![gap.to_string()]));
+        }
+        commands
+    }
+}
+
 /// Application configuration settings.
 /// Grouped separately from window/display state for clarity.
 #[derive(Debug, Clone, Default)]
@@ -8,12 +38,66 @@ pub struct Config {
     pub cursor_warp: CursorWarpMode,
     pub auto_raise_mode: AutoRaiseMode,
     pub auto_raise_delay_ms: u64,
+    /// Modifiers that turn a left/right drag into a move/resize of the floating
+    /// window under the cursor (None: disabled)
+    pub drag_modifier: Option<Modifiers>,
     pub outer_gap: OuterGap,
+    /// Force inner and outer gaps to zero while only one display is connected
+    pub gaps_multi_monitor_only: bool,
+    /// Displays show and windows carry exactly one tag
+    pub exclusive_tags: bool,
+    /// Cadence at which coalesced event-loop work (retiles) is flushed;
+    /// 0 (the default) runs it immediately
+    pub tick_rate_ms: u32,
+    pub layout_defaults: LayoutDefaults,
+    /// Raise the window (AXRaise) when focusing it
+    pub raise_on_focus: bool,
     pub init_completed: bool,
 }
 
 impl Config {
     pub fn new() -> Self {
-        Self::default()
+        Self {
+            raise_on_focus: true,
+            drag_modifier: Some(Modifiers {
+                cmd: true,
+                alt: true,
+                ..Modifiers::default()
+            }),
+            ..Self::default()
+        }
+    }
+
+    /// Set the tick rate. 0 turns coalescing off; other values are clamped to
+    /// `MIN_TICK_RATE_MS`. Returns the stored value.
+    pub fn set_tick_rate(&mut self, ms: u32) -> u32 {
+        self.tick_rate_ms = if ms == 0 { 0 } else { ms.max(MIN_TICK_RATE_MS) };
+        self.tick_rate_ms
+    }
+
+    pub fn tick_rate(&self) -> Duration {
+        Duration::from_millis(self.tick_rate_ms as u64)
+    }
+
+    /// Interval between animation steps: the tick, or one frame when ticks are immediate.
+    pub fn animation_frame(&self) -> Duration {
+        match self.tick_rate_ms {
+            0 => Duration::from_millis(ANIMATION_FRAME_MS as u64),
+            ms => Duration::from_millis(ms as u64),
+        }
+    }
+
+    /// Whether gaps are forced off for the given number of connected displays.
+    pub fn gaps_suppressed(&self, display_count: usize) -> bool {
+        self.gaps_multi_monitor_only && display_count <= 1
+    }
+
+    /// Outer gap to apply for the given number of connected displays.
+    pub fn effective_outer_gap(&self, display_count: usize) -> OuterGap {
+        if self.gaps_suppressed(display_count) {
+            OuterGap::default()
+        } else {
+            self.outer_gap
+        }
     }
 }
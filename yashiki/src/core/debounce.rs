@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+
+/// Leading-and-trailing edge debouncer driven by the event-loop tick.
+///
+/// The first request for a key runs immediately. Requests arriving within `interval`
+/// of the last run are coalesced into one trailing run, picked up with `take_due`
+/// once the interval has passed.
+#[derive(Debug)]
+pub struct Debouncer<K> {
+    last_run: HashMap<K, Instant>,
+    /// Deadline of the coalesced trailing run, per key
+    pending: HashMap<K, Instant>,
+}
+
+impl<K: Copy + Eq + Hash> Debouncer<K> {
+    pub fn new() -> Self {
+        Self {
+            last_run: HashMap::new(),
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Returns true if the work for `key` should run now. Otherwise it is queued.
+    pub fn request(&mut self, key: K, now: Instant, interval: Duration) -> bool {
+        match self.last_run.get(&key) {
+            Some(&last) if now.saturating_duration_since(last) < interval => {
+                self.pending.entry(key).or_insert(last + interval);
+                false
+            }
+            _ => {
+                self.last_run.insert(key, now);
+                self.pending.remove(&key);
+                true
+            }
+        }
+    }
+
+    /// Earliest deadline of a queued run, for scheduling the next tick.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.pending.values().min().copied()
+    }
+
+    /// Keys whose queued run is due at `now`. They count as run at `now`.
+    pub fn take_due(&mut self, now: Instant) -> Vec<K> {
+        let due: Vec<K> = self
+            .pending
+            .iter()
+            .filter(|(_, &deadline)| deadline <= now)
+            .map(|(&key, _)| key)
+            .collect();
+        for key in &due {
+            self.pending.remove(key);
+            self.last_run.insert(*key, now);
+        }
+        due
+    }
+}
+
+impl<K: Copy + Eq + Hash> Default for Debouncer<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_request_runs_immediately() {
+        let mut debouncer = Debouncer::new();
+        assert!(debouncer.request((), Instant::now(), Duration::from_millis(16)));
+        assert_eq!(debouncer.next_deadline(), None);
+    }
+
+    #[test]
+    fn test_rapid_requests_coalesce_into_one_trailing_run() {
+        let mut debouncer = Debouncer::new();
+        let interval = Duration::from_millis(16);
+        let start = Instant::now();
+
+        assert!(debouncer.request(1, start, interval));
+        assert!(!debouncer.request(1, start + Duration::from_millis(5), interval));
+        assert!(!debouncer.request(1, start + Duration::from_millis(10), interval));
+        // Other keys are independent
+        assert!(debouncer.request(2, start + Duration::from_millis(10), interval));
+
+        assert_eq!(debouncer.next_deadline(), Some(start + interval));
+        assert!(debouncer
+            .take_due(start + Duration::from_millis(15))
+            .is_empty());
+        assert_eq!(debouncer.take_due(start + interval), vec![1]);
+        assert_eq!(debouncer.next_deadline(), None);
+    }
+
+    #[test]
+    fn test_request_after_interval_runs_immediately() {
+        let mut debouncer = Debouncer::new();
+        let interval = Duration::from_millis(16);
+        let start = Instant::now();
+
+        assert!(debouncer.request((), start, interval));
+        assert!(debouncer.request((), start + interval, interval));
+    }
+}
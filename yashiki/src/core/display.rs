@@ -1,7 +1,13 @@
-use crate::macos::DisplayId;
+use std::collections::VecDeque;
+
+use crate::macos::{DisplayId, DisplayInfo};
+use yashiki_ipc::{Edge, OuterGap};
 
 use super::{Rect, Tag, WindowId};
 
+/// Previous tag sets kept per display for `tag-view-back`.
+pub const TAG_HISTORY_LIMIT: usize = 16;
+
 #[derive(Debug, Clone)]
 pub struct Display {
     pub id: DisplayId,
@@ -10,9 +16,18 @@ pub struct Display {
     pub is_main: bool,
     pub visible_tags: Tag,
     pub previous_visible_tags: Tag,
+    /// Tag sets viewed before the current one, most recent last (at most `TAG_HISTORY_LIMIT`)
+    pub tag_history: VecDeque<Tag>,
     pub window_order: Vec<WindowId>,
     pub current_layout: Option<String>,
     pub previous_layout: Option<String>,
+    /// Directional focus/swap treats left/right as up/down (set-focus-axis-swap)
+    pub focus_axis_swap: bool,
+    /// Space kept free for external bars, per edge (reserve-edge)
+    pub reserved: OuterGap,
+    /// Outer gap used instead of the global one (clear-reservations zeroes it;
+    /// set-outer-gap drops it)
+    pub outer_gap: Option<OuterGap>,
 }
 
 impl Display {
@@ -24,9 +39,145 @@ impl Display {
             is_main,
             visible_tags: Tag::new(1),
             previous_visible_tags: Tag::new(1),
+            tag_history: VecDeque::new(),
             window_order: Vec::new(),
             current_layout: None,
             previous_layout: None,
+            focus_axis_swap: false,
+            reserved: OuterGap::default(),
+            outer_gap: None,
+        }
+    }
+
+    /// Record `tags` as the most recently left tag set, dropping the oldest past the limit.
+    pub fn push_tag_history(&mut self, tags: Tag) {
+        self.tag_history.push_back(tags);
+        if self.tag_history.len() > TAG_HISTORY_LIMIT {
+            self.tag_history.pop_front();
+        }
+    }
+
+    /// Add `size` to the space reserved at `edge`. Fails, leaving the reservations
+    /// unchanged, if they would cover the whole display along that axis.
+    pub fn reserve_edge(&mut self, edge: Edge, size: u32) -> Result<(), String> {
+        let mut reserved = self.reserved;
+        let side = match edge {
+            Edge::Top => &mut reserved.top,
+            Edge::Bottom => &mut reserved.bottom,
+            Edge::Left => &mut reserved.left,
+            Edge::Right => &mut reserved.right,
+        };
+        *side = side.saturating_add(size);
+        let (total, extent) = match edge {
+            Edge::Top | Edge::Bottom => (reserved.vertical(), self.frame.height),
+            Edge::Left | Edge::Right => (reserved.horizontal(), self.frame.width),
+        };
+        if total >= extent {
+            return Err(format!(
+                "Reserving {}px at {:?} edge would leave no room on display {} ({}px reserved of {}px)",
+                size, edge, self.id, total, extent
+            ));
         }
+        self.reserved = reserved;
+        Ok(())
+    }
+}
+
+/// Collapse display descriptors that refer to the same screen: repeated ids,
+/// members of a mirror set whose primary is listed, and displays with an
+/// identical frame. Tiling each of them separately would lay windows out twice.
+///
+/// Within a group the primary (non-mirroring) display wins, then the main
+/// display, then the lowest id. Survivors keep their enumeration order.
+pub fn dedup_displays(infos: Vec<DisplayInfo>) -> Vec<DisplayInfo> {
+    let listed: Vec<DisplayId> = infos.iter().map(|d| d.id).collect();
+    let mut candidates: Vec<usize> = (0..infos.len()).collect();
+    candidates.sort_by_key(|&i| {
+        let d = &infos[i];
+        (d.mirror_of.is_some(), !d.is_main, d.id)
+    });
+
+    let mut kept: Vec<usize> = Vec::new();
+    for i in candidates {
+        let info = &infos[i];
+        let duplicate = kept.iter().any(|&k| {
+            let other = &infos[k];
+            other.id == info.id || other.frame == info.frame
+        });
+        let mirrors_listed = info
+            .mirror_of
+            .is_some_and(|primary| primary != info.id && listed.contains(&primary));
+        if duplicate || mirrors_listed {
+            tracing::debug!(
+                "Ignoring display {} ({}): duplicate or mirror of another display",
+                info.id,
+                info.name
+            );
+            continue;
+        }
+        kept.push(i);
+    }
+    kept.sort();
+
+    let mut infos: Vec<Option<DisplayInfo>> = infos.into_iter().map(Some).collect();
+    kept.into_iter().filter_map(|i| infos[i].take()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::macos::Bounds;
+
+    fn info(id: DisplayId, x: f64, is_main: bool, mirror_of: Option<DisplayId>) -> DisplayInfo {
+        DisplayInfo {
+            id,
+            name: format!("Display {}", id),
+            frame: Bounds {
+                x,
+                y: 0.0,
+                width: 1920.0,
+                height: 1080.0,
+            },
+            is_main,
+            mirror_of,
+        }
+    }
+
+    fn ids(infos: &[DisplayInfo]) -> Vec<DisplayId> {
+        infos.iter().map(|d| d.id).collect()
+    }
+
+    #[test]
+    fn test_dedup_displays_keeps_distinct_displays() {
+        let infos = vec![info(1, 0.0, true, None), info(2, 1920.0, false, None)];
+        assert_eq!(ids(&dedup_displays(infos)), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_dedup_displays_collapses_mirrored_pair() {
+        let infos = vec![
+            info(3, 0.0, false, Some(1)),
+            info(1, 0.0, true, None),
+            info(2, 1920.0, false, None),
+        ];
+        assert_eq!(ids(&dedup_displays(infos)), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_dedup_displays_collapses_repeated_id_and_same_frame() {
+        let infos = vec![
+            info(2, 1920.0, false, None),
+            info(1, 0.0, true, None),
+            info(2, 1920.0, false, None),
+            // Same frame as the main display, no mirror info
+            info(5, 0.0, false, None),
+        ];
+        assert_eq!(ids(&dedup_displays(infos)), vec![2, 1]);
+    }
+
+    #[test]
+    fn test_dedup_displays_keeps_mirror_when_primary_missing() {
+        let infos = vec![info(3, 0.0, false, Some(9)), info(2, 1920.0, false, None)];
+        assert_eq!(ids(&dedup_displays(infos)), vec![3, 2]);
     }
 }
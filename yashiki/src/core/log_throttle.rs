@@ -0,0 +1,138 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Interval for sampling high-frequency log lines (focus changes, window updates).
+pub const LOG_THROTTLE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Rate limiter for a single high-frequency log line. At most one message is let through
+/// per interval; the ones dropped in between are counted and reported with the next.
+/// If the burst ends on a dropped message, `take_trailing` lets the caller log the final
+/// state once the interval has passed.
+#[derive(Debug)]
+pub struct LogThrottle {
+    interval: Duration,
+    last_emitted: Option<Instant>,
+    suppressed: u32,
+}
+
+/// Number of messages dropped since the last emitted one. Displays as a suffix
+/// (" (+N suppressed)"), or nothing when none were dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Suppressed(pub u32);
+
+impl fmt::Display for Suppressed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 > 0 {
+            write!(f, " (+{} suppressed)", self.0)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl LogThrottle {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_emitted: None,
+            suppressed: 0,
+        }
+    }
+
+    /// Returns Some with the dropped count if a message should be logged at `now`.
+    pub fn check(&mut self, now: Instant) -> Option<Suppressed> {
+        if let Some(last) = self.last_emitted {
+            if now.saturating_duration_since(last) < self.interval {
+                self.suppressed += 1;
+                return None;
+            }
+        }
+        self.last_emitted = Some(now);
+        Some(Suppressed(std::mem::take(&mut self.suppressed)))
+    }
+
+    /// When a trailing message for dropped ones is due, if any were dropped.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        let last = self.last_emitted?;
+        (self.suppressed > 0).then(|| last + self.interval)
+    }
+
+    /// Returns Some if messages were dropped and the last of them should be logged now.
+    /// The count excludes that last message.
+    pub fn take_trailing(&mut self, now: Instant) -> Option<Suppressed> {
+        if self.next_deadline().is_none_or(|deadline| now < deadline) {
+            return None;
+        }
+        self.last_emitted = Some(now);
+        Some(Suppressed(std::mem::take(&mut self.suppressed) - 1))
+    }
+}
+
+impl Default for LogThrottle {
+    fn default() -> Self {
+        Self::new(LOG_THROTTLE_INTERVAL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rapid_messages_collapse_to_one_plus_count() {
+        let mut throttle = LogThrottle::new(Duration::from_millis(500));
+        let start = Instant::now();
+
+        assert_eq!(throttle.check(start), Some(Suppressed(0)));
+        for i in 1..=9 {
+            assert_eq!(throttle.check(start + Duration::from_millis(i * 10)), None);
+        }
+
+        let next = throttle.check(start + Duration::from_millis(600));
+        assert_eq!(next, Some(Suppressed(9)));
+        assert_eq!(next.unwrap().to_string(), " (+9 suppressed)");
+    }
+
+    #[test]
+    fn test_last_dropped_message_is_flushed_after_interval() {
+        let mut throttle = LogThrottle::new(Duration::from_millis(500));
+        let start = Instant::now();
+
+        assert_eq!(throttle.check(start), Some(Suppressed(0)));
+        assert_eq!(throttle.next_deadline(), None);
+        assert_eq!(throttle.take_trailing(start), None);
+
+        for i in 1..=3 {
+            assert_eq!(throttle.check(start + Duration::from_millis(i * 10)), None);
+        }
+        let deadline = start + Duration::from_millis(500);
+        assert_eq!(throttle.next_deadline(), Some(deadline));
+        assert_eq!(
+            throttle.take_trailing(deadline - Duration::from_millis(1)),
+            None
+        );
+
+        // The third dropped message is the one logged; two others went unreported
+        assert_eq!(throttle.take_trailing(deadline), Some(Suppressed(2)));
+        assert_eq!(throttle.next_deadline(), None);
+        assert_eq!(
+            throttle.take_trailing(deadline + Duration::from_secs(1)),
+            None
+        );
+
+        // The flush counts as an emission for the next interval
+        assert_eq!(throttle.check(deadline + Duration::from_millis(10)), None);
+    }
+
+    #[test]
+    fn test_spaced_messages_are_all_logged() {
+        let mut throttle = LogThrottle::new(Duration::from_millis(500));
+        let start = Instant::now();
+
+        for i in 0..3 {
+            let message = throttle.check(start + Duration::from_secs(i));
+            assert_eq!(message, Some(Suppressed(0)));
+            assert_eq!(message.unwrap().to_string(), "");
+        }
+    }
+}
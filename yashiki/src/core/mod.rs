@@ -1,13 +1,27 @@
+mod binding_export;
 mod config;
+mod debounce;
 mod display;
+mod log_throttle;
+mod ratio_animation;
 mod rules_engine;
+mod snapshot;
 mod state;
 mod tag;
 mod window;
+mod window_drag;
+mod window_override;
 
+pub use binding_export::*;
 pub use config::*;
+pub use debounce::*;
 pub use display::*;
+pub use log_throttle::*;
+pub use ratio_animation::*;
 pub use rules_engine::*;
+pub use snapshot::*;
 pub use state::*;
 pub use tag::*;
 pub use window::*;
+pub use window_drag::*;
+pub use window_override::*;
@@ -0,0 +1,119 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+pub const MIN_ANIMATED_RATIO: f64 = 0.1;
+pub const MAX_ANIMATED_RATIO: f64 = 0.9;
+
+/// Intermediate main ratios from `start` to `target`, one per tick over `duration`.
+/// The last step is always exactly `target`.
+pub fn main_ratio_steps(start: f64, target: f64, duration: Duration, tick: Duration) -> Vec<f64> {
+    let count = if tick.is_zero() {
+        1
+    } else {
+        duration.as_millis().div_ceil(tick.as_millis()).max(1) as usize
+    };
+    (1..=count)
+        .map(|i| {
+            if i == count {
+                target
+            } else {
+                start + (target - start) * i as f64 / count as f64
+            }
+        })
+        .collect()
+}
+
+/// A running `main-ratio-animate`: the steps still to send to `layout`, one per tick.
+#[derive(Debug, Clone)]
+pub struct MainRatioAnimation {
+    pub layout: String,
+    steps: VecDeque<f64>,
+    interval: Duration,
+    next_at: Instant,
+}
+
+impl MainRatioAnimation {
+    /// The first step is due immediately.
+    pub fn new(layout: String, steps: Vec<f64>, interval: Duration, now: Instant) -> Self {
+        Self {
+            layout,
+            steps: steps.into(),
+            interval,
+            next_at: now,
+        }
+    }
+
+    pub fn next_deadline(&self) -> Option<Instant> {
+        (!self.steps.is_empty()).then_some(self.next_at)
+    }
+
+    /// The ratio to apply at `now`, if a step is due.
+    pub fn take_due(&mut self, now: Instant) -> Option<f64> {
+        if now < self.next_at {
+            return None;
+        }
+        let ratio = self.steps.pop_front()?;
+        self.next_at = now + self.interval;
+        Some(ratio)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_steps(actual: Vec<f64>, expected: &[f64]) {
+        assert_eq!(actual.len(), expected.len(), "{:?}", actual);
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((a - e).abs() < 1e-9, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_main_ratio_steps_one_per_tick() {
+        let steps = main_ratio_steps(
+            0.5,
+            0.7,
+            Duration::from_millis(64),
+            Duration::from_millis(16),
+        );
+        assert_steps(steps, &[0.55, 0.6, 0.65, 0.7]);
+    }
+
+    #[test]
+    fn test_main_ratio_steps_shrinking() {
+        let steps = main_ratio_steps(
+            0.6,
+            0.3,
+            Duration::from_millis(50),
+            Duration::from_millis(16),
+        );
+        // 50ms rounds up to 4 ticks
+        assert_steps(steps, &[0.525, 0.45, 0.375, 0.3]);
+    }
+
+    #[test]
+    fn test_main_ratio_steps_zero_duration_jumps_to_target() {
+        let steps = main_ratio_steps(0.5, 0.8, Duration::ZERO, Duration::from_millis(16));
+        assert_steps(steps, &[0.8]);
+    }
+
+    #[test]
+    fn test_animation_emits_one_step_per_interval() {
+        let start = Instant::now();
+        let tick = Duration::from_millis(16);
+        let mut animation =
+            MainRatioAnimation::new("tatami".to_string(), vec![0.6, 0.7], tick, start);
+
+        assert_eq!(animation.next_deadline(), Some(start));
+        assert_eq!(animation.take_due(start), Some(0.6));
+        assert_eq!(animation.take_due(start + Duration::from_millis(10)), None);
+        assert_eq!(animation.take_due(start + tick), Some(0.7));
+        assert!(animation.is_finished());
+        assert_eq!(animation.next_deadline(), None);
+    }
+}
@@ -5,7 +5,7 @@ use crate::macos::DisplayId;
 /// Result of applying rules to a window
 #[derive(Debug, Default)]
 pub struct RuleApplicationResult {
-    pub tags: Option<u32>,
+    pub tags: Option<u64>,
     pub display_id: Option<DisplayId>,
     pub position: Option<(i32, i32)>,
     pub dimensions: Option<(u32, u32)>,
@@ -0,0 +1,268 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::{Window, WindowId};
+use crate::effect::Effect;
+
+/// Position and size of a single window at the time a snapshot was taken.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WindowFrame {
+    pub id: WindowId,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Saved arrangement of managed windows, restored with `snapshot-restore`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub windows: Vec<WindowFrame>,
+}
+
+impl Snapshot {
+    /// Record the frame of every window. Hidden windows are recorded at their
+    /// on-screen position rather than the offscreen hiding spot.
+    pub fn capture<'a>(windows: impl IntoIterator<Item = &'a Window>) -> Self {
+        let mut frames: Vec<WindowFrame> = windows
+            .into_iter()
+            .map(|w| {
+                let frame = w.saved_frame.unwrap_or(w.frame);
+                WindowFrame {
+                    id: w.id,
+                    x: frame.x,
+                    y: frame.y,
+                    width: frame.width,
+                    height: frame.height,
+                }
+            })
+            .collect();
+        frames.sort_by_key(|f| f.id);
+        Self { windows: frames }
+    }
+
+    /// Effects that move and resize windows back to their recorded frames.
+    /// Windows that no longer exist or are currently hidden are skipped.
+    pub fn restore_effects(&self, windows: &HashMap<WindowId, Window>) -> Vec<Effect> {
+        let mut effects = Vec::new();
+        for frame in &self.windows {
+            let Some(window) = windows.get(&frame.id) else {
+                continue;
+            };
+            if window.is_hidden() {
+                continue;
+            }
+            effects.push(Effect::MoveWindowToPosition {
+                window_id: window.id,
+                pid: window.pid,
+                x: frame.x,
+                y: frame.y,
+            });
+            effects.push(Effect::SetWindowDimensions {
+                window_id: window.id,
+                pid: window.pid,
+                width: frame.width,
+                height: frame.height,
+            });
+        }
+        effects
+    }
+}
+
+/// Snapshot names become file names, so keep them to a single path component.
+pub fn is_valid_snapshot_name(name: &str) -> bool {
+    !name.is_empty() && !name.starts_with('.') && !name.contains('/')
+}
+
+/// Directory snapshots are persisted to (~/.config/yashiki/snapshots).
+pub fn snapshot_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|dir| dir.join(".config").join("yashiki").join("snapshots"))
+}
+
+pub fn save_snapshot(dir: &Path, name: &str, snapshot: &Snapshot) -> Result<(), String> {
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create snapshot directory {:?}: {}", dir, e))?;
+    let json = serde_json::to_string_pretty(snapshot)
+        .map_err(|e| format!("Failed to serialize snapshot '{}': {}", name, e))?;
+    let path = dir.join(format!("{}.json", name));
+    std::fs::write(&path, json).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
+
+/// Load every `<name>.json` snapshot in `dir`. Unreadable files are skipped.
+pub fn load_snapshots(dir: &Path) -> HashMap<String, Snapshot> {
+    let mut snapshots = HashMap::new();
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return snapshots;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let Some(name) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        let snapshot = std::fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|json| serde_json::from_str(&json).map_err(|e| e.to_string()));
+        match snapshot {
+            Ok(snapshot) => {
+                snapshots.insert(name.to_string(), snapshot);
+            }
+            Err(e) => tracing::warn!("Failed to load snapshot {:?}: {}", path, e),
+        }
+    }
+
+    snapshots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Rect, Tag};
+    use crate::platform::mock::create_test_window;
+
+    fn window(id: u32, pid: i32, x: f64, y: f64, w: f64, h: f64) -> Window {
+        let info = create_test_window(id, pid, "App", x, y, w, h);
+        Window::from_window_info(&info, Tag::new(1), 1)
+    }
+
+    #[test]
+    fn test_capture_records_frames_sorted_by_id() {
+        let mut hidden = window(101, 1001, 5000.0, 5000.0, 800.0, 600.0);
+        hidden.saved_frame = Some(Rect {
+            x: 100,
+            y: 50,
+            width: 800,
+            height: 600,
+        });
+        let windows = [hidden, window(100, 1000, 0.0, 0.0, 960.0, 1080.0)];
+
+        let snapshot = Snapshot::capture(windows.iter());
+        assert_eq!(
+            snapshot.windows,
+            vec![
+                WindowFrame {
+                    id: 100,
+                    x: 0,
+                    y: 0,
+                    width: 960,
+                    height: 1080
+                },
+                WindowFrame {
+                    id: 101,
+                    x: 100,
+                    y: 50,
+                    width: 800,
+                    height: 600
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_snapshot_serialization_roundtrip() {
+        let snapshot = Snapshot {
+            windows: vec![WindowFrame {
+                id: 100,
+                x: -20,
+                y: 40,
+                width: 640,
+                height: 480,
+            }],
+        };
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert!(json.contains("\"id\":100"));
+        assert!(json.contains("\"x\":-20"));
+
+        let deserialized: Snapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized, snapshot);
+    }
+
+    #[test]
+    fn test_save_and_load_snapshots() {
+        let dir = std::env::temp_dir().join(format!("yashiki-snapshots-{}", std::process::id()));
+        let snapshot = Snapshot {
+            windows: vec![WindowFrame {
+                id: 7,
+                x: 1,
+                y: 2,
+                width: 3,
+                height: 4,
+            }],
+        };
+
+        save_snapshot(&dir, "work", &snapshot).unwrap();
+        std::fs::write(dir.join("broken.json"), "not json").unwrap();
+
+        let loaded = load_snapshots(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded.get("work"), Some(&snapshot));
+    }
+
+    #[test]
+    fn test_restore_effects_skip_missing_and_hidden_windows() {
+        let mut windows = HashMap::new();
+        windows.insert(100, window(100, 1000, 0.0, 0.0, 960.0, 1080.0));
+        let mut hidden = window(102, 1002, 0.0, 0.0, 960.0, 540.0);
+        hidden.saved_frame = Some(hidden.frame);
+        windows.insert(102, hidden);
+
+        let snapshot = Snapshot {
+            windows: vec![
+                WindowFrame {
+                    id: 100,
+                    x: 200,
+                    y: 100,
+                    width: 800,
+                    height: 600,
+                },
+                WindowFrame {
+                    id: 101,
+                    x: 0,
+                    y: 0,
+                    width: 100,
+                    height: 100,
+                },
+                WindowFrame {
+                    id: 102,
+                    x: 0,
+                    y: 0,
+                    width: 100,
+                    height: 100,
+                },
+            ],
+        };
+
+        assert_eq!(
+            snapshot.restore_effects(&windows),
+            vec![
+                Effect::MoveWindowToPosition {
+                    window_id: 100,
+                    pid: 1000,
+                    x: 200,
+                    y: 100,
+                },
+                Effect::SetWindowDimensions {
+                    window_id: 100,
+                    pid: 1000,
+                    width: 800,
+                    height: 600,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_snapshot_name_validation() {
+        assert!(is_valid_snapshot_name("work"));
+        assert!(!is_valid_snapshot_name(""));
+        assert!(!is_valid_snapshot_name(".hidden"));
+        assert!(!is_valid_snapshot_name("../escape"));
+    }
+}
@@ -1,15 +1,16 @@
 use std::collections::HashSet;
 
+use super::super::{dedup_displays, WindowId};
 use crate::macos::DisplayId;
 use crate::platform::WindowSystem;
 use yashiki_ipc::OutputDirection;
 
 use super::super::state::{DisplayChangeResult, FocusOutputResult, SendToOutputResult, State};
 use super::layout::{
-    add_to_window_order, compute_layout_changes_for_display, remove_from_window_order,
-    visible_windows_on_display,
+    compute_layout_changes_for_display, move_window_order_to_display, visible_windows_on_display,
 };
 use super::sync::sync_all;
+use super::tags::allowed_tags;
 
 /// Handle display configuration changes (connection/disconnection).
 ///
@@ -21,7 +22,7 @@ use super::sync::sync_all;
 /// - On reconnect: must sync first to create Display entries, then restore saved state
 /// - On disconnect: must save state before removing displays
 pub fn handle_display_change<W: WindowSystem>(state: &mut State, ws: &W) -> DisplayChangeResult {
-    let display_infos = ws.get_all_displays();
+    let display_infos = dedup_displays(ws.get_all_displays());
     let current_ids: HashSet<_> = display_infos.iter().map(|d| d.id).collect();
     let previous_ids: HashSet<_> = state.displays.keys().copied().collect();
 
@@ -51,6 +52,7 @@ pub fn handle_display_change<W: WindowSystem>(state: &mut State, ws: &W) -> Disp
         }
 
         // Restore orphaned windows to their original displays if those displays have returned
+        let mut restored = Vec::new();
         for window in state.windows.values_mut() {
             if let Some(original_display_id) = window.orphaned_from {
                 // Guard: Only restore if the original display is back and exists in state
@@ -69,10 +71,14 @@ pub fn handle_display_change<W: WindowSystem>(state: &mut State, ws: &W) -> Disp
                     window.orphaned_from = None; // Clear orphan state after successful restoration
                     displays_to_retile.insert(previous_display);
                     displays_to_retile.insert(original_display_id);
+                    restored.push((window.id, original_display_id));
                 }
                 // If original display didn't return, keep orphaned_from set for future restoration
             }
         }
+        for (window_id, display_id) in restored {
+            move_window_order_to_display(state, window_id, display_id);
+        }
 
         // Compute visibility changes for all displays to ensure window visibility
         // is correctly set (clear saved_frame for windows that should be visible)
@@ -119,6 +125,7 @@ pub fn handle_display_change<W: WindowSystem>(state: &mut State, ws: &W) -> Disp
 
     let mut window_moves = Vec::new();
     let mut affected_displays = HashSet::new();
+    let mut orphaned = Vec::new();
 
     for window in state.windows.values_mut() {
         if removed_ids.contains(&window.display_id) {
@@ -136,8 +143,12 @@ pub fn handle_display_change<W: WindowSystem>(state: &mut State, ws: &W) -> Disp
             }
             window.display_id = fallback_id;
             affected_displays.insert(fallback_id);
+            orphaned.push(window.id);
         }
     }
+    for window_id in orphaned {
+        move_window_order_to_display(state, window_id, fallback_id);
+    }
 
     if removed_ids.contains(&state.focused_display) {
         tracing::info!(
@@ -245,18 +256,42 @@ pub fn send_to_output(state: &mut State, direction: OutputDirection) -> Option<S
         return None;
     }
 
-    let target_display = state.displays.get(&target_display_id)?;
-    let target_frame_x = target_display.frame.x;
-    let target_frame_y = target_display.frame.y;
-
-    // Update window's display_id and frame position
-    let window = state.windows.get_mut(&focused_id)?;
     tracing::info!(
         "Send window {} to output: {} -> {}",
-        window.id,
+        focused_id,
         source_display_id,
         target_display_id
     );
+    move_window_to_display(state, focused_id, target_display_id)?;
+    let tags = allowed_tags(state, state.windows.get(&focused_id)?.tags);
+    state.windows.get_mut(&focused_id)?.tags = tags;
+
+    // Compute visibility changes for target display
+    let moves = compute_layout_changes_for_display(state, target_display_id);
+
+    // Note: focused_display is NOT changed (River-style: focus stays on source display)
+
+    Some(SendToOutputResult {
+        source_display_id,
+        target_display_id,
+        window_moves: moves,
+    })
+}
+
+/// Reassign a window to `target_display_id`: its display, window order and frame origin.
+/// The caller computes the layout changes. Returns the display the window came from.
+pub fn move_window_to_display(
+    state: &mut State,
+    window_id: WindowId,
+    target_display_id: DisplayId,
+) -> Option<DisplayId> {
+    let target_display = state.displays.get(&target_display_id)?;
+    let target_frame_x = target_display.frame.x;
+    let target_frame_y = target_display.frame.y;
+
+    // Update window's display_id and frame position
+    let window = state.windows.get_mut(&window_id)?;
+    let source_display_id = window.display_id;
     window.display_id = target_display_id;
     // User intentionally moved the window - clear orphan state
     window.orphaned_from = None;
@@ -273,17 +308,6 @@ pub fn send_to_output(state: &mut State, direction: OutputDirection) -> Option<S
     }
 
     // Update window_order (move from source to target)
-    remove_from_window_order(state, focused_id);
-    add_to_window_order(state, focused_id, target_display_id);
-
-    // Compute visibility changes for target display
-    let moves = compute_layout_changes_for_display(state, target_display_id);
-
-    // Note: focused_display is NOT changed (River-style: focus stays on source display)
-
-    Some(SendToOutputResult {
-        source_display_id,
-        target_display_id,
-        window_moves: moves,
-    })
+    move_window_order_to_display(state, window_id, target_display_id);
+    Some(source_display_id)
 }
@@ -1,18 +1,51 @@
 use super::super::{Window, WindowId};
 use crate::macos::DisplayId;
-use yashiki_ipc::Direction;
+use yashiki_ipc::{Direction, OutputDirection, SizeSelection};
+
+use super::super::state::{FocusUrgentResult, State};
+use super::layout::visible_windows_on_display;
+use super::tags::view_tags_on_display;
+
+/// Directional (left/right/up/down) focus and swap may cross into other displays
+/// unless `same_output` is set. Next/prev always stay on the focused display.
+fn crosses_outputs(direction: Direction, same_output: bool) -> bool {
+    !same_output
+        && matches!(
+            direction,
+            Direction::Left | Direction::Right | Direction::Up | Direction::Down
+        )
+}
 
-use super::super::state::State;
+/// On a display with its focus axis swapped, left/right act as up/down and vice versa.
+fn resolve_direction(state: &State, direction: Direction) -> Direction {
+    let swapped = state
+        .displays
+        .get(&state.focused_display)
+        .is_some_and(|d| d.focus_axis_swap);
+    if !swapped {
+        return direction;
+    }
+    match direction {
+        Direction::Left => Direction::Up,
+        Direction::Up => Direction::Left,
+        Direction::Right => Direction::Down,
+        Direction::Down => Direction::Right,
+        Direction::Next | Direction::Prev => direction,
+    }
+}
 
-pub fn focus_window(state: &State, direction: Direction) -> Option<(WindowId, i32)> {
-    let visible_tags = state.visible_tags();
+pub fn focus_window(
+    state: &State,
+    direction: Direction,
+    same_output: bool,
+) -> Option<(WindowId, i32)> {
+    let direction = resolve_direction(state, direction);
+    let cross_outputs = crosses_outputs(direction, same_output);
     let visible: Vec<_> = state
         .windows
         .values()
         .filter(|w| {
-            w.display_id == state.focused_display
-                && w.tags.intersects(visible_tags)
-                && !w.is_hidden()
+            (cross_outputs || w.display_id == state.focused_display) && state.is_window_visible(w)
         })
         .collect();
 
@@ -30,6 +63,35 @@ pub fn focus_window(state: &State, direction: Direction) -> Option<(WindowId, i3
     }
 }
 
+/// Visible window on the focused display with the largest or smallest frame area.
+/// Ties go to the window that comes first in the display's `window_order`.
+pub fn focus_window_by_size(state: &State, which: SizeSelection) -> Option<(WindowId, i32)> {
+    let display = state.displays.get(&state.focused_display)?;
+    let order_key = |w: &Window| {
+        display
+            .window_order
+            .iter()
+            .position(|&id| id == w.id)
+            .map(|p| (0, p))
+            .unwrap_or((1, w.id as usize))
+    };
+    let area = |w: &Window| w.frame.width as u64 * w.frame.height as u64;
+
+    state
+        .windows
+        .values()
+        .filter(|w| w.display_id == state.focused_display && state.is_window_visible(w))
+        .min_by_key(|w| {
+            let area = area(w);
+            let size_key = match which {
+                SizeSelection::Largest => u64::MAX - area,
+                SizeSelection::Smallest => area,
+            };
+            (size_key, order_key(w))
+        })
+        .map(|w| (w.id, w.pid))
+}
+
 fn focus_window_stack(
     state: &State,
     visible: &[&Window],
@@ -106,7 +168,13 @@ fn focus_window_directional(
     best.map(|(w, _)| (w.id, w.pid))
 }
 
-pub fn swap_window(state: &mut State, direction: Direction) -> Option<DisplayId> {
+/// Swap the focused window with the window in the given direction.
+/// Returns the displays that need retiling.
+pub fn swap_window(
+    state: &mut State,
+    direction: Direction,
+    same_output: bool,
+) -> Option<Vec<DisplayId>> {
     let focused_id = state.focused?;
     let focused_window = state.windows.get(&focused_id)?;
 
@@ -115,7 +183,12 @@ pub fn swap_window(state: &mut State, direction: Direction) -> Option<DisplayId>
     }
 
     let display_id = focused_window.display_id;
-    let target_id = find_swap_target(state, direction)?;
+    let target_id = find_swap_target(state, direction, same_output)?;
+    let target_display_id = state.windows.get(&target_id)?.display_id;
+
+    if target_display_id != display_id {
+        return swap_window_across_displays(state, focused_id, target_id);
+    }
 
     if let Some(display) = state.displays.get_mut(&display_id) {
         let focused_idx = display
@@ -133,21 +206,206 @@ pub fn swap_window(state: &mut State, direction: Direction) -> Option<DisplayId>
             target_id,
             direction
         );
-        Some(display_id)
+        Some(vec![display_id])
     } else {
         None
     }
 }
 
-fn find_swap_target(state: &State, direction: Direction) -> Option<WindowId> {
-    let visible_tags = state.visible_tags();
+/// Swap two windows on different displays: each takes over the other's display,
+/// slot in the window order and tags, so both stay visible.
+fn swap_window_across_displays(
+    state: &mut State,
+    focused_id: WindowId,
+    target_id: WindowId,
+) -> Option<Vec<DisplayId>> {
+    let focused = state.windows.get(&focused_id)?;
+    let target = state.windows.get(&target_id)?;
+    let (focused_display, focused_tags, focused_frame) =
+        (focused.display_id, focused.tags, focused.frame);
+    let (target_display, target_tags, target_frame) =
+        (target.display_id, target.tags, target.frame);
+
+    let focused_idx = state
+        .displays
+        .get(&focused_display)?
+        .window_order
+        .iter()
+        .position(|&id| id == focused_id)?;
+    let target_idx = state
+        .displays
+        .get(&target_display)?
+        .window_order
+        .iter()
+        .position(|&id| id == target_id)?;
+
+    state.displays.get_mut(&focused_display)?.window_order[focused_idx] = target_id;
+    state.displays.get_mut(&target_display)?.window_order[target_idx] = focused_id;
+
+    for (id, display_id, tags, frame) in [
+        (focused_id, target_display, target_tags, target_frame),
+        (target_id, focused_display, focused_tags, focused_frame),
+    ] {
+        if let Some(window) = state.windows.get_mut(&id) {
+            window.display_id = display_id;
+            window.tags = tags;
+            window.frame.x = frame.x;
+            window.frame.y = frame.y;
+            // User intentionally moved the window - clear orphan state
+            window.orphaned_from = None;
+        }
+    }
+
+    // Focus follows the window to its new display
+    state.focused_display = target_display;
+
+    tracing::info!(
+        "Swapped window {} (display {}) with {} (display {})",
+        focused_id,
+        focused_display,
+        target_id,
+        target_display
+    );
+    Some(vec![focused_display, target_display])
+}
+
+/// Pick the window to focus after `removed_id` is destroyed: the next window in `order`,
+/// or the previous one when it was last. Falls back to the first window when `removed_id`
+/// isn't part of the order (e.g. it was floating).
+pub fn pick_focus_successor(
+    order: &[WindowId],
+    removed_id: WindowId,
+    is_alive: impl Fn(WindowId) -> bool,
+) -> Option<WindowId> {
+    match order.iter().position(|&id| id == removed_id) {
+        Some(idx) => order[idx + 1..]
+            .iter()
+            .chain(order[..idx].iter().rev())
+            .copied()
+            .find(|&id| is_alive(id)),
+        None => order.iter().copied().find(|&id| is_alive(id)),
+    }
+}
+
+/// Switch to the display and tag of the most recently flagged urgent window and clear
+/// its urgency. Stale entries for windows that no longer exist are dropped.
+pub fn focus_urgent(state: &mut State) -> Option<FocusUrgentResult> {
+    state
+        .urgent_windows
+        .retain(|id| state.windows.contains_key(id));
+    let window_id = state.urgent_windows.pop()?;
+    let window = state.windows.get(&window_id)?;
+    let (pid, display_id, tags) = (window.pid, window.display_id, window.tags);
+
+    let display_changed = display_id != state.focused_display;
+    state.focused_display = display_id;
+
+    let tag_visible = state
+        .displays
+        .get(&display_id)
+        .is_some_and(|d| tags.intersects(d.visible_tags));
+    let window_moves = match tags.first_tag() {
+        Some(tag) if !tag_visible => {
+            tracing::info!(
+                "Switching display {} to tag {} for urgent window {}",
+                display_id,
+                tag,
+                window_id
+            );
+            view_tags_on_display(state, 1 << (tag - 1), display_id)
+        }
+        _ => vec![],
+    };
+
+    Some(FocusUrgentResult {
+        window_id,
+        pid,
+        display_changed,
+        window_moves,
+    })
+}
+
+/// Visible always-on-top windows that must be raised again after focus moved to
+/// `focused_id`. The focused window itself is already frontmost.
+pub fn always_on_top_to_reassert(state: &State, focused_id: WindowId) -> Vec<(WindowId, i32)> {
+    let mut windows: Vec<(WindowId, i32)> = state
+        .windows
+        .values()
+        .filter(|w| w.is_always_on_top && w.id != focused_id && state.is_window_visible(w))
+        .map(|w| (w.id, w.pid))
+        .collect();
+    windows.sort_unstable();
+    windows
+}
+
+/// Move the focused window one slot forward/backward among the visible tiled windows,
+/// wrapping around at the ends.
+pub fn shift_window(state: &mut State, direction: OutputDirection) -> Option<DisplayId> {
+    let focused_id = state.focused?;
+    let focused_window = state.windows.get(&focused_id)?;
+
+    if !focused_window.is_tiled() {
+        return None;
+    }
+
+    let display_id = focused_window.display_id;
+    let visible: Vec<WindowId> = visible_windows_on_display(state, display_id)
+        .iter()
+        .map(|w| w.id)
+        .collect();
+    if visible.len() <= 1 {
+        return None;
+    }
+
+    let visible_idx = visible.iter().position(|&id| id == focused_id)?;
+    let last = visible.len() - 1;
+    let display = state.displays.get_mut(&display_id)?;
+    let position_of = |order: &[WindowId], id: WindowId| order.iter().position(|&w| w == id);
+
+    match (direction, visible_idx) {
+        (OutputDirection::Next, idx) if idx == last => {
+            // Wrap: move to the front of the visible windows
+            let from = position_of(&display.window_order, focused_id)?;
+            display.window_order.remove(from);
+            let to = position_of(&display.window_order, visible[0])?;
+            display.window_order.insert(to, focused_id);
+        }
+        (OutputDirection::Prev, 0) => {
+            // Wrap: move to the back of the visible windows
+            let from = position_of(&display.window_order, focused_id)?;
+            display.window_order.remove(from);
+            let to = position_of(&display.window_order, visible[last])?;
+            display.window_order.insert(to + 1, focused_id);
+        }
+        (direction, idx) => {
+            let neighbor = match direction {
+                OutputDirection::Next => visible[idx + 1],
+                OutputDirection::Prev => visible[idx - 1],
+            };
+            let from = position_of(&display.window_order, focused_id)?;
+            let to = position_of(&display.window_order, neighbor)?;
+            display.window_order.swap(from, to);
+        }
+    }
+
+    tracing::info!(
+        "Shifted window {} {:?} on display {}",
+        focused_id,
+        direction,
+        display_id
+    );
+    Some(display_id)
+}
+
+fn find_swap_target(state: &State, direction: Direction, same_output: bool) -> Option<WindowId> {
+    let direction = resolve_direction(state, direction);
+    let cross_outputs = crosses_outputs(direction, same_output);
     let visible: Vec<_> = state
         .windows
         .values()
         .filter(|w| {
-            w.display_id == state.focused_display
-                && w.tags.intersects(visible_tags)
-                && !w.is_hidden()
+            (cross_outputs || w.display_id == state.focused_display)
+                && state.is_window_visible(w)
                 && w.is_tiled()
         })
         .collect();
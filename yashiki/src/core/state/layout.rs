@@ -1,6 +1,7 @@
 use super::super::window::Rect;
 use super::super::{Window, WindowId};
 use crate::macos::DisplayId;
+use yashiki_ipc::layout::WindowGeometry;
 
 use super::super::state::{State, WindowMove};
 
@@ -277,6 +278,7 @@ pub fn visible_windows_on_display(state: &State, display_id: DisplayId) -> Vec<&
             w.display_id == display_id
                 && w.tags.intersects(display.visible_tags)
                 && !w.is_hidden()
+                && !w.is_minimized
                 && w.is_tiled()
         })
         .collect();
@@ -292,6 +294,159 @@ pub fn visible_windows_on_display(state: &State, display_id: DisplayId) -> Vec<&
     windows
 }
 
+/// Share of the display's width and height a peeked window covers.
+const PEEK_RATIO: f64 = 0.8;
+
+/// Display-relative frame of a peeked window, centered on the display.
+pub fn peek_rect(display_frame: &Rect) -> Rect {
+    let width = (display_frame.width as f64 * PEEK_RATIO) as u32;
+    let height = (display_frame.height as f64 * PEEK_RATIO) as u32;
+    Rect {
+        x: ((display_frame.width - width) / 2) as i32,
+        y: ((display_frame.height - height) / 2) as i32,
+        width,
+        height,
+    }
+}
+
+/// Enlarge the focused tiled window, remembering its slot.
+/// Returns the window and its new absolute frame, or None if there is nothing to peek.
+pub fn start_peek(state: &mut State) -> Option<(WindowId, i32, Rect)> {
+    let focused_id = state.focused?;
+    let window = state.windows.get(&focused_id)?;
+    if !window.is_tiled() || window.is_hidden() || peeked_window(state).is_some() {
+        return None;
+    }
+    let display_frame = state.displays.get(&window.display_id)?.frame;
+    let rect = peek_rect(&display_frame);
+
+    let window = state.windows.get_mut(&focused_id)?;
+    window.peek_slot = Some(Rect {
+        x: window.frame.x - display_frame.x,
+        y: window.frame.y - display_frame.y,
+        ..window.frame
+    });
+    tracing::info!("Peek window {}", focused_id);
+    Some((
+        window.id,
+        window.pid,
+        Rect {
+            x: display_frame.x + rect.x,
+            y: display_frame.y + rect.y,
+            ..rect
+        },
+    ))
+}
+
+/// The window currently enlarged by a peek, if any. Focus may have moved on since.
+pub fn peeked_window(state: &State) -> Option<WindowId> {
+    state
+        .windows
+        .values()
+        .find(|w| w.peek_slot.is_some())
+        .map(|w| w.id)
+}
+
+/// Stop peeking, whichever window is focused now. Returns the peeked window's
+/// display, which needs a retile to put the window back into its slot.
+pub fn release_peek(state: &mut State) -> Option<DisplayId> {
+    let window = state.windows.get_mut(&peeked_window(state)?)?;
+    window.peek_slot = None;
+    tracing::info!("Release peek of window {}", window.id);
+    Some(window.display_id)
+}
+
+/// Keep peeked windows enlarged across a retile: their freshly computed
+/// geometry becomes the remembered slot and is replaced by the peek frame.
+pub fn apply_peek_frames(
+    state: &mut State,
+    display_id: DisplayId,
+    geometries: &mut [WindowGeometry],
+) {
+    let Some(display_frame) = state.displays.get(&display_id).map(|d| d.frame) else {
+        return;
+    };
+    let rect = peek_rect(&display_frame);
+    for geometry in geometries {
+        let Some(window) = state.windows.get_mut(&geometry.id) else {
+            continue;
+        };
+        if window.peek_slot.is_none() {
+            continue;
+        }
+        window.peek_slot = Some(Rect {
+            x: geometry.x,
+            y: geometry.y,
+            width: geometry.width,
+            height: geometry.height,
+        });
+        geometry.x = rect.x;
+        geometry.y = rect.y;
+        geometry.width = rect.width;
+        geometry.height = rect.height;
+    }
+}
+
+/// Frame of a `width` x `height` window centered in `tile`. A window larger than
+/// the tile is shrunk to fit it.
+pub fn centered_in_tile(tile: &Rect, width: u32, height: u32) -> Rect {
+    let width = width.min(tile.width);
+    let height = height.min(tile.height);
+    Rect {
+        x: tile.x + ((tile.width - width) / 2) as i32,
+        y: tile.y + ((tile.height - height) / 2) as i32,
+        width,
+        height,
+    }
+}
+
+/// Shrink windows flagged with `keep_native_size` to their native size, centered
+/// in the tile the layout engine gave them. Peeked windows are left alone.
+pub fn apply_native_sizes(state: &State, geometries: &mut [WindowGeometry]) {
+    for geometry in geometries {
+        let Some(window) = state.windows.get(&geometry.id) else {
+            continue;
+        };
+        if !window.keep_native_size || window.peek_slot.is_some() {
+            continue;
+        }
+        let tile = Rect {
+            x: geometry.x,
+            y: geometry.y,
+            width: geometry.width,
+            height: geometry.height,
+        };
+        let (width, height) = window.native_size;
+        let frame = centered_in_tile(&tile, width, height);
+        geometry.x = frame.x;
+        geometry.y = frame.y;
+        geometry.width = frame.width;
+        geometry.height = frame.height;
+    }
+}
+
+/// Adopt the order the layout engine returned for `laid_out` windows. Only their
+/// slots in `window_order` are rewritten, so windows on hidden tags keep their place.
+pub fn apply_layout_order(state: &mut State, display_id: DisplayId, laid_out: &[WindowId]) {
+    let Some(display) = state.displays.get_mut(&display_id) else {
+        return;
+    };
+    let mut engine_order = laid_out.iter().copied();
+    let mut order: Vec<WindowId> = display
+        .window_order
+        .iter()
+        .map(|&id| {
+            if laid_out.contains(&id) {
+                engine_order.next().unwrap_or(id)
+            } else {
+                id
+            }
+        })
+        .collect();
+    order.extend(engine_order);
+    display.window_order = order;
+}
+
 pub fn add_to_window_order(state: &mut State, window_id: WindowId, display_id: DisplayId) {
     if let Some(display) = state.displays.get_mut(&display_id) {
         if !display.window_order.contains(&window_id) {
@@ -306,6 +461,118 @@ pub fn remove_from_window_order(state: &mut State, window_id: WindowId) {
     }
 }
 
+/// Move a window's `window_order` entry to the end of another display's order.
+pub fn move_window_order_to_display(state: &mut State, window_id: WindowId, display_id: DisplayId) {
+    remove_from_window_order(state, window_id);
+    add_to_window_order(state, window_id, display_id);
+}
+
+/// Move a window to the front of its display's window order so layouts give it
+/// the main slot. Returns the display to retile, or None if the window is unknown.
+pub fn move_window_to_front(state: &mut State, window_id: WindowId) -> Option<DisplayId> {
+    let display_id = state.windows.get(&window_id)?.display_id;
+    let display = state.displays.get_mut(&display_id)?;
+    display.window_order.retain(|&id| id != window_id);
+    display.window_order.insert(0, window_id);
+    tracing::info!("Set window {} as main on display {}", window_id, display_id);
+    Some(display_id)
+}
+
+/// Move the focused window to slot `index` among the windows tiled on its display,
+/// clamped to the last slot. Returns the display if the order changed.
+pub fn move_focused_window_to_slot(state: &mut State, index: usize) -> Option<DisplayId> {
+    let focused_id = state.focused?;
+    let display_id = state.windows.get(&focused_id)?.display_id;
+    let slots: Vec<WindowId> = visible_windows_on_display(state, display_id)
+        .iter()
+        .map(|w| w.id)
+        .collect();
+    let from = slots.iter().position(|&id| id == focused_id)?;
+    let to = index.min(slots.len() - 1);
+    if from == to {
+        return None;
+    }
+
+    // Take the slot's current window's place; it shifts towards where we came from
+    let target_id = slots[to];
+    let display = state.displays.get_mut(&display_id)?;
+    display.window_order.retain(|&id| id != focused_id);
+    let at = display
+        .window_order
+        .iter()
+        .position(|&id| id == target_id)
+        .map(|p| if to > from { p + 1 } else { p })
+        .unwrap_or(display.window_order.len());
+    display.window_order.insert(at, focused_id);
+    tracing::info!(
+        "Moved window {} to slot {} on display {}",
+        focused_id,
+        to,
+        display_id
+    );
+    Some(display_id)
+}
+
+/// Put the sticky main window at the front of the display's window order if it is
+/// visible there. Returns true if the order changed.
+pub fn apply_sticky_main(state: &mut State, display_id: DisplayId) -> bool {
+    let Some(sticky) = state.sticky_main else {
+        return false;
+    };
+    if !visible_windows_on_display(state, display_id)
+        .iter()
+        .any(|w| w.id == sticky)
+    {
+        return false;
+    }
+    let Some(display) = state.displays.get_mut(&display_id) else {
+        return false;
+    };
+    if display.window_order.first() == Some(&sticky) {
+        return false;
+    }
+    display.window_order.retain(|&id| id != sticky);
+    display.window_order.insert(0, sticky);
+    true
+}
+
+/// Reverse the window order of a display. Returns false if there was nothing to reverse.
+pub fn reverse_window_order(state: &mut State, display_id: DisplayId) -> bool {
+    match state.displays.get_mut(&display_id) {
+        Some(display) if display.window_order.len() > 1 => {
+            display.window_order.reverse();
+            true
+        }
+        _ => false,
+    }
+}
+
 pub fn compute_layout_changes(state: &mut State, display_id: DisplayId) -> Vec<WindowMove> {
     compute_layout_changes_for_display(state, display_id)
 }
+
+/// Toggle gaps for a layout. Returns the inner gap to apply:
+/// 0 when turning gaps off (the current gap is saved), or the saved gap when turning them back on.
+pub fn toggle_inner_gap(state: &mut State, layout: &str, current_gap: u32) -> u32 {
+    match state.saved_inner_gaps.remove(layout) {
+        Some(saved) => {
+            tracing::info!(
+                "Gaps on for layout '{}': restoring inner gap {}",
+                layout,
+                saved
+            );
+            saved
+        }
+        None => {
+            tracing::info!(
+                "Gaps off for layout '{}': saving inner gap {}",
+                layout,
+                current_gap
+            );
+            state
+                .saved_inner_gaps
+                .insert(layout.to_string(), current_gap);
+            0
+        }
+    }
+}
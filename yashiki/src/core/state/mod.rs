@@ -1,13 +1,19 @@
-use std::collections::HashMap;
-use std::time::Instant;
-
-use super::{Config, Display, Rect, RulesEngine, Tag, Window, WindowId};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::{
+    Config, Debouncer, Display, DragKind, DragTarget, DragTargets, LogThrottle, MainRatioAnimation,
+    Rect, RulesEngine, Snapshot, Tag, Window, WindowDrag, WindowId, WindowOverride,
+};
 use crate::effect::Effect;
 use crate::event::Event;
 use crate::macos::DisplayId;
 use crate::platform::WindowSystem;
 use yashiki_ipc::{
-    Direction, OutputDirection, OutputSpecifier, RuleAction, RuleMatcher, WindowRule,
+    Direction, OuterGap, OutputDirection, OutputSpecifier, RuleAction, RuleMatcher, SizeSelection,
+    StatsInfo, UnmanagedWindowInfo, WindowRule,
 };
 
 /// Information about a window that was ignored by rule, tracked for re-evaluation.
@@ -47,6 +53,8 @@ use rules::*;
 use sync::*;
 use tags::*;
 
+pub use tags::SCRATCHPAD_TAG;
+
 /// Result of handling display configuration changes
 #[derive(Debug, Default)]
 pub struct DisplayChangeResult {
@@ -64,6 +72,17 @@ pub enum FocusOutputResult {
     EmptyDisplay { display_id: DisplayId },
 }
 
+/// Result of focus_urgent operation
+#[derive(Debug)]
+pub struct FocusUrgentResult {
+    pub window_id: WindowId,
+    pub pid: i32,
+    /// The urgent window is on a different display than the one that was focused
+    pub display_changed: bool,
+    /// Hide/show moves from switching to the window's tag (empty if already visible)
+    pub window_moves: Vec<WindowMove>,
+}
+
 /// Result of send_to_output operation
 #[derive(Debug)]
 pub struct SendToOutputResult {
@@ -72,6 +91,27 @@ pub struct SendToOutputResult {
     pub window_moves: Vec<WindowMove>,
 }
 
+/// Result of scratchpad_toggle
+#[derive(Debug, PartialEq)]
+pub enum ScratchpadToggle {
+    /// The app has no window: launch it
+    Launch,
+    /// Parked on `SCRATCHPAD_TAG`, out of every view
+    Hidden {
+        display_id: DisplayId,
+        window_moves: Vec<WindowMove>,
+    },
+    /// Moved onto the focused display's visible tags, to be restored if minimized and focused
+    Shown {
+        window_id: WindowId,
+        pid: i32,
+        minimized: bool,
+        /// The focused display, then the display the window left, if any
+        display_ids: Vec<DisplayId>,
+        window_moves: Vec<WindowMove>,
+    },
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct WindowMove {
     pub window_id: WindowId,
@@ -127,6 +167,84 @@ impl FocusIntent {
 pub struct AutoRaiseState {
     pub last_hovered: Option<WindowId>,
     pub hover_start: Option<Instant>,
+    /// When windows were last moved by a retile
+    pub last_retile: Option<Instant>,
+}
+
+impl AutoRaiseState {
+    /// Mouse events are ignored for this long after a retile, so windows sliding
+    /// under a stationary pointer don't trigger auto-raise
+    pub const RETILE_SUPPRESSION_MS: u128 = 100;
+
+    pub fn record_retile(&mut self, now: Instant) {
+        self.last_retile = Some(now);
+        self.last_hovered = None;
+        self.hover_start = None;
+    }
+
+    /// Check if a mouse event at `now` falls within the post-retile suppression window
+    pub fn is_suppressed_at(&self, now: Instant) -> bool {
+        self.last_retile.is_some_and(|retile| {
+            now.saturating_duration_since(retile).as_millis() < Self::RETILE_SUPPRESSION_MS
+        })
+    }
+}
+
+/// Counters from one retile (all displays it touched).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RetileStats {
+    pub duration: Duration,
+    /// Windows a frame was written to
+    pub writes: u32,
+    /// Windows skipped because they were already at their target frame
+    pub skipped: u32,
+}
+
+/// Performance counters reported by `stats`.
+#[derive(Debug, Clone)]
+pub struct RuntimeStats {
+    started_at: Instant,
+    retile_count: u64,
+    last_retile: Option<RetileStats>,
+    events_processed: u64,
+}
+
+impl RuntimeStats {
+    pub fn new(now: Instant) -> Self {
+        Self {
+            started_at: now,
+            retile_count: 0,
+            last_retile: None,
+            events_processed: 0,
+        }
+    }
+
+    pub fn record_retile(&mut self, retile: RetileStats) {
+        self.retile_count += 1;
+        self.last_retile = Some(retile);
+    }
+
+    pub fn record_event(&mut self) {
+        self.events_processed += 1;
+    }
+
+    pub fn info(&self, now: Instant) -> StatsInfo {
+        let uptime = now.saturating_duration_since(self.started_at);
+        let last = self.last_retile.unwrap_or_default();
+        StatsInfo {
+            last_retile_us: self.last_retile.map(|r| r.duration.as_micros() as u64),
+            last_retile_writes: last.writes,
+            last_retile_skipped: last.skipped,
+            retile_count: self.retile_count,
+            events_processed: self.events_processed,
+            uptime_secs: uptime.as_secs(),
+            events_per_sec: if uptime.is_zero() {
+                0.0
+            } else {
+                self.events_processed as f64 / uptime.as_secs_f64()
+            },
+        }
+    }
 }
 
 pub struct State {
@@ -137,6 +255,11 @@ pub struct State {
     pub(crate) default_tag: Tag,
     pub default_layout: String,
     pub tag_layouts: HashMap<u8, String>,
+    /// Labels set by set-tag-name, keyed by tag number. Kept in memory only.
+    pub tag_names: HashMap<u32, String>,
+    /// Inner gaps saved by toggle-gaps, keyed by layout, restored on the next toggle.
+    /// The gap is a setting of the layout engine, shared by every display and tag using it.
+    pub saved_inner_gaps: HashMap<String, u32>,
     pub rules_engine: RulesEngine,
     pub tracked_processes: Vec<TrackedProcess>,
     pub config: Config,
@@ -151,9 +274,60 @@ pub struct State {
     /// Cached window z-order from CGWindowList (front-to-back).
     /// Updated on every sync operation. Contains both managed and ignored window IDs.
     pub window_z_order: Vec<WindowId>,
+    /// Number of connected event subscribers, shared with the event server.
+    pub event_subscribers: Arc<AtomicUsize>,
+    /// Saved window arrangements by name, loaded from disk at startup.
+    pub snapshots: HashMap<String, Snapshot>,
+    /// Window to focus because the focused window was destroyed.
+    /// Set by sync_pid, consumed by the event loop.
+    pub focus_successor: Option<WindowId>,
+    /// Windows flagged as needing attention, oldest first. Focusing a window clears its flag.
+    pub urgent_windows: Vec<WindowId>,
+    /// Retile timing and event-loop throughput, reported by `stats`.
+    pub stats: RuntimeStats,
+    /// Apps whose window move/resize events are ignored (set-app-events-paused).
+    pub paused_event_apps: HashSet<String>,
+    /// manage-window/unmanage-window decisions, persisted to disk.
+    pub window_overrides: HashMap<WindowId, WindowOverride>,
+    /// Overrides were dropped because their windows were destroyed or pruned.
+    /// Set by handle_event and prune_missing_windows, consumed by the event loop and
+    /// the command dispatcher to rewrite the file.
+    pub window_overrides_pruned: bool,
+    /// Configured inner gaps of layouts zeroed by gaps-multi-monitor-only, keyed by layout.
+    /// Restored once a second display is attached.
+    pub suppressed_inner_gaps: HashMap<String, u32>,
+    /// Samples "Focus changed" lines, which flood the log during rapid app switching.
+    /// Shared by all windows on purpose: a storm hops between windows, so a throttle
+    /// per window would let almost every line through. `flush_focus_log` still logs
+    /// where the focus ended up.
+    pub focus_log_throttle: LogThrottle,
+    /// Samples "Window updated" lines from sync, which flood the log during a drag.
+    pub window_update_log_throttle: LogThrottle,
+    /// Coalesces event-driven retiles to at most one per tick (`config.tick_rate_ms`).
+    pub retile_debouncer: Debouncer<()>,
+    /// Coalesces WindowMoved/WindowResized syncs per pid (`MOVE_SYNC_INTERVAL`).
+    pub move_sync_debouncer: Debouncer<i32>,
+    /// Running main-ratio-animate, stepped on the event-loop tick.
+    pub ratio_animation: Option<MainRatioAnimation>,
+    /// Window kept in the main slot whenever it is visible (set-sticky-main).
+    pub sticky_main: Option<WindowId>,
+    /// Windows floated by float-all, put back into tiling when it is turned off.
+    /// None while float-all is off.
+    pub float_all_restore: Option<Vec<WindowId>>,
+    /// Floating window being moved or resized by a modifier+drag.
+    pub window_drag: Option<WindowDrag>,
+    /// Scratchpad window of each app toggled by scratchpad-toggle, keyed by app name.
+    pub scratchpads: HashMap<String, WindowId>,
+    /// Windows seen minimized by sync, most recently minimized last.
+    /// May hold restored or destroyed windows; check `Window::is_minimized`.
+    pub minimized_order: Vec<WindowId>,
 }
 
 impl State {
+    /// Minimum spacing of move/resize-driven syncs of one app. Drags fire these
+    /// events continuously; the last one always gets a trailing sync.
+    pub const MOVE_SYNC_INTERVAL: Duration = Duration::from_millis(50);
+
     pub fn new() -> Self {
         Self {
             windows: HashMap::new(),
@@ -163,6 +337,11 @@ impl State {
             default_tag: Tag::new(1),
             default_layout: "tatami".to_string(),
             tag_layouts: HashMap::new(),
+            tag_names: HashMap::new(),
+            saved_inner_gaps: HashMap::new(),
+            suppressed_inner_gaps: HashMap::new(),
+            window_overrides: HashMap::new(),
+            window_overrides_pruned: false,
             rules_engine: RulesEngine::new(),
             tracked_processes: Vec::new(),
             config: Config::new(),
@@ -171,6 +350,22 @@ impl State {
             focus_intent: None,
             auto_raise_state: AutoRaiseState::default(),
             window_z_order: Vec::new(),
+            event_subscribers: Arc::new(AtomicUsize::new(0)),
+            snapshots: HashMap::new(),
+            focus_successor: None,
+            urgent_windows: Vec::new(),
+            stats: RuntimeStats::new(Instant::now()),
+            paused_event_apps: HashSet::new(),
+            focus_log_throttle: LogThrottle::default(),
+            window_update_log_throttle: LogThrottle::default(),
+            retile_debouncer: Debouncer::new(),
+            move_sync_debouncer: Debouncer::new(),
+            ratio_animation: None,
+            sticky_main: None,
+            float_all_restore: None,
+            window_drag: None,
+            scratchpads: HashMap::new(),
+            minimized_order: Vec::new(),
         }
     }
 
@@ -181,7 +376,7 @@ impl State {
 
     pub fn set_layout_on_display(
         &mut self,
-        tags: Option<u32>,
+        tags: Option<u64>,
         display_id: Option<DisplayId>,
         layout: String,
     ) {
@@ -213,7 +408,7 @@ impl State {
         }
     }
 
-    pub fn get_layout_on_display(&self, tags: Option<u32>, display_id: Option<DisplayId>) -> &str {
+    pub fn get_layout_on_display(&self, tags: Option<u64>, display_id: Option<DisplayId>) -> &str {
         let target_display = display_id.unwrap_or(self.focused_display);
 
         match tags {
@@ -246,17 +441,137 @@ impl State {
             .unwrap_or(&self.default_layout)
     }
 
+    /// Displays currently showing `layout`, sorted by id.
+    pub fn displays_using_layout(&self, layout: &str) -> Vec<DisplayId> {
+        let mut display_ids: Vec<DisplayId> = self
+            .displays
+            .keys()
+            .copied()
+            .filter(|&id| self.current_layout_for_display(id) == layout)
+            .collect();
+        display_ids.sort_unstable();
+        display_ids
+    }
+
+    /// Distinct layouts currently shown on a display or assigned to a tag, sorted by name.
+    pub fn active_layouts(&self) -> Vec<String> {
+        let mut layouts: Vec<String> = self
+            .displays
+            .keys()
+            .map(|&id| self.current_layout_for_display(id).to_string())
+            .chain(self.tag_layouts.values().cloned())
+            .collect();
+        layouts.sort();
+        layouts.dedup();
+        layouts
+    }
+
+    /// Whether gaps are currently forced off (gaps-multi-monitor-only with a single display).
+    pub fn gaps_suppressed(&self) -> bool {
+        self.config.gaps_suppressed(self.displays.len())
+    }
+
+    /// Outer gap to apply to layouts, taking gaps-multi-monitor-only into account.
+    pub fn effective_outer_gap(&self) -> OuterGap {
+        self.config.effective_outer_gap(self.displays.len())
+    }
+
+    /// Area windows are tiled into: the display frame (menu bar already excluded)
+    /// minus edge reservations and the outer gap.
+    pub fn usable_frame(&self, display_id: DisplayId) -> Option<Rect> {
+        let display = self.displays.get(&display_id)?;
+        let gap = display
+            .outer_gap
+            .unwrap_or_else(|| self.effective_outer_gap());
+        let reserved = display.reserved;
+        let top = reserved
+            .top
+            .saturating_add(gap.top)
+            .min(display.frame.height);
+        let left = reserved
+            .left
+            .saturating_add(gap.left)
+            .min(display.frame.width);
+        Some(Rect {
+            x: display.frame.x.saturating_add(left as i32),
+            y: display.frame.y.saturating_add(top as i32),
+            width: display
+                .frame
+                .width
+                .saturating_sub(reserved.horizontal() + gap.horizontal()),
+            height: display
+                .frame
+                .height
+                .saturating_sub(reserved.vertical() + gap.vertical()),
+        })
+    }
+
+    /// Visible tags of the focused display.
+    /// Each display owns its visible tags; use `visible_tags_on_display` for other displays.
     pub fn visible_tags(&self) -> Tag {
+        self.visible_tags_on_display(self.focused_display)
+    }
+
+    pub fn visible_tags_on_display(&self, display_id: DisplayId) -> Tag {
         self.displays
-            .get(&self.focused_display)
+            .get(&display_id)
             .map(|d| d.visible_tags)
             .unwrap_or(Tag::new(1))
     }
 
+    /// A window is visible when its tags intersect the visible tags of its own display.
+    /// Minimized windows are never visible.
+    pub fn is_window_visible(&self, window: &Window) -> bool {
+        !window.is_hidden()
+            && !window.is_minimized
+            && self
+                .displays
+                .get(&window.display_id)
+                .is_some_and(|d| window.tags.intersects(d.visible_tags))
+    }
+
+    /// All visible windows across displays (tiled, floating and fullscreen).
+    pub fn visible_windows(&self) -> Vec<&Window> {
+        self.windows
+            .values()
+            .filter(|w| self.is_window_visible(w))
+            .collect()
+    }
+
+    pub fn event_subscriber_count(&self) -> usize {
+        self.event_subscribers.load(Ordering::SeqCst)
+    }
+
     pub fn has_windows_for_pid(&self, pid: i32) -> bool {
         self.windows.values().any(|w| w.pid == pid)
     }
 
+    /// Pause or resume handling of an app's move/resize events.
+    /// Returns true if the setting changed.
+    pub fn set_app_events_paused(&mut self, app_name: &str, paused: bool) -> bool {
+        let changed = if paused {
+            self.paused_event_apps.insert(app_name.to_string())
+        } else {
+            self.paused_event_apps.remove(app_name)
+        };
+        if changed {
+            tracing::info!(
+                "{} window events for app '{}'",
+                if paused { "Paused" } else { "Resumed" },
+                app_name
+            );
+        }
+        changed
+    }
+
+    fn are_app_events_paused(&self, pid: i32) -> bool {
+        !self.paused_event_apps.is_empty()
+            && self
+                .windows
+                .values()
+                .any(|w| w.pid == pid && self.paused_event_apps.contains(&w.app_name))
+    }
+
     /// Find the topmost visible window at the given screen coordinates.
     /// Uses cached z-order (front-to-back) to return the window that is visually on top.
     /// Returns WindowAtPoint indicating whether the window is managed or ignored.
@@ -305,6 +620,100 @@ impl State {
         None
     }
 
+    /// The most recently minimized window that is still minimized: (id, pid).
+    pub fn last_minimized_window(&self) -> Option<(WindowId, i32)> {
+        self.minimized_order
+            .iter()
+            .rev()
+            .filter_map(|id| self.windows.get(id))
+            .find(|w| w.is_minimized)
+            .map(|w| (w.id, w.pid))
+    }
+
+    /// Visible windows front-to-back, for the drag event tap to hit test clicks the
+    /// same way `find_window_at_point` and `begin_window_drag` do.
+    pub fn drag_targets(&self) -> DragTargets {
+        let targets = self
+            .window_z_order
+            .iter()
+            .filter_map(|window_id| {
+                if let Some(window) = self.windows.get(window_id) {
+                    let display = self.displays.get(&window.display_id)?;
+                    if !window.tags.intersects(display.visible_tags) || window.is_hidden() {
+                        return None;
+                    }
+                    return Some(DragTarget {
+                        frame: window.frame,
+                        display_frame: display.frame,
+                        draggable: window.is_floating && !window.is_fullscreen,
+                    });
+                }
+                let info = self.ignored_windows.get(window_id)?;
+                Some(DragTarget {
+                    frame: info.frame,
+                    display_frame: self.displays.get(&info.display_id)?.frame,
+                    draggable: false,
+                })
+            })
+            .collect();
+        DragTargets(targets)
+    }
+
+    /// Start a modifier+drag at (x, y) if the topmost window there is a managed
+    /// floating window; tiled windows belong to the layout. Returns whether a drag started.
+    pub fn begin_window_drag(&mut self, kind: DragKind, x: i32, y: i32) -> bool {
+        self.window_drag = None;
+        let Some(WindowAtPoint::Managed { window_id, pid }) = self.find_window_at_point(x, y)
+        else {
+            return false;
+        };
+        let window = &self.windows[&window_id];
+        if !window.is_floating || window.is_fullscreen {
+            return false;
+        }
+        tracing::debug!("Drag ({:?}) started on window {}", kind, window_id);
+        self.window_drag = Some(WindowDrag {
+            window_id,
+            pid,
+            kind,
+            origin: (x, y),
+            start_frame: window.frame,
+        });
+        true
+    }
+
+    /// Effect that moves or resizes the dragged window for the cursor at (x, y).
+    /// The cached frame follows along so hit testing stays accurate during the drag.
+    pub fn update_window_drag(&mut self, x: i32, y: i32) -> Option<Effect> {
+        let drag = self.window_drag.as_ref()?;
+        let frame = drag.frame_at(x, y);
+        let window = self.windows.get_mut(&drag.window_id)?;
+        if window.frame == frame {
+            return None;
+        }
+        window.frame = frame;
+        Some(match drag.kind {
+            DragKind::Move => Effect::MoveWindowToPosition {
+                window_id: drag.window_id,
+                pid: drag.pid,
+                x: frame.x,
+                y: frame.y,
+            },
+            DragKind::Resize => Effect::ResizeWindow {
+                window_id: drag.window_id,
+                pid: drag.pid,
+                x: frame.x,
+                y: frame.y,
+                width: frame.width,
+                height: frame.height,
+            },
+        })
+    }
+
+    pub fn end_window_drag(&mut self) {
+        self.window_drag = None;
+    }
+
     /// Set the focus intent when intentionally focusing a window.
     /// This helps suppress spurious macOS focus changes to other windows of the same app.
     pub fn set_focus_intent(&mut self, window_id: WindowId, pid: i32) {
@@ -390,7 +799,7 @@ impl State {
 
         for id in &window_ids {
             self.windows.remove(id);
-            remove_from_window_order(self, *id);
+            forget_window(self, *id);
             if self.focused == Some(*id) {
                 self.focused = None;
             }
@@ -470,14 +879,151 @@ impl State {
         sync_windows_for_display(self, ws, display_id)
     }
 
+    pub fn reconcile_windows<W: WindowSystem>(&mut self, ws: &W) -> Vec<WindowId> {
+        reconcile_windows(self, ws)
+    }
+
+    pub fn unmanaged_windows<W: WindowSystem>(&self, ws: &W) -> Vec<UnmanagedWindowInfo> {
+        unmanaged_windows(self, ws)
+    }
+
+    /// Forced managed status of a window, if an override exists for it and its app.
+    pub fn window_override(&self, window_id: WindowId, app_name: &str) -> Option<bool> {
+        self.window_overrides
+            .get(&window_id)
+            .filter(|o| o.app_name == app_name)
+            .map(|o| o.managed)
+    }
+
+    pub fn set_window_override(&mut self, window_id: WindowId, app_name: &str, managed: bool) {
+        tracing::info!(
+            "Window override: [{}] ({}) managed={}",
+            window_id,
+            app_name,
+            managed
+        );
+        self.window_overrides.insert(
+            window_id,
+            WindowOverride {
+                id: window_id,
+                app_name: app_name.to_string(),
+                managed,
+            },
+        );
+    }
+
+    /// Drop the overrides of windows in `tracked` that are neither managed nor ignored
+    /// any more, i.e. were destroyed. Returns true if any override was dropped.
+    pub fn prune_window_overrides(&mut self, tracked: &[WindowId]) -> bool {
+        let mut pruned = false;
+        for id in tracked {
+            if !self.windows.contains_key(id) && !self.ignored_windows.contains_key(id) {
+                if let Some(o) = self.window_overrides.remove(id) {
+                    tracing::info!("Window override dropped: [{}] ({})", o.id, o.app_name);
+                    pruned = true;
+                }
+            }
+        }
+        pruned
+    }
+
+    /// Whether overrides were pruned since the last call, so they need persisting.
+    pub fn take_window_overrides_pruned(&mut self) -> bool {
+        std::mem::take(&mut self.window_overrides_pruned)
+    }
+
+    /// All overrides, ordered by window ID for stable persistence.
+    pub fn window_override_list(&self) -> Vec<WindowOverride> {
+        let mut overrides: Vec<_> = self.window_overrides.values().cloned().collect();
+        overrides.sort_by_key(|o| o.id);
+        overrides
+    }
+
+    pub fn unmanage_window(&mut self, window_id: WindowId) -> Option<DisplayId> {
+        unmanage_window(self, window_id)
+    }
+
+    /// Whether an event-driven retile may run now. If not, it is deferred to the next tick.
+    pub fn request_retile(&mut self, now: Instant) -> bool {
+        let tick = self.config.tick_rate();
+        self.retile_debouncer.request((), now, tick)
+    }
+
+    /// Whether a deferred retile is due at `now`.
+    pub fn take_due_retile(&mut self, now: Instant) -> bool {
+        !self.retile_debouncer.take_due(now).is_empty()
+    }
+
+    /// Whether a move/resize event of `pid` may be synced now. If not, one trailing
+    /// sync is deferred until `MOVE_SYNC_INTERVAL` after the last one.
+    pub fn request_move_sync(&mut self, pid: i32, now: Instant) -> bool {
+        self.move_sync_debouncer
+            .request(pid, now, Self::MOVE_SYNC_INTERVAL)
+    }
+
+    /// Pids whose deferred move/resize sync is due at `now`.
+    pub fn take_due_move_syncs(&mut self, now: Instant) -> Vec<i32> {
+        self.move_sync_debouncer.take_due(now)
+    }
+
+    /// When the event loop should next wake up to flush deferred work.
+    pub fn next_tick_deadline(&self) -> Option<Instant> {
+        let animation = self
+            .ratio_animation
+            .as_ref()
+            .and_then(|a| a.next_deadline());
+        [
+            self.retile_debouncer.next_deadline(),
+            self.move_sync_debouncer.next_deadline(),
+            self.focus_log_throttle.next_deadline(),
+            animation,
+        ]
+        .into_iter()
+        .flatten()
+        .min()
+    }
+
+    /// The next main-ratio-animate step due at `now`: (layout, displays showing it, ratio).
+    /// The animation is dropped once its last step is taken.
+    pub fn take_due_ratio_step(&mut self, now: Instant) -> Option<(String, Vec<DisplayId>, f64)> {
+        let animation = self.ratio_animation.as_mut()?;
+        let ratio = animation.take_due(now)?;
+        let layout = animation.layout.clone();
+        if animation.is_finished() {
+            self.ratio_animation = None;
+        }
+        let display_ids = self.displays_using_layout(&layout);
+        Some((layout, display_ids, ratio))
+    }
+
     pub fn handle_event<W: WindowSystem>(
         &mut self,
         ws: &W,
         event: &Event,
     ) -> (bool, Vec<WindowId>, Vec<WindowMove>) {
         match event {
-            Event::WindowCreated { pid } | Event::WindowDestroyed { pid } => {
-                self.sync_pid(ws, *pid)
+            Event::WindowCreated { pid } => self.sync_pid(ws, *pid),
+            Event::WindowDestroyed { pid } => {
+                let tracked: Vec<WindowId> = self
+                    .window_overrides
+                    .keys()
+                    .filter(|id| {
+                        self.windows.contains_key(id) || self.ignored_windows.contains_key(id)
+                    })
+                    .copied()
+                    .collect();
+                let result = self.sync_pid(ws, *pid);
+                if self.prune_window_overrides(&tracked) {
+                    self.window_overrides_pruned = true;
+                }
+                result
+            }
+            // A paused app's windows stay managed, but its move/resize spam is dropped
+            Event::WindowMoved { pid } | Event::WindowResized { pid }
+                if self.are_app_events_paused(*pid) =>
+            {
+                tracing::trace!("Ignoring {:?} from paused app", event);
+                (false, vec![], vec![])
             }
             Event::WindowMoved { pid }
             | Event::WindowResized { pid }
@@ -500,39 +1046,103 @@ impl State {
         }
     }
 
+    /// Log the focus a burst of sampled-out focus changes ended on, once it is due.
+    pub fn flush_focus_log(&mut self, now: Instant) {
+        if let Some(suppressed) = self.focus_log_throttle.take_trailing(now) {
+            tracing::info!("Focus changed: -> {:?}{}", self.focused, suppressed);
+        }
+    }
+
+    /// Set the focused window. Focusing a window on another display also moves
+    /// `focused_display`, so output-less commands follow the focus.
     pub fn set_focused(&mut self, window_id: Option<WindowId>) {
         if self.focused != window_id {
-            tracing::info!("Focus changed: {:?} -> {:?}", self.focused, window_id);
+            if let Some(suppressed) = self.focus_log_throttle.check(Instant::now()) {
+                tracing::info!(
+                    "Focus changed: {:?} -> {:?}{}",
+                    self.focused,
+                    window_id,
+                    suppressed
+                );
+            }
             self.focused = window_id;
         }
+        if let Some(id) = window_id {
+            self.urgent_windows.retain(|&urgent| urgent != id);
+        }
+        let display_id = window_id
+            .and_then(|id| self.windows.get(&id))
+            .map(|w| w.display_id);
+        if let Some(display_id) = display_id {
+            if self.focused_display != display_id {
+                tracing::info!(
+                    "Focused display changed: {} -> {}",
+                    self.focused_display,
+                    display_id
+                );
+                self.focused_display = display_id;
+            }
+        }
     }
 
     // Tag operations - delegated to state/tags.rs
 
-    pub fn view_tags(&mut self, tags: u32) -> Vec<WindowMove> {
+    pub fn view_tags(&mut self, tags: u64) -> Vec<WindowMove> {
         view_tags(self, tags)
     }
 
-    pub fn view_tags_on_display(&mut self, tags: u32, display_id: DisplayId) -> Vec<WindowMove> {
+    pub fn view_tags_on_display(&mut self, tags: u64, display_id: DisplayId) -> Vec<WindowMove> {
         view_tags_on_display(self, tags, display_id)
     }
 
-    pub fn toggle_tags_on_display(&mut self, tags: u32, display_id: DisplayId) -> Vec<WindowMove> {
+    pub fn toggle_tags_on_display(&mut self, tags: u64, display_id: DisplayId) -> Vec<WindowMove> {
         toggle_tags_on_display(self, tags, display_id)
     }
 
+    pub fn view_tags_back(&mut self, steps: u32) -> Vec<WindowMove> {
+        view_tags_back(self, steps)
+    }
+
     pub fn view_tags_last(&mut self) -> Vec<WindowMove> {
         view_tags_last(self)
     }
 
-    pub fn move_focused_to_tags(&mut self, tags: u32) -> Vec<WindowMove> {
+    pub fn cycle_tags(
+        &mut self,
+        direction: OutputDirection,
+        only_occupied: bool,
+    ) -> Vec<WindowMove> {
+        cycle_tags(self, direction, only_occupied)
+    }
+
+    pub fn tag_stats(&self, display_id: DisplayId) -> Vec<(u32, usize, bool)> {
+        tag_stats(self, display_id)
+    }
+
+    pub fn move_focused_to_tags(&mut self, tags: u64) -> Vec<WindowMove> {
         move_focused_to_tags(self, tags)
     }
 
-    pub fn toggle_focused_window_tags(&mut self, tags: u32) -> Vec<WindowMove> {
+    pub fn toggle_focused_window_tags(&mut self, tags: u64) -> Vec<WindowMove> {
         toggle_focused_window_tags(self, tags)
     }
 
+    pub fn match_focused_to_view(&mut self) -> Option<(Vec<DisplayId>, Vec<WindowMove>)> {
+        match_focused_to_view(self)
+    }
+
+    pub fn tag_windows_by_title(
+        &mut self,
+        pattern: &regex::Regex,
+        tags: u64,
+    ) -> (Vec<WindowId>, Vec<WindowMove>) {
+        tag_windows_by_title(self, pattern, tags)
+    }
+
+    pub fn distribute_windows(&mut self, tags: u64) -> Vec<WindowMove> {
+        distribute_windows(self, tags)
+    }
+
     pub fn toggle_focused_fullscreen(&mut self) -> Option<(DisplayId, bool, u32, i32)> {
         toggle_focused_fullscreen(self)
     }
@@ -541,14 +1151,110 @@ impl State {
         toggle_focused_float(self)
     }
 
+    pub fn set_tag_name(&mut self, tag: u32, name: &str) -> Result<(), String> {
+        set_tag_name(self, tag, name)
+    }
+
+    pub fn tag_by_name(&self, name: &str) -> Option<u32> {
+        tag_by_name(self, name)
+    }
+
+    pub fn tag_names_of(&self, tags: Tag) -> Vec<String> {
+        tag_names_of(self, tags)
+    }
+
+    pub fn set_float_all(&mut self, enabled: bool) -> Vec<DisplayId> {
+        set_float_all(self, enabled)
+    }
+
+    pub fn scratchpad_toggle(&mut self, app_name: &str) -> ScratchpadToggle {
+        scratchpad_toggle(self, app_name)
+    }
+
+    pub fn toggle_always_on_top(
+        &mut self,
+        window_id: Option<WindowId>,
+    ) -> Option<(WindowId, i32, bool)> {
+        toggle_always_on_top(self, window_id)
+    }
+
+    pub fn toggle_native_size(&mut self, window_id: Option<WindowId>) -> Option<(DisplayId, bool)> {
+        toggle_native_size(self, window_id)
+    }
+
     // Focus operations - delegated to state/focus.rs
 
-    pub fn focus_window(&self, direction: Direction) -> Option<(WindowId, i32)> {
-        focus_window(self, direction)
+    pub fn focus_window(&self, direction: Direction, same_output: bool) -> Option<(WindowId, i32)> {
+        focus_window(self, direction, same_output)
+    }
+
+    pub fn focus_window_by_size(&self, which: SizeSelection) -> Option<(WindowId, i32)> {
+        focus_window_by_size(self, which)
+    }
+
+    pub fn swap_window(
+        &mut self,
+        direction: Direction,
+        same_output: bool,
+    ) -> Option<Vec<DisplayId>> {
+        swap_window(self, direction, same_output)
+    }
+
+    /// Take the pending focus successor, if it still exists.
+    pub fn take_focus_successor(&mut self) -> Option<(WindowId, i32)> {
+        let id = self.focus_successor.take()?;
+        self.windows.get(&id).map(|w| (w.id, w.pid))
+    }
+
+    /// Display containing the window's center (its on-screen frame if hidden), falling back
+    /// to the tracked display when the center lies between displays. None for unknown windows.
+    pub fn window_output(&self, window_id: WindowId) -> Option<DisplayId> {
+        let window = self.windows.get(&window_id)?;
+        let (cx, cy) = window.saved_frame.unwrap_or(window.frame).center();
+        Some(display_containing_point(self, cx as f64, cy as f64).unwrap_or(window.display_id))
+    }
+
+    /// Flag a window as needing attention. Returns false if the window is unknown.
+    pub fn set_urgent(&mut self, window_id: WindowId) -> bool {
+        if !self.windows.contains_key(&window_id) {
+            return false;
+        }
+        tracing::info!("Window {} marked urgent", window_id);
+        self.urgent_windows.retain(|&id| id != window_id);
+        self.urgent_windows.push(window_id);
+        true
+    }
+
+    pub fn focus_urgent(&mut self) -> Option<FocusUrgentResult> {
+        focus_urgent(self)
+    }
+
+    pub fn start_peek(&mut self) -> Option<(WindowId, i32, Rect)> {
+        start_peek(self)
+    }
+
+    pub fn release_peek(&mut self) -> Option<DisplayId> {
+        release_peek(self)
+    }
+
+    pub fn apply_peek_frames(
+        &mut self,
+        display_id: DisplayId,
+        geometries: &mut [yashiki_ipc::layout::WindowGeometry],
+    ) {
+        apply_peek_frames(self, display_id, geometries)
+    }
+
+    pub fn apply_native_sizes(&self, geometries: &mut [yashiki_ipc::layout::WindowGeometry]) {
+        apply_native_sizes(self, geometries)
     }
 
-    pub fn swap_window(&mut self, direction: Direction) -> Option<DisplayId> {
-        swap_window(self, direction)
+    pub fn always_on_top_to_reassert(&self, focused_id: WindowId) -> Vec<(WindowId, i32)> {
+        always_on_top_to_reassert(self, focused_id)
+    }
+
+    pub fn shift_window(&mut self, direction: OutputDirection) -> Option<DisplayId> {
+        shift_window(self, direction)
     }
 
     pub fn focus_output(&mut self, direction: OutputDirection) -> Option<FocusOutputResult> {
@@ -565,6 +1271,38 @@ impl State {
         visible_windows_on_display(self, display_id)
     }
 
+    /// Window ids to send to the layout engine, ordered by the display's `window_order`.
+    pub fn layout_window_ids(&self, display_id: DisplayId) -> Vec<WindowId> {
+        self.visible_windows_on_display(display_id)
+            .iter()
+            .map(|w| w.id)
+            .collect()
+    }
+
+    pub fn apply_layout_order(&mut self, display_id: DisplayId, laid_out: &[WindowId]) {
+        apply_layout_order(self, display_id, laid_out)
+    }
+
+    pub fn move_window_to_front(&mut self, window_id: WindowId) -> Option<DisplayId> {
+        move_window_to_front(self, window_id)
+    }
+
+    pub fn move_focused_window_to_slot(&mut self, index: usize) -> Option<DisplayId> {
+        move_focused_window_to_slot(self, index)
+    }
+
+    pub fn reverse_window_order(&mut self, display_id: DisplayId) -> bool {
+        reverse_window_order(self, display_id)
+    }
+
+    pub fn apply_sticky_main(&mut self, display_id: DisplayId) -> bool {
+        apply_sticky_main(self, display_id)
+    }
+
+    pub fn toggle_inner_gap(&mut self, layout: &str, current_gap: u32) -> u32 {
+        toggle_inner_gap(self, layout, current_gap)
+    }
+
     pub(crate) fn compute_layout_changes(&mut self, display_id: DisplayId) -> Vec<WindowMove> {
         compute_layout_changes(self, display_id)
     }
@@ -626,7 +1364,7 @@ mod tests {
         create_test_display, create_test_window, create_test_window_with_layer, MockWindowSystem,
     };
     use layout::compute_hide_position_for_display;
-    use yashiki_ipc::{ButtonInfo, ExtendedWindowAttributes};
+    use yashiki_ipc::{ButtonInfo, Edge, ExtendedWindowAttributes};
 
     /// Age all ignored windows so they're no longer protected by the grace period.
     /// Used in tests that need to verify window removal behavior.
@@ -649,46 +1387,311 @@ mod tests {
     }
 
     #[test]
-    fn test_sync_all_initializes_state() {
-        let ws = setup_mock_system();
-        let mut state = State::new();
-        state.sync_all(&ws);
+    fn test_auto_raise_suppressed_shortly_after_retile() {
+        let mut auto_raise = AutoRaiseState {
+            last_hovered: Some(100),
+            hover_start: Some(Instant::now()),
+            last_retile: None,
+        };
+        let retile = Instant::now();
+        assert!(!auto_raise.is_suppressed_at(retile));
 
-        assert_eq!(state.windows.len(), 3);
-        assert_eq!(state.displays.len(), 1);
-        assert_eq!(state.focused, Some(100));
-        assert_eq!(state.focused_display, 1);
+        auto_raise.record_retile(retile);
+        assert_eq!(auto_raise.last_hovered, None);
+        assert_eq!(auto_raise.hover_start, None);
+
+        assert!(auto_raise.is_suppressed_at(retile));
+        assert!(auto_raise.is_suppressed_at(retile + Duration::from_millis(99)));
+        assert!(!auto_raise.is_suppressed_at(retile + Duration::from_millis(100)));
+        assert!(!auto_raise.is_suppressed_at(retile + Duration::from_millis(500)));
     }
 
     #[test]
-    fn test_view_tags_switches_tags() {
+    fn test_runtime_stats_reflect_last_retile() {
+        let start = Instant::now();
+        let mut stats = RuntimeStats::new(start);
+        let info = stats.info(start);
+        assert_eq!(info.last_retile_us, None);
+        assert_eq!(info.retile_count, 0);
+        assert_eq!(info.events_per_sec, 0.0);
+
+        stats.record_retile(RetileStats {
+            duration: Duration::from_micros(900),
+            writes: 5,
+            skipped: 5,
+        });
+        stats.record_retile(RetileStats {
+            duration: Duration::from_micros(1500),
+            writes: 3,
+            skipped: 1,
+        });
+        for _ in 0..20 {
+            stats.record_event();
+        }
+
+        let info = stats.info(start + Duration::from_secs(10));
+        assert_eq!(info.last_retile_us, Some(1500));
+        assert_eq!(info.last_retile_writes, 3);
+        assert_eq!(info.last_retile_skipped, 1);
+        assert_eq!(info.retile_count, 2);
+        assert_eq!(info.events_processed, 20);
+        assert_eq!(info.uptime_secs, 10);
+        assert_eq!(info.events_per_sec, 2.0);
+    }
+
+    #[test]
+    fn test_paused_app_move_events_are_ignored() {
         let ws = setup_mock_system();
         let mut state = State::new();
         state.sync_all(&ws);
+        assert!(state.set_app_events_paused("Safari", true));
+        assert!(!state.set_app_events_paused("Safari", true));
 
-        assert_eq!(state.visible_tags().mask(), 0b1);
+        let moved = MockWindowSystem::new()
+            .with_displays(vec![create_test_display(1, 0.0, 0.0, 1920.0, 1080.0)])
+            .with_windows(vec![
+                create_test_window(100, 1000, "Safari", 100.0, 100.0, 800.0, 600.0),
+                create_test_window(101, 1001, "Terminal", 200.0, 200.0, 800.0, 600.0),
+                create_test_window(102, 1002, "VSCode", 0.0, 0.0, 960.0, 540.0),
+            ])
+            .with_focused(Some(100));
 
-        let moves = state.view_tags(0b10);
-        assert_eq!(state.visible_tags().mask(), 0b10);
+        // Paused app: nothing is resynced
+        let result = state.handle_event(&moved, &Event::WindowMoved { pid: 1000 });
+        assert_eq!(result, (false, vec![], vec![]));
+        assert_eq!(state.windows[&100].frame.x, 0);
 
-        assert_eq!(moves.len(), 3);
+        // Other apps are still tracked
+        state.handle_event(&moved, &Event::WindowMoved { pid: 1001 });
+        assert_eq!(state.windows[&101].frame.x, 200);
+
+        // Resuming restores normal handling
+        assert!(state.set_app_events_paused("Safari", false));
+        state.handle_event(&moved, &Event::WindowResized { pid: 1000 });
+        assert_eq!(state.windows[&100].frame.width, 800);
     }
 
     #[test]
-    fn test_view_tags_last_toggles_back() {
+    fn test_move_events_update_frames_without_requesting_retile() {
         let ws = setup_mock_system();
         let mut state = State::new();
         state.sync_all(&ws);
 
-        state.view_tags(0b10);
-        assert_eq!(state.visible_tags().mask(), 0b10);
+        // macOS echoes the frames a retile wrote as move/resize events
+        let moved = MockWindowSystem::new()
+            .with_displays(vec![create_test_display(1, 0.0, 0.0, 1920.0, 1080.0)])
+            .with_windows(vec![
+                create_test_window(100, 1000, "Safari", 0.0, 0.0, 1280.0, 1080.0),
+                create_test_window(101, 1001, "Terminal", 1280.0, 0.0, 640.0, 1080.0),
+                create_test_window(102, 1002, "VSCode", 0.0, 0.0, 960.0, 540.0),
+            ])
+            .with_focused(Some(100));
 
-        state.view_tags_last();
-        assert_eq!(state.visible_tags().mask(), 0b1);
+        let (changed, _, _) = state.handle_event(&moved, &Event::WindowResized { pid: 1000 });
+        assert!(!changed, "a frame echo must not retile again");
+        let (changed, _, _) = state.handle_event(&moved, &Event::WindowMoved { pid: 1001 });
+        assert!(!changed, "a frame echo must not retile again");
+
+        // The cached frames still follow, so the next retile's diff sees no work
+        assert_eq!(state.windows[&100].frame.width, 1280);
+        assert_eq!(state.windows[&101].frame.x, 1280);
     }
 
     #[test]
-    fn test_toggle_tags() {
+    fn test_sync_all_initializes_state() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+
+        assert_eq!(state.windows.len(), 3);
+        assert_eq!(state.displays.len(), 1);
+        assert_eq!(state.focused, Some(100));
+        assert_eq!(state.focused_display, 1);
+    }
+
+    #[test]
+    fn test_sync_all_treats_mirrored_displays_as_one() {
+        let mut mirror = create_test_display(2, 0.0, 0.0, 1920.0, 1080.0);
+        mirror.mirror_of = Some(1);
+        let ws = MockWindowSystem::new()
+            .with_displays(vec![
+                create_test_display(1, 0.0, 0.0, 1920.0, 1080.0),
+                mirror,
+            ])
+            .with_windows(vec![create_test_window(
+                100, 1000, "Safari", 0.0, 0.0, 960.0, 1080.0,
+            )]);
+        let mut state = State::new();
+        state.sync_all(&ws);
+
+        assert_eq!(state.displays.keys().copied().collect::<Vec<_>>(), vec![1]);
+        assert_eq!(state.displays[&1].window_order, vec![100]);
+    }
+
+    #[test]
+    fn test_view_tags_switches_tags() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+
+        assert_eq!(state.visible_tags().mask(), 0b1);
+
+        let moves = state.view_tags(0b10);
+        assert_eq!(state.visible_tags().mask(), 0b10);
+
+        assert_eq!(moves.len(), 3);
+    }
+
+    #[test]
+    fn test_view_tags_last_toggles_back() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+
+        state.view_tags(0b10);
+        assert_eq!(state.visible_tags().mask(), 0b10);
+
+        state.view_tags_last();
+        assert_eq!(state.visible_tags().mask(), 0b1);
+    }
+
+    #[test]
+    fn test_tag_view_pushes_history() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+
+        state.view_tags(0b10);
+        state.view_tags(0b100);
+        state.toggle_tags_on_display(0b1000, 1);
+        // Viewing the current tags again is a no-op and records nothing
+        state.view_tags(0b1100);
+        let history: Vec<u64> = state.displays[&1]
+            .tag_history
+            .iter()
+            .map(|t| t.mask())
+            .collect();
+        assert_eq!(history, vec![0b1, 0b10, 0b100]);
+
+        for n in 1..=crate::core::TAG_HISTORY_LIMIT as u32 + 4 {
+            state.view_tags(Tag::new(n % 32 + 1).mask());
+        }
+        assert_eq!(
+            state.displays[&1].tag_history.len(),
+            crate::core::TAG_HISTORY_LIMIT
+        );
+    }
+
+    #[test]
+    fn test_view_tags_back_multiple_steps() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+        state.view_tags(0b10);
+        state.view_tags(0b100);
+        state.view_tags(0b1000);
+
+        state.view_tags_back(2);
+        assert_eq!(state.visible_tags().mask(), 0b10);
+        assert_eq!(state.displays[&1].tag_history.len(), 1);
+
+        // Steps beyond the depth stop at the oldest entry
+        state.view_tags_back(10);
+        assert_eq!(state.visible_tags().mask(), 0b1);
+        assert!(state.displays[&1].tag_history.is_empty());
+        assert!(state.view_tags_back(1).is_empty());
+        assert_eq!(state.visible_tags().mask(), 0b1);
+    }
+
+    #[test]
+    fn test_tag_names() {
+        let mut state = State::new();
+        assert_eq!(state.set_tag_name(4, "mail"), Ok(()));
+        assert_eq!(state.set_tag_name(1, "web"), Ok(()));
+        assert_eq!(state.tag_by_name("mail"), Some(4));
+        assert_eq!(
+            state.tag_names_of(Tag::from_mask(0b1001)),
+            vec!["web", "mail"]
+        );
+
+        // A name labels one tag at a time
+        assert_eq!(state.set_tag_name(2, "mail"), Ok(()));
+        assert_eq!(state.tag_by_name("mail"), Some(2));
+        assert!(!state.tag_names.contains_key(&4));
+
+        // An empty name clears the label
+        assert_eq!(state.set_tag_name(1, ""), Ok(()));
+        assert_eq!(state.tag_by_name("web"), None);
+
+        assert!(state.set_tag_name(0, "none").is_err());
+        assert!(state.set_tag_name(65, "none").is_err());
+    }
+
+    #[test]
+    fn test_cycle_tags_wraps_around() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+
+        state.cycle_tags(OutputDirection::Next, false);
+        assert_eq!(state.visible_tags().mask(), 0b10);
+
+        state.cycle_tags(OutputDirection::Prev, false);
+        state.cycle_tags(OutputDirection::Prev, false);
+        assert_eq!(state.visible_tags(), Tag::new(Tag::MAX));
+
+        state.cycle_tags(OutputDirection::Next, false);
+        assert_eq!(state.visible_tags().mask(), 0b1);
+    }
+
+    #[test]
+    fn test_cycle_tags_skips_empty_tags() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+        state.windows.get_mut(&101).unwrap().tags = Tag::new(3);
+        state.windows.get_mut(&102).unwrap().tags = Tag::new(5);
+
+        state.cycle_tags(OutputDirection::Next, true);
+        assert_eq!(state.visible_tags(), Tag::new(3));
+        state.cycle_tags(OutputDirection::Next, true);
+        assert_eq!(state.visible_tags(), Tag::new(5));
+        state.cycle_tags(OutputDirection::Next, true);
+        assert_eq!(state.visible_tags(), Tag::new(1));
+
+        state.cycle_tags(OutputDirection::Prev, true);
+        assert_eq!(state.visible_tags(), Tag::new(5));
+    }
+
+    #[test]
+    fn test_cycle_tags_stays_when_no_other_tag_is_occupied() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+
+        let moves = state.cycle_tags(OutputDirection::Next, true);
+        assert!(moves.is_empty());
+        assert_eq!(state.visible_tags().mask(), 0b1);
+    }
+
+    #[test]
+    fn test_tag_stats_counts_windows_and_visibility() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+        state.windows.get_mut(&101).unwrap().tags = Tag::from_mask(0b101);
+        state.windows.get_mut(&102).unwrap().tags = Tag::new(5);
+        state.displays.get_mut(&1).unwrap().visible_tags = Tag::from_mask(0b11);
+
+        assert_eq!(
+            state.tag_stats(1),
+            vec![(1, 2, true), (2, 0, true), (3, 1, false), (5, 1, false)]
+        );
+        assert!(state.tag_stats(99).is_empty());
+    }
+
+    #[test]
+    fn test_toggle_tags() {
         let ws = setup_mock_system();
         let mut state = State::new();
         state.sync_all(&ws);
@@ -726,13 +1729,158 @@ mod tests {
         assert!(!moves.is_empty());
     }
 
+    #[test]
+    fn test_match_focused_to_view_overwrites_tags() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+        state.windows.get_mut(&100).unwrap().tags = Tag::from_mask(0b1001);
+        state.displays.get_mut(&1).unwrap().visible_tags = Tag::from_mask(0b0110);
+
+        let (display_ids, _) = state.match_focused_to_view().unwrap();
+
+        assert_eq!(display_ids, vec![1]);
+        assert_eq!(state.windows[&100].tags.mask(), 0b0110);
+        assert!(state.is_window_visible(&state.windows[&100]));
+        // Other windows keep their tags
+        assert_eq!(state.windows[&101].tags.mask(), 0b1);
+
+        state.focused = None;
+        assert!(state.match_focused_to_view().is_none());
+    }
+
+    #[test]
+    fn test_exclusive_tags_limit_window_tags() {
+        use yashiki_ipc::GlobPattern;
+
+        let mut state = setup_two_display_state();
+        state.config.exclusive_tags = true;
+
+        state.move_focused_to_tags(0b110);
+        assert_eq!(state.windows[&100].tags.mask(), 0b10);
+        // Toggling a tag onto the window moves it there instead
+        state.toggle_focused_window_tags(0b1000);
+        assert_eq!(state.windows[&100].tags.mask(), 0b1000);
+
+        // Sent windows keep only one of their tags
+        state.windows.get_mut(&100).unwrap().tags = Tag::from_mask(0b11);
+        state.send_to_output(OutputDirection::Next).unwrap();
+        assert_eq!(state.windows[&100].tags.mask(), 0b1);
+
+        state.add_rule(WindowRule {
+            matcher: RuleMatcher {
+                app_name: Some(GlobPattern::new("Terminal")),
+                app_id: None,
+                title: None,
+                ax_id: None,
+                subrole: None,
+                window_level: None,
+                close_button: None,
+                fullscreen_button: None,
+                minimize_button: None,
+                zoom_button: None,
+            },
+            action: RuleAction::Tags { tags: 0b1100 },
+        });
+        state.apply_rules_to_all_windows();
+        assert_eq!(state.windows[&101].tags.mask(), 0b100);
+    }
+
+    #[test]
+    fn test_match_focused_to_view_uses_focused_display() {
+        let mut state = setup_two_display_state();
+        state.displays.get_mut(&2).unwrap().visible_tags = Tag::from_mask(0b100);
+        // Window 100 (display 1) is focused while display 2 is the focused display
+        state.focused_display = 2;
+
+        let (display_ids, _) = state.match_focused_to_view().unwrap();
+
+        assert_eq!(display_ids, vec![2, 1]);
+        assert_eq!(state.windows[&100].display_id, 2);
+        assert_eq!(state.windows[&100].tags.mask(), 0b100);
+        assert!(state.is_window_visible(&state.windows[&100]));
+    }
+
+    #[test]
+    fn test_tag_windows_by_title() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+        state.windows.get_mut(&100).unwrap().title = "README.md - VSCode".to_string();
+        state.windows.get_mut(&101).unwrap().title = "zsh".to_string();
+        state.windows.get_mut(&102).unwrap().title = "notes.md".to_string();
+
+        let pattern = regex::Regex::new(r"\.md\b").unwrap();
+        let (matched, moves) = state.tag_windows_by_title(&pattern, 0b100);
+
+        assert_eq!(matched, vec![100, 102]);
+        assert_eq!(state.windows[&100].tags.mask(), 0b100);
+        assert_eq!(state.windows[&101].tags.mask(), 0b1);
+        assert_eq!(state.windows[&102].tags.mask(), 0b100);
+        // Both matched windows left the visible tag and are hidden
+        let mut hidden: Vec<_> = moves.iter().map(|m| m.window_id).collect();
+        hidden.sort();
+        assert_eq!(hidden, vec![100, 102]);
+    }
+
+    #[test]
+    fn test_distribute_windows_round_robin() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+        let order = state.layout_window_ids(1);
+        assert_eq!(order.len(), 3);
+
+        // 3 windows over tags 1 and 3: first and third on tag 1, second on tag 3
+        let moves = state.distribute_windows(0b101);
+        assert_eq!(state.windows[&order[0]].tags.mask(), 0b1);
+        assert_eq!(state.windows[&order[1]].tags.mask(), 0b100);
+        assert_eq!(state.windows[&order[2]].tags.mask(), 0b1);
+        // Only the window moved off the visible tag is hidden
+        let hidden: Vec<_> = moves.iter().map(|m| m.window_id).collect();
+        assert_eq!(hidden, vec![order[1]]);
+    }
+
+    #[test]
+    fn test_distribute_windows_more_tags_than_windows() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+        let order = state.layout_window_ids(1);
+
+        state.distribute_windows(0b11110);
+        let tags: Vec<u64> = order
+            .iter()
+            .map(|id| state.windows[id].tags.mask())
+            .collect();
+        assert_eq!(tags, vec![0b10, 0b100, 0b1000]);
+
+        // An empty mask leaves windows alone
+        assert!(state.distribute_windows(0).is_empty());
+        assert_eq!(state.windows[&order[0]].tags.mask(), 0b10);
+    }
+
+    #[test]
+    fn test_tag_windows_by_title_no_match() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+
+        let pattern = regex::Regex::new("^Finder").unwrap();
+        let (matched, moves) = state.tag_windows_by_title(&pattern, 0b10);
+
+        assert!(matched.is_empty());
+        assert!(moves.is_empty());
+        assert!(state.windows.values().all(|w| w.tags.mask() == 0b1));
+    }
+
     #[test]
     fn test_focus_window_next() {
         let ws = setup_mock_system();
         let mut state = State::new();
         state.sync_all(&ws);
 
-        let result = state.focus_window(Direction::Next);
+        let result = state.focus_window(Direction::Next, false);
         assert!(result.is_some());
 
         let (window_id, _pid) = result.unwrap();
@@ -745,7 +1893,7 @@ mod tests {
         let mut state = State::new();
         state.sync_all(&ws);
 
-        let result = state.focus_window(Direction::Prev);
+        let result = state.focus_window(Direction::Prev, false);
         assert!(result.is_some());
 
         let (window_id, _pid) = result.unwrap();
@@ -758,113 +1906,691 @@ mod tests {
         let mut state = State::new();
         state.sync_all(&ws);
 
-        let result = state.focus_window(Direction::Right);
+        let result = state.focus_window(Direction::Right, false);
         assert!(result.is_some());
 
         let (window_id, _pid) = result.unwrap();
         assert_eq!(window_id, 101);
     }
 
-    #[test]
-    fn test_multi_display_focus_output() {
+    /// Two side-by-side displays where the nearest window to the right of 100
+    /// is 200 on display 2, while 101 on display 1 is farther away.
+    fn setup_two_display_state() -> State {
         let ws = MockWindowSystem::new()
             .with_displays(vec![
                 create_test_display(1, 0.0, 0.0, 1920.0, 1080.0),
                 create_test_display(2, 1920.0, 0.0, 1920.0, 1080.0),
             ])
             .with_windows(vec![
-                create_test_window(100, 1000, "Safari", 100.0, 100.0, 800.0, 600.0),
-                create_test_window(101, 1001, "Terminal", 2000.0, 100.0, 800.0, 600.0),
+                create_test_window(100, 1000, "Safari", 0.0, 0.0, 960.0, 100.0),
+                create_test_window(101, 1001, "Terminal", 960.0, 980.0, 960.0, 100.0),
+                create_test_window(200, 1002, "VSCode", 1920.0, 0.0, 960.0, 100.0),
             ])
             .with_focused(Some(100));
-
         let mut state = State::new();
         state.sync_all(&ws);
+        assert_eq!(state.windows[&200].display_id, 2);
+        state
+    }
 
-        assert_eq!(state.focused_display, 1);
-
-        let result = state.focus_output(OutputDirection::Next);
-        assert!(result.is_some());
-        assert_eq!(state.focused_display, 2);
+    #[test]
+    fn test_ratio_step_targets_every_display_using_the_layout() {
+        let mut state = setup_two_display_state();
+        let start = Instant::now();
+        let layout = state.current_layout().to_string();
+        state.ratio_animation = Some(MainRatioAnimation::new(
+            layout.clone(),
+            vec![0.6],
+            Duration::from_millis(16),
+            start,
+        ));
 
-        match result.unwrap() {
-            FocusOutputResult::Window { window_id, .. } => assert_eq!(window_id, 101),
-            FocusOutputResult::EmptyDisplay { .. } => panic!("Expected Window result"),
-        }
+        let (step_layout, display_ids, ratio) = state.take_due_ratio_step(start).unwrap();
+        assert_eq!(step_layout, layout);
+        assert_eq!(display_ids, vec![1, 2]);
+        assert_eq!(ratio, 0.6);
+        assert!(state.ratio_animation.is_none());
     }
 
     #[test]
-    fn test_focus_output_empty_display() {
-        let ws = MockWindowSystem::new()
-            .with_displays(vec![
-                create_test_display(1, 0.0, 0.0, 1920.0, 1080.0),
-                create_test_display(2, 1920.0, 0.0, 1920.0, 1080.0),
-            ])
-            .with_windows(vec![create_test_window(
-                100, 1000, "Safari", 100.0, 100.0, 800.0, 600.0,
-            )])
-            .with_focused(Some(100));
+    fn test_focus_window_directional_crosses_outputs() {
+        let state = setup_two_display_state();
 
-        let mut state = State::new();
-        state.sync_all(&ws);
+        let (window_id, _pid) = state.focus_window(Direction::Right, false).unwrap();
+        assert_eq!(window_id, 200);
+    }
 
-        assert_eq!(state.focused_display, 1);
+    #[test]
+    fn test_focus_axis_swap_maps_left_to_up() {
+        let mut state = State::new();
+        state.sync_all(&setup_mock_system());
+        // 102 sits above the focused window 100, 101 to its right; nothing is to its left
+        assert_eq!(state.focus_window(Direction::Left, false), None);
 
-        let result = state.focus_output(OutputDirection::Next);
-        assert!(result.is_some());
-        assert_eq!(state.focused_display, 2);
+        state.displays.get_mut(&1).unwrap().focus_axis_swap = true;
 
-        match result.unwrap() {
-            FocusOutputResult::EmptyDisplay { display_id } => assert_eq!(display_id, 2),
-            FocusOutputResult::Window { .. } => panic!("Expected EmptyDisplay result"),
-        }
+        let (window_id, _pid) = state.focus_window(Direction::Left, false).unwrap();
+        assert_eq!(window_id, 102);
+        let (window_id, _pid) = state.focus_window(Direction::Down, false).unwrap();
+        assert_eq!(window_id, 101);
+        assert_eq!(state.focus_window(Direction::Up, false), None);
     }
 
     #[test]
-    fn test_sync_pid_adds_new_windows() {
-        let mut ws = MockWindowSystem::new()
-            .with_displays(vec![create_test_display(1, 0.0, 0.0, 1920.0, 1080.0)])
-            .with_windows(vec![create_test_window(
-                100, 1000, "Safari", 0.0, 0.0, 800.0, 600.0,
-            )]);
-
+    fn test_focus_axis_swap_applies_to_swap() {
         let mut state = State::new();
-        state.sync_all(&ws);
-        assert_eq!(state.windows.len(), 1);
+        state.sync_all(&setup_mock_system());
+        state.displays.get_mut(&1).unwrap().focus_axis_swap = true;
+        let order_before = state.displays[&1].window_order.clone();
 
-        ws.add_window(create_test_window(
-            101, 1000, "Safari", 100.0, 100.0, 800.0, 600.0,
-        ));
+        assert_eq!(state.swap_window(Direction::Left, false), Some(vec![1]));
 
-        let (changed, new_ids, rehide_moves) = state.sync_pid(&ws, 1000);
-        assert!(changed);
-        assert_eq!(state.windows.len(), 2);
-        assert_eq!(new_ids, vec![101]);
-        assert!(rehide_moves.is_empty());
+        let order = &state.displays[&1].window_order;
+        let pos = |id| order.iter().position(|&w| w == id).unwrap();
+        let pos_before = |id| order_before.iter().position(|&w| w == id).unwrap();
+        assert_eq!(pos(100), pos_before(102));
+        assert_eq!(pos(102), pos_before(100));
     }
 
     #[test]
-    fn test_sync_pid_removes_closed_windows() {
+    fn test_focus_window_directional_same_output() {
+        let state = setup_two_display_state();
+
+        let (window_id, _pid) = state.focus_window(Direction::Right, true).unwrap();
+        assert_eq!(window_id, 101);
+    }
+
+    /// Three windows side by side with the middle one minimized.
+    fn setup_minimized_middle_state() -> (MockWindowSystem, State) {
         let mut ws = MockWindowSystem::new()
             .with_displays(vec![create_test_display(1, 0.0, 0.0, 1920.0, 1080.0)])
             .with_windows(vec![
-                create_test_window(100, 1000, "Safari", 0.0, 0.0, 800.0, 600.0),
-                create_test_window(101, 1000, "Safari", 100.0, 100.0, 800.0, 600.0),
-            ]);
-
+                create_test_window(100, 1000, "Safari", 0.0, 0.0, 640.0, 1080.0),
+                create_test_window(101, 1001, "Terminal", 640.0, 0.0, 640.0, 1080.0),
+                create_test_window(102, 1002, "VSCode", 1280.0, 0.0, 640.0, 1080.0),
+            ])
+            .with_focused(Some(100));
         let mut state = State::new();
         state.sync_all(&ws);
-        assert_eq!(state.windows.len(), 2);
 
-        ws.remove_window(101);
+        ws.minimize_window(101);
+        let (changed, _, _) = state.sync_pid(&ws, 1001);
+        assert!(changed);
+        assert!(state.windows[&101].is_minimized);
+        (ws, state)
+    }
+
+    #[test]
+    fn test_focus_window_directional_skips_minimized() {
+        let (_ws, state) = setup_minimized_middle_state();
+
+        let (window_id, _pid) = state.focus_window(Direction::Right, false).unwrap();
+        assert_eq!(window_id, 102);
+        assert!(!state.visible_windows().iter().any(|w| w.id == 101));
+        assert!(!state
+            .visible_windows_on_display(1)
+            .iter()
+            .any(|w| w.id == 101));
+    }
+
+    #[test]
+    fn test_swap_window_directional_skips_minimized() {
+        let (_ws, mut state) = setup_minimized_middle_state();
+
+        assert_eq!(state.swap_window(Direction::Right, false), Some(vec![1]));
+        let order = &state.displays[&1].window_order;
+        let pos = |id| order.iter().position(|&w| w == id).unwrap();
+        assert!(pos(102) < pos(100));
+    }
+
+    #[test]
+    fn test_deminiaturized_window_is_navigable_again() {
+        let (mut ws, mut state) = setup_minimized_middle_state();
+
+        ws.minimized_windows.clear();
+        ws.add_window(create_test_window(
+            101, 1001, "Terminal", 640.0, 0.0, 640.0, 1080.0,
+        ));
+        state.sync_pid(&ws, 1001);
+
+        assert!(!state.windows[&101].is_minimized);
+        let (window_id, _pid) = state.focus_window(Direction::Right, false).unwrap();
+        assert_eq!(window_id, 101);
+    }
+
+    #[test]
+    fn test_miniaturize_events_reflow_tiled_windows() {
+        let mut ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+        let tiled = |state: &State| -> Vec<u32> {
+            state
+                .visible_windows_on_display(1)
+                .iter()
+                .map(|w| w.id)
+                .collect()
+        };
+        let order = tiled(&state);
+
+        ws.minimize_window(101);
+        let (changed, _, _) = state.handle_event(&ws, &Event::WindowMiniaturized { pid: 1001 });
+        assert!(changed, "minimizing must trigger a retile");
+        assert!(state.windows[&101].is_minimized);
+        assert!(!tiled(&state).contains(&101));
+
+        // Unrelated events from the same app keep the flag
+        let (changed, _, _) = state.handle_event(&ws, &Event::WindowMoved { pid: 1001 });
+        assert!(!changed);
+        assert!(state.windows[&101].is_minimized);
+
+        ws.minimized_windows.clear();
+        ws.add_window(create_test_window(
+            101, 1001, "Terminal", 960.0, 0.0, 960.0, 1080.0,
+        ));
+        let (changed, _, _) = state.handle_event(&ws, &Event::WindowDeminiaturized { pid: 1001 });
+        assert!(changed, "restoring must trigger a retile");
+        assert!(!state.windows[&101].is_minimized);
+        assert_eq!(tiled(&state), order);
+    }
+
+    #[test]
+    fn test_last_minimized_window_is_most_recent_still_minimized() {
+        let (mut ws, mut state) = setup_minimized_middle_state();
+        assert_eq!(state.last_minimized_window(), Some((101, 1001)));
+
+        ws.minimize_window(102);
+        state.sync_pid(&ws, 1002);
+        assert_eq!(state.last_minimized_window(), Some((102, 1002)));
+
+        // Restoring 102 outside yashiki falls back to the earlier one
+        ws.minimized_windows.retain(|&(id, _)| id != 102);
+        ws.add_window(create_test_window(
+            102, 1002, "VSCode", 1280.0, 0.0, 640.0, 1080.0,
+        ));
+        state.sync_pid(&ws, 1002);
+        assert_eq!(state.last_minimized_window(), Some((101, 1001)));
+        assert_eq!(state.minimized_order, vec![101]);
+    }
+
+    #[test]
+    fn test_removed_window_leaves_minimize_order() {
+        let (mut ws, mut state) = setup_minimized_middle_state();
+        ws.minimize_window(102);
+        state.sync_pid(&ws, 1002);
+        assert_eq!(state.minimized_order, vec![101, 102]);
+
+        state.unmanage_window(102);
+        assert_eq!(state.minimized_order, vec![101]);
+
+        // Closing a minimized window
+        ws.minimized_windows.retain(|&(id, _)| id != 101);
+        ws.ax_only_windows.retain(|&(id, _)| id != 101);
+        state.sync_pid(&ws, 1001);
+        assert!(!state.windows.contains_key(&101));
+        assert!(state.minimized_order.is_empty());
+        assert_eq!(state.last_minimized_window(), None);
+    }
+
+    #[test]
+    fn test_display_containing_point_overlapping_and_gaps() {
+        let ws = MockWindowSystem::new().with_displays(vec![
+            create_test_display(1, 0.0, 0.0, 1920.0, 1080.0),
+            // Overlaps display 1 by 120px
+            create_test_display(2, 1800.0, 0.0, 1920.0, 1080.0),
+            // Separated from display 2 by a 280px gap
+            create_test_display(3, 4000.0, 0.0, 1920.0, 1080.0),
+        ]);
+        let mut state = State::new();
+        state.sync_all(&ws);
+
+        assert_eq!(display_containing_point(&state, 100.0, 500.0), Some(1));
+        assert_eq!(display_containing_point(&state, 1850.0, 500.0), Some(1));
+        assert_eq!(display_containing_point(&state, 1920.0, 500.0), Some(2));
+        assert_eq!(display_containing_point(&state, 3800.0, 500.0), None);
+        assert_eq!(display_containing_point(&state, 4000.0, 0.0), Some(3));
+        assert_eq!(display_containing_point(&state, 100.0, 1080.0), None);
+    }
+
+    #[test]
+    fn test_find_display_for_bounds() {
+        let ws = MockWindowSystem::new().with_displays(vec![
+            create_test_display(1, 0.0, 0.0, 1920.0, 1080.0),
+            create_test_display(2, 1920.0, 0.0, 1920.0, 1080.0),
+            // Below display 2, leaving empty space below display 1
+            create_test_display(3, 1920.0, 1080.0, 1920.0, 1080.0),
+        ]);
+        let mut state = State::new();
+        state.sync_all(&ws);
+        state.focused_display = 1;
+        let bounds = |x, y, width, height| crate::macos::Bounds {
+            x,
+            y,
+            width,
+            height,
+        };
+
+        // Center decides, even when the window straddles a display edge
+        assert_eq!(
+            find_display_for_bounds(&state, &bounds(2000.0, 100.0, 800.0, 600.0)),
+            2
+        );
+        assert_eq!(
+            find_display_for_bounds(&state, &bounds(1600.0, 100.0, 800.0, 600.0)),
+            2
+        );
+        assert_eq!(
+            find_display_for_bounds(&state, &bounds(2000.0, 1200.0, 800.0, 600.0)),
+            3
+        );
+
+        // Center in the dead zone below display 1: most of the window is on display 3
+        assert_eq!(
+            find_display_for_bounds(&state, &bounds(1700.0, 1100.0, 400.0, 400.0)),
+            3
+        );
+
+        // Entirely off-screen falls back to the focused display
+        assert_eq!(
+            find_display_for_bounds(&state, &bounds(0.0, 3000.0, 400.0, 400.0)),
+            1
+        );
+    }
+
+    #[test]
+    fn test_sync_pid_places_new_window_on_display_it_opened_on() {
+        let mut ws = MockWindowSystem::new()
+            .with_displays(vec![
+                create_test_display(1, 0.0, 0.0, 1920.0, 1080.0),
+                create_test_display(2, 1920.0, 0.0, 1920.0, 1080.0),
+            ])
+            .with_windows(vec![create_test_window(
+                100, 1000, "Safari", 0.0, 0.0, 800.0, 600.0,
+            )])
+            .with_focused(Some(100));
+        let mut state = State::new();
+        state.sync_all(&ws);
+        assert_eq!(state.focused_display, 1);
+
+        ws.add_window(create_test_window(
+            101, 1000, "Safari", 2200.0, 100.0, 800.0, 600.0,
+        ));
+        state.sync_pid(&ws, 1000);
+
+        assert_eq!(state.windows[&101].display_id, 2);
+        assert_eq!(state.displays[&2].window_order, vec![101]);
+        assert_eq!(state.displays[&1].window_order, vec![100]);
+    }
+
+    #[test]
+    fn test_window_output() {
+        let mut state = setup_two_display_state();
+        assert_eq!(state.window_output(100), Some(1));
+        assert_eq!(state.window_output(200), Some(2));
+        assert_eq!(state.window_output(999), None);
+
+        // Hidden windows resolve by their on-screen frame
+        let window = state.windows.get_mut(&200).unwrap();
+        window.saved_frame = Some(window.frame);
+        window.frame.x = -5000;
+        assert_eq!(state.window_output(200), Some(2));
+    }
+
+    fn set_frame_size(state: &mut State, id: WindowId, width: u32, height: u32) {
+        let window = state.windows.get_mut(&id).unwrap();
+        window.frame.width = width;
+        window.frame.height = height;
+    }
+
+    #[test]
+    fn test_focus_window_by_size() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+        set_frame_size(&mut state, 100, 800, 600);
+        set_frame_size(&mut state, 101, 1200, 900);
+        set_frame_size(&mut state, 102, 400, 300);
+
+        assert_eq!(
+            state.focus_window_by_size(SizeSelection::Largest),
+            Some((101, 1001))
+        );
+        assert_eq!(
+            state.focus_window_by_size(SizeSelection::Smallest),
+            Some((102, 1002))
+        );
+
+        // Windows on hidden tags are not candidates
+        state.windows.get_mut(&101).unwrap().tags = Tag::new(2);
+        assert_eq!(
+            state.focus_window_by_size(SizeSelection::Largest),
+            Some((100, 1000))
+        );
+    }
+
+    #[test]
+    fn test_focus_window_by_size_tie_follows_window_order() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+        for id in [100, 101, 102] {
+            set_frame_size(&mut state, id, 640, 480);
+        }
+        state.displays.get_mut(&1).unwrap().window_order = vec![102, 100, 101];
+
+        assert_eq!(
+            state.focus_window_by_size(SizeSelection::Largest),
+            Some((102, 1002))
+        );
+        assert_eq!(
+            state.focus_window_by_size(SizeSelection::Smallest),
+            Some((102, 1002))
+        );
+
+        // Equal-area windows with different shapes still tie
+        set_frame_size(&mut state, 102, 320, 960);
+        state.displays.get_mut(&1).unwrap().window_order = vec![101, 100, 102];
+        assert_eq!(
+            state.focus_window_by_size(SizeSelection::Largest),
+            Some((101, 1001))
+        );
+    }
+
+    #[test]
+    fn test_focus_urgent_selects_most_recent_and_clears_it() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+
+        assert!(state.set_urgent(101));
+        assert!(state.set_urgent(102));
+        assert!(!state.set_urgent(999));
+
+        let result = state.focus_urgent().unwrap();
+        assert_eq!((result.window_id, result.pid), (102, 1002));
+        assert!(!result.display_changed);
+        assert!(result.window_moves.is_empty());
+        assert_eq!(state.urgent_windows, vec![101]);
+
+        assert_eq!(state.focus_urgent().unwrap().window_id, 101);
+        assert!(state.focus_urgent().is_none());
+    }
+
+    #[test]
+    fn test_focus_urgent_skips_removed_windows() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+        state.set_urgent(101);
+        state.set_urgent(102);
+        state.windows.remove(&102);
+
+        assert_eq!(state.focus_urgent().unwrap().window_id, 101);
+        assert!(state.urgent_windows.is_empty());
+    }
+
+    #[test]
+    fn test_focus_urgent_switches_to_window_tag() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+        state.windows.get_mut(&102).unwrap().tags = Tag::from_mask(0b100);
+        state.set_urgent(102);
+
+        let result = state.focus_urgent().unwrap();
+        assert_eq!(result.window_id, 102);
+        assert_eq!(state.visible_tags().mask(), 0b100);
+        assert!(!result.window_moves.is_empty());
+    }
+
+    #[test]
+    fn test_focus_urgent_switches_display() {
+        let mut state = setup_two_display_state();
+        state.set_urgent(200);
+
+        let result = state.focus_urgent().unwrap();
+        assert_eq!(result.window_id, 200);
+        assert!(result.display_changed);
+        assert_eq!(state.focused_display, 2);
+        assert!(result.window_moves.is_empty());
+    }
+
+    #[test]
+    fn test_set_focused_clears_urgency() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+        state.set_urgent(101);
+
+        state.set_focused(Some(101));
+        assert!(state.urgent_windows.is_empty());
+        assert!(state.focus_urgent().is_none());
+    }
+
+    #[test]
+    fn test_swap_window_directional_same_output() {
+        let mut state = setup_two_display_state();
+
+        let result = state.swap_window(Direction::Right, true);
+        assert_eq!(result, Some(vec![1]));
+        assert_eq!(state.displays[&1].window_order, vec![101, 100]);
+        assert_eq!(state.displays[&2].window_order, vec![200]);
+        assert_eq!(state.windows[&200].display_id, 2);
+    }
+
+    #[test]
+    fn test_swap_window_directional_across_outputs() {
+        let mut state = setup_two_display_state();
+        state.displays.get_mut(&2).unwrap().visible_tags = Tag::new(2);
+        state.windows.get_mut(&200).unwrap().tags = Tag::new(2);
+
+        let result = state.swap_window(Direction::Right, false);
+        assert_eq!(result, Some(vec![1, 2]));
+        assert_eq!(state.displays[&1].window_order, vec![200, 101]);
+        assert_eq!(state.displays[&2].window_order, vec![100]);
+
+        // Windows trade displays and tags so both stay visible
+        assert_eq!(state.windows[&100].display_id, 2);
+        assert_eq!(state.windows[&100].tags, Tag::new(2));
+        assert_eq!(state.windows[&200].display_id, 1);
+        assert_eq!(state.windows[&200].tags, Tag::new(1));
+        assert_eq!(state.focused_display, 2);
+    }
+
+    #[test]
+    fn test_multi_display_focus_output() {
+        let ws = MockWindowSystem::new()
+            .with_displays(vec![
+                create_test_display(1, 0.0, 0.0, 1920.0, 1080.0),
+                create_test_display(2, 1920.0, 0.0, 1920.0, 1080.0),
+            ])
+            .with_windows(vec![
+                create_test_window(100, 1000, "Safari", 100.0, 100.0, 800.0, 600.0),
+                create_test_window(101, 1001, "Terminal", 2000.0, 100.0, 800.0, 600.0),
+            ])
+            .with_focused(Some(100));
+
+        let mut state = State::new();
+        state.sync_all(&ws);
+
+        assert_eq!(state.focused_display, 1);
+
+        let result = state.focus_output(OutputDirection::Next);
+        assert!(result.is_some());
+        assert_eq!(state.focused_display, 2);
+
+        match result.unwrap() {
+            FocusOutputResult::Window { window_id, .. } => assert_eq!(window_id, 101),
+            FocusOutputResult::EmptyDisplay { .. } => panic!("Expected Window result"),
+        }
+    }
+
+    #[test]
+    fn test_sync_focused_window_updates_focused_display() {
+        let mut ws = MockWindowSystem::new()
+            .with_displays(vec![
+                create_test_display(1, 0.0, 0.0, 1920.0, 1080.0),
+                create_test_display(2, 1920.0, 0.0, 1920.0, 1080.0),
+            ])
+            .with_windows(vec![
+                create_test_window(100, 1000, "Safari", 100.0, 100.0, 800.0, 600.0),
+                create_test_window(101, 1001, "Terminal", 2000.0, 100.0, 800.0, 600.0),
+            ])
+            .with_focused(Some(100));
+
+        let mut state = State::new();
+        state.sync_all(&ws);
+        assert_eq!(state.focused_display, 1);
+
+        ws.focused_window_id = Some(101);
+        state.sync_focused_window(&ws);
+
+        assert_eq!(state.focused, Some(101));
+        assert_eq!(state.focused_display, 2);
+    }
+
+    #[test]
+    fn test_set_focused_on_other_display_updates_focused_display() {
+        let ws = MockWindowSystem::new()
+            .with_displays(vec![
+                create_test_display(1, 0.0, 0.0, 1920.0, 1080.0),
+                create_test_display(2, 1920.0, 0.0, 1920.0, 1080.0),
+            ])
+            .with_windows(vec![
+                create_test_window(100, 1000, "Safari", 100.0, 100.0, 800.0, 600.0),
+                create_test_window(101, 1001, "Terminal", 2000.0, 100.0, 800.0, 600.0),
+            ])
+            .with_focused(Some(100));
+
+        let mut state = State::new();
+        state.sync_all(&ws);
+
+        state.set_focused(Some(101));
+        assert_eq!(state.focused_display, 2);
+
+        // Clearing focus keeps the focused display
+        state.set_focused(None);
+        assert_eq!(state.focused_display, 2);
+    }
+
+    #[test]
+    fn test_focus_output_empty_display() {
+        let ws = MockWindowSystem::new()
+            .with_displays(vec![
+                create_test_display(1, 0.0, 0.0, 1920.0, 1080.0),
+                create_test_display(2, 1920.0, 0.0, 1920.0, 1080.0),
+            ])
+            .with_windows(vec![create_test_window(
+                100, 1000, "Safari", 100.0, 100.0, 800.0, 600.0,
+            )])
+            .with_focused(Some(100));
+
+        let mut state = State::new();
+        state.sync_all(&ws);
+
+        assert_eq!(state.focused_display, 1);
+
+        let result = state.focus_output(OutputDirection::Next);
+        assert!(result.is_some());
+        assert_eq!(state.focused_display, 2);
+
+        match result.unwrap() {
+            FocusOutputResult::EmptyDisplay { display_id } => assert_eq!(display_id, 2),
+            FocusOutputResult::Window { .. } => panic!("Expected EmptyDisplay result"),
+        }
+    }
+
+    #[test]
+    fn test_sync_pid_adds_new_windows() {
+        let mut ws = MockWindowSystem::new()
+            .with_displays(vec![create_test_display(1, 0.0, 0.0, 1920.0, 1080.0)])
+            .with_windows(vec![create_test_window(
+                100, 1000, "Safari", 0.0, 0.0, 800.0, 600.0,
+            )]);
+
+        let mut state = State::new();
+        state.sync_all(&ws);
+        assert_eq!(state.windows.len(), 1);
+
+        ws.add_window(create_test_window(
+            101, 1000, "Safari", 100.0, 100.0, 800.0, 600.0,
+        ));
+
+        let (changed, new_ids, rehide_moves) = state.sync_pid(&ws, 1000);
+        assert!(changed);
+        assert_eq!(state.windows.len(), 2);
+        assert_eq!(new_ids, vec![101]);
+        assert!(rehide_moves.is_empty());
+    }
+
+    #[test]
+    fn test_sync_pid_removes_closed_windows() {
+        let mut ws = MockWindowSystem::new()
+            .with_displays(vec![create_test_display(1, 0.0, 0.0, 1920.0, 1080.0)])
+            .with_windows(vec![
+                create_test_window(100, 1000, "Safari", 0.0, 0.0, 800.0, 600.0),
+                create_test_window(101, 1000, "Safari", 100.0, 100.0, 800.0, 600.0),
+            ]);
+
+        let mut state = State::new();
+        state.sync_all(&ws);
+        assert_eq!(state.windows.len(), 2);
+
+        ws.remove_window(101);
+
+        let (changed, new_ids, rehide_moves) = state.sync_pid(&ws, 1000);
+        assert!(changed);
+        assert!(new_ids.is_empty());
+        assert_eq!(state.windows.len(), 1);
+        assert!(state.windows.contains_key(&100));
+        assert!(!state.windows.contains_key(&101));
+        assert!(rehide_moves.is_empty());
+    }
+
+    fn assert_window_order_matches_windows(state: &State) {
+        for display in state.displays.values() {
+            let mut ordered = display.window_order.clone();
+            ordered.sort();
+            let mut on_display: Vec<WindowId> = state
+                .windows
+                .values()
+                .filter(|w| w.display_id == display.id)
+                .map(|w| w.id)
+                .collect();
+            on_display.sort();
+            assert_eq!(ordered, on_display, "display {}", display.id);
+        }
+    }
+
+    #[test]
+    fn test_sync_pid_keeps_window_order_consistent() {
+        let mut ws = MockWindowSystem::new()
+            .with_displays(vec![create_test_display(1, 0.0, 0.0, 1920.0, 1080.0)])
+            .with_windows(vec![
+                create_test_window(100, 1000, "Safari", 0.0, 0.0, 800.0, 600.0),
+                create_test_window(101, 1000, "Safari", 100.0, 100.0, 800.0, 600.0),
+                create_test_window(102, 1001, "Terminal", 200.0, 200.0, 800.0, 600.0),
+            ]);
+
+        let mut state = State::new();
+        state.sync_all(&ws);
+        assert_window_order_matches_windows(&state);
+        let initial = state.displays[&1].window_order.clone();
+
+        ws.add_window(create_test_window(
+            103, 1000, "Safari", 300.0, 300.0, 800.0, 600.0,
+        ));
+        state.sync_pid(&ws, 1000);
+        assert_window_order_matches_windows(&state);
+        let mut expected = initial.clone();
+        expected.push(103);
+        assert_eq!(state.displays[&1].window_order, expected);
 
-        let (changed, new_ids, rehide_moves) = state.sync_pid(&ws, 1000);
-        assert!(changed);
-        assert!(new_ids.is_empty());
-        assert_eq!(state.windows.len(), 1);
-        assert!(state.windows.contains_key(&100));
-        assert!(!state.windows.contains_key(&101));
-        assert!(rehide_moves.is_empty());
+        // Removal prunes the id and leaves the rest in place
+        ws.remove_window(101);
+        state.sync_pid(&ws, 1000);
+        assert_window_order_matches_windows(&state);
+        expected.retain(|&id| id != 101);
+        assert_eq!(state.displays[&1].window_order, expected);
     }
 
     #[test]
@@ -972,6 +2698,84 @@ mod tests {
         assert!(state.windows.contains_key(&101));
     }
 
+    #[test]
+    fn test_prune_missing_windows_removes_untracked() {
+        let ws = MockWindowSystem::new()
+            .with_displays(vec![create_test_display(1, 0.0, 0.0, 1920.0, 1080.0)])
+            .with_windows(vec![
+                create_test_window(100, 1000, "Safari", 0.0, 0.0, 800.0, 600.0),
+                create_test_window(101, 1001, "Terminal", 100.0, 100.0, 800.0, 600.0),
+                create_test_window(102, 1002, "Finder", 200.0, 200.0, 800.0, 600.0),
+            ])
+            .with_focused(Some(101));
+
+        let mut state = State::new();
+        state.sync_all(&ws);
+        assert_eq!(state.focused, Some(101));
+
+        let live_ids = std::collections::HashSet::from([100, 102]);
+        let removed = sync::prune_missing_windows(&mut state, &live_ids);
+
+        assert_eq!(removed, vec![101]);
+        assert_eq!(state.windows.len(), 2);
+        assert!(!state.windows.contains_key(&101));
+        assert_eq!(state.displays[&1].window_order, vec![100, 102]);
+        assert_eq!(state.focused, None);
+
+        // Nothing left to prune
+        assert!(sync::prune_missing_windows(&mut state, &live_ids).is_empty());
+    }
+
+    #[test]
+    fn test_reconcile_windows_prunes_windows_of_exited_process() {
+        let mut ws = MockWindowSystem::new()
+            .with_displays(vec![create_test_display(1, 0.0, 0.0, 1920.0, 1080.0)])
+            .with_windows(vec![
+                create_test_window(100, 1000, "Safari", 0.0, 0.0, 800.0, 600.0),
+                create_test_window(101, 1001, "Terminal", 100.0, 100.0, 800.0, 600.0),
+            ]);
+
+        let mut state = State::new();
+        state.sync_all(&ws);
+
+        // Terminal crashed: its window vanished and AX is no longer reachable,
+        // which a regular sync treats as "maybe on another Space" and keeps
+        ws.remove_window(101);
+        ws.set_ax_accessible(1001, false);
+        ws.set_process_running(1001, false);
+        state.sync_all(&ws);
+        assert!(state.windows.contains_key(&101));
+        state.set_window_override(101, "Terminal", true);
+
+        let removed = state.reconcile_windows(&ws);
+        assert_eq!(removed, vec![101]);
+        assert!(state.windows.contains_key(&100));
+        assert!(!state.windows.contains_key(&101));
+        // A later window reusing the ID must not inherit the override
+        assert!(state.window_overrides.is_empty());
+        assert!(state.take_window_overrides_pruned());
+    }
+
+    #[test]
+    fn test_reconcile_windows_keeps_windows_of_running_process() {
+        let mut ws = MockWindowSystem::new()
+            .with_displays(vec![create_test_display(1, 0.0, 0.0, 1920.0, 1080.0)])
+            .with_windows(vec![
+                create_test_window(100, 1000, "Safari", 0.0, 0.0, 800.0, 600.0),
+                create_test_window(101, 1001, "Terminal", 100.0, 100.0, 800.0, 600.0),
+            ]);
+
+        let mut state = State::new();
+        state.sync_all(&ws);
+
+        // Off screen but the process is alive and AX is inaccessible (another Space)
+        ws.remove_window(101);
+        ws.set_ax_accessible(1001, false);
+
+        assert!(state.reconcile_windows(&ws).is_empty());
+        assert_eq!(state.windows.len(), 2);
+    }
+
     #[test]
     fn test_sync_with_window_infos_partial_removal_when_mixed_accessibility() {
         let mut ws = MockWindowSystem::new()
@@ -1016,6 +2820,180 @@ mod tests {
         assert_eq!(visible.len(), 2);
     }
 
+    #[test]
+    fn test_outputs_show_different_tags_simultaneously() {
+        let ws = MockWindowSystem::new()
+            .with_displays(vec![
+                create_test_display(1, 0.0, 0.0, 1920.0, 1080.0),
+                create_test_display(2, 1920.0, 0.0, 1920.0, 1080.0),
+            ])
+            .with_windows(vec![
+                create_test_window(100, 1000, "Safari", 100.0, 100.0, 800.0, 600.0),
+                create_test_window(101, 1001, "Terminal", 2000.0, 100.0, 800.0, 600.0),
+            ])
+            .with_focused(Some(100));
+
+        let mut state = State::new();
+        state.sync_all(&ws);
+        state.windows.get_mut(&101).unwrap().tags = Tag::new(2);
+        state.view_tags_on_display(0b10, 2);
+
+        assert_eq!(state.visible_tags_on_display(1), Tag::new(1));
+        assert_eq!(state.visible_tags_on_display(2), Tag::new(2));
+        assert_eq!(state.visible_tags(), Tag::new(1));
+
+        let mut visible: Vec<_> = state.visible_windows().iter().map(|w| w.id).collect();
+        visible.sort();
+        assert_eq!(visible, vec![100, 101]);
+        assert_eq!(state.visible_windows_on_display(1).len(), 1);
+        assert_eq!(state.visible_windows_on_display(2).len(), 1);
+    }
+
+    #[test]
+    fn test_window_visibility_uses_its_own_display_tags() {
+        let ws = MockWindowSystem::new()
+            .with_displays(vec![
+                create_test_display(1, 0.0, 0.0, 1920.0, 1080.0),
+                create_test_display(2, 1920.0, 0.0, 1920.0, 1080.0),
+            ])
+            .with_windows(vec![
+                create_test_window(100, 1000, "Safari", 100.0, 100.0, 800.0, 600.0),
+                create_test_window(101, 1001, "Terminal", 2000.0, 100.0, 800.0, 600.0),
+            ])
+            .with_focused(Some(100));
+
+        let mut state = State::new();
+        state.sync_all(&ws);
+
+        // Tag 2 on display 1 must not make tag-2 windows on display 2 visible
+        state.view_tags_on_display(0b10, 1);
+        state.windows.get_mut(&101).unwrap().tags = Tag::new(2);
+
+        let window = state.windows.get(&101).unwrap();
+        assert!(!state.is_window_visible(window));
+        assert!(state.visible_windows().is_empty());
+    }
+
+    #[test]
+    fn test_toggle_inner_gap_store_and_restore() {
+        let mut state = State::new();
+
+        // First toggle saves the current gap and turns gaps off
+        assert_eq!(state.toggle_inner_gap("tatami", 12), 0);
+        assert_eq!(state.saved_inner_gaps.get("tatami"), Some(&12));
+
+        // Other layouts are independent
+        assert_eq!(state.toggle_inner_gap("grid", 4), 0);
+
+        // Second toggle restores the saved gap, whichever tag it is issued from
+        assert_eq!(state.toggle_inner_gap("tatami", 0), 12);
+        assert!(!state.saved_inner_gaps.contains_key("tatami"));
+        assert_eq!(state.toggle_inner_gap("grid", 0), 4);
+
+        // Cycle starts over
+        assert_eq!(state.toggle_inner_gap("tatami", 12), 0);
+    }
+
+    #[test]
+    fn test_gaps_suppressed_by_display_count() {
+        let mut config = Config::new();
+        config.outer_gap = OuterGap::all(10);
+
+        // Disabled: gaps apply regardless of display count
+        assert!(!config.gaps_suppressed(1));
+        assert_eq!(config.effective_outer_gap(1), OuterGap::all(10));
+
+        config.gaps_multi_monitor_only = true;
+        assert!(config.gaps_suppressed(0));
+        assert!(config.gaps_suppressed(1));
+        assert!(!config.gaps_suppressed(2));
+        assert!(!config.gaps_suppressed(3));
+        assert_eq!(config.effective_outer_gap(1), OuterGap::default());
+        assert_eq!(config.effective_outer_gap(2), OuterGap::all(10));
+    }
+
+    #[test]
+    fn test_effective_outer_gap_follows_connected_displays() {
+        let mut state = State::new();
+        state.sync_all(&setup_mock_system());
+        state.config.outer_gap = OuterGap::all(8);
+        state.config.gaps_multi_monitor_only = true;
+        assert!(state.gaps_suppressed());
+        assert_eq!(state.effective_outer_gap(), OuterGap::default());
+
+        let mut state = setup_two_display_state();
+        state.config.outer_gap = OuterGap::all(8);
+        state.config.gaps_multi_monitor_only = true;
+        assert!(!state.gaps_suppressed());
+        assert_eq!(state.effective_outer_gap(), OuterGap::all(8));
+    }
+
+    #[test]
+    fn test_reserve_top_edge_shrinks_usable_frame() {
+        let mut state = State::new();
+        state.sync_all(&setup_mock_system());
+        assert_eq!(
+            state.usable_frame(1),
+            Some(Rect {
+                x: 0,
+                y: 0,
+                width: 1920,
+                height: 1080,
+            })
+        );
+
+        state
+            .displays
+            .get_mut(&1)
+            .unwrap()
+            .reserve_edge(Edge::Top, 30)
+            .unwrap();
+        assert_eq!(
+            state.usable_frame(1),
+            Some(Rect {
+                x: 0,
+                y: 30,
+                width: 1920,
+                height: 1050,
+            })
+        );
+
+        // Reservations add up per edge and combine with the outer gap
+        let display = state.displays.get_mut(&1).unwrap();
+        display.reserve_edge(Edge::Top, 10).unwrap();
+        display.reserve_edge(Edge::Left, 40).unwrap();
+        state.config.outer_gap = OuterGap::all(5);
+        assert_eq!(
+            state.usable_frame(1),
+            Some(Rect {
+                x: 45,
+                y: 45,
+                width: 1870,
+                height: 1030,
+            })
+        );
+        assert_eq!(state.usable_frame(99), None);
+
+        // A reservation covering the display is refused and changes nothing
+        let display = state.displays.get_mut(&1).unwrap();
+        assert!(display.reserve_edge(Edge::Bottom, 1040).is_err());
+        assert!(display.reserve_edge(Edge::Right, u32::MAX).is_err());
+        assert_eq!(display.reserved.bottom, 0);
+        assert_eq!(display.reserved.right, 0);
+
+        // Oversized outer gaps do not overflow the offsets
+        state.config.outer_gap = OuterGap::all(u32::MAX);
+        assert_eq!(
+            state.usable_frame(1),
+            Some(Rect {
+                x: 1920,
+                y: 1080,
+                width: 0,
+                height: 0,
+            })
+        );
+    }
+
     #[test]
     fn test_handle_display_change_display_added() {
         let ws1 = MockWindowSystem::new()
@@ -1106,44 +3084,248 @@ mod tests {
 
         let result = state.handle_display_change(&ws2);
 
-        assert_eq!(state.windows.get(&101).unwrap().display_id, 1);
+        assert_eq!(state.windows.get(&101).unwrap().display_id, 1);
+
+        assert!(result.displays_to_retile.contains(&1));
+    }
+
+    #[test]
+    fn test_should_ignore_window_with_ignore_rule() {
+        use yashiki_ipc::{GlobPattern, RuleAction, RuleMatcher, WindowRule};
+
+        let mut state = State::new();
+
+        state.add_rule(WindowRule {
+            matcher: RuleMatcher {
+                app_name: None,
+                app_id: None,
+                title: None,
+                ax_id: None,
+                subrole: Some(GlobPattern::new("AXUnknown")),
+                window_level: None,
+                close_button: None,
+                fullscreen_button: None,
+                minimize_button: None,
+                zoom_button: None,
+            },
+            action: RuleAction::Ignore,
+        });
+
+        assert!(state.should_ignore_window("Firefox", None, "Menu", None, Some("AXUnknown")));
+
+        assert!(!state.should_ignore_window(
+            "Firefox",
+            None,
+            "Window",
+            None,
+            Some("AXStandardWindow")
+        ));
+
+        assert!(!state.should_ignore_window("Firefox", None, "Window", None, None));
+    }
+
+    #[test]
+    fn test_unmanaged_windows_report_exclusion_reason() {
+        use yashiki_ipc::{GlobPattern, RuleAction, RuleMatcher, UnmanagedReason, WindowRule};
+
+        let mut ws = setup_mock_system();
+        ws.set_extended_attributes(
+            102,
+            ExtendedWindowAttributes {
+                window_level: 3,
+                ..Default::default()
+            },
+        );
+        let mut state = State::new();
+        state.add_rule(WindowRule {
+            matcher: RuleMatcher {
+                app_name: Some(GlobPattern::new("Terminal")),
+                app_id: None,
+                title: None,
+                ax_id: None,
+                subrole: None,
+                window_level: None,
+                close_button: None,
+                fullscreen_button: None,
+                minimize_button: None,
+                zoom_button: None,
+            },
+            action: RuleAction::Ignore,
+        });
+        state.sync_all(&ws);
+        assert!(state.windows.contains_key(&100));
+
+        let unmanaged = state.unmanaged_windows(&ws);
+        let reasons: Vec<_> = unmanaged
+            .iter()
+            .map(|w| (w.id, w.app_name.as_str(), w.reason))
+            .collect();
+        assert_eq!(
+            reasons,
+            vec![
+                (101, "Terminal", UnmanagedReason::IgnoreRule),
+                (102, "VSCode", UnmanagedReason::WindowLevel),
+            ]
+        );
+        assert_eq!(unmanaged[1].window_level, 3);
+    }
+
+    #[test]
+    fn test_request_retile_uses_configured_tick_rate() {
+        let mut state = State::new();
+        state.config.set_tick_rate(50);
+        let start = Instant::now();
+
+        assert!(state.request_retile(start));
+        // Within the 50ms tick: deferred to the tick deadline
+        assert!(!state.request_retile(start + Duration::from_millis(20)));
+        assert_eq!(
+            state.next_tick_deadline(),
+            Some(start + Duration::from_millis(50))
+        );
+        assert!(!state.take_due_retile(start + Duration::from_millis(49)));
+        assert!(state.take_due_retile(start + Duration::from_millis(50)));
+        assert_eq!(state.next_tick_deadline(), None);
+    }
+
+    #[test]
+    fn test_move_sync_coalesced_per_pid() {
+        let mut state = State::new();
+        let start = Instant::now();
+        let ms = Duration::from_millis;
+
+        assert!(state.request_move_sync(1000, start));
+        // Another app is not held back by the first one's drag
+        assert!(state.request_move_sync(1001, start + ms(10)));
+        for t in [10, 20, 40] {
+            assert!(!state.request_move_sync(1000, start + ms(t)));
+        }
+        assert_eq!(state.next_tick_deadline(), Some(start + ms(50)));
+
+        assert!(state.take_due_move_syncs(start + ms(49)).is_empty());
+        assert_eq!(state.take_due_move_syncs(start + ms(50)), vec![1000]);
+        assert_eq!(state.next_tick_deadline(), None);
+    }
+
+    #[test]
+    fn test_window_override_flips_managed_status() {
+        use yashiki_ipc::{GlobPattern, RuleAction, RuleMatcher, UnmanagedReason, WindowRule};
+
+        let mut ws = setup_mock_system();
+        ws.set_extended_attributes(
+            102,
+            ExtendedWindowAttributes {
+                window_level: 3,
+                ..Default::default()
+            },
+        );
+        let mut state = State::new();
+        state.add_rule(WindowRule {
+            matcher: RuleMatcher {
+                app_name: Some(GlobPattern::new("Terminal")),
+                app_id: None,
+                title: None,
+                ax_id: None,
+                subrole: None,
+                window_level: None,
+                close_button: None,
+                fullscreen_button: None,
+                minimize_button: None,
+                zoom_button: None,
+            },
+            action: RuleAction::Ignore,
+        });
+        state.set_window_override(101, "Terminal", true);
+        state.set_window_override(102, "VSCode", true);
+        state.set_window_override(100, "Safari", false);
+        state.sync_all(&ws);
+
+        // Managed despite the ignore rule and the window level
+        assert!(state.windows.contains_key(&101));
+        assert!(state.windows.contains_key(&102));
+        assert!(!state.windows.contains_key(&100));
+
+        let unmanaged = state.unmanaged_windows(&ws);
+        let reasons: Vec<_> = unmanaged.iter().map(|w| (w.id, w.reason)).collect();
+        assert_eq!(reasons, vec![(100, UnmanagedReason::Manual)]);
+    }
+
+    #[test]
+    fn test_window_override_requires_matching_app_name() {
+        let mut state = State::new();
+        state.set_window_override(100, "Safari", false);
+
+        assert_eq!(state.window_override(100, "Safari"), Some(false));
+        // Window IDs are reused by other apps after a restart
+        assert_eq!(state.window_override(100, "Terminal"), None);
+        assert_eq!(state.window_override(101, "Safari"), None);
+    }
+
+    #[test]
+    fn test_unmanage_window_moves_managed_window_to_ignored() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+        state.set_focused(Some(100));
+        state.set_window_override(100, "Safari", false);
+
+        assert_eq!(state.unmanage_window(100), Some(1));
+        assert!(!state.windows.contains_key(&100));
+        assert!(state.ignored_windows.contains_key(&100));
+        assert_eq!(state.focused, None);
+
+        // The override keeps it excluded on the next sync
+        let (_, new_ids, _) = state.sync_pid(&ws, 1000);
+        assert!(!new_ids.contains(&100));
+        assert!(!state.windows.contains_key(&100));
+        assert_eq!(state.unmanage_window(100), None);
+    }
+
+    #[test]
+    fn test_destroyed_window_drops_its_override() {
+        let mut ws = setup_mock_system();
+        let mut state = State::new();
+        state.set_window_override(100, "Safari", false);
+        state.set_window_override(101, "Terminal", true);
+        // A window from an earlier session that is not tracked stays untouched
+        state.set_window_override(999, "Finder", false);
+        state.sync_all(&ws);
+        assert!(state.ignored_windows.contains_key(&100));
+
+        ws.remove_window(101);
+        state.handle_event(&ws, &Event::WindowDestroyed { pid: 1001 });
+        assert!(state.take_window_overrides_pruned());
+        assert!(!state.window_overrides.contains_key(&101));
+        assert!(state.window_overrides.contains_key(&100));
 
-        assert!(result.displays_to_retile.contains(&1));
+        ws.remove_window(100);
+        state.handle_event(&ws, &Event::WindowDestroyed { pid: 1000 });
+        assert!(state.take_window_overrides_pruned());
+        assert_eq!(
+            state.window_overrides.keys().copied().collect::<Vec<_>>(),
+            vec![999]
+        );
+        assert!(!state.take_window_overrides_pruned());
     }
 
     #[test]
-    fn test_should_ignore_window_with_ignore_rule() {
+    fn test_managed_override_survives_new_ignore_rule() {
         use yashiki_ipc::{GlobPattern, RuleAction, RuleMatcher, WindowRule};
 
+        let ws = setup_mock_system();
         let mut state = State::new();
+        state.sync_all(&ws);
+        state.set_window_override(101, "Terminal", true);
 
         state.add_rule(WindowRule {
-            matcher: RuleMatcher {
-                app_name: None,
-                app_id: None,
-                title: None,
-                ax_id: None,
-                subrole: Some(GlobPattern::new("AXUnknown")),
-                window_level: None,
-                close_button: None,
-                fullscreen_button: None,
-                minimize_button: None,
-                zoom_button: None,
-            },
+            matcher: RuleMatcher::new(Some(GlobPattern::new("*")), None),
             action: RuleAction::Ignore,
         });
+        let (_, _, mut removed) = state.apply_rules_to_all_windows();
+        removed.sort_unstable();
 
-        assert!(state.should_ignore_window("Firefox", None, "Menu", None, Some("AXUnknown")));
-
-        assert!(!state.should_ignore_window(
-            "Firefox",
-            None,
-            "Window",
-            None,
-            Some("AXStandardWindow")
-        ));
-
-        assert!(!state.should_ignore_window("Firefox", None, "Window", None, None));
+        assert_eq!(removed, vec![100, 102]);
+        assert!(state.windows.contains_key(&101));
     }
 
     #[test]
@@ -1212,9 +3394,9 @@ mod tests {
         let display = state.displays.get(&1).unwrap();
         let initial_order = display.window_order.clone();
 
-        let result = state.swap_window(Direction::Next);
+        let result = state.swap_window(Direction::Next, false);
         assert!(result.is_some());
-        assert_eq!(result.unwrap(), 1);
+        assert_eq!(result.unwrap(), vec![1]);
 
         let display = state.displays.get(&1).unwrap();
         let new_order = &display.window_order;
@@ -1233,9 +3415,613 @@ mod tests {
 
         state.focused = Some(101);
 
-        let result = state.swap_window(Direction::Prev);
+        let result = state.swap_window(Direction::Prev, false);
         assert!(result.is_some());
-        assert_eq!(result.unwrap(), 1);
+        assert_eq!(result.unwrap(), vec![1]);
+    }
+
+    #[test]
+    fn test_apply_layout_order_keeps_hidden_tag_windows() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+        state.displays.get_mut(&1).unwrap().window_order = vec![100, 101, 102];
+        state.windows.get_mut(&101).unwrap().tags = Tag::new(2);
+        assert_eq!(state.layout_window_ids(1), vec![100, 102]);
+
+        // Engine moved 102 to the front; 101 is off-tag and keeps its slot
+        state.apply_layout_order(1, &[102, 100]);
+        assert_eq!(state.displays[&1].window_order, vec![102, 101, 100]);
+    }
+
+    #[test]
+    fn test_move_window_to_front_gives_window_main_slot() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+        state.displays.get_mut(&1).unwrap().window_order = vec![100, 101, 102];
+
+        assert_eq!(state.move_window_to_front(101), Some(1));
+        assert_eq!(state.displays[&1].window_order, vec![101, 100, 102]);
+        assert_eq!(state.layout_window_ids(1)[0], 101);
+
+        assert_eq!(state.move_window_to_front(999), None);
+        assert_eq!(state.displays[&1].window_order, vec![101, 100, 102]);
+    }
+
+    #[test]
+    fn test_move_focused_window_to_slot() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+        state.displays.get_mut(&1).unwrap().window_order = vec![100, 101, 102];
+        state.focused = Some(102);
+
+        // First slot
+        assert_eq!(state.move_focused_window_to_slot(0), Some(1));
+        assert_eq!(state.displays[&1].window_order, vec![102, 100, 101]);
+
+        // Middle slot
+        assert_eq!(state.move_focused_window_to_slot(1), Some(1));
+        assert_eq!(state.displays[&1].window_order, vec![100, 102, 101]);
+
+        // Beyond the end clamps to the last slot
+        assert_eq!(state.move_focused_window_to_slot(10), Some(1));
+        assert_eq!(state.displays[&1].window_order, vec![100, 101, 102]);
+
+        // Already there: nothing to retile
+        assert_eq!(state.move_focused_window_to_slot(5), None);
+
+        // Slots count only the windows tiled on the current tags
+        state.displays.get_mut(&1).unwrap().window_order = vec![100, 101, 102];
+        state.windows.get_mut(&101).unwrap().tags = Tag::new(2);
+        assert_eq!(state.move_focused_window_to_slot(0), Some(1));
+        assert_eq!(state.layout_window_ids(1), vec![102, 100]);
+        assert_eq!(state.move_focused_window_to_slot(1), Some(1));
+        assert_eq!(state.layout_window_ids(1), vec![100, 102]);
+        assert_eq!(state.move_focused_window_to_slot(1), None);
+        state.focused = None;
+        assert_eq!(state.move_focused_window_to_slot(0), None);
+    }
+
+    #[test]
+    fn test_sticky_main_leads_every_visible_set() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+        state.displays.get_mut(&1).unwrap().window_order = vec![100, 101, 102];
+        state.windows.get_mut(&101).unwrap().tags = Tag::from_mask(0b11);
+        state.windows.get_mut(&102).unwrap().tags = Tag::new(2);
+        state.sticky_main = Some(101);
+
+        // Tag 1: 100 and 101 visible
+        assert!(state.apply_sticky_main(1));
+        assert_eq!(state.layout_window_ids(1), vec![101, 100]);
+        assert!(!state.apply_sticky_main(1));
+
+        // Tag 2: 101 and 102 visible, even after the engine moved 102 to the front
+        state.view_tags_on_display(0b10, 1);
+        state.apply_layout_order(1, &[102, 101]);
+        assert_eq!(state.layout_window_ids(1), vec![102, 101]);
+        assert!(state.apply_sticky_main(1));
+        assert_eq!(state.layout_window_ids(1), vec![101, 102]);
+
+        // Not visible on tag 1 anymore: order is left alone
+        state.windows.get_mut(&101).unwrap().tags = Tag::new(2);
+        state.view_tags_on_display(0b1, 1);
+        state.displays.get_mut(&1).unwrap().window_order = vec![100, 102, 101];
+        assert!(!state.apply_sticky_main(1));
+        assert_eq!(state.displays[&1].window_order, vec![100, 102, 101]);
+
+        state.sticky_main = None;
+        state.view_tags_on_display(0b10, 1);
+        assert!(!state.apply_sticky_main(1));
+        assert_eq!(state.layout_window_ids(1), vec![102, 101]);
+    }
+
+    #[test]
+    fn test_destroyed_sticky_main_window_is_released() {
+        let mut ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+        state.sticky_main = Some(101);
+
+        ws.remove_window(100);
+        state.handle_event(&ws, &Event::WindowDestroyed { pid: 1000 });
+        assert_eq!(state.sticky_main, Some(101));
+
+        ws.remove_window(101);
+        state.handle_event(&ws, &Event::WindowDestroyed { pid: 1001 });
+        assert_eq!(state.sticky_main, None);
+
+        // Unmanaging releases it too
+        state.sticky_main = Some(102);
+        assert_eq!(state.unmanage_window(102), Some(1));
+        assert_eq!(state.sticky_main, None);
+    }
+
+    #[test]
+    fn test_peek_and_release() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+
+        let (window_id, pid, frame) = state.start_peek().unwrap();
+        assert_eq!((window_id, pid), (100, 1000));
+        assert_eq!(
+            frame,
+            Rect {
+                x: 192,
+                y: 108,
+                width: 1536,
+                height: 864
+            }
+        );
+        assert_eq!(
+            state.windows[&100].peek_slot,
+            Some(Rect {
+                x: 0,
+                y: 0,
+                width: 960,
+                height: 1080
+            })
+        );
+        // Already peeking
+        assert!(state.start_peek().is_none());
+
+        assert_eq!(state.release_peek(), Some(1));
+        assert_eq!(state.windows[&100].peek_slot, None);
+        assert_eq!(state.release_peek(), None);
+    }
+
+    #[test]
+    fn test_release_peek_after_focus_moves() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+
+        state.start_peek().unwrap();
+        state.focused = Some(101);
+        // Another window can't be peeked while 100 still is
+        assert!(state.start_peek().is_none());
+        assert_eq!(state.windows[&101].peek_slot, None);
+
+        assert_eq!(state.release_peek(), Some(1));
+        assert_eq!(state.windows[&100].peek_slot, None);
+        assert_eq!(state.release_peek(), None);
+    }
+
+    #[test]
+    fn test_toggle_float_removes_window_from_tiling_and_back() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+        state.focused = Some(101);
+        let tiled = |state: &State| -> Vec<u32> {
+            state
+                .visible_windows_on_display(1)
+                .iter()
+                .map(|w| w.id)
+                .collect()
+        };
+        let order = tiled(&state);
+        assert!(order.contains(&101));
+
+        assert_eq!(state.toggle_focused_float(), Some((1, true, 101, 1001)));
+        assert!(!tiled(&state).contains(&101));
+        // Floating windows keep focus and stay visible
+        assert_eq!(state.focused, Some(101));
+        assert!(state.is_window_visible(&state.windows[&101]));
+
+        assert_eq!(state.toggle_focused_float(), Some((1, false, 101, 1001)));
+        assert_eq!(tiled(&state), order);
+    }
+
+    #[test]
+    fn test_window_drag_moves_and_resizes_only_floating_windows() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+
+        // Tiled windows are left to the layout, and the tap lets their clicks through
+        assert!(!state.drag_targets().accepts(1200, 500));
+        assert!(!state.begin_window_drag(DragKind::Move, 1200, 500));
+        assert_eq!(state.update_window_drag(1250, 520), None);
+
+        state.windows.get_mut(&101).unwrap().is_floating = true;
+        assert!(state.drag_targets().accepts(1200, 500));
+        assert!(state.begin_window_drag(DragKind::Move, 1200, 500));
+        assert_eq!(
+            state.update_window_drag(1250, 520),
+            Some(Effect::MoveWindowToPosition {
+                window_id: 101,
+                pid: 1001,
+                x: 1010,
+                y: 20,
+            })
+        );
+        assert_eq!(
+            (state.windows[&101].frame.x, state.windows[&101].frame.y),
+            (1010, 20)
+        );
+        // No effect until the cursor moves again
+        assert_eq!(state.update_window_drag(1250, 520), None);
+        state.end_window_drag();
+        assert_eq!(state.update_window_drag(1300, 600), None);
+
+        assert!(state.begin_window_drag(DragKind::Resize, 1300, 600));
+        assert_eq!(
+            state.update_window_drag(1200, 700),
+            Some(Effect::ResizeWindow {
+                window_id: 101,
+                pid: 1001,
+                x: 1010,
+                y: 20,
+                width: 860,
+                height: 1180,
+            })
+        );
+    }
+
+    #[test]
+    fn test_scratchpad_toggle_parks_window_on_reserved_tag() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+        state.windows.get_mut(&101).unwrap().tags = Tag::new(3);
+        state.displays.get_mut(&1).unwrap().visible_tags = Tag::from_mask(0b101);
+
+        match state.scratchpad_toggle("Terminal") {
+            ScratchpadToggle::Hidden { display_id, .. } => assert_eq!(display_id, 1),
+            other => panic!("Expected Hidden, got {:?}", other),
+        }
+        assert_eq!(state.windows[&101].tags, Tag::new(SCRATCHPAD_TAG));
+        assert_eq!(state.scratchpads.get("Terminal"), Some(&101));
+
+        // Comes back on every visible tag, not the tag it started on
+        match state.scratchpad_toggle("Terminal") {
+            ScratchpadToggle::Shown { window_id, pid, .. } => {
+                assert_eq!((window_id, pid), (101, 1001))
+            }
+            other => panic!("Expected Shown, got {:?}", other),
+        }
+        assert_eq!(state.windows[&101].tags, Tag::from_mask(0b101));
+
+        // A destroyed scratchpad window is forgotten
+        state.windows.remove(&101);
+        assert_eq!(
+            state.scratchpad_toggle("Terminal"),
+            ScratchpadToggle::Launch
+        );
+        assert!(state.scratchpads.is_empty());
+    }
+
+    #[test]
+    fn test_scratchpad_toggle_summons_onto_focused_display() {
+        let mut state = setup_two_display_state();
+        state.displays.get_mut(&2).unwrap().visible_tags = Tag::new(2);
+        state.focused_display = 2;
+
+        // 101 sits on display 1, which is not focused
+        match state.scratchpad_toggle("Terminal") {
+            ScratchpadToggle::Hidden { display_id, .. } => assert_eq!(display_id, 1),
+            other => panic!("Expected Hidden, got {:?}", other),
+        }
+        match state.scratchpad_toggle("Terminal") {
+            ScratchpadToggle::Shown {
+                display_ids,
+                minimized,
+                ..
+            } => {
+                assert_eq!(display_ids, vec![2, 1]);
+                assert!(!minimized);
+            }
+            other => panic!("Expected Shown, got {:?}", other),
+        }
+        let window = &state.windows[&101];
+        assert_eq!((window.display_id, window.tags), (2, Tag::new(2)));
+        assert!(state.displays[&2].window_order.contains(&101));
+        assert!(!state.displays[&1].window_order.contains(&101));
+    }
+
+    #[test]
+    fn test_cycle_tags_skips_scratchpad_tag() {
+        let mut state = State::new();
+        state.sync_all(&setup_mock_system());
+        state.displays.get_mut(&1).unwrap().visible_tags = Tag::new(SCRATCHPAD_TAG - 1);
+
+        state.cycle_tags(OutputDirection::Next, false);
+        assert_eq!(state.visible_tags(), Tag::new(1));
+        state.cycle_tags(OutputDirection::Prev, false);
+        assert_eq!(state.visible_tags(), Tag::new(SCRATCHPAD_TAG - 1));
+    }
+
+    #[test]
+    fn test_float_all_floats_everything_and_restores_tiled_windows() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+        // Floated by the user before float-all: must stay floating afterwards
+        state.windows.get_mut(&102).unwrap().is_floating = true;
+
+        assert_eq!(state.set_float_all(true), vec![1]);
+        assert!(state.windows.values().all(|w| w.is_floating));
+        assert!(state.visible_windows_on_display(1).is_empty());
+        // Enabling twice must not forget the windows to restore
+        assert!(state.set_float_all(true).is_empty());
+
+        assert_eq!(state.set_float_all(false), vec![1]);
+        assert!(!state.windows[&100].is_floating);
+        assert!(!state.windows[&101].is_floating);
+        assert!(state.windows[&102].is_floating);
+        assert!(state.float_all_restore.is_none());
+        assert!(state.set_float_all(false).is_empty());
+    }
+
+    #[test]
+    fn test_peek_skips_floating_window() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+        state.windows.get_mut(&100).unwrap().is_floating = true;
+
+        assert!(state.start_peek().is_none());
+        assert_eq!(state.windows[&100].peek_slot, None);
+    }
+
+    #[test]
+    fn test_retile_while_peeking_keeps_peek_and_updates_slot() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+        state.start_peek().unwrap();
+
+        let geometry = |id, x, width| yashiki_ipc::layout::WindowGeometry {
+            id,
+            x,
+            y: 0,
+            width,
+            height: 1080,
+        };
+        let mut geometries = vec![geometry(100, 640, 1280), geometry(101, 0, 640)];
+        state.apply_peek_frames(1, &mut geometries);
+
+        // The peeked window stays enlarged; its new tiled slot is remembered
+        assert_eq!(
+            (
+                geometries[0].x,
+                geometries[0].y,
+                geometries[0].width,
+                geometries[0].height
+            ),
+            (192, 108, 1536, 864)
+        );
+        assert_eq!(
+            state.windows[&100].peek_slot,
+            Some(Rect {
+                x: 640,
+                y: 0,
+                width: 1280,
+                height: 1080
+            })
+        );
+        assert_eq!((geometries[1].x, geometries[1].width), (0, 640));
+        assert_eq!(state.windows[&101].peek_slot, None);
+    }
+
+    #[test]
+    fn test_centered_in_tile() {
+        let tile = Rect {
+            x: 960,
+            y: 0,
+            width: 960,
+            height: 1080,
+        };
+        assert_eq!(
+            layout::centered_in_tile(&tile, 600, 400),
+            Rect {
+                x: 1140,
+                y: 340,
+                width: 600,
+                height: 400,
+            }
+        );
+        // Larger than the tile: clamped to it
+        assert_eq!(
+            layout::centered_in_tile(&tile, 1200, 400),
+            Rect {
+                x: 960,
+                y: 340,
+                width: 960,
+                height: 400,
+            }
+        );
+    }
+
+    #[test]
+    fn test_toggle_native_size_centers_window_in_tile() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+        // Terminal opened at 960x1080; pretend it asked for a smaller size
+        state.windows.get_mut(&101).unwrap().native_size = (480, 540);
+
+        assert_eq!(state.toggle_native_size(Some(101)), Some((1, true)));
+        let mut geometries = vec![yashiki_ipc::layout::WindowGeometry {
+            id: 101,
+            x: 960,
+            y: 0,
+            width: 960,
+            height: 1080,
+        }];
+        state.apply_native_sizes(&mut geometries);
+        assert_eq!(
+            (
+                geometries[0].x,
+                geometries[0].y,
+                geometries[0].width,
+                geometries[0].height
+            ),
+            (1200, 270, 480, 540)
+        );
+
+        assert_eq!(state.toggle_native_size(Some(101)), Some((1, false)));
+        assert_eq!(state.toggle_native_size(Some(999)), None);
+    }
+
+    #[test]
+    fn test_shift_window_middle() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+        state.displays.get_mut(&1).unwrap().window_order = vec![100, 101, 102];
+
+        state.focused = Some(101);
+        assert_eq!(state.shift_window(OutputDirection::Next), Some(1));
+        assert_eq!(
+            state.displays.get(&1).unwrap().window_order,
+            vec![100, 102, 101]
+        );
+
+        assert_eq!(state.shift_window(OutputDirection::Prev), Some(1));
+        assert_eq!(
+            state.displays.get(&1).unwrap().window_order,
+            vec![100, 101, 102]
+        );
+    }
+
+    #[test]
+    fn test_shift_window_wraps_at_ends() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+        state.displays.get_mut(&1).unwrap().window_order = vec![100, 101, 102];
+
+        state.focused = Some(102);
+        state.shift_window(OutputDirection::Next);
+        assert_eq!(
+            state.displays.get(&1).unwrap().window_order,
+            vec![102, 100, 101]
+        );
+
+        state.shift_window(OutputDirection::Prev);
+        assert_eq!(
+            state.displays.get(&1).unwrap().window_order,
+            vec![100, 101, 102]
+        );
+    }
+
+    #[test]
+    fn test_shift_window_skips_hidden_windows() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+        state.displays.get_mut(&1).unwrap().window_order = vec![100, 101, 102];
+        state.windows.get_mut(&101).unwrap().tags = Tag::new(2);
+
+        state.focused = Some(100);
+        state.shift_window(OutputDirection::Next);
+        assert_eq!(
+            state.displays.get(&1).unwrap().window_order,
+            vec![102, 101, 100]
+        );
+    }
+
+    #[test]
+    fn test_pick_focus_successor_prefers_next_window() {
+        let order = [100, 101, 102];
+        let alive = |id| id != 101;
+        assert_eq!(pick_focus_successor(&order, 101, alive), Some(102));
+        assert_eq!(pick_focus_successor(&order, 100, |_| true), Some(101));
+    }
+
+    #[test]
+    fn test_pick_focus_successor_falls_back_to_previous_when_last() {
+        let order = [100, 101, 102];
+        assert_eq!(pick_focus_successor(&order, 102, |_| true), Some(101));
+
+        // Skips neighbours that were removed in the same pass
+        assert_eq!(pick_focus_successor(&order, 102, |id| id == 100), Some(100));
+    }
+
+    #[test]
+    fn test_pick_focus_successor_for_window_outside_order() {
+        // e.g. a floating window: focus the first tiled window
+        assert_eq!(pick_focus_successor(&[100, 101], 200, |_| true), Some(100));
+        assert_eq!(pick_focus_successor(&[100], 100, |_| true), None);
+        assert_eq!(pick_focus_successor(&[], 100, |_| true), None);
+    }
+
+    #[test]
+    fn test_sync_pid_sets_focus_successor_when_focused_window_destroyed() {
+        let mut ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+        state.displays.get_mut(&1).unwrap().window_order = vec![100, 101, 102];
+        state.focused = Some(101);
+
+        ws.remove_window(101);
+        state.sync_pid(&ws, 1001);
+
+        assert_eq!(state.focused, None);
+        assert_eq!(state.take_focus_successor(), Some((102, 1002)));
+        assert_eq!(state.focus_successor, None);
+    }
+
+    #[test]
+    fn test_sync_pid_no_focus_successor_for_unfocused_window() {
+        let mut ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+        state.focused = Some(100);
+
+        ws.remove_window(101);
+        state.sync_pid(&ws, 1001);
+
+        assert_eq!(state.focused, Some(100));
+        assert_eq!(state.focus_successor, None);
+    }
+
+    #[test]
+    fn test_toggle_always_on_top_stores_flag() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+
+        // Defaults to the focused window
+        assert_eq!(state.toggle_always_on_top(None), Some((100, 1000, true)));
+        assert!(state.windows.get(&100).unwrap().is_always_on_top);
+
+        // Explicit id leaves the focused window alone
+        assert_eq!(
+            state.toggle_always_on_top(Some(101)),
+            Some((101, 1001, true))
+        );
+        assert!(state.windows.get(&100).unwrap().is_always_on_top);
+
+        assert_eq!(state.toggle_always_on_top(None), Some((100, 1000, false)));
+        assert!(!state.windows.get(&100).unwrap().is_always_on_top);
+        assert!(state.windows.get(&101).unwrap().is_always_on_top);
+
+        assert_eq!(state.toggle_always_on_top(Some(999)), None);
+    }
+
+    #[test]
+    fn test_always_on_top_reassert_on_focus_change() {
+        let ws = setup_mock_system();
+        let mut state = State::new();
+        state.sync_all(&ws);
+        state.windows.get_mut(&101).unwrap().is_always_on_top = true;
+
+        // Focusing another window re-raises the flagged one
+        assert_eq!(state.always_on_top_to_reassert(100), vec![(101, 1001)]);
+
+        // The flagged window itself is already on top when focused
+        assert!(state.always_on_top_to_reassert(101).is_empty());
+
+        // Hidden windows are left alone
+        state.windows.get_mut(&101).unwrap().tags = Tag::new(2);
+        assert!(state.always_on_top_to_reassert(100).is_empty());
     }
 
     #[test]
@@ -1247,7 +4033,7 @@ mod tests {
         state.windows.get_mut(&100).unwrap().is_floating = true;
         state.focused = Some(100);
 
-        let result = state.swap_window(Direction::Next);
+        let result = state.swap_window(Direction::Next, false);
         assert!(result.is_none());
     }
 
@@ -1260,7 +4046,7 @@ mod tests {
         state.windows.get_mut(&100).unwrap().is_fullscreen = true;
         state.focused = Some(100);
 
-        let result = state.swap_window(Direction::Next);
+        let result = state.swap_window(Direction::Next, false);
         assert!(result.is_none());
     }
 
@@ -1276,7 +4062,7 @@ mod tests {
         let mut state = State::new();
         state.sync_all(&ws);
 
-        let result = state.swap_window(Direction::Next);
+        let result = state.swap_window(Direction::Next, false);
         assert!(result.is_none());
     }
 
@@ -1288,9 +4074,9 @@ mod tests {
 
         state.focused = Some(100);
 
-        let result = state.swap_window(Direction::Right);
+        let result = state.swap_window(Direction::Right, false);
         assert!(result.is_some());
-        assert_eq!(result.unwrap(), 1);
+        assert_eq!(result.unwrap(), vec![1]);
 
         let display = state.displays.get(&1).unwrap();
         let idx_100 = display
@@ -2020,6 +4806,7 @@ mod tests {
         let _result = state.handle_display_change(&ws2);
         assert_eq!(state.windows.get(&101).unwrap().display_id, 1);
         assert_eq!(state.windows.get(&101).unwrap().orphaned_from, Some(2));
+        assert_window_order_matches_windows(&state);
 
         // Bring display 2 back
         let ws3 = MockWindowSystem::new()
@@ -2038,6 +4825,7 @@ mod tests {
         // Window 101 should be restored to display 2
         assert_eq!(state.windows.get(&101).unwrap().display_id, 2);
         assert_eq!(state.windows.get(&101).unwrap().orphaned_from, None);
+        assert_window_order_matches_windows(&state);
 
         // Both displays should be retiled
         assert!(result.displays_to_retile.contains(&1));
@@ -6,6 +6,9 @@ use crate::macos::DisplayId;
 use yashiki_ipc::{ExtendedWindowAttributes, RuleAction, RuleMatcher, WindowRule};
 
 use super::super::state::{State, WindowMove};
+use super::layout::move_window_order_to_display;
+use super::sync::forget_window;
+use super::tags::allowed_tags;
 
 pub fn add_rule(state: &mut State, rule: WindowRule) {
     state.rules_engine.add_rule(rule);
@@ -98,14 +101,17 @@ pub fn apply_rules_to_new_window(state: &mut State, window_id: WindowId) -> Vec<
 
     let rule_result =
         apply_rules_to_window_extended(state, &app_name, app_id.as_deref(), &title, &ext);
+    let new_tags = rule_result
+        .tags
+        .map(|mask| allowed_tags(state, Tag::from_mask(mask)));
 
     if let Some(window) = state.windows.get_mut(&window_id) {
-        if let Some(tag_mask) = rule_result.tags {
-            window.tags = Tag::from_mask(tag_mask);
+        if let Some(tags) = new_tags {
+            window.tags = tags;
             tracing::info!(
                 "Applied rule: window {} tags set to {}",
                 window_id,
-                tag_mask
+                tags.mask()
             );
         }
         if let Some(display_id) = rule_result.display_id {
@@ -125,6 +131,9 @@ pub fn apply_rules_to_new_window(state: &mut State, window_id: WindowId) -> Vec<
             );
         }
     }
+    if let Some(display_id) = rule_result.display_id {
+        move_window_order_to_display(state, window_id, display_id);
+    }
 
     let mut effects = Vec::new();
 
@@ -238,6 +247,10 @@ pub fn apply_rules_to_all_windows(
         .iter()
         .filter_map(|&id| {
             let window = state.windows.get(&id)?;
+            // manage-window outranks ignore rules, as it does when the window is created
+            if state.window_override(id, &window.app_name) == Some(true) {
+                return None;
+            }
             let ext = window.extended_attributes();
             if should_ignore_window_extended(
                 state,
@@ -260,6 +273,7 @@ pub fn apply_rules_to_all_windows(
                 window_id,
                 window.app_name
             );
+            forget_window(state, *window_id);
             affected_displays.insert(*display_id);
             removed_window_ids.push(*window_id);
 
@@ -293,7 +307,9 @@ pub fn apply_rules_to_all_windows(
         let rule_result =
             apply_rules_to_window_extended(state, &app_name, app_id.as_deref(), &title, &ext);
 
-        let new_tags = rule_result.tags.map(Tag::from_mask);
+        let new_tags = rule_result
+            .tags
+            .map(|mask| allowed_tags(state, Tag::from_mask(mask)));
         let new_display_id = rule_result.display_id;
 
         let tags_changed = new_tags.is_some() && new_tags != Some(original_tags);
@@ -301,13 +317,13 @@ pub fn apply_rules_to_all_windows(
             new_display_id.is_some() && new_display_id != Some(original_display_id);
 
         if let Some(window) = state.windows.get_mut(&window_id) {
-            if let Some(tag_mask) = rule_result.tags {
-                window.tags = Tag::from_mask(tag_mask);
+            if let Some(tags) = new_tags {
+                window.tags = tags;
                 tracing::info!(
                     "Applied rule: window {} ({}) tags set to {}",
                     window_id,
                     app_name,
-                    tag_mask
+                    tags.mask()
                 );
             }
             if let Some(display_id) = rule_result.display_id {
@@ -330,6 +346,12 @@ pub fn apply_rules_to_all_windows(
             }
         }
 
+        if display_changed {
+            if let Some(new_disp) = new_display_id {
+                move_window_order_to_display(state, window_id, new_disp);
+            }
+        }
+
         if tags_changed || display_changed {
             affected_displays.insert(original_display_id);
             if let Some(new_disp) = new_display_id {
@@ -1,14 +1,17 @@
 use std::collections::HashSet;
 use std::time::{Duration, Instant};
 
-use super::super::{Display, Rect, Window, WindowId};
+use super::super::{dedup_displays, Display, Rect, Window, WindowId};
 use crate::macos::DisplayId;
 use crate::platform::WindowSystem;
+use yashiki_ipc::{ExtendedWindowAttributes, UnmanagedReason, UnmanagedWindowInfo};
 
 use super::super::state::{IgnoredWindowInfo, State, WindowMove};
 
+use super::focus::pick_focus_successor;
 use super::layout::{
     add_to_window_order, compute_hide_position_for_display, remove_from_window_order,
+    visible_windows_on_display,
 };
 use super::rules::{has_matching_non_ignore_rule, should_ignore_window_extended};
 
@@ -114,6 +117,40 @@ fn should_remove_window_if_not_transitioning<W: WindowSystem>(
     should_remove_window(ws, window_id, pid, ax_accessible, window_level)
 }
 
+/// Minimized windows drop out of CGWindowList but stay in the AX API, so they survive
+/// removal. Flag them so navigation and layout skip them until they're restored.
+/// Returns true if any window's minimized state changed.
+fn update_minimized_windows<'a, W: WindowSystem>(
+    state: &mut State,
+    ws: &W,
+    ids: impl IntoIterator<Item = &'a WindowId>,
+    on_screen_ids: &HashSet<WindowId>,
+) -> bool {
+    let mut changed = false;
+    for id in ids {
+        let Some(window) = state.windows.get_mut(id) else {
+            continue;
+        };
+        let minimized = !on_screen_ids.contains(id) && ws.is_window_minimized(*id, window.pid);
+        if window.is_minimized != minimized {
+            tracing::info!(
+                "Window [{}] {} ({}) {}",
+                window.id,
+                window.title,
+                window.app_name,
+                if minimized { "minimized" } else { "restored" }
+            );
+            window.is_minimized = minimized;
+            state.minimized_order.retain(|&w| w != *id);
+            if minimized {
+                state.minimized_order.push(*id);
+            }
+            changed = true;
+        }
+    }
+    changed
+}
+
 /// Check if a hidden window needs to be re-hidden (returns Some if moved from hide position)
 fn check_window_rehide(
     window: &Window,
@@ -178,7 +215,7 @@ fn detect_rehide_moves(
 }
 
 pub fn sync_all<W: WindowSystem>(state: &mut State, ws: &W) -> (Vec<WindowMove>, Vec<WindowId>) {
-    let display_infos = ws.get_all_displays();
+    let display_infos = dedup_displays(ws.get_all_displays());
     for info in &display_infos {
         state
             .displays
@@ -242,18 +279,9 @@ pub fn sync_focused_window_with_hint<W: WindowSystem>(
         let window_id = focused_info.window_id;
 
         // Window exists - just update focus and z-order
-        if let Some(window) = state.windows.get(&window_id) {
-            let display_id = window.display_id;
+        if state.windows.contains_key(&window_id) {
             state.set_focused(Some(window_id));
             state.move_to_front_in_z_order(window_id);
-            if state.focused_display != display_id {
-                tracing::info!(
-                    "Focused display changed: {} -> {}",
-                    state.focused_display,
-                    display_id
-                );
-                state.focused_display = display_id;
-            }
             return (false, vec![]);
         }
 
@@ -270,12 +298,8 @@ pub fn sync_focused_window_with_hint<W: WindowSystem>(
             let (changed, new_ids, _) = sync_pid(state, ws, pid);
 
             // Set focus on the new window if it was added
-            if let Some(window) = state.windows.get(&window_id) {
-                let display_id = window.display_id;
+            if state.windows.contains_key(&window_id) {
                 state.set_focused(Some(window_id));
-                if state.focused_display != display_id {
-                    state.focused_display = display_id;
-                }
             }
 
             return (changed, new_ids);
@@ -284,12 +308,13 @@ pub fn sync_focused_window_with_hint<W: WindowSystem>(
 
     // Fallback: use pid_hint
     if let Some(pid) = pid_hint {
-        let visible_tags = state.visible_tags();
-        let pid_windows: Vec<_> = state
-            .windows
-            .values()
-            .filter(|w| w.pid == pid && w.tags.intersects(visible_tags) && !w.is_hidden())
+        let mut pid_windows: Vec<_> = state
+            .visible_windows()
+            .into_iter()
+            .filter(|w| w.pid == pid)
             .collect();
+        // Prefer a window on the focused display
+        pid_windows.sort_by_key(|w| (w.display_id != state.focused_display, w.id));
 
         if let Some(window) = pid_windows.first() {
             tracing::debug!(
@@ -298,11 +323,7 @@ pub fn sync_focused_window_with_hint<W: WindowSystem>(
                 pid
             );
             let window_id = window.id;
-            let display_id = window.display_id;
             state.set_focused(Some(window_id));
-            if state.focused_display != display_id {
-                state.focused_display = display_id;
-            }
             return (false, vec![]);
         }
     }
@@ -356,6 +377,20 @@ pub fn sync_pid<W: WindowSystem>(
             HashSet::new()
         };
 
+    // Remember the focused window's neighbours before it's removed, so focus can move
+    // to a predictable successor instead of wherever macOS puts it
+    let focus_order: Option<Vec<WindowId>> = state
+        .focused
+        .filter(|id| current_ids.contains(id) && !on_screen_ids.contains(id))
+        .and_then(|id| state.windows.get(&id))
+        .map(|w| {
+            visible_windows_on_display(state, w.display_id)
+                .iter()
+                .map(|w| w.id)
+                .collect()
+        });
+    let mut removed_focused = None;
+
     // Remove managed windows that are no longer on screen
     for id in current_ids.difference(&on_screen_ids) {
         let window_level = state.windows.get(id).map(|w| w.window_level).unwrap_or(0);
@@ -377,13 +412,29 @@ pub fn sync_pid<W: WindowSystem>(
                 window.title,
                 window.app_name
             );
+            forget_window(state, *id);
             if state.focused == Some(*id) {
                 state.focused = None;
+                removed_focused = Some(*id);
             }
             changed = true;
         }
     }
 
+    if update_minimized_windows(state, ws, &current_ids, &on_screen_ids) {
+        changed = true;
+    }
+
+    if let (Some(order), Some(removed_id)) = (focus_order, removed_focused) {
+        state.focus_successor =
+            pick_focus_successor(&order, removed_id, |id| state.windows.contains_key(&id));
+        tracing::debug!(
+            "Focus successor for destroyed window {}: {:?}",
+            removed_id,
+            state.focus_successor
+        );
+    }
+
     // Remove ignored windows that are no longer on screen
     let ignored_to_check: Vec<(WindowId, i32)> = current_ignored_ids
         .iter()
@@ -436,6 +487,7 @@ pub fn sync_pid<W: WindowSystem>(
                         window.window_level
                     );
                     state.ignored_windows.remove(&id);
+                    add_to_window_order(state, window.id, display_id);
                     state.windows.insert(window.id, window);
                     added_window_ids.push(id);
                     changed = true;
@@ -479,6 +531,7 @@ pub fn sync_pid<W: WindowSystem>(
                         window.subrole,
                         window.window_level
                     );
+                    add_to_window_order(state, window.id, display_id);
                     state.windows.insert(window.id, window);
                     added_window_ids.push(*id);
                     changed = true;
@@ -523,16 +576,20 @@ pub fn sync_pid<W: WindowSystem>(
                     || window.frame.height != new_frame.height;
 
                 if title_changed || frame_changed {
-                    tracing::debug!(
-                        "Window updated: [{}] {} ({}) pos=({},{}) -> ({},{})",
-                        window.id,
-                        window.title,
-                        window.app_name,
-                        window.frame.x,
-                        window.frame.y,
-                        new_frame.x,
-                        new_frame.y
-                    );
+                    if let Some(suppressed) = state.window_update_log_throttle.check(Instant::now())
+                    {
+                        tracing::debug!(
+                            "Window updated: [{}] {} ({}) pos=({},{}) -> ({},{}){}",
+                            window.id,
+                            window.title,
+                            window.app_name,
+                            window.frame.x,
+                            window.frame.y,
+                            new_frame.x,
+                            new_frame.y,
+                            suppressed
+                        );
+                    }
                     window.title = new_title;
 
                     if let Some((hide_x, hide_y)) = hide_pos {
@@ -569,19 +626,47 @@ pub fn sync_pid<W: WindowSystem>(
     (changed, added_window_ids, rehide_moves)
 }
 
+/// Display whose frame contains the point. Overlapping frames resolve to the lowest ID.
+pub fn display_containing_point(state: &State, x: f64, y: f64) -> Option<DisplayId> {
+    state
+        .displays
+        .values()
+        .filter(|display| {
+            let dx = display.frame.x as f64;
+            let dy = display.frame.y as f64;
+            let dw = display.frame.width as f64;
+            let dh = display.frame.height as f64;
+            x >= dx && x < dx + dw && y >= dy && y < dy + dh
+        })
+        .map(|display| display.id)
+        .min()
+}
+
+/// Display a window with these bounds belongs to: the one containing its center,
+/// else the one it overlaps most, else the focused display.
 pub fn find_display_for_bounds(state: &State, bounds: &crate::macos::Bounds) -> DisplayId {
     let cx = bounds.x + bounds.width / 2.0;
     let cy = bounds.y + bounds.height / 2.0;
 
-    for display in state.displays.values() {
-        let dx = display.frame.x as f64;
-        let dy = display.frame.y as f64;
-        let dw = display.frame.width as f64;
-        let dh = display.frame.height as f64;
+    if let Some(display_id) = display_containing_point(state, cx, cy) {
+        return display_id;
+    }
 
-        if cx >= dx && cx < dx + dw && cy >= dy && cy < dy + dh {
-            return display.id;
-        }
+    let largest_overlap = state
+        .displays
+        .values()
+        .map(|display| {
+            let f = &display.frame;
+            let w = (bounds.x + bounds.width).min((f.x + f.width as i32) as f64)
+                - bounds.x.max(f.x as f64);
+            let h = (bounds.y + bounds.height).min((f.y + f.height as i32) as f64)
+                - bounds.y.max(f.y as f64);
+            (display.id, w.max(0.0) * h.max(0.0))
+        })
+        .filter(|&(_, area)| area > 0.0)
+        .max_by(|a, b| a.1.total_cmp(&b.1).then(b.0.cmp(&a.0)));
+    if let Some((display_id, _)) = largest_overlap {
+        return display_id;
     }
 
     if state.focused_display != 0 {
@@ -591,6 +676,97 @@ pub fn find_display_for_bounds(state: &State, bounds: &crate::macos::Bounds) ->
     }
 }
 
+/// Control Center creates many transient windows; users never need to manage it.
+const CONTROL_CENTER_APP_ID: &str = "com.apple.controlcenter";
+
+/// Why a window should not be managed, or None if it should be.
+/// A manage-window/unmanage-window override takes precedence over everything else.
+pub fn exclusion_reason(
+    state: &State,
+    window_id: WindowId,
+    app_name: &str,
+    app_id: Option<&str>,
+    title: &str,
+    ext: &ExtendedWindowAttributes,
+) -> Option<UnmanagedReason> {
+    match state.window_override(window_id, app_name) {
+        Some(true) => return None,
+        Some(false) => return Some(UnmanagedReason::Manual),
+        None => {}
+    }
+    if app_id == Some(CONTROL_CENTER_APP_ID) {
+        return Some(UnmanagedReason::SystemUi);
+    }
+    if ext.window_level != 0 && !has_matching_non_ignore_rule(state, app_name, app_id, title, ext) {
+        return Some(UnmanagedReason::WindowLevel);
+    }
+    if should_ignore_window_extended(state, app_name, app_id, title, ext) {
+        return Some(UnmanagedReason::IgnoreRule);
+    }
+    None
+}
+
+/// On-screen windows that are not managed, with the reason each one is excluded.
+pub fn unmanaged_windows<W: WindowSystem>(state: &State, ws: &W) -> Vec<UnmanagedWindowInfo> {
+    let mut windows: Vec<UnmanagedWindowInfo> = ws
+        .get_on_screen_windows()
+        .iter()
+        .filter(|info| !state.windows.contains_key(&info.window_id))
+        .filter_map(|info| {
+            let ext = ws.get_extended_attributes(info.window_id, info.pid, info.layer);
+            let title = ext
+                .title
+                .clone()
+                .unwrap_or_else(|| info.name.clone().unwrap_or_default());
+            let reason = exclusion_reason(
+                state,
+                info.window_id,
+                &info.owner_name,
+                info.bundle_id.as_deref(),
+                &title,
+                &ext,
+            )?;
+            Some(UnmanagedWindowInfo {
+                id: info.window_id,
+                pid: info.pid,
+                app_name: info.owner_name.clone(),
+                title,
+                window_level: ext.window_level,
+                reason,
+            })
+        })
+        .collect();
+    windows.sort_by_key(|w| w.id);
+    windows
+}
+
+/// Stop managing a window (unmanage-window): it is tracked as ignored from now on,
+/// so syncs keep re-evaluating it. Returns the display that needs retiling.
+pub fn unmanage_window(state: &mut State, window_id: WindowId) -> Option<DisplayId> {
+    let window = state.windows.remove(&window_id)?;
+    forget_window(state, window_id);
+    if state.focused == Some(window_id) {
+        state.focused = None;
+    }
+    tracing::info!(
+        "Window unmanaged: [{}] {} ({})",
+        window.id,
+        window.title,
+        window.app_name
+    );
+    state.ignored_windows.insert(
+        window_id,
+        IgnoredWindowInfo {
+            pid: window.pid,
+            added_at: Instant::now(),
+            frame: window.saved_frame.unwrap_or(window.frame),
+            display_id: window.display_id,
+            window_level: window.window_level,
+        },
+    );
+    Some(window.display_id)
+}
+
 /// Result of try_create_window:
 /// - `None`: Window should not be tracked at all (Control Center, non-normal layer without rule)
 /// - `Some(Ok(window))`: Window created successfully, should be managed
@@ -606,7 +782,9 @@ pub fn try_create_window<W: WindowSystem>(
 
     // Filter Control Center early - system UI that users never need to manage,
     // and it creates many transient windows that slow down processing
-    if app_id == Some("com.apple.controlcenter") {
+    if app_id == Some(CONTROL_CENTER_APP_ID)
+        && state.window_override(info.window_id, app_name) != Some(true)
+    {
         return None;
     }
 
@@ -635,37 +813,39 @@ pub fn try_create_window<W: WindowSystem>(
         ext.zoom_button
     );
 
-    // Non-normal layer windows without matching rule: not tracked at all
-    if ext.window_level != 0 && !has_matching_non_ignore_rule(state, app_name, app_id, &title, &ext)
-    {
-        tracing::debug!(
-            "Window skipped (non-normal layer without matching rule): [{}] {} ({}) level={}",
-            info.window_id,
-            title,
-            app_name,
-            ext.window_level
-        );
-        return None;
-    }
-
-    // Window ignored by rule: track for re-evaluation
-    if should_ignore_window_extended(state, app_name, app_id, &title, &ext) {
-        tracing::info!(
-            "Window ignored by rule: [{}] {} ({}) [ax_id={:?}, subrole={:?}, level={}]",
-            info.window_id,
-            title,
-            app_name,
-            ext.ax_id,
-            ext.subrole,
-            ext.window_level
-        );
-        return Some(Err(IgnoredWindowInfo {
-            pid: info.pid,
-            added_at: Instant::now(),
-            frame: Rect::from_bounds(&info.bounds),
-            display_id,
-            window_level: ext.window_level,
-        }));
+    match exclusion_reason(state, info.window_id, app_name, app_id, &title, &ext) {
+        None => {}
+        // Non-normal layer windows without matching rule: not tracked at all
+        Some(UnmanagedReason::SystemUi | UnmanagedReason::WindowLevel) => {
+            tracing::debug!(
+                "Window skipped (non-normal layer without matching rule): [{}] {} ({}) level={}",
+                info.window_id,
+                title,
+                app_name,
+                ext.window_level
+            );
+            return None;
+        }
+        // Window ignored by rule or unmanage-window: track for re-evaluation
+        Some(reason @ (UnmanagedReason::IgnoreRule | UnmanagedReason::Manual)) => {
+            tracing::info!(
+                "Window ignored ({}): [{}] {} ({}) [ax_id={:?}, subrole={:?}, level={}]",
+                reason,
+                info.window_id,
+                title,
+                app_name,
+                ext.ax_id,
+                ext.subrole,
+                ext.window_level
+            );
+            return Some(Err(IgnoredWindowInfo {
+                pid: info.pid,
+                added_at: Instant::now(),
+                frame: Rect::from_bounds(&info.bounds),
+                display_id,
+                window_level: ext.window_level,
+            }));
+        }
     }
 
     let initial_tag = state
@@ -758,11 +938,13 @@ pub fn sync_with_window_infos<W: WindowSystem>(
                 window.title,
                 window.app_name
             );
-            remove_from_window_order(state, *id);
+            forget_window(state, *id);
             state.windows.remove(id);
         }
     }
 
+    update_minimized_windows(state, ws, &current_ids, &on_screen_ids);
+
     // Remove ignored windows that are no longer on screen
     let ignored_to_check: Vec<(WindowId, i32, i32)> = current_ignored_ids
         .difference(&on_screen_ids)
@@ -879,6 +1061,83 @@ pub fn sync_with_window_infos<W: WindowSystem>(
     (detect_rehide_moves(state, window_infos), added_window_ids)
 }
 
+/// Release all per-window state of a removed window: its place in the window and
+/// minimize order, its peek slot, and the sticky main slot if it held it.
+pub fn forget_window(state: &mut State, window_id: WindowId) {
+    remove_from_window_order(state, window_id);
+    state.minimized_order.retain(|&id| id != window_id);
+    if let Some(window) = state.windows.get_mut(&window_id) {
+        window.peek_slot = None;
+    }
+    if state.sticky_main == Some(window_id) {
+        state.sticky_main = None;
+    }
+}
+
+/// Remove managed windows that are not in `live_ids`.
+/// Returns the removed window IDs.
+pub fn prune_missing_windows(state: &mut State, live_ids: &HashSet<WindowId>) -> Vec<WindowId> {
+    let mut stale_ids: Vec<WindowId> = state
+        .windows
+        .keys()
+        .filter(|id| !live_ids.contains(id))
+        .copied()
+        .collect();
+    stale_ids.sort_unstable();
+
+    for id in &stale_ids {
+        if let Some(window) = state.windows.remove(id) {
+            tracing::info!(
+                "Pruning stale window: [{}] {} ({})",
+                window.id,
+                window.title,
+                window.app_name
+            );
+        }
+        forget_window(state, *id);
+        if state.focused == Some(*id) {
+            state.focused = None;
+        }
+    }
+    // A recycled window ID must not inherit the pruned window's override
+    if state.prune_window_overrides(&stale_ids) {
+        state.window_overrides_pruned = true;
+    }
+
+    stale_ids
+}
+
+/// Diff managed windows against a fresh on-screen window list and prune windows
+/// that vanished without a destroy notification (e.g. the owning app crashed).
+/// Off-screen windows are kept while their process is alive and the window still
+/// exists (or AX cannot tell, as for windows on another Space).
+pub fn reconcile_windows<W: WindowSystem>(state: &mut State, ws: &W) -> Vec<WindowId> {
+    let on_screen_ids: HashSet<WindowId> = ws
+        .get_on_screen_windows()
+        .iter()
+        .map(|w| w.window_id)
+        .collect();
+
+    let live_ids: HashSet<WindowId> = state
+        .windows
+        .values()
+        .filter(|w| {
+            on_screen_ids.contains(&w.id)
+                || (ws.is_process_running(w.pid)
+                    && !should_remove_window(
+                        ws,
+                        w.id,
+                        w.pid,
+                        ws.can_access_ax_windows(w.pid),
+                        w.window_level,
+                    ))
+        })
+        .map(|w| w.id)
+        .collect();
+
+    prune_missing_windows(state, &live_ids)
+}
+
 /// Sync all windows on a display, removing stale windows.
 /// Returns (changed, new_window_ids, rehide_moves).
 pub fn sync_windows_for_display<W: WindowSystem>(
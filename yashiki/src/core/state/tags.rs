@@ -1,19 +1,36 @@
+use regex::Regex;
+use yashiki_ipc::OutputDirection;
+
 use super::super::{Tag, WindowId};
 use crate::macos::DisplayId;
 
-use super::super::state::{State, WindowMove};
-use super::layout::compute_layout_changes_for_display;
+use super::super::state::{ScratchpadToggle, State, WindowMove};
+use super::display::move_window_to_display;
+use super::layout::{compute_layout_changes_for_display, visible_windows_on_display};
+
+/// Reserved tag that dismissed scratchpad windows are parked on.
+pub const SCRATCHPAD_TAG: u32 = Tag::MAX;
+
+/// Tags a display may show or a window may carry: with exclusive tags
+/// (set-exclusive-tags), only the lowest of `tags`.
+pub fn allowed_tags(state: &State, tags: Tag) -> Tag {
+    if state.config.exclusive_tags {
+        tags.lowest()
+    } else {
+        tags
+    }
+}
 
-pub fn view_tags(state: &mut State, tags: u32) -> Vec<WindowMove> {
+pub fn view_tags(state: &mut State, tags: u64) -> Vec<WindowMove> {
     view_tags_on_display(state, tags, state.focused_display)
 }
 
 pub fn view_tags_on_display(
     state: &mut State,
-    tags: u32,
+    tags: u64,
     display_id: DisplayId,
 ) -> Vec<WindowMove> {
-    let new_visible = Tag::from_mask(tags);
+    let new_visible = allowed_tags(state, Tag::from_mask(tags));
     let first_tag = new_visible.first_tag().unwrap_or(1);
     let new_layout = state.resolve_layout_for_tag(first_tag as u8).to_string();
     let Some(disp) = state.displays.get_mut(&display_id) else {
@@ -30,6 +47,7 @@ pub fn view_tags_on_display(
         disp.current_layout,
         new_layout
     );
+    disp.push_tag_history(disp.visible_tags);
     disp.previous_visible_tags = disp.visible_tags;
     disp.visible_tags = new_visible;
     disp.previous_layout = disp.current_layout.take();
@@ -39,9 +57,13 @@ pub fn view_tags_on_display(
 
 pub fn toggle_tags_on_display(
     state: &mut State,
-    tags: u32,
+    tags: u64,
     display_id: DisplayId,
 ) -> Vec<WindowMove> {
+    // Views never combine tags, so toggling one in just views it
+    if state.config.exclusive_tags {
+        return view_tags_on_display(state, tags, display_id);
+    }
     let Some(disp) = state.displays.get_mut(&display_id) else {
         return vec![];
     };
@@ -56,6 +78,7 @@ pub fn toggle_tags_on_display(
         disp.visible_tags.mask(),
         new_visible.mask()
     );
+    disp.push_tag_history(disp.visible_tags);
     disp.previous_visible_tags = disp.visible_tags;
     disp.visible_tags = new_visible;
     compute_layout_changes_for_display(state, display_id)
@@ -76,16 +99,147 @@ pub fn view_tags_last(state: &mut State) -> Vec<WindowMove> {
         disp.current_layout,
         disp.previous_layout
     );
+    disp.push_tag_history(disp.visible_tags);
     std::mem::swap(&mut disp.visible_tags, &mut disp.previous_visible_tags);
     std::mem::swap(&mut disp.current_layout, &mut disp.previous_layout);
     compute_layout_changes_for_display(state, state.focused_display)
 }
 
-pub fn move_focused_to_tags(state: &mut State, tags: u32) -> Vec<WindowMove> {
+/// Go back `steps` entries in the focused display's tag history (clamped to its depth).
+/// The skipped entries are dropped; going back is not itself recorded.
+pub fn view_tags_back(state: &mut State, steps: u32) -> Vec<WindowMove> {
+    let display_id = state.focused_display;
+    let Some(disp) = state.displays.get_mut(&display_id) else {
+        return vec![];
+    };
+    let steps = (steps as usize).min(disp.tag_history.len());
+    if steps == 0 {
+        return vec![];
+    }
+    let keep = disp.tag_history.len() - steps;
+    let target = disp.tag_history.drain(keep..).next().unwrap();
+    let first_tag = target.first_tag().unwrap_or(1);
+    let new_layout = state.resolve_layout_for_tag(first_tag as u8).to_string();
+    let disp = state.displays.get_mut(&display_id).unwrap();
+    tracing::info!(
+        "View tags back {} on display {}: {} -> {}",
+        steps,
+        display_id,
+        disp.visible_tags.mask(),
+        target.mask()
+    );
+    disp.previous_visible_tags = disp.visible_tags;
+    disp.visible_tags = target;
+    disp.previous_layout = disp.current_layout.take();
+    disp.current_layout = Some(new_layout);
+    compute_layout_changes_for_display(state, display_id)
+}
+
+/// Step the focused display to the next/previous single tag, wrapping around.
+/// With `only_occupied`, tags that have no windows on that display are skipped.
+pub fn cycle_tags(
+    state: &mut State,
+    direction: OutputDirection,
+    only_occupied: bool,
+) -> Vec<WindowMove> {
+    let display_id = state.focused_display;
+    let Some(current) = state
+        .displays
+        .get(&display_id)
+        .and_then(|d| d.visible_tags.first_tag())
+    else {
+        return vec![];
+    };
+
+    let candidates = if only_occupied {
+        state
+            .windows
+            .values()
+            .filter(|w| w.display_id == display_id)
+            .fold(0u64, |mask, w| mask | w.tags.mask())
+    } else {
+        u64::MAX
+    } & !Tag::new(SCRATCHPAD_TAG).mask();
+
+    // Walk at most a full lap; landing back on `current` means nothing else qualifies
+    let step = match direction {
+        OutputDirection::Next => 1,
+        OutputDirection::Prev => Tag::MAX - 1,
+    };
+    let target = (1..=Tag::MAX)
+        .map(|i| (current - 1 + step * i) % Tag::MAX + 1)
+        .find(|&n| candidates & Tag::new(n).mask() != 0);
+
+    match target {
+        Some(n) => view_tags_on_display(state, Tag::new(n).mask(), display_id),
+        None => vec![],
+    }
+}
+
+/// Label tag number `tag`; an empty name removes its label.
+/// A name labels one tag at a time, so reusing it moves it to `tag`.
+pub fn set_tag_name(state: &mut State, tag: u32, name: &str) -> Result<(), String> {
+    if Tag::try_new(tag).is_none() {
+        return Err(format!("Tag number must be 1-{}, got {}", Tag::MAX, tag));
+    }
+    if tag == SCRATCHPAD_TAG {
+        return Err(format!("Tag {} is reserved for scratchpads", tag));
+    }
+    if name.is_empty() {
+        state.tag_names.remove(&tag);
+        return Ok(());
+    }
+    state.tag_names.retain(|_, existing| existing != name);
+    state.tag_names.insert(tag, name.to_string());
+    tracing::info!("Named tag {}: {}", tag, name);
+    Ok(())
+}
+
+/// Tag number labelled `name` by set-tag-name
+pub fn tag_by_name(state: &State, name: &str) -> Option<u32> {
+    state
+        .tag_names
+        .iter()
+        .find(|(_, existing)| *existing == name)
+        .map(|(&tag, _)| tag)
+}
+
+/// Labels of the named tags in `tags`, lowest tag first
+pub fn tag_names_of(state: &State, tags: Tag) -> Vec<String> {
+    tags.iter_tags()
+        .filter_map(|n| state.tag_names.get(&n).cloned())
+        .collect()
+}
+
+/// Window count per tag on a display, for tags that have windows or are visible.
+/// Returns (tag number, window count, visible), ordered by tag number.
+pub fn tag_stats(state: &State, display_id: DisplayId) -> Vec<(u32, usize, bool)> {
+    let mut counts = [0usize; Tag::MAX as usize];
+    for window in state.windows.values() {
+        if window.display_id == display_id {
+            for n in window.tags.iter_tags() {
+                counts[n as usize - 1] += 1;
+            }
+        }
+    }
+    let visible = state
+        .displays
+        .get(&display_id)
+        .map(|d| d.visible_tags)
+        .unwrap_or(Tag::from_mask(0));
+
+    Tag::from_mask(u64::MAX)
+        .iter_tags()
+        .map(|n| (n, counts[n as usize - 1], visible.intersects(Tag::new(n))))
+        .filter(|&(_, count, is_visible)| count > 0 || is_visible)
+        .collect()
+}
+
+pub fn move_focused_to_tags(state: &mut State, tags: u64) -> Vec<WindowMove> {
     let Some(focused_id) = state.focused else {
         return vec![];
     };
-    let new_tags = Tag::from_mask(tags);
+    let new_tags = allowed_tags(state, Tag::from_mask(tags));
     let display_id = if let Some(window) = state.windows.get_mut(&focused_id) {
         tracing::info!("Move window {} to tags {}", window.id, new_tags.mask());
         window.tags = new_tags;
@@ -96,10 +250,170 @@ pub fn move_focused_to_tags(state: &mut State, tags: u32) -> Vec<WindowMove> {
     compute_layout_changes_for_display(state, display_id)
 }
 
-pub fn toggle_focused_window_tags(state: &mut State, tags: u32) -> Vec<WindowMove> {
+/// Set the focused window's tags to the focused display's visible tags, moving it to
+/// that display if needed, so it shows on exactly the current view.
+/// Returns the displays to retile and the hide/show moves.
+pub fn match_focused_to_view(state: &mut State) -> Option<(Vec<DisplayId>, Vec<WindowMove>)> {
+    let focused_id = state.focused?;
+    let display_id = state.focused_display;
+    let visible_tags = allowed_tags(state, state.displays.get(&display_id)?.visible_tags);
+    let mut display_ids = vec![display_id];
+    let window_display_id = state.windows.get(&focused_id)?.display_id;
+    if window_display_id != display_id
+        && move_window_to_display(state, focused_id, display_id).is_some()
+    {
+        display_ids.push(window_display_id);
+    }
+    let window = state.windows.get_mut(&focused_id)?;
+    tracing::info!(
+        "Match window {} tags to view: {} -> {}",
+        window.id,
+        window.tags.mask(),
+        visible_tags.mask()
+    );
+    window.tags = visible_tags;
+    Some((
+        display_ids,
+        compute_layout_changes_for_display(state, display_id),
+    ))
+}
+
+/// Dismiss the scratchpad window of `app_name` if it is visible, otherwise bring it
+/// onto the focused display's current tags (restoring it if minimized). The window is
+/// the one remembered from an earlier toggle while it exists, else the app's
+/// lowest-numbered window.
+pub fn scratchpad_toggle(state: &mut State, app_name: &str) -> ScratchpadToggle {
+    let remembered = state
+        .scratchpads
+        .get(app_name)
+        .copied()
+        .filter(|id| state.windows.contains_key(id));
+    let window_id = remembered.or_else(|| {
+        state
+            .windows
+            .values()
+            .filter(|w| w.app_name == app_name)
+            .map(|w| w.id)
+            .min()
+    });
+    let Some(window_id) = window_id else {
+        state.scratchpads.remove(app_name);
+        return ScratchpadToggle::Launch;
+    };
+    state.scratchpads.insert(app_name.to_string(), window_id);
+
+    let window = &state.windows[&window_id];
+    let (pid, display_id, minimized) = (window.pid, window.display_id, window.is_minimized);
+    if state.is_window_visible(window) {
+        tracing::info!("Scratchpad: dismiss window {} ({})", window_id, app_name);
+        state.windows.get_mut(&window_id).unwrap().tags = Tag::new(SCRATCHPAD_TAG);
+        return ScratchpadToggle::Hidden {
+            display_id,
+            window_moves: compute_layout_changes_for_display(state, display_id),
+        };
+    }
+
+    let target_display_id = state.focused_display;
+    let mut display_ids = vec![target_display_id];
+    if display_id != target_display_id
+        && move_window_to_display(state, window_id, target_display_id).is_some()
+    {
+        display_ids.push(display_id);
+    }
+    let visible_tags = allowed_tags(state, state.visible_tags_on_display(target_display_id));
+    tracing::info!(
+        "Scratchpad: summon window {} ({}) on display {}",
+        window_id,
+        app_name,
+        target_display_id
+    );
+    state.windows.get_mut(&window_id).unwrap().tags = visible_tags;
+    ScratchpadToggle::Shown {
+        window_id,
+        pid,
+        minimized,
+        window_moves: compute_layout_changes_for_display(state, target_display_id),
+        display_ids,
+    }
+}
+
+/// Set the tags of every window whose title matches `pattern`.
+/// Returns the matched window IDs (sorted) and the hide/show moves for their displays.
+pub fn tag_windows_by_title(
+    state: &mut State,
+    pattern: &Regex,
+    tags: u64,
+) -> (Vec<WindowId>, Vec<WindowMove>) {
+    let new_tags = allowed_tags(state, Tag::from_mask(tags));
+    let mut matched = Vec::new();
+    let mut display_ids = Vec::new();
+    for window in state.windows.values_mut() {
+        if !pattern.is_match(&window.title) {
+            continue;
+        }
+        tracing::info!(
+            "Tag window {} ({}) by title: {} -> {}",
+            window.id,
+            window.title,
+            window.tags.mask(),
+            new_tags.mask()
+        );
+        window.tags = new_tags;
+        matched.push(window.id);
+        if !display_ids.contains(&window.display_id) {
+            display_ids.push(window.display_id);
+        }
+    }
+    matched.sort();
+    display_ids.sort();
+
+    let moves = display_ids
+        .into_iter()
+        .flat_map(|display_id| compute_layout_changes_for_display(state, display_id))
+        .collect();
+    (matched, moves)
+}
+
+/// Spread the visible tiled windows of each display round-robin over the single
+/// tags in `tags`, in window order. Returns the hide/show moves.
+pub fn distribute_windows(state: &mut State, tags: u64) -> Vec<WindowMove> {
+    let targets: Vec<u32> = Tag::from_mask(tags).iter_tags().collect();
+    if targets.is_empty() {
+        return vec![];
+    }
+
+    let mut display_ids: Vec<DisplayId> = state.displays.keys().copied().collect();
+    display_ids.sort();
+
+    let mut moves = Vec::new();
+    for display_id in display_ids {
+        let window_ids: Vec<WindowId> = visible_windows_on_display(state, display_id)
+            .iter()
+            .map(|w| w.id)
+            .collect();
+        for (i, window_id) in window_ids.iter().enumerate() {
+            if let Some(window) = state.windows.get_mut(window_id) {
+                window.tags = Tag::new(targets[i % targets.len()]);
+            }
+        }
+        tracing::info!(
+            "Distributed {} windows of display {} over tags {:?}",
+            window_ids.len(),
+            display_id,
+            targets
+        );
+        moves.extend(compute_layout_changes_for_display(state, display_id));
+    }
+    moves
+}
+
+pub fn toggle_focused_window_tags(state: &mut State, tags: u64) -> Vec<WindowMove> {
     let Some(focused_id) = state.focused else {
         return vec![];
     };
+    if state.config.exclusive_tags {
+        return move_focused_to_tags(state, tags);
+    }
     let tag = Tag::from_mask(tags);
     let display_id = if let Some(window) = state.windows.get_mut(&focused_id) {
         let new_tags = window.tags.toggle(tag);
@@ -152,3 +466,85 @@ pub fn toggle_focused_float(state: &mut State) -> Option<(DisplayId, bool, Windo
 
     Some((window.display_id, window.is_floating, window.id, window.pid))
 }
+
+/// Float every managed window (enabled), or put the windows float-all floated back
+/// into tiling. Windows that were already floating are left alone either way.
+/// Returns the displays whose tiled windows changed.
+pub fn set_float_all(state: &mut State, enabled: bool) -> Vec<DisplayId> {
+    let changed: Vec<WindowId> = if enabled {
+        if state.float_all_restore.is_some() {
+            return vec![];
+        }
+        let ids: Vec<WindowId> = state
+            .windows
+            .values_mut()
+            .filter(|w| !w.is_floating)
+            .map(|w| {
+                w.is_floating = true;
+                w.id
+            })
+            .collect();
+        state.float_all_restore = Some(ids.clone());
+        ids
+    } else {
+        let Some(ids) = state.float_all_restore.take() else {
+            return vec![];
+        };
+        ids.into_iter()
+            .filter(|id| match state.windows.get_mut(id) {
+                Some(window) => {
+                    window.is_floating = false;
+                    true
+                }
+                None => false,
+            })
+            .collect()
+    };
+    tracing::info!("Float all {}: {} windows", enabled, changed.len());
+
+    let mut displays: Vec<DisplayId> = changed
+        .iter()
+        .filter_map(|id| state.windows.get(id).map(|w| w.display_id))
+        .collect();
+    displays.sort();
+    displays.dedup();
+    displays
+}
+
+/// Toggle `keep_native_size` for a window (the focused one if None).
+/// Returns the window's display and the new value.
+pub fn toggle_native_size(
+    state: &mut State,
+    window_id: Option<WindowId>,
+) -> Option<(DisplayId, bool)> {
+    let window_id = window_id.or(state.focused)?;
+    let window = state.windows.get_mut(&window_id)?;
+
+    window.keep_native_size = !window.keep_native_size;
+    tracing::info!(
+        "Toggle native size for window {}: {} ({}x{})",
+        window.id,
+        window.keep_native_size,
+        window.native_size.0,
+        window.native_size.1
+    );
+
+    Some((window.display_id, window.keep_native_size))
+}
+
+pub fn toggle_always_on_top(
+    state: &mut State,
+    window_id: Option<WindowId>,
+) -> Option<(WindowId, i32, bool)> {
+    let window_id = window_id.or(state.focused)?;
+    let window = state.windows.get_mut(&window_id)?;
+
+    window.is_always_on_top = !window.is_always_on_top;
+    tracing::info!(
+        "Toggle always-on-top for window {}: {}",
+        window.id,
+        window.is_always_on_top
+    );
+
+    Some((window.id, window.pid, window.is_always_on_top))
+}
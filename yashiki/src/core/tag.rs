@@ -1,17 +1,25 @@
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct Tag(u32);
+pub struct Tag(u64);
 
 impl Tag {
+    /// Highest tag number; one bit per tag in the u64 mask
+    pub const MAX: u32 = 64;
+
     pub fn new(n: u32) -> Self {
-        assert!(n > 0 && n <= 32);
+        assert!(n > 0 && n <= Self::MAX);
         Self(1 << (n - 1))
     }
 
-    pub fn from_mask(mask: u32) -> Self {
+    /// Like `new`, but returns None when `n` is outside 1..=64
+    pub fn try_new(n: u32) -> Option<Self> {
+        (1..=Self::MAX).contains(&n).then(|| Self::new(n))
+    }
+
+    pub fn from_mask(mask: u64) -> Self {
         Self(mask)
     }
 
-    pub fn mask(self) -> u32 {
+    pub fn mask(self) -> u64 {
         self.0
     }
 
@@ -23,13 +31,23 @@ impl Tag {
         Self(self.0 ^ other.0)
     }
 
-    /// Returns the tag number (1-32) of the lowest set bit, or None if empty
+    /// Returns the tag number (1-64) of the lowest set bit, or None if empty
     pub fn first_tag(self) -> Option<u32> {
         if self.0 == 0 {
             return None;
         }
         Some(self.0.trailing_zeros() + 1)
     }
+
+    /// Keep only the lowest set bit (empty stays empty)
+    pub fn lowest(self) -> Self {
+        Self(self.0 & self.0.wrapping_neg())
+    }
+
+    /// Tag numbers (1-64) of the set bits, lowest first
+    pub fn iter_tags(self) -> impl Iterator<Item = u32> {
+        (1..=Self::MAX).filter(move |&n| self.0 & (1 << (n - 1)) != 0)
+    }
 }
 
 #[cfg(test)]
@@ -43,6 +61,7 @@ mod tests {
         assert_eq!(Tag::new(3).mask(), 0b0100);
         assert_eq!(Tag::new(4).mask(), 0b1000);
         assert_eq!(Tag::new(32).mask(), 1 << 31);
+        assert_eq!(Tag::new(64).mask(), 1 << 63);
     }
 
     #[test]
@@ -53,8 +72,19 @@ mod tests {
 
     #[test]
     #[should_panic]
-    fn test_new_panics_on_33() {
-        Tag::new(33);
+    fn test_new_panics_on_65() {
+        Tag::new(65);
+    }
+
+    #[test]
+    fn test_try_new() {
+        assert_eq!(Tag::try_new(1), Some(Tag::new(1)));
+        assert_eq!(Tag::try_new(3).map(Tag::mask), Some(0b0100));
+        assert_eq!(Tag::try_new(32).map(Tag::mask), Some(1 << 31));
+        assert_eq!(Tag::try_new(0), None);
+        assert_eq!(Tag::try_new(33).map(Tag::mask), Some(1 << 32));
+        assert_eq!(Tag::try_new(64).map(Tag::mask), Some(1 << 63));
+        assert_eq!(Tag::try_new(65), None);
     }
 
     #[test]
@@ -98,6 +128,26 @@ mod tests {
         assert_eq!(Tag::new(3).toggle(Tag::new(4)).first_tag(), Some(3));
     }
 
+    #[test]
+    fn test_lowest() {
+        assert_eq!(Tag::from_mask(0b1010).lowest(), Tag::new(2));
+        assert_eq!(Tag::new(32).lowest(), Tag::new(32));
+        assert_eq!(Tag::from_mask(0).lowest().mask(), 0);
+    }
+
+    #[test]
+    fn test_iter_tags() {
+        assert_eq!(
+            Tag::from_mask(0b1010).iter_tags().collect::<Vec<_>>(),
+            vec![2, 4]
+        );
+        assert_eq!(
+            Tag::from_mask(1 | 1 << 31).iter_tags().collect::<Vec<_>>(),
+            vec![1, 32]
+        );
+        assert_eq!(Tag::from_mask(0).iter_tags().count(), 0);
+    }
+
     #[test]
     fn test_equality() {
         assert_eq!(Tag::new(1), Tag::new(1));
@@ -25,6 +25,17 @@ pub struct Window {
     pub saved_frame: Option<Rect>,
     pub is_floating: bool,
     pub is_fullscreen: bool,
+    /// Re-raised above tiled windows whenever focus changes
+    pub is_always_on_top: bool,
+    /// Minimized to the Dock: still tracked, but has no on-screen geometry
+    pub is_minimized: bool,
+    /// Display-relative tiled slot while enlarged by `window-peek` (None when not peeking)
+    pub peek_slot: Option<Rect>,
+    /// Size the window had when first seen, used as its preferred size. For windows
+    /// already tiled before yashiki started this is their old tile size.
+    pub native_size: (u32, u32),
+    /// Keep `native_size`, centered in the tile, instead of filling the tile
+    pub keep_native_size: bool,
     /// Display ID that this window was orphaned from during display disconnection.
     /// Some(display_id): Window was orphaned due to display removal (remembers original display)
     /// None: Window is on its intended display
@@ -52,6 +63,11 @@ impl Window {
             saved_frame: None,
             is_floating: false,
             is_fullscreen: false,
+            is_always_on_top: false,
+            is_minimized: false,
+            peek_slot: None,
+            native_size: (info.bounds.width as u32, info.bounds.height as u32),
+            keep_native_size: false,
             orphaned_from: None,
         }
     }
@@ -105,6 +121,13 @@ impl Rect {
         }
     }
 
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x
+            && x < self.x + self.width as i32
+            && y >= self.y
+            && y < self.y + self.height as i32
+    }
+
     pub fn center(&self) -> (i32, i32) {
         (
             self.x + self.width as i32 / 2,
@@ -0,0 +1,143 @@
+use super::{Rect, WindowId};
+
+/// Windows are never resized below this by a drag, in either dimension.
+pub const MIN_DRAG_SIZE: u32 = 50;
+
+/// What a modifier+drag does to the window under the cursor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DragKind {
+    /// Left-drag: move the window with the cursor
+    Move,
+    /// Right-drag: grow or shrink the window from its bottom-right corner
+    Resize,
+}
+
+/// A running modifier+drag of a floating window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WindowDrag {
+    pub window_id: WindowId,
+    pub pid: i32,
+    pub kind: DragKind,
+    /// Cursor position when the drag started
+    pub origin: (i32, i32),
+    /// Window frame when the drag started
+    pub start_frame: Rect,
+}
+
+/// A visible window as seen by the drag event tap.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DragTarget {
+    pub frame: Rect,
+    /// Frame of the display the window belongs to; clicks outside it miss the window
+    pub display_frame: Rect,
+    /// Managed floating window that a drag can start on
+    pub draggable: bool,
+}
+
+/// Front-to-back snapshot of the visible windows, published for the drag event tap.
+/// The tap has to decide whether to consume a click before the main thread sees it,
+/// so it can't consult `State` directly.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DragTargets(pub Vec<DragTarget>);
+
+impl DragTargets {
+    /// Whether the topmost window at (x, y) can be dragged.
+    pub fn accepts(&self, x: i32, y: i32) -> bool {
+        self.0
+            .iter()
+            .find(|t| t.display_frame.contains(x, y) && t.frame.contains(x, y))
+            .is_some_and(|t| t.draggable)
+    }
+}
+
+impl WindowDrag {
+    /// Window frame for the cursor at (x, y): the start frame offset (Move) or
+    /// stretched (Resize) by how far the cursor has travelled since the drag started.
+    pub fn frame_at(&self, x: i32, y: i32) -> Rect {
+        let dx = x - self.origin.0;
+        let dy = y - self.origin.1;
+        let f = &self.start_frame;
+        match self.kind {
+            DragKind::Move => Rect {
+                x: f.x + dx,
+                y: f.y + dy,
+                ..*f
+            },
+            DragKind::Resize => Rect {
+                width: (f.width as i64 + dx as i64).max(MIN_DRAG_SIZE as i64) as u32,
+                height: (f.height as i64 + dy as i64).max(MIN_DRAG_SIZE as i64) as u32,
+                ..*f
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drag(kind: DragKind) -> WindowDrag {
+        WindowDrag {
+            window_id: 100,
+            pid: 1000,
+            kind,
+            origin: (500, 400),
+            start_frame: Rect {
+                x: 300,
+                y: 200,
+                width: 600,
+                height: 400,
+            },
+        }
+    }
+
+    #[test]
+    fn test_targets_accept_only_topmost_draggable_window() {
+        let display_frame = Rect {
+            x: 0,
+            y: 0,
+            width: 1920,
+            height: 1080,
+        };
+        let target = |x, width, draggable| DragTarget {
+            frame: Rect {
+                x,
+                y: 0,
+                width,
+                height: 1080,
+            },
+            display_frame,
+            draggable,
+        };
+        // A tiled window in front of the left half of a floating one
+        let targets = DragTargets(vec![target(0, 500, false), target(0, 1000, true)]);
+
+        assert!(!targets.accepts(200, 500));
+        assert!(targets.accepts(700, 500));
+        assert!(!targets.accepts(1500, 500));
+        assert!(!DragTargets::default().accepts(700, 500));
+    }
+
+    #[test]
+    fn test_move_follows_cursor_delta() {
+        let drag = drag(DragKind::Move);
+        assert_eq!(drag.frame_at(500, 400), drag.start_frame);
+
+        let frame = drag.frame_at(450, 520);
+        assert_eq!((frame.x, frame.y), (250, 320));
+        assert_eq!((frame.width, frame.height), (600, 400));
+    }
+
+    #[test]
+    fn test_resize_keeps_origin_and_clamps_size() {
+        let drag = drag(DragKind::Resize);
+
+        let frame = drag.frame_at(600, 350);
+        assert_eq!((frame.x, frame.y), (300, 200));
+        assert_eq!((frame.width, frame.height), (700, 350));
+
+        // Dragging past the top-left corner stops at the minimum size
+        let frame = drag.frame_at(-2000, -2000);
+        assert_eq!((frame.width, frame.height), (MIN_DRAG_SIZE, MIN_DRAG_SIZE));
+    }
+}
@@ -0,0 +1,83 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::WindowId;
+
+/// Manual decision from `manage-window` / `unmanage-window`, overriding rules and the
+/// window level filter. The app name guards against window IDs reused after a reboot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WindowOverride {
+    pub id: WindowId,
+    pub app_name: String,
+    pub managed: bool,
+}
+
+/// File overrides are persisted to (~/.config/yashiki/window-overrides.json).
+pub fn window_overrides_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|dir| {
+        dir.join(".config")
+            .join("yashiki")
+            .join("window-overrides.json")
+    })
+}
+
+pub fn save_window_overrides(path: &Path, overrides: &[WindowOverride]) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create directory {:?}: {}", dir, e))?;
+    }
+    let json = serde_json::to_string_pretty(overrides)
+        .map_err(|e| format!("Failed to serialize window overrides: {}", e))?;
+    std::fs::write(path, json).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
+
+/// Load persisted overrides. A missing or unreadable file yields no overrides.
+pub fn load_window_overrides(path: &Path) -> Vec<WindowOverride> {
+    let Ok(json) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    match serde_json::from_str(&json) {
+        Ok(overrides) => overrides,
+        Err(e) => {
+            tracing::warn!("Failed to load window overrides {:?}: {}", path, e);
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_load_window_overrides() {
+        let path = std::env::temp_dir()
+            .join(format!("yashiki-overrides-{}", std::process::id()))
+            .join("window-overrides.json");
+        let overrides = vec![
+            WindowOverride {
+                id: 100,
+                app_name: "Raycast".to_string(),
+                managed: true,
+            },
+            WindowOverride {
+                id: 101,
+                app_name: "Terminal".to_string(),
+                managed: false,
+            },
+        ];
+
+        save_window_overrides(&path, &overrides).unwrap();
+        let loaded = load_window_overrides(&path);
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+        assert_eq!(loaded, overrides);
+    }
+
+    #[test]
+    fn test_load_missing_window_overrides_is_empty() {
+        let path = std::env::temp_dir().join("yashiki-no-such-overrides.json");
+        assert!(load_window_overrides(&path).is_empty());
+    }
+}
@@ -1,4 +1,4 @@
-use crate::core::WindowMove;
+use crate::core::{LayoutDefaults, Snapshot, WindowMove, WindowOverride};
 use crate::macos::DisplayId;
 
 use yashiki_ipc::Response;
@@ -23,10 +23,31 @@ pub enum Effect {
         width: u32,
         height: u32,
     },
+    /// Resize a dragged window. The origin is set along with the size so the
+    /// corner opposite the cursor stays put even if the app adjusts the size.
+    ResizeWindow {
+        window_id: u32,
+        pid: i32,
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+    },
     CloseWindow {
         window_id: u32,
         pid: i32,
     },
+    /// Minimize to or restore from the Dock. `is_minimized` is updated by sync
+    /// once the app reports the change.
+    SetWindowMinimized {
+        window_id: u32,
+        pid: i32,
+        minimized: bool,
+    },
+    RaiseWindow {
+        window_id: u32,
+        pid: i32,
+    },
     ApplyFullscreen {
         window_id: u32,
         pid: i32,
@@ -39,6 +60,23 @@ pub enum Effect {
         cmd: String,
         args: Vec<String>,
     },
+    /// Send a command to each of `layouts`; failures are reported together
+    /// after the remaining effects have run.
+    BroadcastLayoutCommand {
+        layouts: Vec<String>,
+        cmd: String,
+        args: Vec<String>,
+    },
+    /// Ask a layout engine to `zoom` a window into its main area. Engines without
+    /// `zoom` are skipped; they take the main window from the window order.
+    ZoomLayoutWindow {
+        layout: String,
+        window_id: u32,
+    },
+    /// Zero the layout's inner gap, or restore the one saved by the previous toggle
+    ToggleInnerGap {
+        layout: String,
+    },
     ExecCommand {
         command: String,
         path: String,
@@ -50,10 +88,31 @@ pub enum Effect {
     UpdateLayoutExecPath {
         path: String,
     },
+    UpdateLayoutDefaults {
+        defaults: LayoutDefaults,
+    },
+    SetLayoutAlias {
+        alias: String,
+        target: String,
+    },
     FocusVisibleWindowIfNeeded,
     WarpCursorToDisplay {
         display_id: DisplayId,
     },
+    SetMainDisplay {
+        display_id: DisplayId,
+    },
+    PersistSnapshot {
+        name: String,
+        snapshot: Snapshot,
+    },
+    PersistWindowOverrides {
+        overrides: Vec<WindowOverride>,
+    },
+    WriteBindingsExport {
+        path: String,
+        content: String,
+    },
 }
 
 pub struct CommandResult {
@@ -51,9 +51,9 @@ impl EventEmitter {
     }
 
     /// Emit a display added event
-    pub fn emit_display_added(&self, display: &Display, focused_display: u32) {
+    pub fn emit_display_added(&self, display: &Display, state: &State) {
         self.emit(StateEvent::DisplayAdded {
-            display: display_to_info(display, focused_display),
+            display: display_to_info(display, state),
         });
     }
 
@@ -64,14 +64,14 @@ impl EventEmitter {
 
     /// Emit a display updated event
     #[allow(dead_code)]
-    pub fn emit_display_updated(&self, display: &Display, focused_display: u32) {
+    pub fn emit_display_updated(&self, display: &Display, state: &State) {
         self.emit(StateEvent::DisplayUpdated {
-            display: display_to_info(display, focused_display),
+            display: display_to_info(display, state),
         });
     }
 
     /// Emit a tags changed event
-    pub fn emit_tags_changed(&self, display_id: u32, visible_tags: u32, previous_tags: u32) {
+    pub fn emit_tags_changed(&self, display_id: u32, visible_tags: u64, previous_tags: u64) {
         self.emit(StateEvent::TagsChanged {
             display_id,
             visible_tags,
@@ -99,7 +99,7 @@ pub fn create_snapshot(state: &State) -> StateEvent {
     let displays: Vec<OutputInfo> = state
         .displays
         .values()
-        .map(|d| display_to_info(d, state.focused_display))
+        .map(|d| display_to_info(d, state))
         .collect();
 
     StateEvent::Snapshot {
@@ -140,8 +140,8 @@ pub fn window_to_info(window: &Window, focused: Option<u32>) -> WindowInfo {
     }
 }
 
-/// Convert a Display to OutputInfo
-pub fn display_to_info(display: &Display, focused_display: u32) -> OutputInfo {
+/// Convert a Display to OutputInfo, naming its visible tags from `state`
+pub fn display_to_info(display: &Display, state: &State) -> OutputInfo {
     OutputInfo {
         id: display.id,
         name: display.name.clone(),
@@ -151,7 +151,8 @@ pub fn display_to_info(display: &Display, focused_display: u32) -> OutputInfo {
         height: display.frame.height,
         is_main: display.is_main,
         visible_tags: display.visible_tags.mask(),
-        is_focused: focused_display == display.id,
+        visible_tag_names: state.tag_names_of(display.visible_tags),
+        is_focused: state.focused_display == display.id,
     }
 }
 
@@ -185,6 +186,11 @@ mod tests {
             saved_frame: None,
             is_floating: false,
             is_fullscreen: false,
+            is_always_on_top: false,
+            is_minimized: false,
+            peek_slot: None,
+            native_size: (800, 600),
+            keep_native_size: false,
             orphaned_from: None,
         }
     }
@@ -289,16 +295,34 @@ mod tests {
     #[test]
     fn test_display_to_info_focused() {
         let display = create_test_display(1, "Main Display", true);
+        let mut state = State::new();
+        state.focused_display = 1;
 
         // When display is focused
-        let info = display_to_info(&display, 1);
+        let info = display_to_info(&display, &state);
         assert!(info.is_focused);
         assert_eq!(info.id, 1);
         assert_eq!(info.name, "Main Display");
         assert!(info.is_main);
 
         // When different display is focused
-        let info = display_to_info(&display, 2);
+        state.focused_display = 2;
+        let info = display_to_info(&display, &state);
         assert!(!info.is_focused);
     }
+
+    #[test]
+    fn test_display_to_info_names_visible_tags() {
+        let display = create_test_display(1, "Main Display", true);
+        let mut state = State::new();
+        assert!(display_to_info(&display, &state)
+            .visible_tag_names
+            .is_empty());
+
+        state.tag_names.insert(1, "web".to_string());
+        state.tag_names.insert(2, "mail".to_string());
+        let info = display_to_info(&display, &state);
+        assert_eq!(info.visible_tags, 0b1);
+        assert_eq!(info.visible_tag_names, vec!["web"]);
+    }
 }
@@ -64,6 +64,18 @@ impl EventClient {
     }
 }
 
+/// Blocking stream of events; ends when the daemon closes the connection.
+impl Iterator for EventClient {
+    type Item = Result<StateEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_event() {
+            Err(e) if e.to_string().contains("Connection closed") => None,
+            result => Some(result),
+        }
+    }
+}
+
 /// Subscribe and print events to stdout
 pub fn subscribe_and_print(snapshot: bool, filter: Option<EventFilter>) -> Result<()> {
     let request = SubscribeRequest {
@@ -71,21 +83,9 @@ pub fn subscribe_and_print(snapshot: bool, filter: Option<EventFilter>) -> Resul
         filter: filter.unwrap_or_default(),
     };
 
-    let mut client = EventClient::connect(&request)?;
-
-    loop {
-        match client.next_event() {
-            Ok(event) => {
-                let json = serde_json::to_string(&event)?;
-                println!("{}", json);
-            }
-            Err(e) => {
-                if e.to_string().contains("Connection closed") {
-                    break;
-                }
-                return Err(e);
-            }
-        }
+    for event in EventClient::connect(&request)? {
+        let json = serde_json::to_string(&event?)?;
+        println!("{}", json);
     }
 
     Ok(())
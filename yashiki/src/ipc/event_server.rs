@@ -1,4 +1,6 @@
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
 use anyhow::Result;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
@@ -7,23 +9,42 @@ use tokio::sync::broadcast;
 
 use yashiki_ipc::{StateEvent, SubscribeRequest};
 
-const EVENT_SOCKET_PATH: &str = "/tmp/yashiki-events.sock";
+pub(super) const EVENT_SOCKET_PATH: &str = "/tmp/yashiki-events.sock";
+
+/// Decrements the subscriber count when a subscriber connection ends.
+struct SubscriberGuard(Arc<AtomicUsize>);
+
+impl SubscriberGuard {
+    fn new(count: Arc<AtomicUsize>) -> Self {
+        count.fetch_add(1, Ordering::SeqCst);
+        Self(count)
+    }
+}
+
+impl Drop for SubscriberGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
 
 pub struct EventServer {
     socket_path: PathBuf,
     event_rx: broadcast::Receiver<StateEvent>,
     snapshot_tx: tokio::sync::mpsc::Sender<tokio::sync::oneshot::Sender<StateEvent>>,
+    subscriber_count: Arc<AtomicUsize>,
 }
 
 impl EventServer {
     pub fn new(
         event_rx: broadcast::Receiver<StateEvent>,
         snapshot_tx: tokio::sync::mpsc::Sender<tokio::sync::oneshot::Sender<StateEvent>>,
+        subscriber_count: Arc<AtomicUsize>,
     ) -> Self {
         Self {
             socket_path: PathBuf::from(EVENT_SOCKET_PATH),
             event_rx,
             snapshot_tx,
+            subscriber_count,
         }
     }
 
@@ -41,7 +62,9 @@ impl EventServer {
                 Ok((stream, _addr)) => {
                     let event_rx = self.event_rx.resubscribe();
                     let snapshot_tx = self.snapshot_tx.clone();
+                    let guard = SubscriberGuard::new(Arc::clone(&self.subscriber_count));
                     tokio::spawn(async move {
+                        let _guard = guard;
                         if let Err(e) = Self::handle_connection(stream, event_rx, snapshot_tx).await
                         {
                             // Only log if it's not a normal disconnection
@@ -5,3 +5,9 @@ mod server;
 pub use client::{subscribe_and_print, IpcClient};
 pub use event_server::{EventBroadcaster, EventServer};
 pub use server::IpcServer;
+
+/// Remove the IPC and event socket files.
+pub fn remove_sockets() {
+    let _ = std::fs::remove_file(server::SOCKET_PATH);
+    let _ = std::fs::remove_file(event_server::EVENT_SOCKET_PATH);
+}
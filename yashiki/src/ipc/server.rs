@@ -7,6 +7,8 @@ use tokio::sync::mpsc;
 
 use yashiki_ipc::{Command, Response};
 
+pub(super) const SOCKET_PATH: &str = "/tmp/yashiki.sock";
+
 pub struct IpcServer {
     socket_path: PathBuf,
     cmd_tx: mpsc::Sender<(Command, mpsc::Sender<Response>)>,
@@ -15,7 +17,7 @@ pub struct IpcServer {
 impl IpcServer {
     pub fn new(cmd_tx: mpsc::Sender<(Command, mpsc::Sender<Response>)>) -> Self {
         Self {
-            socket_path: PathBuf::from("/tmp/yashiki.sock"),
+            socket_path: PathBuf::from(SOCKET_PATH),
             cmd_tx,
         }
     }
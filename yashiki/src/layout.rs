@@ -1,11 +1,17 @@
-use std::collections::HashMap;
-use std::io::{BufRead, BufReader, Write};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::io::{BufRead, BufReader, Read, Write};
 use std::path::PathBuf;
 use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
 
 use yashiki_ipc::layout::{LayoutMessage, LayoutResult, WindowGeometry};
+use yashiki_ipc::LayoutInfo;
+
+use crate::core::LayoutDefaults;
 
 fn find_layout_engine(name: &str) -> Option<PathBuf> {
     let command_name = format!("yashiki-layout-{}", name);
@@ -41,18 +47,144 @@ fn find_layout_engine(name: &str) -> Option<PathBuf> {
     None
 }
 
+/// Layout names become part of the engine's command name (`yashiki-layout-<name>`).
+pub fn is_valid_layout_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Layout alias name -> engine name, set by `layout-alias`.
+pub type LayoutAliases = BTreeMap<String, String>;
+
+/// Engine to run for layout `name`: its alias target, or the name itself.
+fn resolve_layout_alias<'a>(aliases: &'a LayoutAliases, name: &'a str) -> &'a str {
+    aliases.get(name).map_or(name, String::as_str)
+}
+
+/// Format a layout engine error for a command, calling out commands the engine doesn't
+/// support. `commands` is what the engine reported for Describe, if it could.
+fn command_error_message(
+    name: &str,
+    cmd: &str,
+    message: &str,
+    commands: Option<&[String]>,
+) -> String {
+    match commands {
+        Some(commands) if !commands.iter().any(|c| c == cmd) => {
+            format!("Layout '{}' does not support '{}'", name, cmd)
+        }
+        _ => format!("Layout engine error: {}", message),
+    }
+}
+
+/// Parse the value an engine reports for `get-main-ratio`.
+pub fn parse_main_ratio(name: &str, value: &str) -> Result<f64, String> {
+    match value.trim().parse::<f64>() {
+        Ok(ratio) if (0.0..=1.0).contains(&ratio) => Ok(ratio),
+        _ => Err(format!(
+            "Invalid main ratio from layout '{}': {}",
+            name,
+            value.trim()
+        )),
+    }
+}
+
+/// Number of stderr lines kept per layout engine for `layout-engine-log`.
+const STDERR_LOG_LINES: usize = 200;
+
+type StderrLog = Arc<Mutex<VecDeque<String>>>;
+
+/// Append each line from `reader` to `log`, dropping the oldest past
+/// `STDERR_LOG_LINES`. Lines are also traced so they show up in the daemon log.
+fn record_stderr_lines(name: &str, reader: impl Read, log: &StderrLog) {
+    for line in BufReader::new(reader).lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        tracing::warn!("[layout {}] {}", name, line);
+        let mut log = log.lock().unwrap();
+        if log.len() == STDERR_LOG_LINES {
+            log.pop_front();
+        }
+        log.push_back(line);
+    }
+}
+
+/// The last `count` lines of `log`, oldest first.
+fn tail_lines(log: &StderrLog, count: usize) -> Vec<String> {
+    let log = log.lock().unwrap();
+    log.iter()
+        .skip(log.len().saturating_sub(count))
+        .cloned()
+        .collect()
+}
+
+/// How long an engine gets to exit after SIGTERM before it is killed.
+const SHUTDOWN_GRACE: Duration = Duration::from_millis(500);
+
+/// Ask `child` to exit with SIGTERM, falling back to SIGKILL once `grace`
+/// has passed, and reap it so no zombie or orphan is left behind.
+/// Only SIGTERM is sent before returning; the rest happens on a thread whose
+/// handle yields the reaped child.
+fn terminate_child(mut child: Child, grace: Duration) -> JoinHandle<Child> {
+    let running = request_exit(&mut child);
+    std::thread::spawn(move || {
+        if running {
+            reap_child(&mut child, grace);
+        }
+        child
+    })
+}
+
+/// Send SIGTERM to `child`. Returns false if it had already exited.
+fn request_exit(child: &mut Child) -> bool {
+    if matches!(child.try_wait(), Ok(Some(_))) {
+        return false;
+    }
+    unsafe {
+        libc::kill(child.id() as libc::pid_t, libc::SIGTERM);
+    }
+    true
+}
+
+/// Wait up to `grace` for `child` to exit, then SIGKILL and reap it.
+fn reap_child(child: &mut Child, grace: Duration) {
+    let deadline = Instant::now() + grace;
+    while Instant::now() < deadline {
+        match child.try_wait() {
+            Ok(Some(_)) => return,
+            Ok(None) => std::thread::sleep(Duration::from_millis(10)),
+            Err(_) => break,
+        }
+    }
+
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
 pub struct LayoutEngine {
-    // Keep process alive until this struct is dropped
-    _child: Child,
+    name: String,
+    // Taken, terminated and reaped in the background when this struct is dropped
+    child: Option<Child>,
     stdin: ChildStdin,
     stdout: BufReader<ChildStdout>,
+    stderr_log: StderrLog,
+    /// Commands reported for Describe, asked on the first command error.
+    /// `Some(None)` if the engine could not describe itself.
+    commands: Option<Option<Vec<String>>>,
+    /// Inner gap set on or reported by the engine, so retiles need not ask for it.
+    /// None while unknown, `Some(None)` if the engine has no inner gap.
+    inner_gap: Option<Option<u32>>,
 }
 
 impl LayoutEngine {
-    pub fn spawn(name: &str, exec_path: &str) -> Result<Self> {
-        let command_name = format!("yashiki-layout-{}", name);
+    pub fn spawn(name: &str, exec_path: &str, aliases: &LayoutAliases) -> Result<Self> {
+        let engine_name = resolve_layout_alias(aliases, name);
+        let command_name = format!("yashiki-layout-{}", engine_name);
 
-        let mut cmd = if let Some(path) = find_layout_engine(name) {
+        let mut cmd = if let Some(path) = find_layout_engine(engine_name) {
             // Found in bundle or exe directory
             Command::new(path)
         } else {
@@ -67,22 +199,44 @@ impl LayoutEngine {
         let mut child = cmd
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
-            .stderr(Stdio::inherit())
+            .stderr(Stdio::piped())
             .spawn()
             .with_context(|| format!("Failed to spawn layout engine: {}", command_name))?;
 
         let stdin = child.stdin.take().context("Failed to get stdin")?;
         let stdout = child.stdout.take().context("Failed to get stdout")?;
+        let stderr = child.stderr.take().context("Failed to get stderr")?;
+
+        // Drain stderr on a thread so a chatty engine never blocks on a full pipe
+        let stderr_log = StderrLog::default();
+        {
+            let name = name.to_string();
+            let log = Arc::clone(&stderr_log);
+            std::thread::spawn(move || record_stderr_lines(&name, stderr, &log));
+        }
 
-        tracing::info!("Layout engine '{}' spawned", command_name);
+        if engine_name == name {
+            tracing::info!("Layout engine '{}' spawned", command_name);
+        } else {
+            tracing::info!("Layout engine '{}' spawned as '{}'", command_name, name);
+        }
 
         Ok(Self {
-            _child: child,
+            name: name.to_string(),
+            child: Some(child),
             stdin,
             stdout: BufReader::new(stdout),
+            stderr_log,
+            commands: None,
+            inner_gap: None,
         })
     }
 
+    /// The last `count` lines the engine wrote to stderr, oldest first.
+    pub fn stderr_lines(&self, count: usize) -> Vec<String> {
+        tail_lines(&self.stderr_log, count)
+    }
+
     pub fn request_layout(
         &mut self,
         width: u32,
@@ -102,8 +256,12 @@ impl LayoutEngine {
             LayoutResult::Error { message } => {
                 anyhow::bail!("Layout engine error: {}", message)
             }
-            LayoutResult::Ok | LayoutResult::NeedsRetile => {
-                anyhow::bail!("Unexpected 'ok' or 'needs_retile' response for layout request")
+            LayoutResult::Ok
+            | LayoutResult::NeedsRetile
+            | LayoutResult::Value { .. }
+            | LayoutResult::LastInput { .. }
+            | LayoutResult::Capabilities { .. } => {
+                anyhow::bail!("Unexpected command response for layout request")
             }
         }
     }
@@ -117,19 +275,100 @@ impl LayoutEngine {
         };
 
         let result = self.send(&msg)?;
+        if !matches!(result, LayoutResult::Error { .. }) {
+            self.track_inner_gap(cmd, args);
+        }
 
         match result {
-            LayoutResult::Ok => Ok(false),
+            LayoutResult::Ok | LayoutResult::Value { .. } => Ok(false),
             LayoutResult::NeedsRetile => Ok(true),
             LayoutResult::Error { message } => {
-                anyhow::bail!("Layout engine error: {}", message)
+                anyhow::bail!(self.command_error(cmd, &message))
             }
-            LayoutResult::Layout { .. } => {
+            LayoutResult::Layout { .. }
+            | LayoutResult::LastInput { .. }
+            | LayoutResult::Capabilities { .. } => {
                 anyhow::bail!("Unexpected 'layout' response for command")
             }
         }
     }
 
+    /// Send a query command to the layout engine and return the value it reports.
+    pub fn query(&mut self, cmd: &str, args: &[String]) -> Result<String> {
+        let msg = LayoutMessage::Command {
+            cmd: cmd.to_string(),
+            args: args.to_vec(),
+        };
+
+        match self.send(&msg)? {
+            LayoutResult::Value { value } => Ok(value),
+            LayoutResult::Error { message } => {
+                anyhow::bail!(self.command_error(cmd, &message))
+            }
+            _ => anyhow::bail!("Layout engine returned no value for '{}'", cmd),
+        }
+    }
+
+    /// The engine's inner gap, asked only when not known from earlier commands.
+    /// None if the engine has no inner gap.
+    pub fn inner_gap(&mut self) -> Option<u32> {
+        if let Some(gap) = self.inner_gap {
+            return gap;
+        }
+        let gap = self
+            .query("get-inner-gap", &[])
+            .ok()
+            .and_then(|value| value.trim().parse::<u32>().ok());
+        self.inner_gap = Some(gap);
+        gap
+    }
+
+    /// Keep the cached inner gap in step with a command the engine accepted.
+    fn track_inner_gap(&mut self, cmd: &str, args: &[String]) {
+        match cmd {
+            "set-inner-gap" => {
+                self.inner_gap = args.first().and_then(|s| s.parse().ok()).map(Some);
+            }
+            "inc-inner-gap" | "dec-inner-gap" | "reset" => self.inner_gap = None,
+            _ => {}
+        }
+    }
+
+    /// The most recent layout request the engine processed: (width, height, window ids).
+    pub fn last_input(&mut self) -> Result<(u32, u32, Vec<u32>)> {
+        match self.send(&LayoutMessage::LastInput)? {
+            LayoutResult::LastInput {
+                width,
+                height,
+                windows,
+            } => Ok((width, height, windows)),
+            LayoutResult::Error { message } => {
+                anyhow::bail!("Layout engine '{}' error: {}", self.name, message)
+            }
+            _ => anyhow::bail!("Unexpected response to last-input request"),
+        }
+    }
+
+    /// The engine's canonical name and the commands it accepts.
+    pub fn describe(&mut self) -> Result<(String, Vec<String>)> {
+        match self.send(&LayoutMessage::Describe)? {
+            LayoutResult::Capabilities { name, commands } => Ok((name, commands)),
+            LayoutResult::Error { message } => {
+                anyhow::bail!("Layout engine '{}' error: {}", self.name, message)
+            }
+            _ => anyhow::bail!("Unexpected response to describe request"),
+        }
+    }
+
+    /// Error for a failed command, naming it as unsupported if the engine doesn't list it.
+    fn command_error(&mut self, cmd: &str, message: &str) -> String {
+        if self.commands.is_none() {
+            self.commands = Some(self.describe().ok().map(|(_, commands)| commands));
+        }
+        let commands = self.commands.as_ref().and_then(|c| c.as_deref());
+        command_error_message(&self.name, cmd, message, commands)
+    }
+
     fn send(&mut self, msg: &LayoutMessage) -> Result<LayoutResult> {
         serde_json::to_writer(&mut self.stdin, msg)?;
         writeln!(self.stdin)?;
@@ -145,9 +384,30 @@ impl LayoutEngine {
     }
 }
 
+/// Push the daemon's layout defaults to a freshly spawned or reset engine. Engines
+/// that don't support a setting (e.g. byobu has no main ratio) are skipped.
+fn apply_defaults(engine: &mut LayoutEngine, defaults: &LayoutDefaults) {
+    for (cmd, args) in defaults.commands() {
+        if let Err(e) = engine.send_command(cmd, &args) {
+            tracing::debug!("Layout '{}' default not applied: {}", engine.name, e);
+        }
+    }
+}
+
+impl Drop for LayoutEngine {
+    fn drop(&mut self) {
+        if let Some(child) = self.child.take() {
+            terminate_child(child, SHUTDOWN_GRACE);
+        }
+        tracing::info!("Layout engine '{}' stopped", self.name);
+    }
+}
+
 pub struct LayoutEngineManager {
     engines: HashMap<String, LayoutEngine>,
     exec_path: String,
+    defaults: LayoutDefaults,
+    aliases: LayoutAliases,
 }
 
 impl LayoutEngineManager {
@@ -155,6 +415,8 @@ impl LayoutEngineManager {
         Self {
             engines: HashMap::new(),
             exec_path: String::new(),
+            defaults: LayoutDefaults::default(),
+            aliases: LayoutAliases::new(),
         }
     }
 
@@ -162,9 +424,45 @@ impl LayoutEngineManager {
         self.exec_path = exec_path.to_string();
     }
 
+    /// Defaults for engines spawned from now on. Running engines keep their settings.
+    pub fn set_defaults(&mut self, defaults: LayoutDefaults) {
+        self.defaults = defaults;
+    }
+
+    /// Run `yashiki-layout-<target>` for layout `alias` from now on. An engine already
+    /// running under `alias` is stopped so the next use spawns the target.
+    pub fn set_alias(&mut self, alias: &str, target: &str) {
+        self.aliases.insert(alias.to_string(), target.to_string());
+        if self.engines.remove(alias).is_some() {
+            tracing::info!("Layout '{}' restarts as alias of '{}'", alias, target);
+        }
+    }
+
+    /// `default_layout`, every running engine and every alias, sorted by name.
+    pub fn list_layouts(&self, default_layout: &str) -> Vec<LayoutInfo> {
+        let mut names: Vec<&str> = self
+            .engines
+            .keys()
+            .chain(self.aliases.keys())
+            .map(String::as_str)
+            .chain(std::iter::once(default_layout))
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+        names
+            .into_iter()
+            .map(|name| LayoutInfo {
+                name: name.to_string(),
+                alias_of: self.aliases.get(name).cloned(),
+                running: self.engines.contains_key(name),
+            })
+            .collect()
+    }
+
     pub fn get_or_spawn(&mut self, name: &str) -> Result<&mut LayoutEngine> {
         if !self.engines.contains_key(name) {
-            let engine = LayoutEngine::spawn(name, &self.exec_path)?;
+            let mut engine = LayoutEngine::spawn(name, &self.exec_path, &self.aliases)?;
+            apply_defaults(&mut engine, &self.defaults);
             self.engines.insert(name.to_string(), engine);
         }
         Ok(self.engines.get_mut(name).unwrap())
@@ -182,8 +480,59 @@ impl LayoutEngineManager {
     }
 
     pub fn send_command(&mut self, name: &str, cmd: &str, args: &[String]) -> Result<bool> {
+        // reset goes back to the engine's own defaults; the daemon's take precedence
+        let defaults = (cmd == "reset").then(|| self.defaults.clone());
+        let engine = self.get_or_spawn(name)?;
+        let needs_retile = engine.send_command(cmd, args)?;
+        if let Some(defaults) = defaults {
+            apply_defaults(engine, &defaults);
+        }
+        Ok(needs_retile)
+    }
+
+    pub fn query(&mut self, name: &str, cmd: &str, args: &[String]) -> Result<String> {
+        let engine = self.get_or_spawn(name)?;
+        engine.query(cmd, args)
+    }
+
+    /// Recent stderr output of a running engine. Does not spawn the engine.
+    pub fn stderr_lines(&self, name: &str, count: usize) -> Option<Vec<String>> {
+        self.engines
+            .get(name)
+            .map(|engine| engine.stderr_lines(count))
+    }
+
+    /// Ask a running engine for the last layout request it processed.
+    /// Returns None if the engine is not running; does not spawn it.
+    pub fn last_input(&mut self, name: &str) -> Option<Result<(u32, u32, Vec<u32>)>> {
+        self.engines.get_mut(name).map(|engine| engine.last_input())
+    }
+
+    pub fn describe(&mut self, name: &str) -> Result<(String, Vec<String>)> {
+        let engine = self.get_or_spawn(name)?;
+        engine.describe()
+    }
+
+    /// Inner gap of the engine, cached after the first ask. None if it has none.
+    pub fn inner_gap(&mut self, name: &str) -> Result<Option<u32>> {
         let engine = self.get_or_spawn(name)?;
-        engine.send_command(cmd, args)
+        Ok(engine.inner_gap())
+    }
+
+    /// Stop every running engine, waiting until each has exited or been killed.
+    /// Unlike dropping a single engine this blocks, since the daemon may exit right
+    /// after and take the reaper threads with it. Engines are spawned again on next use.
+    pub fn shutdown(&mut self) {
+        let reapers: Vec<JoinHandle<Child>> = self
+            .engines
+            .values_mut()
+            .filter_map(|engine| engine.child.take())
+            .map(|child| terminate_child(child, SHUTDOWN_GRACE))
+            .collect();
+        self.engines.clear();
+        for reaper in reapers {
+            let _ = reaper.join();
+        }
     }
 }
 
@@ -192,3 +541,369 @@ impl Default for LayoutEngineManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_error_message_unsupported_command() {
+        let commands = vec!["set-main-ratio".to_string(), "zoom".to_string()];
+        assert_eq!(
+            command_error_message(
+                "tatami",
+                "toggle-orientation",
+                "no such thing",
+                Some(&commands)
+            ),
+            "Layout 'tatami' does not support 'toggle-orientation'"
+        );
+        // Without capabilities the engine's own message is kept
+        assert_eq!(
+            command_error_message(
+                "custom",
+                "toggle-orientation",
+                "unknown command: toggle-orientation",
+                None
+            ),
+            "Layout engine error: unknown command: toggle-orientation"
+        );
+    }
+
+    #[test]
+    fn test_parse_main_ratio() {
+        assert_eq!(parse_main_ratio("tatami", "0.55"), Ok(0.55));
+        assert_eq!(parse_main_ratio("tatami", " 0.5\n"), Ok(0.5));
+        assert!(parse_main_ratio("tatami", "wide").is_err());
+        assert!(parse_main_ratio("tatami", "1.5").is_err());
+    }
+
+    #[test]
+    fn test_record_stderr_lines_keeps_order_and_drops_oldest() {
+        let log = StderrLog::default();
+        record_stderr_lines("test", "first\nsecond\nthird\n".as_bytes(), &log);
+        assert_eq!(tail_lines(&log, 10), vec!["first", "second", "third"]);
+        assert_eq!(tail_lines(&log, 2), vec!["second", "third"]);
+
+        let overflow: String = (0..STDERR_LOG_LINES + 5)
+            .map(|i| format!("line {}\n", i))
+            .collect();
+        record_stderr_lines("test", overflow.as_bytes(), &log);
+        let log = log.lock().unwrap();
+        assert_eq!(log.len(), STDERR_LOG_LINES);
+        assert_eq!(log.front().unwrap(), "line 5");
+        assert_eq!(
+            log.back().unwrap(),
+            &format!("line {}", STDERR_LOG_LINES + 4)
+        );
+    }
+
+    fn spawn_ignoring_sigterm() -> Child {
+        let child = Command::new("sh")
+            .args(["-c", "trap '' TERM; while :; do sleep 1; done"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        // Give the shell time to install its trap
+        std::thread::sleep(Duration::from_millis(100));
+        child
+    }
+
+    #[test]
+    fn test_terminate_child_stops_and_reaps_process() {
+        let child = Command::new("sleep").arg("30").spawn().unwrap();
+
+        let mut child = terminate_child(child, SHUTDOWN_GRACE).join().unwrap();
+
+        assert!(matches!(child.try_wait(), Ok(Some(_))));
+    }
+
+    #[test]
+    fn test_terminate_child_kills_process_ignoring_sigterm() {
+        let child = spawn_ignoring_sigterm();
+
+        let started = Instant::now();
+        let reaper = terminate_child(child, Duration::from_millis(200));
+        assert!(started.elapsed() < Duration::from_millis(200));
+
+        let mut child = reaper.join().unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(200));
+        assert!(matches!(child.try_wait(), Ok(Some(_))));
+    }
+
+    fn engine_with_child(mut child: Child) -> LayoutEngine {
+        LayoutEngine {
+            name: "test".to_string(),
+            stdin: child.stdin.take().unwrap(),
+            stdout: BufReader::new(child.stdout.take().unwrap()),
+            child: Some(child),
+            stderr_log: StderrLog::default(),
+            commands: None,
+            inner_gap: None,
+        }
+    }
+
+    /// Wait up to `timeout` for `pid` to be reaped (a zombie still answers signal 0).
+    fn wait_until_reaped(pid: u32, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if unsafe { libc::kill(pid as libc::pid_t, 0) } != 0 {
+                return true;
+            }
+            if Instant::now() >= deadline {
+                return false;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_dropping_layout_engine_does_not_wait_for_child() {
+        let child = spawn_ignoring_sigterm();
+        let pid = child.id();
+        let engine = engine_with_child(child);
+
+        // The child is reaped by the background thread once the grace period ends
+        let started = Instant::now();
+        drop(engine);
+        assert!(started.elapsed() < SHUTDOWN_GRACE);
+
+        // Don't leave the shell behind; the reaper notices the exit and reaps it
+        unsafe {
+            libc::kill(pid as libc::pid_t, libc::SIGKILL);
+        }
+        assert!(wait_until_reaped(pid, Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn test_shutdown_waits_for_engines_ignoring_sigterm() {
+        let child = spawn_ignoring_sigterm();
+        let pid = child.id();
+        let mut manager = LayoutEngineManager::new();
+        manager
+            .engines
+            .insert("test".to_string(), engine_with_child(child));
+
+        let started = Instant::now();
+        manager.shutdown();
+
+        assert!(started.elapsed() >= SHUTDOWN_GRACE);
+        assert!(manager.engines.is_empty());
+        // Already reaped by the time shutdown returns
+        assert!(wait_until_reaped(pid, Duration::ZERO));
+    }
+
+    #[test]
+    fn test_spawned_engine_receives_layout_defaults() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("yashiki-defaults-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let log_path = dir.join("received.log");
+        let script = dir.join("yashiki-layout-recorder");
+        std::fs::write(
+            &script,
+            format!(
+                "#!/bin/sh\nwhile read line; do echo \"$line\" >> '{}'; echo '{{\"type\":\"ok\"}}'; done\n",
+                log_path.display()
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let mut manager = LayoutEngineManager::new();
+        manager.set_exec_path(&format!("{}:/bin:/usr/bin", dir.display()));
+        manager.set_defaults(LayoutDefaults {
+            main_ratio: Some(0.6),
+            inner_gap: Some(12),
+        });
+        manager.get_or_spawn("recorder").unwrap();
+        // A reset engine gets the defaults again
+        manager.send_command("recorder", "reset", &[]).unwrap();
+        // Stop the engine so its log is complete
+        drop(manager);
+
+        let received = std::fs::read_to_string(&log_path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(
+            received.lines().collect::<Vec<_>>(),
+            vec![
+                r#"{"type":"command","cmd":"set-main-ratio","args":["0.6"]}"#,
+                r#"{"type":"command","cmd":"set-inner-gap","args":["12"]}"#,
+                r#"{"type":"command","cmd":"reset","args":[]}"#,
+                r#"{"type":"command","cmd":"set-main-ratio","args":["0.6"]}"#,
+                r#"{"type":"command","cmd":"set-inner-gap","args":["12"]}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_layout_request_sends_window_order() {
+        use crate::core::State;
+        use crate::platform::mock::{create_test_display, create_test_window, MockWindowSystem};
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("yashiki-order-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let log_path = dir.join("received.log");
+        let script = dir.join("yashiki-layout-recorder");
+        std::fs::write(
+            &script,
+            format!(
+                "#!/bin/sh\nwhile read line; do echo \"$line\" >> '{}'; echo '{{\"type\":\"layout\",\"windows\":[]}}'; done\n",
+                log_path.display()
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let ws = MockWindowSystem::new()
+            .with_displays(vec![create_test_display(1, 0.0, 0.0, 1920.0, 1080.0)])
+            .with_windows(vec![
+                create_test_window(100, 1000, "Safari", 0.0, 0.0, 960.0, 1080.0),
+                create_test_window(101, 1001, "Terminal", 960.0, 0.0, 960.0, 540.0),
+                create_test_window(102, 1002, "VSCode", 960.0, 540.0, 960.0, 540.0),
+            ]);
+        let mut state = State::new();
+        state.sync_all(&ws);
+
+        let mut manager = LayoutEngineManager::new();
+        manager.set_exec_path(&format!("{}:/bin:/usr/bin", dir.display()));
+        for order in [vec![102, 100, 101], vec![101, 102, 100]] {
+            state.displays.get_mut(&1).unwrap().window_order = order;
+            manager
+                .request_layout("recorder", 1920, 1080, &state.layout_window_ids(1))
+                .unwrap();
+        }
+        drop(manager);
+
+        let received = std::fs::read_to_string(&log_path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(
+            received.lines().collect::<Vec<_>>(),
+            vec![
+                r#"{"type":"layout","width":1920,"height":1080,"windows":[102,100,101]}"#,
+                r#"{"type":"layout","width":1920,"height":1080,"windows":[101,102,100]}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_inner_gap_is_cached_between_commands() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("yashiki-gap-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let log_path = dir.join("received.log");
+        let script = dir.join("yashiki-layout-gapper");
+        std::fs::write(
+            &script,
+            format!(
+                "#!/bin/sh\nwhile read line; do echo \"$line\" >> '{}'; echo '{{\"type\":\"value\",\"value\":\"7\"}}'; done\n",
+                log_path.display()
+            ),
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let mut manager = LayoutEngineManager::new();
+        manager.set_exec_path(&format!("{}:/bin:/usr/bin", dir.display()));
+        assert_eq!(manager.inner_gap("gapper").unwrap(), Some(7));
+        assert_eq!(manager.inner_gap("gapper").unwrap(), Some(7));
+        manager
+            .send_command("gapper", "set-inner-gap", &["3".to_string()])
+            .unwrap();
+        assert_eq!(manager.inner_gap("gapper").unwrap(), Some(3));
+        // reset changes the gap in ways only the engine knows
+        manager.send_command("gapper", "reset", &[]).unwrap();
+        assert_eq!(manager.inner_gap("gapper").unwrap(), Some(7));
+        drop(manager);
+
+        let received = std::fs::read_to_string(&log_path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        let queries = received
+            .lines()
+            .filter(|line| line.contains("get-inner-gap"))
+            .count();
+        assert_eq!(queries, 2);
+    }
+
+    #[test]
+    fn test_resolve_layout_alias() {
+        let mut aliases = LayoutAliases::new();
+        aliases.insert("myalias".to_string(), "byobu".to_string());
+        assert_eq!(resolve_layout_alias(&aliases, "myalias"), "byobu");
+        assert_eq!(resolve_layout_alias(&aliases, "tatami"), "tatami");
+    }
+
+    #[test]
+    fn test_alias_spawns_target_engine() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("yashiki-alias-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let script = dir.join("yashiki-layout-recorder");
+        std::fs::write(
+            &script,
+            "#!/bin/sh\nwhile read line; do echo '{\"type\":\"ok\"}'; done\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let mut manager = LayoutEngineManager::new();
+        manager.set_exec_path(&format!("{}:/bin:/usr/bin", dir.display()));
+        assert!(manager.get_or_spawn("myalias").is_err());
+
+        manager.set_alias("myalias", "recorder");
+        let spawned = manager.get_or_spawn("myalias").is_ok();
+        drop(manager);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(spawned);
+    }
+
+    #[test]
+    fn test_list_layouts_shows_aliases() {
+        let mut manager = LayoutEngineManager::new();
+        manager.set_alias("myalias", "byobu");
+        manager.set_alias("tatami", "deck");
+
+        assert_eq!(
+            manager.list_layouts("tatami"),
+            vec![
+                LayoutInfo {
+                    name: "myalias".to_string(),
+                    alias_of: Some("byobu".to_string()),
+                    running: false,
+                },
+                LayoutInfo {
+                    name: "tatami".to_string(),
+                    alias_of: Some("deck".to_string()),
+                    running: false,
+                },
+            ]
+        );
+        assert_eq!(
+            LayoutEngineManager::new().list_layouts("byobu"),
+            vec![LayoutInfo {
+                name: "byobu".to_string(),
+                alias_of: None,
+                running: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_command_error_message_passes_through_other_errors() {
+        let commands = vec!["set-padding".to_string()];
+        assert_eq!(
+            command_error_message(
+                "byobu",
+                "set-padding",
+                "usage: set-padding <px>",
+                Some(&commands)
+            ),
+            "Layout engine error: usage: set-padding <px>"
+        );
+    }
+}
@@ -28,8 +28,21 @@ extern "C" {
         callback: unsafe extern "C" fn(CGDirectDisplayID, u32, *mut c_void),
         user_info: *mut c_void,
     ) -> i32;
+    fn CGDisplayMirrorsDisplay(display: CGDirectDisplayID) -> CGDirectDisplayID;
+    fn CGBeginDisplayConfiguration(config: *mut *mut c_void) -> i32;
+    fn CGConfigureDisplayOrigin(
+        config: *mut c_void,
+        display: CGDirectDisplayID,
+        x: i32,
+        y: i32,
+    ) -> i32;
+    fn CGCompleteDisplayConfiguration(config: *mut c_void, option: u32) -> i32;
+    fn CGCancelDisplayConfiguration(config: *mut c_void) -> i32;
 }
 
+/// kCGConfigurePermanently
+const CONFIGURE_PERMANENTLY: u32 = 2;
+
 #[derive(Debug, Clone)]
 pub struct DisplayReconfigEvent {
     pub display_id: DisplayId,
@@ -90,6 +103,8 @@ pub struct DisplayInfo {
     pub name: String,
     pub frame: Bounds,
     pub is_main: bool,
+    /// Primary display of the mirror set this display belongs to, if it mirrors one
+    pub mirror_of: Option<DisplayId>,
 }
 
 #[derive(Debug, Clone)]
@@ -227,6 +242,11 @@ pub fn get_all_displays() -> Vec<DisplayInfo> {
                     height: visible_height,
                 },
                 is_main: display_id == main_display_id,
+                // kCGNullDirectDisplay (0) when not mirroring
+                mirror_of: match unsafe { CGDisplayMirrorsDisplay(display_id) } {
+                    0 => None,
+                    primary => Some(primary),
+                },
             }
         })
         .collect()
@@ -279,6 +299,51 @@ pub fn get_active_display_ids() -> Vec<DisplayId> {
     display_ids[..display_count as usize].to_vec()
 }
 
+/// Make `display_id` the main display (the one with the menu bar).
+/// The main display is the one at the global origin, so every active display is
+/// shifted by the target's origin in a single configuration transaction.
+pub fn set_main_display(display_id: DisplayId) -> Result<(), String> {
+    let display_ids = get_active_display_ids();
+    if !display_ids.contains(&display_id) {
+        return Err(format!("Display {} is not active", display_id));
+    }
+    let target = get_display_bounds(display_id);
+
+    let mut config: *mut c_void = std::ptr::null_mut();
+    let result = unsafe { CGBeginDisplayConfiguration(&mut config) };
+    if result != 0 {
+        return Err(format!(
+            "Failed to begin display configuration: error {}",
+            result
+        ));
+    }
+
+    for &id in &display_ids {
+        let bounds = get_display_bounds(id);
+        let x = (bounds.x - target.x) as i32;
+        let y = (bounds.y - target.y) as i32;
+        let result = unsafe { CGConfigureDisplayOrigin(config, id, x, y) };
+        if result != 0 {
+            unsafe { CGCancelDisplayConfiguration(config) };
+            return Err(format!(
+                "Failed to move display {} to ({}, {}): error {}",
+                id, x, y, result
+            ));
+        }
+    }
+
+    let result = unsafe { CGCompleteDisplayConfiguration(config, CONFIGURE_PERMANENTLY) };
+    if result != 0 {
+        return Err(format!(
+            "Failed to apply display configuration (display config privileges may be required): error {}",
+            result
+        ));
+    }
+
+    tracing::info!("Set main display to {}", display_id);
+    Ok(())
+}
+
 /// Get display bounds in Core Graphics coordinates using CGDisplayBounds.
 fn get_display_bounds(display_id: DisplayId) -> Bounds {
     let rect = unsafe { CGDisplayBounds(display_id) };
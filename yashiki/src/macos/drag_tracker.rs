@@ -0,0 +1,197 @@
+use std::ffi::c_void;
+use std::ptr;
+use std::sync::atomic::{AtomicPtr, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+
+use core_foundation::base::TCFType;
+use core_foundation::runloop::{kCFRunLoopCommonModes, CFRunLoop, CFRunLoopSource};
+use core_foundation_sys::mach_port::CFMachPortRef;
+use core_foundation_sys::runloop::{CFRunLoopSourceRef, CFRunLoopSourceSignal};
+use core_graphics::event::{
+    CGEventTap, CGEventTapLocation, CGEventTapOptions, CGEventTapPlacement, CGEventType,
+    CallbackResult,
+};
+
+use super::Modifiers;
+use crate::core::{DragKind, DragTargets};
+
+extern "C" {
+    fn CGEventTapEnable(tap: CFMachPortRef, enable: bool);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DragEvent {
+    /// Mouse button pressed with the drag modifier held over a draggable window
+    Start { kind: DragKind, x: i32, y: i32 },
+    /// Cursor moved while the button is held
+    Update { x: i32, y: i32 },
+    /// Button released
+    End,
+}
+
+/// Modifier+drag tap. A left/right button press with the modifier held over a draggable
+/// window, and the drags that follow, are consumed and forwarded to the main thread
+/// instead of the app. Every other click passes through untouched.
+pub struct DragTracker {
+    event_tx: mpsc::Sender<DragEvent>,
+    tap: Option<DragTap>,
+    modifiers: Option<Modifiers>,
+    targets: Arc<Mutex<DragTargets>>,
+    runloop_source: Arc<AtomicPtr<c_void>>,
+}
+
+impl DragTracker {
+    pub fn new(event_tx: mpsc::Sender<DragEvent>, runloop_source: Arc<AtomicPtr<c_void>>) -> Self {
+        Self {
+            event_tx,
+            tap: None,
+            modifiers: None,
+            targets: Arc::new(Mutex::new(DragTargets::default())),
+            runloop_source,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.tap.is_some()
+    }
+
+    /// Replace the windows the tap hit tests button presses against.
+    pub fn set_targets(&self, targets: DragTargets) {
+        *self.targets.lock().unwrap() = targets;
+    }
+
+    /// Listen for drags with `modifiers` held, or stop listening when None.
+    /// The tap is only recreated when the modifiers change.
+    pub fn set_modifiers(&mut self, modifiers: Option<Modifiers>) -> Result<(), String> {
+        if modifiers == self.modifiers && (modifiers.is_none() || self.tap.is_some()) {
+            return Ok(());
+        }
+        self.tap = None;
+        self.modifiers = modifiers;
+        match modifiers {
+            Some(modifiers) => {
+                self.tap = Some(self.create_tap(modifiers)?);
+                tracing::info!("Drag tracker started");
+            }
+            None => tracing::info!("Drag tracker stopped"),
+        }
+        Ok(())
+    }
+
+    fn create_tap(&self, modifiers: Modifiers) -> Result<DragTap, String> {
+        let tx = self.event_tx.clone();
+        let source = Arc::clone(&self.runloop_source);
+        let targets = Arc::clone(&self.targets);
+        // Button of the drag in progress, so its drags and release are consumed
+        // even if the modifier is let go mid-drag
+        let active: Mutex<Option<DragKind>> = Mutex::new(None);
+
+        let mach_port_ptr: Arc<AtomicPtr<c_void>> = Arc::new(AtomicPtr::new(ptr::null_mut()));
+        let mach_port_for_callback = Arc::clone(&mach_port_ptr);
+
+        let tap = CGEventTap::new(
+            CGEventTapLocation::Session,
+            CGEventTapPlacement::HeadInsertEventTap,
+            CGEventTapOptions::Default,
+            vec![
+                CGEventType::LeftMouseDown,
+                CGEventType::LeftMouseDragged,
+                CGEventType::LeftMouseUp,
+                CGEventType::RightMouseDown,
+                CGEventType::RightMouseDragged,
+                CGEventType::RightMouseUp,
+            ],
+            move |_proxy, event_type, event| {
+                let (kind, phase) = match event_type {
+                    CGEventType::TapDisabledByTimeout | CGEventType::TapDisabledByUserInput => {
+                        tracing::warn!("Drag event tap disabled, re-enabling...");
+                        let ptr = mach_port_for_callback.load(Ordering::Acquire);
+                        if !ptr.is_null() {
+                            unsafe {
+                                CGEventTapEnable(ptr as CFMachPortRef, true);
+                            }
+                        }
+                        return CallbackResult::Keep;
+                    }
+                    CGEventType::LeftMouseDown => (DragKind::Move, Phase::Down),
+                    CGEventType::LeftMouseDragged => (DragKind::Move, Phase::Dragged),
+                    CGEventType::LeftMouseUp => (DragKind::Move, Phase::Up),
+                    CGEventType::RightMouseDown => (DragKind::Resize, Phase::Down),
+                    CGEventType::RightMouseDragged => (DragKind::Resize, Phase::Dragged),
+                    CGEventType::RightMouseUp => (DragKind::Resize, Phase::Up),
+                    _ => return CallbackResult::Keep,
+                };
+
+                let location = event.location();
+                let (x, y) = (location.x as i32, location.y as i32);
+
+                let mut active = active.lock().unwrap();
+                let drag_event = match phase {
+                    Phase::Down => {
+                        if active.is_some()
+                            || Modifiers::from_flags(event.get_flags()) != modifiers
+                            || !targets.lock().unwrap().accepts(x, y)
+                        {
+                            return CallbackResult::Keep;
+                        }
+                        *active = Some(kind);
+                        DragEvent::Start { kind, x, y }
+                    }
+                    Phase::Dragged if *active == Some(kind) => DragEvent::Update { x, y },
+                    Phase::Up if *active == Some(kind) => {
+                        *active = None;
+                        DragEvent::End
+                    }
+                    _ => return CallbackResult::Keep,
+                };
+
+                if tx.send(drag_event).is_ok() {
+                    // Signal CFRunLoopSource for immediate processing
+                    let source_ptr = source.load(Ordering::Acquire);
+                    if !source_ptr.is_null() {
+                        unsafe {
+                            CFRunLoopSourceSignal(source_ptr as CFRunLoopSourceRef);
+                        }
+                    }
+                }
+
+                CallbackResult::Drop
+            },
+        )
+        .map_err(|_| {
+            "Failed to create drag event tap. Make sure Accessibility permission is granted."
+        })?;
+
+        mach_port_ptr.store(
+            tap.mach_port().as_concrete_TypeRef() as *mut c_void,
+            Ordering::Release,
+        );
+
+        tap.enable();
+
+        let source = tap
+            .mach_port()
+            .create_runloop_source(0)
+            .map_err(|_| "Failed to create run loop source for drag tracker")?;
+
+        CFRunLoop::get_current().add_source(&source, unsafe { kCFRunLoopCommonModes });
+
+        Ok(DragTap {
+            _tap: tap,
+            _source: source,
+        })
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Phase {
+    Down,
+    Dragged,
+    Up,
+}
+
+struct DragTap {
+    _tap: CGEventTap<'static>,
+    _source: CFRunLoopSource,
+}
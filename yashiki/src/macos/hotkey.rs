@@ -1,9 +1,11 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::ffi::c_void;
 use std::ptr;
 use std::sync::atomic::{AtomicPtr, Ordering};
 use std::sync::mpsc;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use core_foundation::base::TCFType;
 use core_foundation::runloop::{kCFRunLoopCommonModes, CFRunLoop, CFRunLoopSource};
@@ -16,6 +18,8 @@ use core_graphics::event::{
 
 use yashiki_ipc::Command;
 
+use crate::core::ExportedBinding;
+
 extern "C" {
     fn CGEventTapEnable(tap: CFMachPortRef, enable: bool);
 }
@@ -24,6 +28,8 @@ extern "C" {
 pub struct Hotkey {
     pub key_code: u16,
     pub modifiers: Modifiers,
+    /// Fires when the modifier `key_code` is tapped twice on its own (`double-shift`)
+    pub double_tap: bool,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
@@ -34,8 +40,24 @@ pub struct Modifiers {
     pub shift: bool,
 }
 
+/// Parse `<modifiers>-<key>`. Modifiers may also be joined with `+` (`cmd+shift+h`);
+/// the `-` and `+` keys themselves are bound by name (`minus`, `plus`).
 pub fn parse_hotkey(key_str: &str) -> Result<Hotkey, String> {
-    let parts: Vec<&str> = key_str.split('-').collect();
+    let lower = key_str.to_lowercase();
+    if let Some(modifier) = lower
+        .strip_prefix("double-")
+        .or_else(|| lower.strip_prefix("double+"))
+    {
+        let key_code =
+            modifier_key_code(modifier).ok_or_else(|| format!("Unknown modifier: {}", modifier))?;
+        return Ok(Hotkey {
+            key_code,
+            modifiers: Modifiers::default(),
+            double_tap: true,
+        });
+    }
+
+    let parts: Vec<&str> = key_str.split(['-', '+']).collect();
     if parts.is_empty() {
         return Err("Empty key string".to_string());
     }
@@ -44,13 +66,7 @@ pub fn parse_hotkey(key_str: &str) -> Result<Hotkey, String> {
     let key_part = parts.last().unwrap();
 
     for part in &parts[..parts.len() - 1] {
-        match part.to_lowercase().as_str() {
-            "cmd" | "super" | "command" => modifiers.cmd = true,
-            "alt" | "opt" | "option" => modifiers.alt = true,
-            "ctrl" | "control" => modifiers.ctrl = true,
-            "shift" => modifiers.shift = true,
-            _ => return Err(format!("Unknown modifier: {}", part)),
-        }
+        modifiers.add(part)?;
     }
 
     let key_code = parse_key_code(key_part)?;
@@ -58,29 +74,203 @@ pub fn parse_hotkey(key_str: &str) -> Result<Hotkey, String> {
     Ok(Hotkey {
         key_code,
         modifiers,
+        double_tap: false,
     })
 }
 
 pub fn format_hotkey(hotkey: &Hotkey) -> String {
-    let mut parts = Vec::new();
-    if hotkey.modifiers.cmd {
-        parts.push("cmd");
+    if hotkey.double_tap {
+        return format!("double-{}", modifier_key_name(hotkey.key_code));
     }
-    if hotkey.modifiers.alt {
-        parts.push("alt");
+    let mut parts = hotkey.modifiers.names();
+    parts.push(key_code_to_str(hotkey.key_code));
+    parts.join("-")
+}
+
+/// Parse a modifier-only combination such as `cmd-alt` (or `cmd+alt`).
+pub fn parse_modifiers(s: &str) -> Result<Modifiers, String> {
+    let mut modifiers = Modifiers::default();
+    for part in s.split(['-', '+']) {
+        modifiers.add(part)?;
     }
-    if hotkey.modifiers.ctrl {
-        parts.push("ctrl");
+    Ok(modifiers)
+}
+
+pub fn format_modifiers(modifiers: &Modifiers) -> String {
+    modifiers.names().join("-")
+}
+
+/// Key code of the (left) modifier key named `name`.
+fn modifier_key_code(name: &str) -> Option<u16> {
+    match name {
+        "cmd" | "super" | "command" => Some(0x37),
+        "alt" | "opt" | "option" => Some(0x3A),
+        "ctrl" | "control" => Some(0x3B),
+        "shift" => Some(0x38),
+        _ => None,
     }
-    if hotkey.modifiers.shift {
-        parts.push("shift");
+}
+
+fn modifier_key_name(code: u16) -> &'static str {
+    match code {
+        0x37 => "cmd",
+        0x3A => "alt",
+        0x3B => "ctrl",
+        0x38 => "shift",
+        _ => "unknown",
+    }
+}
+
+/// Map left and right modifier keys to the left key's code.
+fn canonical_modifier_code(code: u16) -> Option<u16> {
+    match code {
+        0x37 | 0x36 => Some(0x37),
+        0x3A | 0x3D => Some(0x3A),
+        0x3B | 0x3E => Some(0x3B),
+        0x38 | 0x3C => Some(0x38),
+        _ => None,
+    }
+}
+
+impl Modifiers {
+    /// Modifiers held according to an event's flags
+    pub fn from_flags(flags: CGEventFlags) -> Self {
+        Self {
+            cmd: flags.contains(CGEventFlags::CGEventFlagCommand),
+            alt: flags.contains(CGEventFlags::CGEventFlagAlternate),
+            ctrl: flags.contains(CGEventFlags::CGEventFlagControl),
+            shift: flags.contains(CGEventFlags::CGEventFlagShift),
+        }
+    }
+
+    fn add(&mut self, name: &str) -> Result<(), String> {
+        match name.to_lowercase().as_str() {
+            "cmd" | "super" | "command" => self.cmd = true,
+            "alt" | "opt" | "option" => self.alt = true,
+            "ctrl" | "control" => self.ctrl = true,
+            "shift" => self.shift = true,
+            _ => return Err(format!("Unknown modifier: {}", name)),
+        }
+        Ok(())
+    }
+
+    /// Names of the held modifiers, in canonical order
+    fn names(&self) -> Vec<&'static str> {
+        [
+            (self.cmd, "cmd"),
+            (self.alt, "alt"),
+            (self.ctrl, "ctrl"),
+            (self.shift, "shift"),
+        ]
+        .into_iter()
+        .filter_map(|(held, name)| held.then_some(name))
+        .collect()
+    }
+
+    /// Whether the modifier with canonical key code `code` is held
+    fn holds(&self, code: u16) -> bool {
+        match code {
+            0x37 => self.cmd,
+            0x3A => self.alt,
+            0x3B => self.ctrl,
+            0x38 => self.shift,
+            _ => false,
+        }
+    }
+
+    fn held_count(&self) -> usize {
+        [self.cmd, self.alt, self.ctrl, self.shift]
+            .iter()
+            .filter(|&&held| held)
+            .count()
+    }
+}
+
+/// Longest gap between releasing a modifier and pressing it again for a double-tap.
+const DOUBLE_TAP_INTERVAL: Duration = Duration::from_millis(300);
+
+/// Detects a modifier pressed and released twice with no other key in between.
+#[derive(Debug, Default)]
+struct DoubleTapDetector {
+    /// Modifier currently held on its own
+    held: Option<u16>,
+    /// First tap of a possible double-tap: modifier and release time
+    last_tap: Option<(u16, Instant)>,
+}
+
+impl DoubleTapDetector {
+    /// Feed a modifier press/release. `others_held` is whether any other modifier is down.
+    /// Returns the modifier's key code when this release completes a double-tap.
+    fn modifier_changed(
+        &mut self,
+        code: u16,
+        pressed: bool,
+        others_held: bool,
+        now: Instant,
+    ) -> Option<u16> {
+        if others_held {
+            self.reset();
+            return None;
+        }
+
+        if pressed {
+            let continues_tap = self.last_tap.is_some_and(|(last, released)| {
+                last == code && now.saturating_duration_since(released) <= DOUBLE_TAP_INTERVAL
+            });
+            if !continues_tap {
+                self.last_tap = None;
+            }
+            self.held = Some(code);
+            return None;
+        }
+
+        if self.held.take() != Some(code) {
+            self.last_tap = None;
+            return None;
+        }
+        if self.last_tap.take().is_some() {
+            return Some(code);
+        }
+        self.last_tap = Some((code, now));
+        None
+    }
+
+    /// Any non-modifier key cancels a pending tap.
+    fn key_pressed(&mut self) {
+        self.reset();
+    }
+
+    fn reset(&mut self) {
+        self.held = None;
+        self.last_tap = None;
+    }
+}
+
+/// Hand a matched binding to the main thread and wake its run loop source.
+fn send_hotkey_command(
+    tx: &mpsc::Sender<Command>,
+    source: &AtomicPtr<c_void>,
+    hotkey: &Hotkey,
+    command: Command,
+) {
+    tracing::debug!("Hotkey matched: {:?} -> {:?}", hotkey, command);
+    if tx.send(command).is_err() {
+        tracing::error!("Failed to send command from hotkey");
+    }
+    // Signal CFRunLoopSource for immediate processing
+    let source_ptr = source.load(Ordering::Acquire);
+    if !source_ptr.is_null() {
+        unsafe {
+            CFRunLoopSourceSignal(source_ptr as CFRunLoopSourceRef);
+        }
     }
-    parts.push(key_code_to_str(hotkey.key_code));
-    parts.join("-")
 }
 
 pub struct HotkeyManager {
     bindings: HashMap<Hotkey, Command>,
+    labels: HashMap<Hotkey, String>,
+    /// Command line each action was bound with, for `export-bindings`
+    action_args: HashMap<Hotkey, Vec<String>>,
     command_tx: mpsc::Sender<Command>,
     tap: Option<HotkeyTap>,
     dirty: bool,
@@ -94,6 +284,8 @@ impl HotkeyManager {
     ) -> Self {
         Self {
             bindings: HashMap::new(),
+            labels: HashMap::new(),
+            action_args: HashMap::new(),
             command_tx,
             tap: None,
             dirty: false,
@@ -101,9 +293,20 @@ impl HotkeyManager {
         }
     }
 
-    pub fn bind(&mut self, key_str: &str, command: Command) -> Result<(), String> {
+    pub fn bind(
+        &mut self,
+        key_str: &str,
+        command: Command,
+        label: Option<String>,
+        args: Vec<String>,
+    ) -> Result<(), String> {
         let hotkey = parse_hotkey(key_str)?;
         tracing::info!("Binding {} to {:?}", key_str, command);
+        match label {
+            Some(label) => self.labels.insert(hotkey, label),
+            None => self.labels.remove(&hotkey),
+        };
+        self.action_args.insert(hotkey, args);
         self.bindings.insert(hotkey, command);
         self.dirty = true;
         Ok(())
@@ -112,15 +315,34 @@ impl HotkeyManager {
     pub fn unbind(&mut self, key_str: &str) -> Result<(), String> {
         let hotkey = parse_hotkey(key_str)?;
         self.bindings.remove(&hotkey);
+        self.labels.remove(&hotkey);
+        self.action_args.remove(&hotkey);
         tracing::info!("Unbound {}", key_str);
         self.dirty = true;
         Ok(())
     }
 
-    pub fn list_bindings(&self) -> Vec<(String, Command)> {
+    pub fn list_bindings(&self) -> Vec<(String, Command, Option<String>)> {
         self.bindings
             .iter()
-            .map(|(hotkey, cmd)| (format_hotkey(hotkey), cmd.clone()))
+            .map(|(hotkey, cmd)| {
+                (
+                    format_hotkey(hotkey),
+                    cmd.clone(),
+                    self.labels.get(hotkey).cloned(),
+                )
+            })
+            .collect()
+    }
+
+    pub fn export_bindings(&self) -> Vec<ExportedBinding> {
+        self.bindings
+            .keys()
+            .map(|hotkey| ExportedBinding {
+                key: format_hotkey(hotkey),
+                args: self.action_args.get(hotkey).cloned().unwrap_or_default(),
+                label: self.labels.get(hotkey).cloned(),
+            })
             .collect()
     }
 
@@ -150,11 +372,18 @@ impl HotkeyManager {
         let mach_port_ptr: Arc<AtomicPtr<c_void>> = Arc::new(AtomicPtr::new(ptr::null_mut()));
         let mach_port_for_callback = Arc::clone(&mach_port_ptr);
 
+        // Modifier presses only matter for double-tap bindings
+        let mut events = vec![CGEventType::KeyDown];
+        if bindings.keys().any(|hotkey| hotkey.double_tap) {
+            events.push(CGEventType::FlagsChanged);
+        }
+        let double_tap = RefCell::new(DoubleTapDetector::default());
+
         let tap = CGEventTap::new(
             CGEventTapLocation::Session,
             CGEventTapPlacement::HeadInsertEventTap,
             CGEventTapOptions::Default,
-            vec![CGEventType::KeyDown],
+            events,
             move |_proxy, event_type, event| {
                 match event_type {
                     CGEventType::TapDisabledByTimeout | CGEventType::TapDisabledByUserInput => {
@@ -177,32 +406,43 @@ impl HotkeyManager {
 
                 let key_code =
                     event.get_integer_value_field(EventField::KEYBOARD_EVENT_KEYCODE) as u16;
-                let flags = event.get_flags();
+                let modifiers = Modifiers::from_flags(event.get_flags());
 
-                let modifiers = Modifiers {
-                    cmd: flags.contains(CGEventFlags::CGEventFlagCommand),
-                    alt: flags.contains(CGEventFlags::CGEventFlagAlternate),
-                    ctrl: flags.contains(CGEventFlags::CGEventFlagControl),
-                    shift: flags.contains(CGEventFlags::CGEventFlagShift),
-                };
+                if matches!(event_type, CGEventType::FlagsChanged) {
+                    let Some(code) = canonical_modifier_code(key_code) else {
+                        return CallbackResult::Keep;
+                    };
+                    let pressed = modifiers.holds(code);
+                    let others_held = modifiers.held_count() > usize::from(pressed);
+                    let tapped = double_tap.borrow_mut().modifier_changed(
+                        code,
+                        pressed,
+                        others_held,
+                        Instant::now(),
+                    );
+                    if let Some(code) = tapped {
+                        let hotkey = Hotkey {
+                            key_code: code,
+                            modifiers: Modifiers::default(),
+                            double_tap: true,
+                        };
+                        if let Some(command) = bindings.get(&hotkey).cloned() {
+                            send_hotkey_command(&tx, &source, &hotkey, command);
+                        }
+                    }
+                    // Never swallow modifier changes, or the system loses track of them
+                    return CallbackResult::Keep;
+                }
+                double_tap.borrow_mut().key_pressed();
 
                 let hotkey = Hotkey {
                     key_code,
                     modifiers,
+                    double_tap: false,
                 };
 
                 if let Some(command) = bindings.get(&hotkey).cloned() {
-                    tracing::debug!("Hotkey matched: {:?} -> {:?}", hotkey, command);
-                    if tx.send(command).is_err() {
-                        tracing::error!("Failed to send command from hotkey");
-                    }
-                    // Signal CFRunLoopSource for immediate processing
-                    let source_ptr = source.load(Ordering::Acquire);
-                    if !source_ptr.is_null() {
-                        unsafe {
-                            CFRunLoopSourceSignal(source_ptr as CFRunLoopSourceRef);
-                        }
-                    }
+                    send_hotkey_command(&tx, &source, &hotkey, command);
                     return CallbackResult::Drop;
                 }
 
@@ -239,156 +479,121 @@ struct HotkeyTap {
     _source: CFRunLoopSource,
 }
 
+/// Key names and their macOS virtual key codes. The single source for parsing,
+/// formatting and `list-keys`; each name and each code appears once.
+const KEY_CODES: &[(&str, u16)] = &[
+    // Letters
+    ("a", 0x00),
+    ("b", 0x0B),
+    ("c", 0x08),
+    ("d", 0x02),
+    ("e", 0x0E),
+    ("f", 0x03),
+    ("g", 0x05),
+    ("h", 0x04),
+    ("i", 0x22),
+    ("j", 0x26),
+    ("k", 0x28),
+    ("l", 0x25),
+    ("m", 0x2E),
+    ("n", 0x2D),
+    ("o", 0x1F),
+    ("p", 0x23),
+    ("q", 0x0C),
+    ("r", 0x0F),
+    ("s", 0x01),
+    ("t", 0x11),
+    ("u", 0x20),
+    ("v", 0x09),
+    ("w", 0x0D),
+    ("x", 0x07),
+    ("y", 0x10),
+    ("z", 0x06),
+    // Numbers
+    ("1", 0x12),
+    ("2", 0x13),
+    ("3", 0x14),
+    ("4", 0x15),
+    ("5", 0x17),
+    ("6", 0x16),
+    ("7", 0x1A),
+    ("8", 0x1C),
+    ("9", 0x19),
+    ("0", 0x1D),
+    // Special keys
+    ("return", 0x24),
+    ("tab", 0x30),
+    ("space", 0x31),
+    ("delete", 0x33),
+    ("escape", 0x35),
+    ("left", 0x7B),
+    ("right", 0x7C),
+    ("down", 0x7D),
+    ("up", 0x7E),
+    ("f1", 0x7A),
+    ("f2", 0x78),
+    ("f3", 0x63),
+    ("f4", 0x76),
+    ("f5", 0x60),
+    ("f6", 0x61),
+    ("f7", 0x62),
+    ("f8", 0x64),
+    ("f9", 0x65),
+    ("f10", 0x6D),
+    ("f11", 0x67),
+    ("f12", 0x6F),
+    // Punctuation
+    ("minus", 0x1B),
+    ("equal", 0x18),
+    ("leftbracket", 0x21),
+    ("rightbracket", 0x1E),
+    ("backslash", 0x2A),
+    ("semicolon", 0x29),
+    ("quote", 0x27),
+    ("comma", 0x2B),
+    ("period", 0x2F),
+    ("slash", 0x2C),
+    ("grave", 0x32),
+];
+
+/// Alternative spellings accepted by `parse_hotkey`, mapped to their name in `KEY_CODES`.
+const KEY_ALIASES: &[(&str, &str)] = &[
+    ("enter", "return"),
+    ("backspace", "delete"),
+    ("esc", "escape"),
+    ("plus", "equal"),
+];
+
+fn key_code_for_name(name: &str) -> Option<u16> {
+    KEY_CODES
+        .iter()
+        .find(|(key, _)| *key == name)
+        .map(|(_, code)| *code)
+}
+
+fn key_name_for_code(code: u16) -> Option<&'static str> {
+    KEY_CODES
+        .iter()
+        .find(|(_, key_code)| *key_code == code)
+        .map(|(name, _)| *name)
+}
+
+/// Canonical names of every key `parse_hotkey` accepts, in table order.
+pub fn supported_key_names() -> Vec<&'static str> {
+    KEY_CODES.iter().map(|(name, _)| *name).collect()
+}
+
 fn parse_key_code(key: &str) -> Result<u16, String> {
-    match key.to_lowercase().as_str() {
-        // Letters
-        "a" => Ok(0x00),
-        "b" => Ok(0x0B),
-        "c" => Ok(0x08),
-        "d" => Ok(0x02),
-        "e" => Ok(0x0E),
-        "f" => Ok(0x03),
-        "g" => Ok(0x05),
-        "h" => Ok(0x04),
-        "i" => Ok(0x22),
-        "j" => Ok(0x26),
-        "k" => Ok(0x28),
-        "l" => Ok(0x25),
-        "m" => Ok(0x2E),
-        "n" => Ok(0x2D),
-        "o" => Ok(0x1F),
-        "p" => Ok(0x23),
-        "q" => Ok(0x0C),
-        "r" => Ok(0x0F),
-        "s" => Ok(0x01),
-        "t" => Ok(0x11),
-        "u" => Ok(0x20),
-        "v" => Ok(0x09),
-        "w" => Ok(0x0D),
-        "x" => Ok(0x07),
-        "y" => Ok(0x10),
-        "z" => Ok(0x06),
-        // Numbers
-        "1" => Ok(0x12),
-        "2" => Ok(0x13),
-        "3" => Ok(0x14),
-        "4" => Ok(0x15),
-        "5" => Ok(0x17),
-        "6" => Ok(0x16),
-        "7" => Ok(0x1A),
-        "8" => Ok(0x1C),
-        "9" => Ok(0x19),
-        "0" => Ok(0x1D),
-        // Special keys
-        "return" | "enter" => Ok(0x24),
-        "tab" => Ok(0x30),
-        "space" => Ok(0x31),
-        "delete" | "backspace" => Ok(0x33),
-        "escape" | "esc" => Ok(0x35),
-        "left" => Ok(0x7B),
-        "right" => Ok(0x7C),
-        "down" => Ok(0x7D),
-        "up" => Ok(0x7E),
-        "f1" => Ok(0x7A),
-        "f2" => Ok(0x78),
-        "f3" => Ok(0x63),
-        "f4" => Ok(0x76),
-        "f5" => Ok(0x60),
-        "f6" => Ok(0x61),
-        "f7" => Ok(0x62),
-        "f8" => Ok(0x64),
-        "f9" => Ok(0x65),
-        "f10" => Ok(0x6D),
-        "f11" => Ok(0x67),
-        "f12" => Ok(0x6F),
-        // Punctuation
-        "minus" => Ok(0x1B),
-        "equal" => Ok(0x18),
-        "leftbracket" => Ok(0x21),
-        "rightbracket" => Ok(0x1E),
-        "backslash" => Ok(0x2A),
-        "semicolon" => Ok(0x29),
-        "quote" => Ok(0x27),
-        "comma" => Ok(0x2B),
-        "period" => Ok(0x2F),
-        "slash" => Ok(0x2C),
-        "grave" => Ok(0x32),
-        _ => Err(format!("Unknown key: {}", key)),
-    }
+    let key = key.to_lowercase();
+    let name = KEY_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == key)
+        .map_or(key.as_str(), |(_, name)| *name);
+    key_code_for_name(name).ok_or_else(|| format!("Unknown key: {}", key))
 }
 
 fn key_code_to_str(code: u16) -> &'static str {
-    match code {
-        0x00 => "a",
-        0x0B => "b",
-        0x08 => "c",
-        0x02 => "d",
-        0x0E => "e",
-        0x03 => "f",
-        0x05 => "g",
-        0x04 => "h",
-        0x22 => "i",
-        0x26 => "j",
-        0x28 => "k",
-        0x25 => "l",
-        0x2E => "m",
-        0x2D => "n",
-        0x1F => "o",
-        0x23 => "p",
-        0x0C => "q",
-        0x0F => "r",
-        0x01 => "s",
-        0x11 => "t",
-        0x20 => "u",
-        0x09 => "v",
-        0x0D => "w",
-        0x07 => "x",
-        0x10 => "y",
-        0x06 => "z",
-        0x12 => "1",
-        0x13 => "2",
-        0x14 => "3",
-        0x15 => "4",
-        0x17 => "5",
-        0x16 => "6",
-        0x1A => "7",
-        0x1C => "8",
-        0x19 => "9",
-        0x1D => "0",
-        0x24 => "return",
-        0x30 => "tab",
-        0x31 => "space",
-        0x33 => "delete",
-        0x35 => "escape",
-        0x7B => "left",
-        0x7C => "right",
-        0x7D => "down",
-        0x7E => "up",
-        0x7A => "f1",
-        0x78 => "f2",
-        0x63 => "f3",
-        0x76 => "f4",
-        0x60 => "f5",
-        0x61 => "f6",
-        0x62 => "f7",
-        0x64 => "f8",
-        0x65 => "f9",
-        0x6D => "f10",
-        0x67 => "f11",
-        0x6F => "f12",
-        0x1B => "minus",
-        0x18 => "equal",
-        0x21 => "leftbracket",
-        0x1E => "rightbracket",
-        0x2A => "backslash",
-        0x29 => "semicolon",
-        0x27 => "quote",
-        0x2B => "comma",
-        0x2F => "period",
-        0x2C => "slash",
-        0x32 => "grave",
-        _ => "unknown",
-    }
+    key_name_for_code(code).unwrap_or("unknown")
 }
 
 #[cfg(test)]
@@ -505,11 +710,28 @@ mod tests {
         assert!(parse_hotkey("meta-a").is_err());
     }
 
+    #[test]
+    fn test_parse_modifiers_round_trip() {
+        let modifiers = parse_modifiers("Option+cmd").unwrap();
+        assert_eq!(
+            modifiers,
+            Modifiers {
+                cmd: true,
+                alt: true,
+                ..Default::default()
+            }
+        );
+        assert_eq!(format_modifiers(&modifiers), "cmd-alt");
+        assert!(parse_modifiers("cmd-a").is_err());
+        assert!(parse_modifiers("").is_err());
+    }
+
     #[test]
     fn test_format_hotkey_simple() {
         let hotkey = Hotkey {
             key_code: 0x00,
             modifiers: Modifiers::default(),
+            double_tap: false,
         };
         assert_eq!(format_hotkey(&hotkey), "a");
     }
@@ -524,6 +746,7 @@ mod tests {
                 ctrl: false,
                 shift: false,
             },
+            double_tap: false,
         };
         assert_eq!(format_hotkey(&hotkey), "alt-1");
     }
@@ -538,10 +761,154 @@ mod tests {
                 ctrl: true,
                 shift: true,
             },
+            double_tap: false,
         };
         assert_eq!(format_hotkey(&hotkey), "cmd-alt-ctrl-shift-space");
     }
 
+    #[test]
+    fn test_parse_double_tap() {
+        let hotkey = parse_hotkey("double-shift").unwrap();
+        assert!(hotkey.double_tap);
+        assert_eq!(hotkey.key_code, 0x38);
+        assert_eq!(hotkey.modifiers, Modifiers::default());
+        assert_eq!(format_hotkey(&hotkey), "double-shift");
+
+        assert_eq!(
+            parse_hotkey("Double+Command").unwrap(),
+            parse_hotkey("double-cmd").unwrap()
+        );
+        assert_ne!(
+            parse_hotkey("double-alt").unwrap(),
+            parse_hotkey("double-ctrl").unwrap()
+        );
+        assert!(parse_hotkey("double-a").is_err());
+    }
+
+    #[test]
+    fn test_double_tap_detector_fires_on_second_release() {
+        let mut detector = DoubleTapDetector::default();
+        let t0 = Instant::now();
+        let ms = Duration::from_millis;
+
+        assert_eq!(detector.modifier_changed(0x38, true, false, t0), None);
+        assert_eq!(
+            detector.modifier_changed(0x38, false, false, t0 + ms(50)),
+            None
+        );
+        assert_eq!(
+            detector.modifier_changed(0x38, true, false, t0 + ms(150)),
+            None
+        );
+        assert_eq!(
+            detector.modifier_changed(0x38, false, false, t0 + ms(200)),
+            Some(0x38)
+        );
+
+        // A third tap starts over
+        assert_eq!(
+            detector.modifier_changed(0x38, true, false, t0 + ms(250)),
+            None
+        );
+        assert_eq!(
+            detector.modifier_changed(0x38, false, false, t0 + ms(300)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_double_tap_detector_rejects_slow_or_interrupted_taps() {
+        let t0 = Instant::now();
+        let ms = Duration::from_millis;
+        let tap = |detector: &mut DoubleTapDetector, code, at| {
+            detector.modifier_changed(code, true, false, at);
+            detector.modifier_changed(code, false, false, at + ms(30))
+        };
+
+        // Too slow
+        let mut detector = DoubleTapDetector::default();
+        tap(&mut detector, 0x38, t0);
+        assert_eq!(tap(&mut detector, 0x38, t0 + ms(500)), None);
+
+        // Another key pressed between the taps
+        let mut detector = DoubleTapDetector::default();
+        tap(&mut detector, 0x38, t0);
+        detector.key_pressed();
+        assert_eq!(tap(&mut detector, 0x38, t0 + ms(100)), None);
+
+        // Different modifiers
+        let mut detector = DoubleTapDetector::default();
+        tap(&mut detector, 0x38, t0);
+        assert_eq!(tap(&mut detector, 0x37, t0 + ms(100)), None);
+
+        // Used as part of a modifier combination
+        let mut detector = DoubleTapDetector::default();
+        tap(&mut detector, 0x38, t0);
+        detector.modifier_changed(0x38, true, false, t0 + ms(100));
+        assert_eq!(
+            detector.modifier_changed(0x38, false, true, t0 + ms(130)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_plus_separator() {
+        let plus = parse_hotkey("cmd+shift+h").unwrap();
+        let minus = parse_hotkey("cmd-shift-h").unwrap();
+        assert_eq!(plus, minus);
+        assert_eq!(plus.key_code, 0x04);
+        assert!(plus.modifiers.cmd);
+        assert!(plus.modifiers.shift);
+        assert_eq!(format_hotkey(&plus), "cmd-shift-h");
+    }
+
+    #[test]
+    fn test_parse_plus_and_minus_keys_by_name() {
+        let plus = parse_hotkey("alt+plus").unwrap();
+        assert_eq!(plus.key_code, 0x18);
+        assert!(plus.modifiers.alt);
+        assert_eq!(parse_hotkey("alt-plus").unwrap(), plus);
+
+        let minus = parse_hotkey("alt+minus").unwrap();
+        assert_eq!(minus.key_code, 0x1B);
+        assert_eq!(format_hotkey(&minus), "alt-minus");
+
+        assert!(parse_hotkey("alt++").is_err());
+    }
+
+    #[test]
+    fn test_supported_key_names_roundtrip() {
+        let names = supported_key_names();
+        assert!(names.contains(&"a"));
+        assert!(names.contains(&"f12"));
+        assert!(names.contains(&"grave"));
+
+        for name in names {
+            let hotkey = parse_hotkey(name).unwrap();
+            assert_eq!(format_hotkey(&hotkey), name);
+        }
+    }
+
+    #[test]
+    fn test_key_table_roundtrips_without_duplicates() {
+        let mut names = std::collections::HashSet::new();
+        let mut codes = std::collections::HashSet::new();
+        for &(name, code) in KEY_CODES {
+            assert!(names.insert(name), "Duplicate key name: {}", name);
+            assert!(codes.insert(code), "Duplicate key code: {:#04X}", code);
+            assert_eq!(key_code_for_name(name), Some(code));
+            assert_eq!(key_name_for_code(code), Some(name));
+        }
+        for &(alias, name) in KEY_ALIASES {
+            assert!(
+                !names.contains(alias),
+                "Alias shadows a key name: {}",
+                alias
+            );
+            assert!(names.contains(name), "Alias target missing: {}", name);
+        }
+    }
+
     #[test]
     fn test_parse_format_roundtrip() {
         let inputs = [
@@ -1,5 +1,6 @@
 mod accessibility;
 mod display;
+mod drag_tracker;
 mod hotkey;
 mod mouse_tracker;
 mod observer;
@@ -7,6 +8,7 @@ mod workspace;
 
 pub use accessibility::*;
 pub use display::*;
+pub use drag_tracker::*;
 pub use hotkey::*;
 pub use mouse_tracker::*;
 pub use observer::*;
@@ -15,9 +15,9 @@ use tracing_subscriber::EnvFilter;
 
 use ipc::IpcClient;
 use yashiki_ipc::{
-    AutoRaiseMode, ButtonInfo, ButtonState, Command, CursorWarpMode, Direction, EventFilter,
-    GlobPattern, OutputDirection, OutputSpecifier, Response, RuleAction, RuleMatcher, WindowLevel,
-    WindowLevelName, WindowLevelOther, WindowRule, WindowStatus,
+    AutoRaiseMode, ButtonInfo, ButtonState, Command, CursorWarpMode, Direction, Edge, EventFilter,
+    GlobPattern, OutputDirection, OutputSpecifier, Response, RuleAction, RuleMatcher,
+    SizeSelection, WindowLevel, WindowLevelName, WindowLevelOther, WindowRule, WindowStatus,
 };
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -25,6 +25,9 @@ const VERSION: &str = env!("CARGO_PKG_VERSION");
 /// Yashiki - macOS tiling window manager
 #[derive(FromArgs)]
 struct Cli {
+    /// print list/get results as JSON (list-windows, list-outputs, get-state, list-bindings, focused-window)
+    #[argh(switch)]
+    json: bool,
     #[argh(subcommand)]
     command: Option<SubCommand>,
 }
@@ -37,29 +40,86 @@ enum SubCommand {
     Bind(BindCmd),
     Unbind(UnbindCmd),
     ListBindings(ListBindingsCmd),
+    ExportBindings(ExportBindingsCmd),
+    ListKeys(ListKeysCmd),
     TagView(TagViewCmd),
+    TagViewLayout(TagViewLayoutCmd),
+    TagViewN(TagViewNCmd),
+    TagViewName(TagViewNameCmd),
+    SetTagName(SetTagNameCmd),
     TagToggle(TagToggleCmd),
     TagViewLast(TagViewLastCmd),
+    TagViewBack(TagViewBackCmd),
+    TagHistoryClear(TagHistoryClearCmd),
+    TagCycle(TagCycleCmd),
     WindowMoveToTag(WindowMoveToTagCmd),
     WindowToggleTag(WindowToggleTagCmd),
+    WindowMatchView(WindowMatchViewCmd),
+    TagWindowsByTitle(TagWindowsByTitleCmd),
+    DistributeWindows(DistributeWindowsCmd),
     WindowFocus(WindowFocusCmd),
+    WindowFocusBySize(WindowFocusBySizeCmd),
     WindowSwap(WindowSwapCmd),
+    WindowShift(WindowShiftCmd),
+    WindowToSlot(WindowToSlotCmd),
     WindowToggleFullscreen(WindowToggleFullscreenCmd),
     WindowToggleFloat(WindowToggleFloatCmd),
+    FloatAll(FloatAllCmd),
+    WindowToggleAlwaysOnTop(WindowToggleAlwaysOnTopCmd),
+    WindowToggleNativeSize(WindowToggleNativeSizeCmd),
+    WindowPeek(WindowPeekCmd),
+    WindowPeekRelease(WindowPeekReleaseCmd),
+    WindowSetUrgent(WindowSetUrgentCmd),
+    FocusUrgent(FocusUrgentCmd),
     WindowClose(WindowCloseCmd),
+    WindowMinimize(WindowMinimizeCmd),
+    WindowUnminimize(WindowUnminimizeCmd),
     OutputFocus(OutputFocusCmd),
     OutputSend(OutputSendCmd),
+    SetMainOutput(SetMainOutputCmd),
+    SetFocusAxisSwap(SetFocusAxisSwapCmd),
     Retile(RetileCmd),
+    LayoutReverse(LayoutReverseCmd),
+    SetMain(SetMainCmd),
+    SetStickyMain(SetStickyMainCmd),
+    ClearStickyMain(ClearStickyMainCmd),
+    LayoutBalanceAll(LayoutBalanceAllCmd),
     LayoutSetDefault(LayoutSetDefaultCmd),
     LayoutSet(LayoutSetCmd),
+    LayoutSetCurrent(LayoutSetCurrentCmd),
     LayoutGet(LayoutGetCmd),
+    MainRatioGet(MainRatioGetCmd),
+    MainRatioAnimate(MainRatioAnimateCmd),
+    SetDefaultLayoutParams(SetDefaultLayoutParamsCmd),
+    LayoutEngineLog(LayoutEngineLogCmd),
+    LayoutLastInput(LayoutLastInputCmd),
+    LayoutDescribe(LayoutDescribeCmd),
+    LayoutAlias(LayoutAliasCmd),
+    LayoutList(LayoutListCmd),
     LayoutCmd(LayoutCmdCmd),
+    LayoutCmdAll(LayoutCmdAllCmd),
+    LayoutCmdFor(LayoutCmdForCmd),
+    ToggleOrientation(ToggleOrientationCmd),
+    ToggleGaps(ToggleGapsCmd),
     ListWindows(ListWindowsCmd),
     ListOutputs(ListOutputsCmd),
+    OutputUsableFrame(OutputUsableFrameCmd),
     GetState(GetStateCmd),
+    Stats(StatsCmd),
+    TagStats(TagStatsCmd),
+    ListUnmanaged(ListUnmanagedCmd),
+    ManageWindow(ManageWindowCmd),
+    UnmanageWindow(UnmanageWindowCmd),
+    GetConfig(GetConfigCmd),
     FocusedWindow(FocusedWindowCmd),
+    WindowGeometry(WindowGeometryCmd),
+    ResyncWindow(ResyncWindowCmd),
+    SetAppEventsPaused(SetAppEventsPausedCmd),
+    WindowOutput(WindowOutputCmd),
+    WindowTags(WindowTagsCmd),
     Exec(ExecCmd),
     ExecOrFocus(ExecOrFocusCmd),
+    ScratchpadToggle(ScratchpadToggleCmd),
     ExecPath(ExecPathCmd),
     SetExecPath(SetExecPathCmd),
     AddExecPath(AddExecPathCmd),
@@ -67,12 +127,23 @@ enum SubCommand {
     RuleDel(RuleDelCmd),
     ListRules(ListRulesCmd),
     SetCursorWarp(SetCursorWarpCmd),
+    SetExclusiveTags(SetExclusiveTagsCmd),
     GetCursorWarp(GetCursorWarpCmd),
     SetAutoRaise(SetAutoRaiseCmd),
     GetAutoRaise(GetAutoRaiseCmd),
+    SetRaiseOnFocus(SetRaiseOnFocusCmd),
+    SetDragModifier(SetDragModifierCmd),
     SetOuterGap(SetOuterGapCmd),
     GetOuterGap(GetOuterGapCmd),
+    ReserveEdge(ReserveEdgeCmd),
+    ClearReservations(ClearReservationsCmd),
+    SetGapsMultiMonitorOnly(SetGapsMultiMonitorOnlyCmd),
+    SetTickRate(SetTickRateCmd),
+    SnapshotSave(SnapshotSaveCmd),
+    SnapshotRestore(SnapshotRestoreCmd),
     Subscribe(SubscribeCmd),
+    Reconcile(ReconcileCmd),
+    Resync(ResyncCmd),
     Quit(QuitCmd),
 }
 
@@ -93,6 +164,9 @@ struct BindCmd {
     /// hotkey (e.g., alt-1, cmd-shift-h)
     #[argh(positional)]
     key: String,
+    /// description shown by list-bindings
+    #[argh(option)]
+    label: Option<String>,
     /// command and arguments to bind
     #[argh(positional, greedy)]
     action: Vec<String>,
@@ -112,6 +186,20 @@ struct UnbindCmd {
 #[argh(subcommand, name = "list-bindings")]
 struct ListBindingsCmd {}
 
+/// Write current bindings as `yashiki bind` lines (stdout if no path given)
+#[derive(FromArgs)]
+#[argh(subcommand, name = "export-bindings")]
+struct ExportBindingsCmd {
+    /// file to write
+    #[argh(positional)]
+    path: Option<String>,
+}
+
+/// List key names accepted by bind
+#[derive(FromArgs)]
+#[argh(subcommand, name = "list-keys")]
+struct ListKeysCmd {}
+
 /// Switch to specific tags (bitmask)
 #[derive(FromArgs)]
 #[argh(subcommand, name = "tag-view")]
@@ -121,7 +209,58 @@ struct TagViewCmd {
     output: Option<String>,
     /// tags bitmask (e.g., 1 for tag 1, 2 for tag 2, 3 for tags 1+2)
     #[argh(positional)]
-    tags: u32,
+    tags: u64,
+}
+
+/// Switch to tags and set the layout of the newly visible tag
+#[derive(FromArgs)]
+#[argh(subcommand, name = "tag-view-layout")]
+struct TagViewLayoutCmd {
+    /// output (display) ID or name
+    #[argh(option)]
+    output: Option<String>,
+    /// tags bitmask (e.g., 1 for tag 1, 2 for tag 2, 3 for tags 1+2)
+    #[argh(positional)]
+    tags: u64,
+    /// layout engine name
+    #[argh(positional)]
+    layout: String,
+}
+
+/// Switch to a single tag by number
+#[derive(FromArgs)]
+#[argh(subcommand, name = "tag-view-n")]
+struct TagViewNCmd {
+    /// output (display) ID or name
+    #[argh(option)]
+    output: Option<String>,
+    /// tag number (1-64)
+    #[argh(positional)]
+    n: u32,
+}
+
+/// Switch to the tag labelled by set-tag-name
+#[derive(FromArgs)]
+#[argh(subcommand, name = "tag-view-name")]
+struct TagViewNameCmd {
+    /// output (display) ID or name
+    #[argh(option)]
+    output: Option<String>,
+    /// tag name
+    #[argh(positional)]
+    name: String,
+}
+
+/// Label a tag so tag-view-name can switch to it
+#[derive(FromArgs)]
+#[argh(subcommand, name = "set-tag-name")]
+struct SetTagNameCmd {
+    /// tag number (1-64)
+    #[argh(positional)]
+    tag: u32,
+    /// label (empty string removes it)
+    #[argh(positional)]
+    name: String,
 }
 
 /// Toggle visibility of tags (bitmask)
@@ -133,7 +272,7 @@ struct TagToggleCmd {
     output: Option<String>,
     /// tags bitmask to toggle
     #[argh(positional)]
-    tags: u32,
+    tags: u64,
 }
 
 /// Switch to the previously viewed tags
@@ -141,13 +280,39 @@ struct TagToggleCmd {
 #[argh(subcommand, name = "tag-view-last")]
 struct TagViewLastCmd {}
 
+/// Go back through the focused display's previously viewed tags
+#[derive(FromArgs)]
+#[argh(subcommand, name = "tag-view-back")]
+struct TagViewBackCmd {
+    /// number of steps to go back (default: 1)
+    #[argh(positional, default = "1")]
+    steps: u32,
+}
+
+/// Forget the focused display's tag history
+#[derive(FromArgs)]
+#[argh(subcommand, name = "tag-history-clear")]
+struct TagHistoryClearCmd {}
+
+/// View the next or previous tag on the focused display
+#[derive(FromArgs)]
+#[argh(subcommand, name = "tag-cycle")]
+struct TagCycleCmd {
+    /// direction: next, prev
+    #[argh(positional)]
+    direction: String,
+    /// skip tags without windows
+    #[argh(switch)]
+    only_occupied: bool,
+}
+
 /// Move focused window to tags (bitmask)
 #[derive(FromArgs)]
 #[argh(subcommand, name = "window-move-to-tag")]
 struct WindowMoveToTagCmd {
     /// tags bitmask
     #[argh(positional)]
-    tags: u32,
+    tags: u64,
 }
 
 /// Toggle tags on the focused window (bitmask)
@@ -156,27 +321,86 @@ struct WindowMoveToTagCmd {
 struct WindowToggleTagCmd {
     /// tags bitmask to toggle
     #[argh(positional)]
-    tags: u32,
+    tags: u64,
+}
+
+/// Set the focused window's tags to the focused display's visible tags
+#[derive(FromArgs)]
+#[argh(subcommand, name = "window-match-view")]
+struct WindowMatchViewCmd {}
+
+/// Set the tags of every window whose title matches a regex
+#[derive(FromArgs)]
+#[argh(subcommand, name = "tag-windows-by-title")]
+struct TagWindowsByTitleCmd {
+    /// regex matched against window titles
+    #[argh(positional)]
+    pattern: String,
+    /// tags bitmask to set
+    #[argh(positional)]
+    tags: u64,
+}
+
+/// Spread the visible windows round-robin over the tags in a bitmask
+#[derive(FromArgs)]
+#[argh(subcommand, name = "distribute-windows")]
+struct DistributeWindowsCmd {
+    /// tags bitmask to spread windows over
+    #[argh(positional)]
+    tags: u64,
 }
 
 /// Focus a window in the specified direction
 #[derive(FromArgs)]
 #[argh(subcommand, name = "window-focus")]
 struct WindowFocusCmd {
+    /// only consider windows on the focused display
+    #[argh(switch)]
+    same_output: bool,
     /// direction: left, right, up, down, next, prev
     #[argh(positional)]
     direction: String,
 }
 
+/// Focus the largest or smallest visible window on the focused display
+#[derive(FromArgs)]
+#[argh(subcommand, name = "window-focus-by-size")]
+struct WindowFocusBySizeCmd {
+    /// largest or smallest
+    #[argh(positional)]
+    which: String,
+}
+
 /// Swap focused window with window in the specified direction
 #[derive(FromArgs)]
 #[argh(subcommand, name = "window-swap")]
 struct WindowSwapCmd {
+    /// only consider windows on the focused display
+    #[argh(switch)]
+    same_output: bool,
     /// direction: left, right, up, down, next, prev
     #[argh(positional)]
     direction: String,
 }
 
+/// Move focused window one slot forward or backward in the window order
+#[derive(FromArgs)]
+#[argh(subcommand, name = "window-shift")]
+struct WindowShiftCmd {
+    /// direction: next, prev
+    #[argh(positional)]
+    direction: String,
+}
+
+/// Move focused window to a position among the tiled windows (0 is the main slot)
+#[derive(FromArgs)]
+#[argh(subcommand, name = "window-to-slot")]
+struct WindowToSlotCmd {
+    /// position, 0-based; larger values move the window to the end
+    #[argh(positional)]
+    index: usize,
+}
+
 /// Toggle fullscreen for focused window (AeroSpace-style, not macOS native)
 #[derive(FromArgs)]
 #[argh(subcommand, name = "window-toggle-fullscreen")]
@@ -187,11 +411,76 @@ struct WindowToggleFullscreenCmd {}
 #[argh(subcommand, name = "window-toggle-float")]
 struct WindowToggleFloatCmd {}
 
+/// Float every window (true) or tile them again (false)
+#[derive(FromArgs)]
+#[argh(subcommand, name = "float-all")]
+struct FloatAllCmd {
+    /// true or false
+    #[argh(positional)]
+    enabled: bool,
+}
+
+/// Toggle always-on-top for a window (focused window by default)
+#[derive(FromArgs)]
+#[argh(subcommand, name = "window-toggle-always-on-top")]
+struct WindowToggleAlwaysOnTopCmd {
+    /// window ID (defaults to the focused window)
+    #[argh(option)]
+    id: Option<u32>,
+}
+
+/// Keep a window at its native size, centered in its tile (focused window by default)
+#[derive(FromArgs)]
+#[argh(subcommand, name = "window-toggle-native-size")]
+struct WindowToggleNativeSizeCmd {
+    /// window ID (defaults to the focused window)
+    #[argh(option)]
+    id: Option<u32>,
+}
+
+/// Enlarge the focused window to 80% of the display (again to restore)
+#[derive(FromArgs)]
+#[argh(subcommand, name = "window-peek")]
+struct WindowPeekCmd {}
+
+/// Return a peeked window to its tiled slot
+#[derive(FromArgs)]
+#[argh(subcommand, name = "window-peek-release")]
+struct WindowPeekReleaseCmd {}
+
+/// Flag a window as needing attention (for focus-urgent)
+#[derive(FromArgs)]
+#[argh(subcommand, name = "window-set-urgent")]
+struct WindowSetUrgentCmd {
+    /// window ID
+    #[argh(positional)]
+    id: u32,
+}
+
+/// Jump to the most recently flagged urgent window
+#[derive(FromArgs)]
+#[argh(subcommand, name = "focus-urgent")]
+struct FocusUrgentCmd {}
+
 /// Close the focused window
 #[derive(FromArgs)]
 #[argh(subcommand, name = "window-close")]
 struct WindowCloseCmd {}
 
+/// Minimize the focused window to the Dock
+#[derive(FromArgs)]
+#[argh(subcommand, name = "window-minimize")]
+struct WindowMinimizeCmd {}
+
+/// Restore a minimized window and focus it
+#[derive(FromArgs)]
+#[argh(subcommand, name = "window-unminimize")]
+struct WindowUnminimizeCmd {
+    /// window ID (default: the most recently minimized window)
+    #[argh(positional)]
+    id: Option<u32>,
+}
+
 /// Focus the next or previous display
 #[derive(FromArgs)]
 #[argh(subcommand, name = "output-focus")]
@@ -210,6 +499,27 @@ struct OutputSendCmd {
     direction: String,
 }
 
+/// Make a display the system main display (the one with the menu bar)
+#[derive(FromArgs)]
+#[argh(subcommand, name = "set-main-output")]
+struct SetMainOutputCmd {
+    /// output (display) ID or name
+    #[argh(positional)]
+    output: String,
+}
+
+/// Make left/right focus and swap act as up/down on a display (and vice versa)
+#[derive(FromArgs)]
+#[argh(subcommand, name = "set-focus-axis-swap")]
+struct SetFocusAxisSwapCmd {
+    /// true or false
+    #[argh(positional)]
+    enabled: bool,
+    /// output (display) ID or name, defaults to the focused display
+    #[argh(option)]
+    output: Option<String>,
+}
+
 /// Re-apply the current layout
 #[derive(FromArgs)]
 #[argh(subcommand, name = "retile")]
@@ -219,6 +529,43 @@ struct RetileCmd {
     output: Option<String>,
 }
 
+/// Reverse the window order so the layout flips end-to-end
+#[derive(FromArgs)]
+#[argh(subcommand, name = "layout-reverse")]
+struct LayoutReverseCmd {
+    /// output (display) ID or name
+    #[argh(option)]
+    output: Option<String>,
+}
+
+/// Move a window to the main slot of its display's layout
+#[derive(FromArgs)]
+#[argh(subcommand, name = "set-main")]
+struct SetMainCmd {
+    /// window ID
+    #[argh(positional)]
+    id: u32,
+}
+
+/// Keep a window in the main slot whenever it is visible, across tag switches
+#[derive(FromArgs)]
+#[argh(subcommand, name = "set-sticky-main")]
+struct SetStickyMainCmd {
+    /// window ID
+    #[argh(positional)]
+    id: u32,
+}
+
+/// Stop keeping the sticky main window in the main slot
+#[derive(FromArgs)]
+#[argh(subcommand, name = "clear-sticky-main")]
+struct ClearStickyMainCmd {}
+
+/// Reset ratios, counts and gaps of every layout engine in use
+#[derive(FromArgs)]
+#[argh(subcommand, name = "layout-balance-all")]
+struct LayoutBalanceAllCmd {}
+
 /// Set the default layout engine
 #[derive(FromArgs)]
 #[argh(subcommand, name = "layout-set-default")]
@@ -234,7 +581,7 @@ struct LayoutSetDefaultCmd {
 struct LayoutSetCmd {
     /// tags bitmask, defaults to current tag
     #[argh(option)]
-    tags: Option<u32>,
+    tags: Option<u64>,
     /// output (display) ID or name
     #[argh(option)]
     output: Option<String>,
@@ -243,18 +590,110 @@ struct LayoutSetCmd {
     layout: String,
 }
 
+/// Set the layout engine for the visible tags of the focused output
+#[derive(FromArgs)]
+#[argh(subcommand, name = "layout-set-current")]
+struct LayoutSetCurrentCmd {
+    /// layout engine name
+    #[argh(positional)]
+    layout: String,
+}
+
 /// Get the current layout engine
 #[derive(FromArgs)]
 #[argh(subcommand, name = "layout-get")]
 struct LayoutGetCmd {
     /// tags bitmask, defaults to current layout
     #[argh(option)]
-    tags: Option<u32>,
+    tags: Option<u64>,
+    /// output (display) ID or name
+    #[argh(option)]
+    output: Option<String>,
+}
+
+/// Smoothly move the current layout's main ratio to a target (0.1-0.9)
+#[derive(FromArgs)]
+#[argh(subcommand, name = "main-ratio-animate")]
+struct MainRatioAnimateCmd {
+    /// animation length in milliseconds (default: 150)
+    #[argh(option)]
+    duration: Option<u32>,
+    /// target main ratio
+    #[argh(positional)]
+    target: f64,
+}
+
+/// Set the main ratio and inner gap applied to newly spawned layout engines
+#[derive(FromArgs)]
+#[argh(subcommand, name = "set-default-layout-params")]
+struct SetDefaultLayoutParamsCmd {
+    /// main ratio (0-1) sent as set-main-ratio
+    #[argh(option)]
+    main_ratio: Option<f64>,
+    /// inner gap in pixels sent as set-inner-gap
+    #[argh(option)]
+    inner_gap: Option<u32>,
+}
+
+/// Get the main ratio of the current layout engine
+#[derive(FromArgs)]
+#[argh(subcommand, name = "main-ratio-get")]
+struct MainRatioGetCmd {
+    /// tags bitmask, defaults to current layout
+    #[argh(option)]
+    tags: Option<u64>,
     /// output (display) ID or name
     #[argh(option)]
     output: Option<String>,
 }
 
+/// Show recent stderr output of a running layout engine
+#[derive(FromArgs)]
+#[argh(subcommand, name = "layout-engine-log")]
+struct LayoutEngineLogCmd {
+    /// layout engine name (e.g., tatami)
+    #[argh(positional)]
+    name: String,
+    /// number of most recent lines to show (default: all buffered)
+    #[argh(option)]
+    lines: Option<usize>,
+}
+
+/// Show the window list a running layout engine last received
+#[derive(FromArgs)]
+#[argh(subcommand, name = "layout-last-input")]
+struct LayoutLastInputCmd {
+    /// layout engine name (e.g., tatami)
+    #[argh(positional)]
+    name: String,
+}
+
+/// Show a layout engine's name and the commands it accepts
+#[derive(FromArgs)]
+#[argh(subcommand, name = "layout-describe")]
+struct LayoutDescribeCmd {
+    /// layout engine name (e.g., tatami)
+    #[argh(positional)]
+    name: String,
+}
+
+/// Run another layout engine whenever a layout name is used
+#[derive(FromArgs)]
+#[argh(subcommand, name = "layout-alias")]
+struct LayoutAliasCmd {
+    /// layout name to remap (e.g., myalias)
+    #[argh(positional)]
+    alias: String,
+    /// layout engine to run for it (e.g., byobu)
+    #[argh(positional)]
+    target: String,
+}
+
+/// List the default layout, running layout engines and aliases
+#[derive(FromArgs)]
+#[argh(subcommand, name = "layout-list")]
+struct LayoutListCmd {}
+
 /// Send a command to the layout engine
 #[derive(FromArgs)]
 #[argh(subcommand, name = "layout-cmd")]
@@ -270,6 +709,60 @@ struct LayoutCmdCmd {
     args: Vec<String>,
 }
 
+/// Send a command to every layout engine in use
+#[derive(FromArgs)]
+#[argh(subcommand, name = "layout-cmd-all")]
+struct LayoutCmdAllCmd {
+    /// layout command
+    #[argh(positional)]
+    cmd: String,
+    /// command arguments
+    #[argh(positional, greedy)]
+    args: Vec<String>,
+}
+
+/// Send a command to the layout engine bound to the given tags
+#[derive(FromArgs)]
+#[argh(subcommand, name = "layout-cmd-for")]
+struct LayoutCmdForCmd {
+    /// tags bitmask whose layout engine receives the command
+    #[argh(option)]
+    tags: u64,
+    /// output (display) ID or name to retile
+    #[argh(option)]
+    output: String,
+    /// layout command
+    #[argh(positional)]
+    cmd: String,
+    /// command arguments
+    #[argh(positional, greedy)]
+    args: Vec<String>,
+}
+
+/// Toggle horizontal/vertical orientation of the active layout engine
+#[derive(FromArgs)]
+#[argh(subcommand, name = "toggle-orientation")]
+struct ToggleOrientationCmd {
+    /// output (display) ID or name
+    #[argh(option)]
+    output: Option<String>,
+    /// tags bitmask, defaults to current layout
+    #[argh(option)]
+    tags: Option<u64>,
+}
+
+/// Toggle the inner gap off and back on, restoring the previous value
+#[derive(FromArgs)]
+#[argh(subcommand, name = "toggle-gaps")]
+struct ToggleGapsCmd {
+    /// output (display) ID or name
+    #[argh(option)]
+    output: Option<String>,
+    /// tags bitmask, defaults to current tag
+    #[argh(option)]
+    tags: Option<u64>,
+}
+
 /// List all managed windows
 #[derive(FromArgs)]
 #[argh(subcommand, name = "list-windows")]
@@ -280,6 +773,9 @@ struct ListWindowsCmd {
     /// show debug info (ax_id, subrole, window_level, buttons)
     #[argh(switch)]
     debug: bool,
+    /// only list windows on the visible tags of their display
+    #[argh(switch)]
+    visible: bool,
 }
 
 /// List all displays/outputs
@@ -287,16 +783,115 @@ struct ListWindowsCmd {
 #[argh(subcommand, name = "list-outputs")]
 struct ListOutputsCmd {}
 
+/// Show the area a display tiles into, after menu bar, reservations and outer gap
+#[derive(FromArgs)]
+#[argh(subcommand, name = "output-usable-frame")]
+struct OutputUsableFrameCmd {
+    /// output (display) ID or name
+    #[argh(option)]
+    output: Option<String>,
+}
+
 /// Get current window manager state
 #[derive(FromArgs)]
 #[argh(subcommand, name = "get-state")]
 struct GetStateCmd {}
 
+/// Show retile timing and event-loop throughput
+#[derive(FromArgs)]
+#[argh(subcommand, name = "stats")]
+struct StatsCmd {}
+
+/// Show window counts of the tags in use on a display
+#[derive(FromArgs)]
+#[argh(subcommand, name = "tag-stats")]
+struct TagStatsCmd {
+    /// output (display) ID or name
+    #[argh(option)]
+    output: Option<String>,
+}
+
+/// List on-screen windows that are not managed and why
+#[derive(FromArgs)]
+#[argh(subcommand, name = "list-unmanaged")]
+struct ListUnmanagedCmd {}
+
+/// Print the daemon's effective settings as JSON
+#[derive(FromArgs)]
+#[argh(subcommand, name = "get-config")]
+struct GetConfigCmd {}
+
 /// Get the focused window ID
 #[derive(FromArgs)]
 #[argh(subcommand, name = "focused-window")]
 struct FocusedWindowCmd {}
 
+/// Print the ID of the output (display) containing a window
+#[derive(FromArgs)]
+#[argh(subcommand, name = "window-output")]
+struct WindowOutputCmd {
+    /// window ID
+    #[argh(positional)]
+    id: u32,
+}
+
+/// Print the tags a window belongs to
+#[derive(FromArgs)]
+#[argh(subcommand, name = "window-tags")]
+struct WindowTagsCmd {
+    /// window ID
+    #[argh(positional)]
+    id: u32,
+}
+
+/// Ignore an app's window move/resize events (its windows stay managed)
+#[derive(FromArgs)]
+#[argh(subcommand, name = "set-app-events-paused")]
+struct SetAppEventsPausedCmd {
+    /// application name (e.g., Slack)
+    #[argh(positional)]
+    app_name: String,
+    /// true to pause, false to resume
+    #[argh(positional)]
+    paused: bool,
+}
+
+/// Manage a window even if rules or its window level exclude it (persisted)
+#[derive(FromArgs)]
+#[argh(subcommand, name = "manage-window")]
+struct ManageWindowCmd {
+    /// window ID
+    #[argh(positional)]
+    id: u32,
+}
+
+/// Stop managing a window regardless of rules (persisted)
+#[derive(FromArgs)]
+#[argh(subcommand, name = "unmanage-window")]
+struct UnmanageWindowCmd {
+    /// window ID
+    #[argh(positional)]
+    id: u32,
+}
+
+/// Update the daemon's tracked frame of a window from its real position (no retile)
+#[derive(FromArgs)]
+#[argh(subcommand, name = "resync-window")]
+struct ResyncWindowCmd {
+    /// window ID
+    #[argh(positional)]
+    id: u32,
+}
+
+/// Print a window's current frame as "x y width height"
+#[derive(FromArgs)]
+#[argh(subcommand, name = "window-geometry")]
+struct WindowGeometryCmd {
+    /// window ID
+    #[argh(positional)]
+    id: u32,
+}
+
 /// Execute a shell command
 #[derive(FromArgs)]
 #[argh(subcommand, name = "exec")]
@@ -321,6 +916,18 @@ struct ExecOrFocusCmd {
     command: String,
 }
 
+/// Summon or dismiss an app's window, launching the app if it has none
+#[derive(FromArgs)]
+#[argh(subcommand, name = "scratchpad-toggle")]
+struct ScratchpadToggleCmd {
+    /// application name of the scratchpad window
+    #[argh(option)]
+    app_name: String,
+    /// shell command to launch the app (default: open -a <app-name>)
+    #[argh(positional)]
+    command: Option<String>,
+}
+
 /// Get the current exec path
 #[derive(FromArgs)]
 #[argh(subcommand, name = "exec-path")]
@@ -439,6 +1046,15 @@ struct SetCursorWarpCmd {
     mode: String,
 }
 
+/// Make views and windows always use exactly one tag
+#[derive(FromArgs)]
+#[argh(subcommand, name = "set-exclusive-tags")]
+struct SetExclusiveTagsCmd {
+    /// true or false
+    #[argh(positional)]
+    enabled: bool,
+}
+
 /// Get current cursor warp mode
 #[derive(FromArgs)]
 #[argh(subcommand, name = "get-cursor-warp")]
@@ -461,6 +1077,24 @@ struct SetAutoRaiseCmd {
 #[argh(subcommand, name = "get-auto-raise")]
 struct GetAutoRaiseCmd {}
 
+/// Set whether focusing a window also raises it (default: true)
+#[derive(FromArgs)]
+#[argh(subcommand, name = "set-raise-on-focus")]
+struct SetRaiseOnFocusCmd {
+    /// true to raise focused windows, false to focus without raising
+    #[argh(positional)]
+    enabled: bool,
+}
+
+/// Set the modifiers for dragging floating windows (default: none, disabled)
+#[derive(FromArgs)]
+#[argh(subcommand, name = "set-drag-modifier")]
+struct SetDragModifierCmd {
+    /// modifiers such as cmd-alt (left drag moves, right drag resizes), or none to disable
+    #[argh(positional)]
+    modifier: String,
+}
+
 /// Set the outer gap (gap between windows and screen edges)
 #[derive(FromArgs)]
 #[argh(subcommand, name = "set-outer-gap")]
@@ -475,22 +1109,99 @@ struct SetOuterGapCmd {
 #[argh(subcommand, name = "get-outer-gap")]
 struct GetOuterGapCmd {}
 
+/// Keep space at a display edge free for an external bar (adds up per edge)
+#[derive(FromArgs)]
+#[argh(subcommand, name = "reserve-edge")]
+struct ReserveEdgeCmd {
+    /// output (display) ID or name
+    #[argh(option)]
+    output: Option<String>,
+    /// edge: top, bottom, left, right
+    #[argh(positional)]
+    edge: String,
+    /// pixels to reserve
+    #[argh(positional)]
+    size: u32,
+}
+
+/// Drop a display's edge reservations and outer gap
+#[derive(FromArgs)]
+#[argh(subcommand, name = "clear-reservations")]
+struct ClearReservationsCmd {
+    /// output (display) ID or name
+    #[argh(option)]
+    output: Option<String>,
+}
+
+/// Drop inner and outer gaps while only one display is connected
+#[derive(FromArgs)]
+#[argh(subcommand, name = "set-gaps-multi-monitor-only")]
+struct SetGapsMultiMonitorOnlyCmd {
+    /// true to only use gaps with multiple displays, false to always use them
+    #[argh(positional)]
+    enabled: bool,
+}
+
+/// Set how often coalesced retiles are flushed during event storms (0 = immediate, else minimum 4ms)
+#[derive(FromArgs)]
+#[argh(subcommand, name = "set-tick-rate")]
+struct SetTickRateCmd {
+    /// tick interval in milliseconds
+    #[argh(positional)]
+    ms: u32,
+}
+
+/// Save the current position and size of every window under a name
+#[derive(FromArgs)]
+#[argh(subcommand, name = "snapshot-save")]
+struct SnapshotSaveCmd {
+    /// snapshot name
+    #[argh(positional)]
+    name: String,
+}
+
+/// Move windows back to the positions recorded in a saved snapshot
+#[derive(FromArgs)]
+#[argh(subcommand, name = "snapshot-restore")]
+struct SnapshotRestoreCmd {
+    /// snapshot name
+    #[argh(positional)]
+    name: String,
+}
+
 /// Subscribe to state change events
 #[derive(FromArgs)]
 #[argh(subcommand, name = "subscribe")]
 struct SubscribeCmd {
+    /// events to receive (comma-separated: window,focus,display,tags,layout), default all
+    #[argh(positional)]
+    events: Option<String>,
     /// request a snapshot on connection
     #[argh(switch)]
     snapshot: bool,
-    /// filter events (comma-separated: window,focus,display,tags,layout)
+    /// filter events (same as the positional events argument; use one or the other)
     #[argh(option)]
     filter: Option<String>,
 }
 
+/// Prune windows that vanished without a destroy notification and retile
+#[derive(FromArgs)]
+#[argh(subcommand, name = "reconcile")]
+struct ReconcileCmd {}
+
+/// Re-read all displays and windows from the system and retile (recovery)
+#[derive(FromArgs)]
+#[argh(subcommand, name = "resync")]
+struct ResyncCmd {}
+
 /// Quit the yashiki daemon
 #[derive(FromArgs)]
 #[argh(subcommand, name = "quit")]
-struct QuitCmd {}
+struct QuitCmd {
+    /// quit even if event subscribers are connected
+    #[argh(switch)]
+    force: bool,
+}
 
 fn main() -> Result<()> {
     let cli: Cli = argh::from_env();
@@ -522,18 +1233,25 @@ fn main() -> Result<()> {
         }
         Some(SubCommand::Subscribe(cmd)) => {
             // Subscribe to events (separate from normal IPC)
-            let filter = cmd.filter.map(|f| parse_event_filter(&f));
+            let filter = subscribe_filter(cmd.events, cmd.filter)?;
             ipc::subscribe_and_print(cmd.snapshot, filter)
         }
-        Some(subcmd) => run_cli(subcmd),
+        Some(subcmd) => run_cli(subcmd, cli.json),
     }
 }
 
-fn run_cli(subcmd: SubCommand) -> Result<()> {
+fn run_cli(subcmd: SubCommand, json: bool) -> Result<()> {
     let cmd = to_command(subcmd)?;
     let mut client = IpcClient::connect()?;
     let response = client.send(&cmd)?;
 
+    if json {
+        if let Some(output) = response_json(&response) {
+            println!("{}", output?);
+            return Ok(());
+        }
+    }
+
     match response {
         Response::Ok => {}
         Response::Error { message } => {
@@ -632,11 +1350,19 @@ fn run_cli(subcmd: SubCommand) -> Result<()> {
                     "{}: {} [{}x{} @ ({},{})]{}{}",
                     o.id, o.name, o.width, o.height, o.x, o.y, main_marker, focused_marker
                 );
-                println!("  visible_tags: {}", o.visible_tags);
+                println!(
+                    "  visible_tags: {}{}",
+                    o.visible_tags,
+                    format_tag_names(&o.visible_tag_names)
+                );
             }
         }
         Response::State { state } => {
-            println!("Visible tags: {}", state.visible_tags);
+            println!(
+                "Visible tags: {}{}",
+                state.visible_tags,
+                format_tag_names(&state.visible_tag_names)
+            );
             println!("Focused window: {:?}", state.focused_window_id);
             println!("Window count: {}", state.window_count);
             println!("Default layout: {}", state.default_layout);
@@ -644,10 +1370,49 @@ fn run_cli(subcmd: SubCommand) -> Result<()> {
                 "Current layout: {}",
                 state.current_layout.as_deref().unwrap_or("(default)")
             );
+            println!("Raise on focus: {}", state.raise_on_focus);
+            println!("Tag history depth: {}", state.tag_history_depth);
+        }
+        Response::TagStats { tags } => {
+            for t in tags {
+                let visible = if t.visible { " (visible)" } else { "" };
+                println!("{}: {} windows{}", t.tag, t.windows, visible);
+            }
         }
+        Response::UnmanagedWindows { windows } => {
+            for w in windows {
+                println!(
+                    "{}: {} - {} [level={}, title={:?}]",
+                    w.id, w.app_name, w.reason, w.window_level, w.title
+                );
+            }
+        }
+        Response::Stats { stats } => {
+            match stats.last_retile_us {
+                Some(us) => println!(
+                    "Last retile: {:.2}ms, {} writes, {} skipped as unchanged",
+                    us as f64 / 1000.0,
+                    stats.last_retile_writes,
+                    stats.last_retile_skipped
+                ),
+                None => println!("Last retile: (none)"),
+            }
+            println!("Retiles: {}", stats.retile_count);
+            println!(
+                "Events: {} ({:.2}/s over {}s)",
+                stats.events_processed, stats.events_per_sec, stats.uptime_secs
+            );
+        }
+        Response::Config { config } => match serde_json::to_string_pretty(&config) {
+            Ok(json) => println!("{}", json),
+            Err(e) => eprintln!("Failed to serialize config: {}", e),
+        },
         Response::Bindings { bindings } => {
             for b in bindings {
-                println!("{} -> {}", b.key, b.action);
+                match b.label {
+                    Some(label) => println!("{} -> {} ({:?})", b.key, b.action, label),
+                    None => println!("{} -> {}", b.key, b.action),
+                }
             }
         }
         Response::WindowId { id } => {
@@ -709,6 +1474,72 @@ fn run_cli(subcmd: SubCommand) -> Result<()> {
         Response::OuterGap { outer_gap } => {
             println!("{}", outer_gap);
         }
+        Response::MainRatio { ratio } => {
+            println!("{}", ratio);
+        }
+        Response::OutputId { id } => {
+            println!("{}", id);
+        }
+        Response::WindowTags { id, tags } => {
+            let tags: Vec<String> = tags.iter().map(|t| t.to_string()).collect();
+            println!("window {} on tags: {}", id, tags.join(", "));
+        }
+        Response::UsableFrame {
+            output: _,
+            x,
+            y,
+            width,
+            height,
+        } => {
+            println!("{} {} {} {}", x, y, width, height);
+        }
+        Response::WindowCount { count } => {
+            println!("{} windows", count);
+        }
+        Response::BindingsExport { content } => {
+            print!("{}", content);
+        }
+        Response::Keys { keys } => {
+            for key in keys {
+                println!("{}", key);
+            }
+        }
+        Response::Log { lines } => {
+            for line in lines {
+                println!("{}", line);
+            }
+        }
+        Response::LayoutInput {
+            width,
+            height,
+            windows,
+        } => {
+            println!("{}x{}", width, height);
+            for id in windows {
+                println!("{}", id);
+            }
+        }
+        Response::LayoutCapabilities { name, commands } => {
+            println!("{}", name);
+            for command in commands {
+                println!("  {}", command);
+            }
+        }
+        Response::Layouts { layouts } => {
+            for layout in layouts {
+                let running = if layout.running { " (running)" } else { "" };
+                match layout.alias_of {
+                    Some(target) => println!("{} -> {}{}", layout.name, target, running),
+                    None => println!("{}{}", layout.name, running),
+                }
+            }
+        }
+        Response::WindowGeometry { geometry } => {
+            println!(
+                "{} {} {} {}",
+                geometry.x, geometry.y, geometry.width, geometry.height
+            );
+        }
     }
 
     Ok(())
@@ -727,40 +1558,114 @@ fn to_command(subcmd: SubCommand) -> Result<Command> {
             Ok(Command::Bind {
                 key: cmd.key,
                 action: Box::new(action),
+                label: cmd.label,
+                args: cmd.action,
             })
         }
         SubCommand::Unbind(cmd) => Ok(Command::Unbind { key: cmd.key }),
         SubCommand::ListBindings(_) => Ok(Command::ListBindings),
+        SubCommand::ExportBindings(cmd) => Ok(Command::ExportBindings {
+            path: cmd.path.map(absolute_path).transpose()?,
+        }),
+        SubCommand::ListKeys(_) => Ok(Command::ListKeys),
         SubCommand::TagView(cmd) => Ok(Command::TagView {
             tags: cmd.tags,
             output: parse_output_specifier(cmd.output),
         }),
+        SubCommand::TagViewLayout(cmd) => Ok(Command::TagViewWithLayout {
+            tags: cmd.tags,
+            output: parse_output_specifier(cmd.output),
+            layout: cmd.layout,
+        }),
+        SubCommand::TagViewN(cmd) => Ok(Command::TagViewN {
+            n: cmd.n,
+            output: parse_output_specifier(cmd.output),
+        }),
+        SubCommand::TagViewName(cmd) => Ok(Command::TagViewName {
+            name: cmd.name,
+            output: parse_output_specifier(cmd.output),
+        }),
+        SubCommand::SetTagName(cmd) => Ok(Command::SetTagName {
+            tag: cmd.tag,
+            name: cmd.name,
+        }),
         SubCommand::TagToggle(cmd) => Ok(Command::TagToggle {
             tags: cmd.tags,
             output: parse_output_specifier(cmd.output),
         }),
         SubCommand::TagViewLast(_) => Ok(Command::TagViewLast),
+        SubCommand::TagViewBack(cmd) => Ok(Command::TagViewBack { steps: cmd.steps }),
+        SubCommand::TagHistoryClear(_) => Ok(Command::TagHistoryClear),
+        SubCommand::TagCycle(cmd) => Ok(Command::TagCycle {
+            direction: parse_output_direction(&cmd.direction)?,
+            only_occupied: cmd.only_occupied,
+        }),
         SubCommand::WindowMoveToTag(cmd) => Ok(Command::WindowMoveToTag { tags: cmd.tags }),
         SubCommand::WindowToggleTag(cmd) => Ok(Command::WindowToggleTag { tags: cmd.tags }),
+        SubCommand::WindowMatchView(_) => Ok(Command::WindowMatchView),
+        SubCommand::TagWindowsByTitle(cmd) => Ok(Command::TagWindowsByTitle {
+            pattern: cmd.pattern,
+            tags: cmd.tags,
+        }),
+        SubCommand::DistributeWindows(cmd) => Ok(Command::DistributeWindows { tags: cmd.tags }),
         SubCommand::WindowFocus(cmd) => Ok(Command::WindowFocus {
             direction: parse_direction(&cmd.direction)?,
+            same_output: cmd.same_output,
+        }),
+        SubCommand::WindowFocusBySize(cmd) => Ok(Command::WindowFocusBySize {
+            which: parse_size_selection(&cmd.which)?,
         }),
         SubCommand::WindowSwap(cmd) => Ok(Command::WindowSwap {
             direction: parse_direction(&cmd.direction)?,
+            same_output: cmd.same_output,
+        }),
+        SubCommand::WindowShift(cmd) => Ok(Command::WindowShift {
+            direction: parse_output_direction(&cmd.direction)?,
         }),
+        SubCommand::WindowToSlot(cmd) => Ok(Command::WindowToSlot { index: cmd.index }),
         SubCommand::WindowToggleFullscreen(_) => Ok(Command::WindowToggleFullscreen),
         SubCommand::WindowToggleFloat(_) => Ok(Command::WindowToggleFloat),
+        SubCommand::FloatAll(cmd) => Ok(Command::FloatAll {
+            enabled: cmd.enabled,
+        }),
+        SubCommand::WindowToggleAlwaysOnTop(cmd) => {
+            Ok(Command::WindowToggleAlwaysOnTop { id: cmd.id })
+        }
+        SubCommand::WindowToggleNativeSize(cmd) => {
+            Ok(Command::WindowToggleNativeSize { id: cmd.id })
+        }
+        SubCommand::WindowPeek(_) => Ok(Command::WindowPeek),
+        SubCommand::WindowPeekRelease(_) => Ok(Command::WindowPeekRelease),
+        SubCommand::WindowSetUrgent(cmd) => Ok(Command::WindowSetUrgent { id: cmd.id }),
+        SubCommand::FocusUrgent(_) => Ok(Command::FocusUrgent),
         SubCommand::WindowClose(_) => Ok(Command::WindowClose),
+        SubCommand::WindowMinimize(_) => Ok(Command::WindowMinimize),
+        SubCommand::WindowUnminimize(cmd) => Ok(Command::WindowUnminimize { id: cmd.id }),
         SubCommand::OutputFocus(cmd) => Ok(Command::OutputFocus {
             direction: parse_output_direction(&cmd.direction)?,
         }),
         SubCommand::OutputSend(cmd) => Ok(Command::OutputSend {
             direction: parse_output_direction(&cmd.direction)?,
         }),
+        SubCommand::SetMainOutput(cmd) => Ok(Command::SetMainOutput {
+            output: output_specifier(cmd.output),
+        }),
+        SubCommand::SetFocusAxisSwap(cmd) => Ok(Command::SetFocusAxisSwap {
+            output: parse_output_specifier(cmd.output),
+            enabled: cmd.enabled,
+        }),
         SubCommand::Retile(cmd) => Ok(Command::Retile {
             output: parse_output_specifier(cmd.output),
         }),
+        SubCommand::LayoutReverse(cmd) => Ok(Command::LayoutReverse {
+            output: parse_output_specifier(cmd.output),
+        }),
+        SubCommand::SetMain(cmd) => Ok(Command::SetMain { id: cmd.id }),
+        SubCommand::SetStickyMain(cmd) => Ok(Command::SetStickyMain { id: cmd.id }),
+        SubCommand::ClearStickyMain(_) => Ok(Command::ClearStickyMain),
+        SubCommand::LayoutBalanceAll(_) => Ok(Command::LayoutBalanceAll),
         SubCommand::LayoutSetDefault(cmd) => Ok(Command::LayoutSetDefault { layout: cmd.layout }),
+        SubCommand::LayoutSetCurrent(cmd) => Ok(Command::LayoutSetCurrent { layout: cmd.layout }),
         SubCommand::LayoutSet(cmd) => Ok(Command::LayoutSet {
             tags: cmd.tags,
             output: parse_output_specifier(cmd.output),
@@ -770,18 +1675,79 @@ fn to_command(subcmd: SubCommand) -> Result<Command> {
             tags: cmd.tags,
             output: parse_output_specifier(cmd.output),
         }),
+        SubCommand::MainRatioAnimate(cmd) => Ok(Command::MainRatioAnimate {
+            target: cmd.target,
+            duration_ms: cmd.duration.unwrap_or(150),
+        }),
+        SubCommand::SetDefaultLayoutParams(cmd) => Ok(Command::SetDefaultLayoutParams {
+            main_ratio: cmd.main_ratio,
+            inner_gap: cmd.inner_gap,
+        }),
+        SubCommand::MainRatioGet(cmd) => Ok(Command::MainRatioGet {
+            output: parse_output_specifier(cmd.output),
+            tags: cmd.tags,
+        }),
+        SubCommand::LayoutEngineLog(cmd) => Ok(Command::LayoutEngineLog {
+            name: cmd.name,
+            lines: cmd.lines,
+        }),
+        SubCommand::LayoutLastInput(cmd) => Ok(Command::LayoutLastInput { name: cmd.name }),
+        SubCommand::LayoutDescribe(cmd) => Ok(Command::LayoutDescribe { name: cmd.name }),
+        SubCommand::LayoutAlias(cmd) => Ok(Command::LayoutAlias {
+            alias: cmd.alias,
+            target: cmd.target,
+        }),
+        SubCommand::LayoutList(_) => Ok(Command::LayoutList),
         SubCommand::LayoutCmd(cmd) => Ok(Command::LayoutCommand {
             layout: cmd.layout,
             cmd: cmd.cmd,
             args: cmd.args,
         }),
+        SubCommand::LayoutCmdAll(cmd) => Ok(Command::LayoutCommandAll {
+            cmd: cmd.cmd,
+            args: cmd.args,
+        }),
+        SubCommand::LayoutCmdFor(cmd) => Ok(Command::LayoutCommandFor {
+            tags: cmd.tags,
+            output: output_specifier(cmd.output),
+            cmd: cmd.cmd,
+            args: cmd.args,
+        }),
+        SubCommand::ToggleOrientation(cmd) => Ok(Command::ToggleOrientation {
+            output: parse_output_specifier(cmd.output),
+            tags: cmd.tags,
+        }),
+        SubCommand::ToggleGaps(cmd) => Ok(Command::ToggleGaps {
+            output: parse_output_specifier(cmd.output),
+            tags: cmd.tags,
+        }),
         SubCommand::ListWindows(cmd) => Ok(Command::ListWindows {
             all: cmd.all,
             debug: cmd.debug,
+            visible: cmd.visible,
         }),
         SubCommand::ListOutputs(_) => Ok(Command::ListOutputs),
+        SubCommand::OutputUsableFrame(cmd) => Ok(Command::OutputUsableFrame {
+            output: parse_output_specifier(cmd.output),
+        }),
         SubCommand::GetState(_) => Ok(Command::GetState),
+        SubCommand::Stats(_) => Ok(Command::Stats),
+        SubCommand::TagStats(cmd) => Ok(Command::TagStats {
+            output: parse_output_specifier(cmd.output),
+        }),
+        SubCommand::ListUnmanaged(_) => Ok(Command::ListUnmanaged),
+        SubCommand::GetConfig(_) => Ok(Command::GetConfig),
         SubCommand::FocusedWindow(_) => Ok(Command::FocusedWindow),
+        SubCommand::WindowGeometry(cmd) => Ok(Command::WindowGeometry { id: cmd.id }),
+        SubCommand::ResyncWindow(cmd) => Ok(Command::ResyncWindow { id: cmd.id }),
+        SubCommand::ManageWindow(cmd) => Ok(Command::ManageWindow { id: cmd.id }),
+        SubCommand::UnmanageWindow(cmd) => Ok(Command::UnmanageWindow { id: cmd.id }),
+        SubCommand::SetAppEventsPaused(cmd) => Ok(Command::SetAppEventsPaused {
+            app_name: cmd.app_name,
+            paused: cmd.paused,
+        }),
+        SubCommand::WindowOutput(cmd) => Ok(Command::WindowOutput { id: cmd.id }),
+        SubCommand::WindowTags(cmd) => Ok(Command::WindowTags { id: cmd.id }),
         SubCommand::Exec(cmd) => Ok(Command::Exec {
             command: cmd.command,
             track: cmd.track,
@@ -790,6 +1756,10 @@ fn to_command(subcmd: SubCommand) -> Result<Command> {
             app_name: cmd.app_name,
             command: cmd.command,
         }),
+        SubCommand::ScratchpadToggle(cmd) => Ok(Command::ScratchpadToggle {
+            app_name: cmd.app_name,
+            command: cmd.command,
+        }),
         SubCommand::ExecPath(_) => Ok(Command::GetExecPath),
         SubCommand::SetExecPath(cmd) => Ok(Command::SetExecPath { path: cmd.path }),
         SubCommand::AddExecPath(cmd) => Ok(Command::AddExecPath {
@@ -908,12 +1878,21 @@ fn to_command(subcmd: SubCommand) -> Result<Command> {
             Ok(Command::SetCursorWarp { mode })
         }
         SubCommand::GetCursorWarp(_) => Ok(Command::GetCursorWarp),
+        SubCommand::SetExclusiveTags(cmd) => Ok(Command::SetExclusiveTags {
+            enabled: cmd.enabled,
+        }),
         SubCommand::SetAutoRaise(cmd) => {
             let mode = parse_auto_raise_mode(&cmd.mode)?;
             let delay_ms = cmd.delay.unwrap_or(0);
             Ok(Command::SetAutoRaise { mode, delay_ms })
         }
         SubCommand::GetAutoRaise(_) => Ok(Command::GetAutoRaise),
+        SubCommand::SetRaiseOnFocus(cmd) => Ok(Command::SetRaiseOnFocus {
+            enabled: cmd.enabled,
+        }),
+        SubCommand::SetDragModifier(cmd) => Ok(Command::SetDragModifier {
+            modifier: parse_drag_modifier(cmd.modifier),
+        }),
         SubCommand::SetOuterGap(cmd) => {
             if cmd.values.is_empty() {
                 bail!("set-outer-gap requires at least one value");
@@ -927,7 +1906,23 @@ fn to_command(subcmd: SubCommand) -> Result<Command> {
             Ok(Command::SetOuterGap { values: cmd.values })
         }
         SubCommand::GetOuterGap(_) => Ok(Command::GetOuterGap),
-        SubCommand::Quit(_) => Ok(Command::Quit),
+        SubCommand::ReserveEdge(cmd) => Ok(Command::ReserveEdge {
+            output: parse_output_specifier(cmd.output),
+            edge: parse_edge(&cmd.edge)?,
+            size: cmd.size,
+        }),
+        SubCommand::ClearReservations(cmd) => Ok(Command::ClearReservations {
+            output: parse_output_specifier(cmd.output),
+        }),
+        SubCommand::SetGapsMultiMonitorOnly(cmd) => Ok(Command::SetGapsMultiMonitorOnly {
+            enabled: cmd.enabled,
+        }),
+        SubCommand::SetTickRate(cmd) => Ok(Command::SetTickRate { ms: cmd.ms }),
+        SubCommand::SnapshotSave(cmd) => Ok(Command::SnapshotSave { name: cmd.name }),
+        SubCommand::SnapshotRestore(cmd) => Ok(Command::SnapshotRestore { name: cmd.name }),
+        SubCommand::Reconcile(_) => Ok(Command::Reconcile),
+        SubCommand::Resync(_) => Ok(Command::Resync),
+        SubCommand::Quit(cmd) => Ok(Command::Quit { force: cmd.force }),
     }
 }
 
@@ -953,6 +1948,8 @@ fn parse_command(args: &[String]) -> Result<Command> {
             Ok(Command::Bind {
                 key: cmd.key,
                 action: Box::new(action),
+                label: cmd.label,
+                args: cmd.action,
             })
         }
         "unbind" => {
@@ -960,6 +1957,13 @@ fn parse_command(args: &[String]) -> Result<Command> {
             Ok(Command::Unbind { key: cmd.key })
         }
         "list-bindings" => Ok(Command::ListBindings),
+        "export-bindings" => {
+            let cmd: ExportBindingsCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::ExportBindings {
+                path: cmd.path.map(absolute_path).transpose()?,
+            })
+        }
+        "list-keys" => Ok(Command::ListKeys),
         "tag-view" => {
             let cmd: TagViewCmd = from_argh(cmd_name, &cmd_args)?;
             Ok(Command::TagView {
@@ -967,6 +1971,35 @@ fn parse_command(args: &[String]) -> Result<Command> {
                 output: parse_output_specifier(cmd.output),
             })
         }
+        "tag-view-layout" => {
+            let cmd: TagViewLayoutCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::TagViewWithLayout {
+                tags: cmd.tags,
+                output: parse_output_specifier(cmd.output),
+                layout: cmd.layout,
+            })
+        }
+        "tag-view-n" => {
+            let cmd: TagViewNCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::TagViewN {
+                n: cmd.n,
+                output: parse_output_specifier(cmd.output),
+            })
+        }
+        "tag-view-name" => {
+            let cmd: TagViewNameCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::TagViewName {
+                name: cmd.name,
+                output: parse_output_specifier(cmd.output),
+            })
+        }
+        "set-tag-name" => {
+            let cmd: SetTagNameCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::SetTagName {
+                tag: cmd.tag,
+                name: cmd.name,
+            })
+        }
         "tag-toggle" => {
             let cmd: TagToggleCmd = from_argh(cmd_name, &cmd_args)?;
             Ok(Command::TagToggle {
@@ -975,6 +2008,18 @@ fn parse_command(args: &[String]) -> Result<Command> {
             })
         }
         "tag-view-last" => Ok(Command::TagViewLast),
+        "tag-view-back" => {
+            let cmd: TagViewBackCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::TagViewBack { steps: cmd.steps })
+        }
+        "tag-history-clear" => Ok(Command::TagHistoryClear),
+        "tag-cycle" => {
+            let cmd: TagCycleCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::TagCycle {
+                direction: parse_output_direction(&cmd.direction)?,
+                only_occupied: cmd.only_occupied,
+            })
+        }
         "window-move-to-tag" => {
             let cmd: WindowMoveToTagCmd = from_argh(cmd_name, &cmd_args)?;
             Ok(Command::WindowMoveToTag { tags: cmd.tags })
@@ -983,21 +2028,77 @@ fn parse_command(args: &[String]) -> Result<Command> {
             let cmd: WindowToggleTagCmd = from_argh(cmd_name, &cmd_args)?;
             Ok(Command::WindowToggleTag { tags: cmd.tags })
         }
+        "window-match-view" => Ok(Command::WindowMatchView),
+        "tag-windows-by-title" => {
+            let cmd: TagWindowsByTitleCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::TagWindowsByTitle {
+                pattern: cmd.pattern,
+                tags: cmd.tags,
+            })
+        }
+        "distribute-windows" => {
+            let cmd: DistributeWindowsCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::DistributeWindows { tags: cmd.tags })
+        }
         "window-focus" => {
             let cmd: WindowFocusCmd = from_argh(cmd_name, &cmd_args)?;
             Ok(Command::WindowFocus {
                 direction: parse_direction(&cmd.direction)?,
+                same_output: cmd.same_output,
+            })
+        }
+        "window-focus-by-size" => {
+            let cmd: WindowFocusBySizeCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::WindowFocusBySize {
+                which: parse_size_selection(&cmd.which)?,
             })
         }
         "window-swap" => {
             let cmd: WindowSwapCmd = from_argh(cmd_name, &cmd_args)?;
             Ok(Command::WindowSwap {
                 direction: parse_direction(&cmd.direction)?,
+                same_output: cmd.same_output,
             })
         }
+        "window-shift" => {
+            let cmd: WindowShiftCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::WindowShift {
+                direction: parse_output_direction(&cmd.direction)?,
+            })
+        }
+        "window-to-slot" => {
+            let cmd: WindowToSlotCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::WindowToSlot { index: cmd.index })
+        }
         "window-toggle-fullscreen" => Ok(Command::WindowToggleFullscreen),
         "window-toggle-float" => Ok(Command::WindowToggleFloat),
+        "float-all" => {
+            let cmd: FloatAllCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::FloatAll {
+                enabled: cmd.enabled,
+            })
+        }
+        "window-toggle-always-on-top" => {
+            let cmd: WindowToggleAlwaysOnTopCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::WindowToggleAlwaysOnTop { id: cmd.id })
+        }
+        "window-toggle-native-size" => {
+            let cmd: WindowToggleNativeSizeCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::WindowToggleNativeSize { id: cmd.id })
+        }
+        "window-peek" => Ok(Command::WindowPeek),
+        "window-peek-release" => Ok(Command::WindowPeekRelease),
+        "window-set-urgent" => {
+            let cmd: WindowSetUrgentCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::WindowSetUrgent { id: cmd.id })
+        }
+        "focus-urgent" => Ok(Command::FocusUrgent),
         "window-close" => Ok(Command::WindowClose),
+        "window-minimize" => Ok(Command::WindowMinimize),
+        "window-unminimize" => {
+            let cmd: WindowUnminimizeCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::WindowUnminimize { id: cmd.id })
+        }
         "output-focus" => {
             let cmd: OutputFocusCmd = from_argh(cmd_name, &cmd_args)?;
             Ok(Command::OutputFocus {
@@ -1010,12 +2111,41 @@ fn parse_command(args: &[String]) -> Result<Command> {
                 direction: parse_output_direction(&cmd.direction)?,
             })
         }
+        "set-main-output" => {
+            let cmd: SetMainOutputCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::SetMainOutput {
+                output: output_specifier(cmd.output),
+            })
+        }
+        "set-focus-axis-swap" => {
+            let cmd: SetFocusAxisSwapCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::SetFocusAxisSwap {
+                output: parse_output_specifier(cmd.output),
+                enabled: cmd.enabled,
+            })
+        }
         "retile" => {
             let cmd: RetileCmd = from_argh(cmd_name, &cmd_args)?;
             Ok(Command::Retile {
                 output: parse_output_specifier(cmd.output),
             })
         }
+        "layout-reverse" => {
+            let cmd: LayoutReverseCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::LayoutReverse {
+                output: parse_output_specifier(cmd.output),
+            })
+        }
+        "set-main" => {
+            let cmd: SetMainCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::SetMain { id: cmd.id })
+        }
+        "set-sticky-main" => {
+            let cmd: SetStickyMainCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::SetStickyMain { id: cmd.id })
+        }
+        "clear-sticky-main" => Ok(Command::ClearStickyMain),
+        "layout-balance-all" => Ok(Command::LayoutBalanceAll),
         "layout-set-default" => {
             let cmd: LayoutSetDefaultCmd = from_argh(cmd_name, &cmd_args)?;
             Ok(Command::LayoutSetDefault { layout: cmd.layout })
@@ -1028,6 +2158,10 @@ fn parse_command(args: &[String]) -> Result<Command> {
                 layout: cmd.layout,
             })
         }
+        "layout-set-current" => {
+            let cmd: LayoutSetCurrentCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::LayoutSetCurrent { layout: cmd.layout })
+        }
         "layout-get" => {
             let cmd: LayoutGetCmd = from_argh(cmd_name, &cmd_args)?;
             Ok(Command::LayoutGet {
@@ -1035,38 +2169,172 @@ fn parse_command(args: &[String]) -> Result<Command> {
                 output: parse_output_specifier(cmd.output),
             })
         }
+        "main-ratio-animate" => {
+            let cmd: MainRatioAnimateCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::MainRatioAnimate {
+                target: cmd.target,
+                duration_ms: cmd.duration.unwrap_or(150),
+            })
+        }
+        "set-default-layout-params" => {
+            let cmd: SetDefaultLayoutParamsCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::SetDefaultLayoutParams {
+                main_ratio: cmd.main_ratio,
+                inner_gap: cmd.inner_gap,
+            })
+        }
+        "main-ratio-get" => {
+            let cmd: MainRatioGetCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::MainRatioGet {
+                output: parse_output_specifier(cmd.output),
+                tags: cmd.tags,
+            })
+        }
+        "layout-engine-log" => {
+            let cmd: LayoutEngineLogCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::LayoutEngineLog {
+                name: cmd.name,
+                lines: cmd.lines,
+            })
+        }
+        "layout-last-input" => {
+            let cmd: LayoutLastInputCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::LayoutLastInput { name: cmd.name })
+        }
+        "layout-describe" => {
+            let cmd: LayoutDescribeCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::LayoutDescribe { name: cmd.name })
+        }
+        "layout-alias" => {
+            let cmd: LayoutAliasCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::LayoutAlias {
+                alias: cmd.alias,
+                target: cmd.target,
+            })
+        }
+        "layout-list" => Ok(Command::LayoutList),
         "layout-cmd" => {
-            let cmd: LayoutCmdCmd = from_argh(cmd_name, &cmd_args)?;
+            let cmd: LayoutCmdCmd = from_argh(cmd_name, &layout_cmd_argv(&cmd_args))?;
             Ok(Command::LayoutCommand {
                 layout: cmd.layout,
                 cmd: cmd.cmd,
                 args: cmd.args,
             })
         }
+        "layout-cmd-all" => {
+            let cmd: LayoutCmdAllCmd = from_argh(cmd_name, &layout_cmd_argv(&cmd_args))?;
+            Ok(Command::LayoutCommandAll {
+                cmd: cmd.cmd,
+                args: cmd.args,
+            })
+        }
+        "layout-cmd-for" => {
+            let cmd: LayoutCmdForCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::LayoutCommandFor {
+                tags: cmd.tags,
+                output: output_specifier(cmd.output),
+                cmd: cmd.cmd,
+                args: cmd.args,
+            })
+        }
+        "toggle-orientation" => {
+            let cmd: ToggleOrientationCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::ToggleOrientation {
+                output: parse_output_specifier(cmd.output),
+                tags: cmd.tags,
+            })
+        }
+        "toggle-gaps" => {
+            let cmd: ToggleGapsCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::ToggleGaps {
+                output: parse_output_specifier(cmd.output),
+                tags: cmd.tags,
+            })
+        }
         "list-windows" => {
             let cmd: ListWindowsCmd = from_argh(cmd_name, &cmd_args)?;
             Ok(Command::ListWindows {
                 all: cmd.all,
                 debug: cmd.debug,
+                visible: cmd.visible,
             })
         }
         "list-outputs" => Ok(Command::ListOutputs),
+        "output-usable-frame" => {
+            let cmd: OutputUsableFrameCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::OutputUsableFrame {
+                output: parse_output_specifier(cmd.output),
+            })
+        }
         "get-state" => Ok(Command::GetState),
+        "stats" => Ok(Command::Stats),
+        "tag-stats" => {
+            let cmd: TagStatsCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::TagStats {
+                output: parse_output_specifier(cmd.output),
+            })
+        }
+        "list-unmanaged" => Ok(Command::ListUnmanaged),
+        "get-config" => Ok(Command::GetConfig),
         "focused-window" => Ok(Command::FocusedWindow),
+        "window-output" => {
+            let cmd: WindowOutputCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::WindowOutput { id: cmd.id })
+        }
+        "window-tags" => {
+            let cmd: WindowTagsCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::WindowTags { id: cmd.id })
+        }
+        "resync-window" => {
+            let cmd: ResyncWindowCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::ResyncWindow { id: cmd.id })
+        }
+        "manage-window" => {
+            let cmd: ManageWindowCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::ManageWindow { id: cmd.id })
+        }
+        "unmanage-window" => {
+            let cmd: UnmanageWindowCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::UnmanageWindow { id: cmd.id })
+        }
+        "set-app-events-paused" => {
+            let cmd: SetAppEventsPausedCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::SetAppEventsPaused {
+                app_name: cmd.app_name,
+                paused: cmd.paused,
+            })
+        }
+        "window-geometry" => {
+            let cmd: WindowGeometryCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::WindowGeometry { id: cmd.id })
+        }
         "exec" => {
-            let cmd: ExecCmd = from_argh(cmd_name, &cmd_args)?;
+            let argv = exec_argv(&cmd_args, &[]);
+            let argv: Vec<&str> = argv.iter().map(|s| s.as_str()).collect();
+            let cmd: ExecCmd = from_argh(cmd_name, &argv)?;
             Ok(Command::Exec {
                 command: cmd.command,
                 track: cmd.track,
             })
         }
         "exec-or-focus" => {
-            let cmd: ExecOrFocusCmd = from_argh(cmd_name, &cmd_args)?;
+            let argv = exec_argv(&cmd_args, &["--app-name"]);
+            let argv: Vec<&str> = argv.iter().map(|s| s.as_str()).collect();
+            let cmd: ExecOrFocusCmd = from_argh(cmd_name, &argv)?;
             Ok(Command::ExecOrFocus {
                 app_name: cmd.app_name,
                 command: cmd.command,
             })
         }
+        "scratchpad-toggle" => {
+            let argv = exec_argv(&cmd_args, &["--app-name"]);
+            let argv: Vec<&str> = argv.iter().map(|s| s.as_str()).collect();
+            let cmd: ScratchpadToggleCmd = from_argh(cmd_name, &argv)?;
+            Ok(Command::ScratchpadToggle {
+                app_name: cmd.app_name,
+                command: cmd.command,
+            })
+        }
         "exec-path" => Ok(Command::GetExecPath),
         "set-exec-path" => {
             let cmd: SetExecPathCmd = from_argh(cmd_name, &cmd_args)?;
@@ -1194,6 +2462,12 @@ fn parse_command(args: &[String]) -> Result<Command> {
             Ok(Command::SetCursorWarp { mode })
         }
         "get-cursor-warp" => Ok(Command::GetCursorWarp),
+        "set-exclusive-tags" => {
+            let cmd: SetExclusiveTagsCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::SetExclusiveTags {
+                enabled: cmd.enabled,
+            })
+        }
         "set-auto-raise" => {
             let cmd: SetAutoRaiseCmd = from_argh(cmd_name, &cmd_args)?;
             let mode = parse_auto_raise_mode(&cmd.mode)?;
@@ -1201,6 +2475,18 @@ fn parse_command(args: &[String]) -> Result<Command> {
             Ok(Command::SetAutoRaise { mode, delay_ms })
         }
         "get-auto-raise" => Ok(Command::GetAutoRaise),
+        "set-raise-on-focus" => {
+            let cmd: SetRaiseOnFocusCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::SetRaiseOnFocus {
+                enabled: cmd.enabled,
+            })
+        }
+        "set-drag-modifier" => {
+            let cmd: SetDragModifierCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::SetDragModifier {
+                modifier: parse_drag_modifier(cmd.modifier),
+            })
+        }
         "set-outer-gap" => {
             let cmd: SetOuterGapCmd = from_argh(cmd_name, &cmd_args)?;
             if cmd.values.is_empty() {
@@ -1215,7 +2501,44 @@ fn parse_command(args: &[String]) -> Result<Command> {
             Ok(Command::SetOuterGap { values: cmd.values })
         }
         "get-outer-gap" => Ok(Command::GetOuterGap),
-        "quit" => Ok(Command::Quit),
+        "reserve-edge" => {
+            let cmd: ReserveEdgeCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::ReserveEdge {
+                output: parse_output_specifier(cmd.output),
+                edge: parse_edge(&cmd.edge)?,
+                size: cmd.size,
+            })
+        }
+        "clear-reservations" => {
+            let cmd: ClearReservationsCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::ClearReservations {
+                output: parse_output_specifier(cmd.output),
+            })
+        }
+        "set-gaps-multi-monitor-only" => {
+            let cmd: SetGapsMultiMonitorOnlyCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::SetGapsMultiMonitorOnly {
+                enabled: cmd.enabled,
+            })
+        }
+        "set-tick-rate" => {
+            let cmd: SetTickRateCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::SetTickRate { ms: cmd.ms })
+        }
+        "snapshot-save" => {
+            let cmd: SnapshotSaveCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::SnapshotSave { name: cmd.name })
+        }
+        "snapshot-restore" => {
+            let cmd: SnapshotRestoreCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::SnapshotRestore { name: cmd.name })
+        }
+        "reconcile" => Ok(Command::Reconcile),
+        "resync" => Ok(Command::Resync),
+        "quit" => {
+            let cmd: QuitCmd = from_argh(cmd_name, &cmd_args)?;
+            Ok(Command::Quit { force: cmd.force })
+        }
         _ => bail!("Unknown command: {}", cmd_name),
     }
 }
@@ -1235,6 +2558,24 @@ fn parse_direction(s: &str) -> Result<Direction> {
     }
 }
 
+fn parse_edge(s: &str) -> Result<Edge> {
+    match s.to_lowercase().as_str() {
+        "top" => Ok(Edge::Top),
+        "bottom" => Ok(Edge::Bottom),
+        "left" => Ok(Edge::Left),
+        "right" => Ok(Edge::Right),
+        _ => bail!("Unknown edge: {} (use top, bottom, left, or right)", s),
+    }
+}
+
+fn parse_size_selection(s: &str) -> Result<SizeSelection> {
+    match s.to_lowercase().as_str() {
+        "largest" => Ok(SizeSelection::Largest),
+        "smallest" => Ok(SizeSelection::Smallest),
+        _ => bail!("Unknown size: {} (use largest or smallest)", s),
+    }
+}
+
 fn parse_output_direction(s: &str) -> Result<OutputDirection> {
     match s.to_lowercase().as_str() {
         "next" => Ok(OutputDirection::Next),
@@ -1263,6 +2604,11 @@ fn parse_auto_raise_mode(s: &str) -> Result<AutoRaiseMode> {
     }
 }
 
+/// `none` disables modifier+drag; anything else is validated by the daemon.
+fn parse_drag_modifier(s: String) -> Option<String> {
+    (!s.eq_ignore_ascii_case("none")).then_some(s)
+}
+
 fn parse_window_level(s: &str) -> Result<WindowLevel> {
     match s.to_lowercase().as_str() {
         "normal" => Ok(WindowLevel::Named(WindowLevelName::Normal)),
@@ -1298,14 +2644,21 @@ fn parse_button_state(s: &str) -> Result<ButtonState> {
     }
 }
 
+/// Resolve a path against the CLI's working directory; the daemon runs elsewhere.
+fn absolute_path(path: String) -> Result<String> {
+    Ok(std::path::absolute(&path)?.to_string_lossy().into_owned())
+}
+
 fn parse_output_specifier(s: Option<String>) -> Option<OutputSpecifier> {
-    s.map(|s| {
-        if let Ok(id) = s.parse::<u32>() {
-            OutputSpecifier::Id(id)
-        } else {
-            OutputSpecifier::Name(s)
-        }
-    })
+    s.map(output_specifier)
+}
+
+fn output_specifier(s: String) -> OutputSpecifier {
+    if let Ok(id) = s.parse::<u32>() {
+        OutputSpecifier::Id(id)
+    } else {
+        OutputSpecifier::Name(s)
+    }
 }
 
 fn parse_rule_action(args: &[String]) -> Result<RuleAction> {
@@ -1325,7 +2678,7 @@ fn parse_rule_action(args: &[String]) -> Result<RuleAction> {
                 bail!("tags action requires a bitmask argument");
             }
             let tags = action_args[0]
-                .parse::<u32>()
+                .parse::<u64>()
                 .map_err(|_| anyhow::anyhow!("Invalid tags bitmask: {}", action_args[0]))?;
             Ok(RuleAction::Tags { tags })
         }
@@ -1371,7 +2724,87 @@ fn parse_rule_action(args: &[String]) -> Result<RuleAction> {
     }
 }
 
-fn parse_event_filter(s: &str) -> EventFilter {
+/// Terminate option parsing before the layout command name so its arguments
+/// are forwarded verbatim (e.g. `layout-cmd dec-main-ratio -0.05`).
+fn layout_cmd_argv<'a>(args: &[&'a str]) -> Vec<&'a str> {
+    let split = if args.first() == Some(&"--layout") {
+        args.len().min(2)
+    } else {
+        0
+    };
+    if args[split..].first().is_none_or(|arg| arg.starts_with('-')) {
+        return args.to_vec();
+    }
+    let mut argv = args[..split].to_vec();
+    argv.push("--");
+    argv.extend_from_slice(&args[split..]);
+    argv
+}
+
+/// Collapse everything after the leading `--` options into a single shell
+/// command, so `exec open -a Terminal` parses like `exec "open -a Terminal"`.
+fn exec_argv(args: &[&str], value_options: &[&str]) -> Vec<String> {
+    let mut i = 0;
+    while i < args.len() {
+        let arg = args[i];
+        if arg == "--" {
+            i += 1;
+            break;
+        }
+        if !arg.starts_with("--") {
+            break;
+        }
+        i += if value_options.contains(&arg) { 2 } else { 1 };
+    }
+    let i = i.min(args.len());
+
+    let mut argv: Vec<String> = args[..i].iter().map(|arg| arg.to_string()).collect();
+    match &args[i..] {
+        [] => {}
+        [command] => argv.push(command.to_string()),
+        words => argv.push(
+            words
+                .iter()
+                .map(|word| shell_quote(word))
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+    }
+    argv
+}
+
+fn shell_quote(word: &str) -> String {
+    let is_plain = |c: char| c.is_ascii_alphanumeric() || "-_./=:,@%+".contains(c);
+    if !word.is_empty() && word.chars().all(is_plain) {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', "'\\''"))
+    }
+}
+
+/// Payload of a list/get response as JSON for `--json`.
+/// None for responses that keep the human format.
+fn response_json(response: &Response) -> Option<serde_json::Result<String>> {
+    match response {
+        Response::Windows { windows } => Some(serde_json::to_string_pretty(windows)),
+        Response::Outputs { outputs } => Some(serde_json::to_string_pretty(outputs)),
+        Response::State { state } => Some(serde_json::to_string_pretty(state)),
+        Response::Bindings { bindings } => Some(serde_json::to_string_pretty(bindings)),
+        Response::WindowId { id } => Some(serde_json::to_string_pretty(id)),
+        _ => None,
+    }
+}
+
+/// " (mail, web)" after a tag mask, or nothing when no visible tag is named
+fn format_tag_names(names: &[String]) -> String {
+    if names.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", names.join(", "))
+    }
+}
+
+fn parse_event_filter(s: &str) -> Result<EventFilter> {
     let mut filter = EventFilter::default();
     for part in s.split(',') {
         match part.trim().to_lowercase().as_str() {
@@ -1380,10 +2813,24 @@ fn parse_event_filter(s: &str) -> EventFilter {
             "display" => filter.display = true,
             "tags" => filter.tags = true,
             "layout" => filter.layout = true,
-            _ => {}
+            other => bail!(
+                "Unknown event type: {} (expected window, focus, display, tags or layout)",
+                other
+            ),
         }
     }
-    filter
+    Ok(filter)
+}
+
+/// Event filter from subscribe's positional events or --filter, which can't be combined.
+fn subscribe_filter(events: Option<String>, filter: Option<String>) -> Result<Option<EventFilter>> {
+    if events.is_some() && filter.is_some() {
+        bail!("subscribe takes either positional events or --filter, not both");
+    }
+    events
+        .or(filter)
+        .map(|f| parse_event_filter(&f))
+        .transpose()
 }
 
 fn format_button_state(btn: &ButtonInfo) -> &'static str {
@@ -1397,3 +2844,396 @@ fn format_button_state(btn: &ButtonInfo) -> &'static str {
         "exists"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command> {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        parse_command(&args)
+    }
+
+    #[test]
+    fn test_parse_bind_layout_cmd_with_args() {
+        let cmd = parse(&["bind", "alt-l", "layout-cmd", "inc-main-ratio", "0.05"]).unwrap();
+        match cmd {
+            Command::Bind {
+                key, action, label, ..
+            } => {
+                assert_eq!(key, "alt-l");
+                assert_eq!(label, None);
+                match *action {
+                    Command::LayoutCommand { layout, cmd, args } => {
+                        assert_eq!(layout, None);
+                        assert_eq!(cmd, "inc-main-ratio");
+                        assert_eq!(args, vec!["0.05"]);
+                    }
+                    _ => panic!("Expected LayoutCommand"),
+                }
+            }
+            _ => panic!("Expected Bind"),
+        }
+    }
+
+    #[test]
+    fn test_parse_bind_layout_cmd_with_layout_and_negative_arg() {
+        let cmd = parse(&[
+            "bind",
+            "alt-h",
+            "layout-cmd",
+            "--layout",
+            "tatami",
+            "inc-main-ratio",
+            "-0.05",
+        ])
+        .unwrap();
+        match cmd {
+            Command::Bind { action, .. } => match *action {
+                Command::LayoutCommand { layout, cmd, args } => {
+                    assert_eq!(layout.as_deref(), Some("tatami"));
+                    assert_eq!(cmd, "inc-main-ratio");
+                    assert_eq!(args, vec!["-0.05"]);
+                }
+                _ => panic!("Expected LayoutCommand"),
+            },
+            _ => panic!("Expected Bind"),
+        }
+    }
+
+    #[test]
+    fn test_parse_bind_with_label() {
+        let cmd = parse(&[
+            "bind",
+            "--label",
+            "focus window left",
+            "cmd-h",
+            "window-focus",
+            "left",
+        ])
+        .unwrap();
+        match cmd {
+            Command::Bind { key, label, .. } => {
+                assert_eq!(key, "cmd-h");
+                assert_eq!(label.as_deref(), Some("focus window left"));
+            }
+            _ => panic!("Expected Bind"),
+        }
+    }
+
+    /// Split a line produced by `shell_quote` (plain words and single-quoted strings).
+    fn split_shell_words(line: &str) -> Vec<String> {
+        let mut words = Vec::new();
+        let mut word = String::new();
+        let mut in_word = false;
+        let mut chars = line.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\'' => {
+                    in_word = true;
+                    for q in chars.by_ref() {
+                        if q == '\'' {
+                            break;
+                        }
+                        word.push(q);
+                    }
+                }
+                '\\' => {
+                    in_word = true;
+                    word.extend(chars.next());
+                }
+                ' ' => {
+                    if in_word {
+                        words.push(std::mem::take(&mut word));
+                        in_word = false;
+                    }
+                }
+                _ => {
+                    in_word = true;
+                    word.push(c);
+                }
+            }
+        }
+        if in_word {
+            words.push(word);
+        }
+        words
+    }
+
+    #[test]
+    fn test_exported_bindings_reparse_into_same_bindings() {
+        let binds = [
+            parse(&["bind", "alt-1", "tag-view", "1"]).unwrap(),
+            parse(&[
+                "bind",
+                "--label",
+                "focus the window's left",
+                "cmd-h",
+                "window-focus",
+                "left",
+            ])
+            .unwrap(),
+            parse(&["bind", "cmd-t", "exec", "open", "-a", "Terminal"]).unwrap(),
+            parse(&["bind", "alt-h", "layout-cmd", "inc-main-ratio", "-0.05"]).unwrap(),
+        ];
+        let exported: Vec<core::ExportedBinding> = binds
+            .iter()
+            .map(|bind| match bind {
+                Command::Bind {
+                    key, label, args, ..
+                } => core::ExportedBinding {
+                    key: key.clone(),
+                    args: args.clone(),
+                    label: label.clone(),
+                },
+                _ => panic!("Expected Bind"),
+            })
+            .collect();
+
+        let content = core::format_bindings_export(&exported);
+        let reparsed: Vec<Command> = content
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .map(|line| {
+                let words = split_shell_words(line);
+                assert_eq!(words[0], "yashiki");
+                parse_command(&words[1..]).unwrap()
+            })
+            .collect();
+
+        let mut expected: Vec<String> = binds.iter().map(|b| format!("{:?}", b)).collect();
+        let mut actual: Vec<String> = reparsed.iter().map(|b| format!("{:?}", b)).collect();
+        expected.sort();
+        actual.sort();
+        assert_eq!(actual, expected);
+    }
+
+    fn parse_bound_action(args: &[&str]) -> Command {
+        match parse(args).unwrap() {
+            Command::Bind { action, .. } => *action,
+            _ => panic!("Expected Bind"),
+        }
+    }
+
+    #[test]
+    fn test_parse_bind_exec_joins_remainder() {
+        match parse_bound_action(&["bind", "cmd-t", "exec", "open", "-a", "Terminal"]) {
+            Command::Exec { command, track } => {
+                assert_eq!(command, "open -a Terminal");
+                assert!(!track);
+            }
+            _ => panic!("Expected Exec"),
+        }
+    }
+
+    #[test]
+    fn test_parse_bind_exec_quoted_command() {
+        match parse_bound_action(&["bind", "cmd-t", "exec", "open -a Terminal"]) {
+            Command::Exec { command, .. } => assert_eq!(command, "open -a Terminal"),
+            _ => panic!("Expected Exec"),
+        }
+    }
+
+    #[test]
+    fn test_parse_bind_exec_track() {
+        match parse_bound_action(&[
+            "bind",
+            "alt-b",
+            "exec",
+            "--track",
+            "borders",
+            "active_color=0xffe1e3e4",
+        ]) {
+            Command::Exec { command, track } => {
+                assert_eq!(command, "borders active_color=0xffe1e3e4");
+                assert!(track);
+            }
+            _ => panic!("Expected Exec"),
+        }
+    }
+
+    #[test]
+    fn test_parse_bind_exec_or_focus() {
+        match parse_bound_action(&[
+            "bind",
+            "alt-c",
+            "exec-or-focus",
+            "--app-name",
+            "Google Chrome",
+            "open",
+            "-a",
+            "Google Chrome",
+        ]) {
+            Command::ExecOrFocus { app_name, command } => {
+                assert_eq!(app_name, "Google Chrome");
+                assert_eq!(command, "open -a 'Google Chrome'");
+            }
+            _ => panic!("Expected ExecOrFocus"),
+        }
+    }
+
+    #[test]
+    fn test_parse_bind_exec_or_focus_quoted_command() {
+        match parse_bound_action(&[
+            "bind",
+            "alt-s",
+            "exec-or-focus",
+            "--app-name",
+            "Safari",
+            "open -a Safari",
+        ]) {
+            Command::ExecOrFocus { app_name, command } => {
+                assert_eq!(app_name, "Safari");
+                assert_eq!(command, "open -a Safari");
+            }
+            _ => panic!("Expected ExecOrFocus"),
+        }
+    }
+
+    #[test]
+    fn test_parse_bind_scratchpad_toggle() {
+        match parse_bound_action(&[
+            "bind",
+            "alt-grave",
+            "scratchpad-toggle",
+            "--app-name",
+            "Terminal",
+        ]) {
+            Command::ScratchpadToggle { app_name, command } => {
+                assert_eq!(app_name, "Terminal");
+                assert_eq!(command, None);
+            }
+            _ => panic!("Expected ScratchpadToggle"),
+        }
+        match parse_bound_action(&[
+            "bind",
+            "alt-grave",
+            "scratchpad-toggle",
+            "--app-name",
+            "Ghostty",
+            "open",
+            "-n",
+            "/Applications/Ghostty.app",
+        ]) {
+            Command::ScratchpadToggle { command, .. } => {
+                assert_eq!(
+                    command.as_deref(),
+                    Some("open -n /Applications/Ghostty.app")
+                );
+            }
+            _ => panic!("Expected ScratchpadToggle"),
+        }
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("open"), "open");
+        assert_eq!(shell_quote("Google Chrome"), "'Google Chrome'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+        assert_eq!(shell_quote(""), "''");
+    }
+
+    #[test]
+    fn test_parse_tag_view_accepts_tags_above_32() {
+        match parse(&["tag-view", "4294967296"]).unwrap() {
+            Command::TagView { tags, output } => {
+                assert_eq!(tags, 1 << 32);
+                assert_eq!(output, None);
+            }
+            _ => panic!("Expected TagView"),
+        }
+    }
+
+    #[test]
+    fn test_parse_layout_cmd_without_args() {
+        match parse(&["layout-cmd", "zoom"]).unwrap() {
+            Command::LayoutCommand { layout, cmd, args } => {
+                assert_eq!(layout, None);
+                assert_eq!(cmd, "zoom");
+                assert!(args.is_empty());
+            }
+            _ => panic!("Expected LayoutCommand"),
+        }
+    }
+
+    #[test]
+    fn test_parse_layout_cmd_for() {
+        let cmd = parse(&[
+            "layout-cmd-for",
+            "--tags",
+            "4",
+            "--output",
+            "DELL",
+            "set-padding",
+            "40",
+        ])
+        .unwrap();
+        match cmd {
+            Command::LayoutCommandFor {
+                tags,
+                output,
+                cmd,
+                args,
+            } => {
+                assert_eq!(tags, 4);
+                assert_eq!(output, OutputSpecifier::Name("DELL".to_string()));
+                assert_eq!(cmd, "set-padding");
+                assert_eq!(args, vec!["40"]);
+            }
+            _ => panic!("Expected LayoutCommandFor"),
+        }
+    }
+
+    #[test]
+    fn test_parse_reserve_edge() {
+        match parse(&["reserve-edge", "--output", "2", "Top", "30"]).unwrap() {
+            Command::ReserveEdge { output, edge, size } => {
+                assert_eq!(output, Some(OutputSpecifier::Id(2)));
+                assert_eq!(edge, Edge::Top);
+                assert_eq!(size, 30);
+            }
+            _ => panic!("Expected ReserveEdge"),
+        }
+        assert!(parse(&["reserve-edge", "middle", "30"]).is_err());
+    }
+
+    #[test]
+    fn test_response_json_emits_payload() {
+        let response = Response::Outputs {
+            outputs: vec![yashiki_ipc::OutputInfo {
+                id: 1,
+                name: "Built-in".to_string(),
+                x: 0,
+                y: 0,
+                width: 1512,
+                height: 944,
+                is_main: true,
+                visible_tags: 1,
+                visible_tag_names: vec![],
+                is_focused: true,
+            }],
+        };
+        let json: serde_json::Value =
+            serde_json::from_str(&response_json(&response).unwrap().unwrap()).unwrap();
+        assert_eq!(json[0]["name"], "Built-in");
+        assert_eq!(json.as_array().unwrap().len(), 1);
+
+        let json = response_json(&Response::WindowId { id: None }).unwrap();
+        assert_eq!(json.unwrap(), "null");
+        assert!(response_json(&Response::Ok).is_none());
+    }
+
+    #[test]
+    fn test_parse_event_filter() {
+        let filter = parse_event_filter("focus, Tags").unwrap();
+        assert!(filter.focus && filter.tags);
+        assert!(!filter.window && !filter.display && !filter.layout);
+
+        assert!(parse_event_filter("focus,focsu").is_err());
+
+        let filter = subscribe_filter(None, Some("layout".to_string())).unwrap();
+        assert!(filter.is_some_and(|f| f.layout && !f.focus));
+        assert!(subscribe_filter(None, None).unwrap().is_none());
+        assert!(subscribe_filter(Some("focus".to_string()), Some("layout".to_string())).is_err());
+    }
+}
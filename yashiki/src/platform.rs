@@ -30,6 +30,13 @@ pub trait WindowSystem {
     /// Used for window-level checks during transitions (e.g., fullscreen).
     /// Returns true if the window is found via AX API.
     fn window_exists_in_ax(&self, window_id: u32, pid: i32) -> bool;
+    /// Check if the process owning windows is still running.
+    fn is_process_running(&self, pid: i32) -> bool;
+    /// Read a window's current frame from the AX API.
+    /// Returns None if the window can't be found or its frame can't be read.
+    fn get_window_frame(&self, window_id: u32, pid: i32) -> Option<Rect>;
+    /// Check if a window is minimized to the Dock via the AX API.
+    fn is_window_minimized(&self, window_id: u32, pid: i32) -> bool;
 }
 
 /// macOS implementation of WindowSystem
@@ -129,6 +136,41 @@ impl WindowSystem for MacOSWindowSystem {
             }
         }
     }
+
+    fn is_process_running(&self, pid: i32) -> bool {
+        // kill with signal 0 only checks that the process exists
+        unsafe { libc::kill(pid, 0) == 0 }
+    }
+
+    fn get_window_frame(&self, window_id: u32, pid: i32) -> Option<Rect> {
+        let app = AXUIElement::application(pid);
+        let ax_win = app
+            .windows()
+            .ok()?
+            .into_iter()
+            .find(|w| w.window_id() == Some(window_id))?;
+        let position = ax_win.position().ok()?;
+        let size = ax_win.size().ok()?;
+        Some(Rect {
+            x: position.x as i32,
+            y: position.y as i32,
+            width: size.width as u32,
+            height: size.height as u32,
+        })
+    }
+
+    fn is_window_minimized(&self, window_id: u32, pid: i32) -> bool {
+        let app = AXUIElement::application(pid);
+        app.windows()
+            .ok()
+            .and_then(|windows| {
+                windows
+                    .into_iter()
+                    .find(|w| w.window_id() == Some(window_id))
+            })
+            .and_then(|w| w.is_minimized().ok())
+            .unwrap_or(false)
+    }
 }
 
 impl Default for MacOSWindowSystem {
@@ -142,15 +184,22 @@ impl Default for MacOSWindowSystem {
 pub trait WindowManipulator {
     fn apply_window_moves(&self, moves: &[WindowMove]);
     fn apply_layout(&self, display_id: DisplayId, frame: &Rect, geometries: &[WindowGeometry]);
-    fn focus_window(&self, window_id: u32, pid: i32);
+    /// Focus a window, also raising it (AXRaise) if `raise` is set
+    fn focus_window(&self, window_id: u32, pid: i32, raise: bool);
     fn move_window_to_position(&self, window_id: u32, pid: i32, x: i32, y: i32);
     fn set_window_dimensions(&self, window_id: u32, pid: i32, width: u32, height: u32);
     fn set_window_frame(&self, window_id: u32, pid: i32, x: i32, y: i32, width: u32, height: u32);
     fn close_window(&self, window_id: u32, pid: i32);
+    /// Minimize a window to the Dock (AXMinimized) or restore it
+    fn set_window_minimized(&self, window_id: u32, pid: i32, minimized: bool);
+    /// Raise a window within the window stack without activating its application
+    fn raise_window(&self, window_id: u32, pid: i32);
     fn exec_command(&self, command: &str, path: &str) -> Result<(), String>;
     fn exec_command_tracked(&self, command: &str, path: &str) -> Result<u32, String>;
     fn terminate_process(&self, pid: u32);
     fn warp_cursor(&self, x: i32, y: i32);
+    /// Make a display the system main display (the one with the menu bar)
+    fn set_main_display(&self, display_id: DisplayId) -> Result<(), String>;
 }
 
 /// macOS implementation of WindowManipulator
@@ -299,7 +348,7 @@ impl WindowManipulator for MacOSWindowManipulator {
         }
     }
 
-    fn focus_window(&self, window_id: u32, pid: i32) {
+    fn focus_window(&self, window_id: u32, pid: i32, raise: bool) {
         let app = AXUIElement::application(pid);
         let ax_windows = match app.windows() {
             Ok(w) => w,
@@ -321,10 +370,14 @@ impl WindowManipulator for MacOSWindowManipulator {
                         Ok(()) => tracing::debug!("Set main window {} (pid {})", window_id, pid),
                         Err(e) => tracing::warn!("Failed to set main window {}: {}", window_id, e),
                     }
-                    // Raise window
-                    match ax_win.raise() {
-                        Ok(()) => tracing::debug!("Raised window {} (pid {})", window_id, pid),
-                        Err(e) => tracing::warn!("Failed to raise window {}: {}", window_id, e),
+                    // Raise window unless disabled with set-raise-on-focus
+                    if raise {
+                        match ax_win.raise() {
+                            Ok(()) => tracing::debug!("Raised window {} (pid {})", window_id, pid),
+                            Err(e) => {
+                                tracing::warn!("Failed to raise window {}: {}", window_id, e)
+                            }
+                        }
                     }
                     // Only activate application if it's not already frontmost
                     // This prevents macOS from re-evaluating which window to focus
@@ -520,6 +573,74 @@ impl WindowManipulator for MacOSWindowManipulator {
         );
     }
 
+    fn set_window_minimized(&self, window_id: u32, pid: i32, minimized: bool) {
+        let app = AXUIElement::application(pid);
+        let ax_windows = match app.windows() {
+            Ok(w) => w,
+            Err(e) => {
+                tracing::warn!("Failed to get windows for pid {}: {}", pid, e);
+                return;
+            }
+        };
+
+        for ax_win in &ax_windows {
+            if ax_win.window_id() == Some(window_id) {
+                if let Err(e) = ax_win.set_minimized(minimized) {
+                    tracing::warn!(
+                        "Failed to set minimized={} for window {}: {}",
+                        minimized,
+                        window_id,
+                        e
+                    );
+                } else {
+                    tracing::info!(
+                        "{} window {} (pid {})",
+                        if minimized { "Minimized" } else { "Restored" },
+                        window_id,
+                        pid
+                    );
+                }
+                return;
+            }
+        }
+
+        tracing::warn!(
+            "Could not find AX window for id {} (pid {})",
+            window_id,
+            pid
+        );
+    }
+
+    fn raise_window(&self, window_id: u32, pid: i32) {
+        // Other processes' window levels can't be changed through AX, so the best we
+        // can do is AXRaise, which brings the window to the front of the stack.
+        let app = AXUIElement::application(pid);
+        let ax_windows = match app.windows() {
+            Ok(w) => w,
+            Err(e) => {
+                tracing::warn!("Failed to get windows for pid {}: {}", pid, e);
+                return;
+            }
+        };
+
+        for ax_win in &ax_windows {
+            if ax_win.window_id() == Some(window_id) {
+                if let Err(e) = ax_win.raise() {
+                    tracing::warn!("Failed to raise window {}: {}", window_id, e);
+                } else {
+                    tracing::debug!("Raised window {} (pid {})", window_id, pid);
+                }
+                return;
+            }
+        }
+
+        tracing::warn!(
+            "Could not find AX window for id {} (pid {})",
+            window_id,
+            pid
+        );
+    }
+
     fn exec_command(&self, command: &str, path: &str) -> Result<(), String> {
         crate::macos::exec_command(command, path)
     }
@@ -532,6 +653,10 @@ impl WindowManipulator for MacOSWindowManipulator {
         crate::macos::terminate_process(pid)
     }
 
+    fn set_main_display(&self, display_id: DisplayId) -> Result<(), String> {
+        crate::macos::set_main_display(display_id)
+    }
+
     fn warp_cursor(&self, x: i32, y: i32) {
         use core_graphics::display::CGWarpMouseCursorPosition;
 
@@ -553,6 +678,7 @@ impl Default for MacOSWindowManipulator {
 
 #[cfg(test)]
 pub mod mock {
+    use std::cell::RefCell;
     use std::collections::{HashMap, HashSet};
 
     use super::*;
@@ -569,6 +695,10 @@ pub mod mock {
         /// Used to simulate transitioning windows during fullscreen.
         /// Stored as (window_id, pid).
         pub ax_only_windows: HashSet<(u32, i32)>,
+        /// PIDs whose process has exited (e.g. crashed without destroy notifications).
+        pub exited_pids: HashSet<i32>,
+        /// Windows minimized to the Dock. Stored as (window_id, pid).
+        pub minimized_windows: HashSet<(u32, i32)>,
     }
 
     impl Default for MockWindowSystem {
@@ -580,6 +710,8 @@ pub mod mock {
                 ax_accessible_pids: HashSet::from([1, 2, 3, 4, 5, 100, 1000, 1001, 1002]),
                 custom_extended_attributes: HashMap::new(),
                 ax_only_windows: HashSet::new(),
+                exited_pids: HashSet::new(),
+                minimized_windows: HashSet::new(),
             }
         }
     }
@@ -629,6 +761,23 @@ pub mod mock {
         pub fn add_ax_only_window(&mut self, window_id: u32, pid: i32) {
             self.ax_only_windows.insert((window_id, pid));
         }
+
+        /// Minimize a window: it leaves the on-screen list but stays in the AX API.
+        pub fn minimize_window(&mut self, window_id: u32) {
+            if let Some(pos) = self.windows.iter().position(|w| w.window_id == window_id) {
+                let info = self.windows.remove(pos);
+                self.ax_only_windows.insert((window_id, info.pid));
+                self.minimized_windows.insert((window_id, info.pid));
+            }
+        }
+
+        pub fn set_process_running(&mut self, pid: i32, running: bool) {
+            if running {
+                self.exited_pids.remove(&pid);
+            } else {
+                self.exited_pids.insert(pid);
+            }
+        }
     }
 
     impl WindowSystem for MockWindowSystem {
@@ -678,6 +827,21 @@ pub mod mock {
                 .any(|w| w.window_id == window_id && w.pid == pid)
                 || self.ax_only_windows.contains(&(window_id, pid))
         }
+
+        fn is_process_running(&self, pid: i32) -> bool {
+            !self.exited_pids.contains(&pid)
+        }
+
+        fn get_window_frame(&self, window_id: u32, pid: i32) -> Option<Rect> {
+            self.windows
+                .iter()
+                .find(|w| w.window_id == window_id && w.pid == pid)
+                .map(|w| Rect::from_bounds(&w.bounds))
+        }
+
+        fn is_window_minimized(&self, window_id: u32, pid: i32) -> bool {
+            self.minimized_windows.contains(&(window_id, pid))
+        }
     }
 
     pub fn create_test_display(
@@ -697,6 +861,7 @@ pub mod mock {
                 height,
             },
             is_main: id == 1,
+            mirror_of: None,
         }
     }
 
@@ -740,11 +905,14 @@ pub mod mock {
 
     /// Mock implementation of WindowManipulator for tests
     #[derive(Default)]
-    pub struct MockWindowManipulator;
+    pub struct MockWindowManipulator {
+        /// (window_id, pid, raise) of every focus_window call
+        pub focus_calls: RefCell<Vec<(u32, i32, bool)>>,
+    }
 
     impl MockWindowManipulator {
         pub fn new() -> Self {
-            Self
+            Self::default()
         }
     }
 
@@ -757,7 +925,9 @@ pub mod mock {
             _geometries: &[WindowGeometry],
         ) {
         }
-        fn focus_window(&self, _window_id: u32, _pid: i32) {}
+        fn focus_window(&self, window_id: u32, pid: i32, raise: bool) {
+            self.focus_calls.borrow_mut().push((window_id, pid, raise));
+        }
         fn move_window_to_position(&self, _window_id: u32, _pid: i32, _x: i32, _y: i32) {}
         fn set_window_dimensions(&self, _window_id: u32, _pid: i32, _width: u32, _height: u32) {}
         fn set_window_frame(
@@ -771,6 +941,8 @@ pub mod mock {
         ) {
         }
         fn close_window(&self, _window_id: u32, _pid: i32) {}
+        fn set_window_minimized(&self, _window_id: u32, _pid: i32, _minimized: bool) {}
+        fn raise_window(&self, _window_id: u32, _pid: i32) {}
         fn exec_command(&self, _command: &str, _path: &str) -> Result<(), String> {
             Ok(())
         }
@@ -779,5 +951,8 @@ pub mod mock {
         }
         fn terminate_process(&self, _pid: u32) {}
         fn warp_cursor(&self, _x: i32, _y: i32) {}
+        fn set_main_display(&self, _display_id: DisplayId) -> Result<(), String> {
+            Ok(())
+        }
     }
 }